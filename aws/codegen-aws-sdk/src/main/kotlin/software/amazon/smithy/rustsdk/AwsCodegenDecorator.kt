@@ -66,6 +66,7 @@ val DECORATORS: List<ClientCodegenDecorator> =
             RecursionDetectionDecorator(),
             InvocationIdDecorator(),
             RetryInformationHeaderDecorator(),
+            RemoveRetryAfterClassifierDecorator(),
             RemoveDefaultsDecorator(),
             TokenProvidersDecorator(),
             ServiceEnvConfigDecorator(),
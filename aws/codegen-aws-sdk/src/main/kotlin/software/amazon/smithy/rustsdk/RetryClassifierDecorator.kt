@@ -7,9 +7,11 @@ package software.amazon.smithy.rustsdk
 
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryAfterClassifierServiceRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.ServiceRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 
@@ -26,6 +28,21 @@ class RetryClassifierDecorator : ClientCodegenDecorator {
             OperationRetryClassifiersFeature(codegenContext, operation)
 }
 
+/**
+ * Removes the generic `RetryAfterClassifier` from AWS SDK clients, which classify retries with
+ * `AwsErrorCodeClassifier` instead.
+ */
+class RemoveRetryAfterClassifierDecorator : ClientCodegenDecorator {
+    override val name: String = "RemoveRetryAfterClassifier"
+    override val order: Byte = 0
+
+    override fun serviceRuntimePluginCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ServiceRuntimePluginCustomization>,
+    ): List<ServiceRuntimePluginCustomization> =
+        baseCustomizations.filterNot { it is RetryAfterClassifierServiceRuntimePluginCustomization }
+}
+
 class OperationRetryClassifiersFeature(
     codegenContext: ClientCodegenContext,
     val operation: OperationShape,
@@ -12,8 +12,10 @@ use aws_runtime::auth::PayloadSigningOverride;
 use aws_runtime::content_encoding::header_value::AWS_CHUNKED;
 use aws_runtime::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions};
 use aws_smithy_checksums::body::ChecksumCache;
+use aws_smithy_checksums::registry::{ChecksumRegistry, SharedChecksumFactory};
 use aws_smithy_checksums::ChecksumAlgorithm;
 use aws_smithy_checksums::{body::calculate, http::HttpChecksum};
+use aws_smithy_runtime::client::http::body::request_compression::CompressedStreamingBody;
 use aws_smithy_runtime::client::sdk_feature::SmithySdkFeature;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::interceptors::context::{
@@ -38,7 +40,8 @@ use std::{fmt, mem};
 /// Errors related to constructing checksum-validated HTTP requests
 #[derive(Debug)]
 pub(crate) enum Error {
-    /// Only request bodies with a known size can be checksum validated
+    /// Only request bodies with a known size (or streaming bodies compressed by request
+    /// compression) can be checksum validated
     UnsizedRequestBody,
     ChecksumHeadersAreUnsupportedForStreamingBody,
 }
@@ -185,12 +188,9 @@ where
         // This value is from the trait, but is needed for runtime logic
         let request_checksum_required = state.request_checksum_required;
 
-        // If the algorithm fails to parse it is not one we support and we error
-        let checksum_algorithm = state
-            .checksum_algorithm
-            .clone()
-            .map(|s| ChecksumAlgorithm::from_str(s.as_str()))
-            .transpose()?;
+        // If the algorithm is neither registered nor one we support we error
+        let requested_checksum =
+            resolve_requested_checksum(state.checksum_algorithm.as_deref(), cfg)?;
 
         // This value is set by the user on the SdkConfig to indicate their preference
         // We provide a default here for users that use a client config instead of the SdkConfig
@@ -212,14 +212,19 @@ where
             _ => true,
         };
 
-        // If a checksum override is set in the ConfigBag we use that instead (currently only used by S3Express)
-        // If we have made it this far without a checksum being set we set the default (currently Crc32)
-        let checksum_algorithm =
-            incorporate_custom_default(checksum_algorithm, cfg).unwrap_or_default();
-
         if calculate_checksum {
             state.calculate_checksum.store(true, Ordering::Release);
 
+            // User-defined algorithms have no user-agent metric
+            let RequestedChecksum::BuiltIn(checksum_algorithm) = requested_checksum else {
+                return Ok(());
+            };
+
+            // If a checksum override is set in the ConfigBag we use that instead (currently only used by S3Express)
+            // If we have made it this far without a checksum being set we set the default (currently Crc32)
+            let checksum_algorithm =
+                incorporate_custom_default(checksum_algorithm, cfg).unwrap_or_default();
+
             // Set the user-agent metric for the selected checksum algorithm
             // NOTE: We have to do this in modify_before_retry_loop since UA interceptor also runs
             // in modify_before_signing but is registered before this interceptor (client level vs operation level).
@@ -248,6 +253,10 @@ where
                     cfg.interceptor_state()
                         .store_append(SmithySdkFeature::FlexibleChecksumsReqSha256);
                 }
+                // There are no user-agent metrics for these algorithms yet
+                ChecksumAlgorithm::Sha512
+                | ChecksumAlgorithm::Xxhash3
+                | ChecksumAlgorithm::Xxhash128 => {}
                 unsupported => tracing::warn!(
                         more_info = "Unsupported value of ChecksumAlgorithm detected when setting user-agent metrics",
                         unsupported = ?unsupported),
@@ -272,23 +281,26 @@ where
 
         let checksum_cache = state.checksum_cache.clone();
 
-        let checksum_algorithm = state
-            .checksum_algorithm
-            .clone()
-            .map(|s| ChecksumAlgorithm::from_str(s.as_str()))
-            .transpose()?;
+        let requested_checksum =
+            resolve_requested_checksum(state.checksum_algorithm.as_deref(), cfg)?;
 
         let calculate_checksum = state.calculate_checksum.load(Ordering::SeqCst);
 
         // Calculate the checksum if necessary
         if calculate_checksum {
-            // If a checksum override is set in the ConfigBag we use that instead (currently only used by S3Express)
-            // If we have made it this far without a checksum being set we set the default (currently Crc32)
-            let checksum_algorithm =
-                incorporate_custom_default(checksum_algorithm, cfg).unwrap_or_default();
+            let checksum_factory = match requested_checksum {
+                RequestedChecksum::Registered(checksum_factory) => checksum_factory,
+                // If a checksum override is set in the ConfigBag we use that instead (currently only used by S3Express)
+                // If we have made it this far without a checksum being set we set the default (currently Crc32)
+                RequestedChecksum::BuiltIn(checksum_algorithm) => {
+                    incorporate_custom_default(checksum_algorithm, cfg)
+                        .unwrap_or_default()
+                        .into()
+                }
+            };
 
             let request = context.request_mut();
-            add_checksum_for_request_body(request, checksum_algorithm, checksum_cache, cfg)?;
+            add_checksum_for_request_body(request, checksum_factory, checksum_cache, cfg)?;
         }
 
         Ok(())
@@ -324,6 +336,35 @@ where
     }
 }
 
+/// The checksum algorithm requested for an operation
+#[derive(Debug)]
+enum RequestedChecksum {
+    /// An algorithm registered in the [`ChecksumRegistry`]
+    Registered(SharedChecksumFactory),
+    /// A built-in algorithm, or `None` if no algorithm was requested
+    BuiltIn(Option<ChecksumAlgorithm>),
+}
+
+/// Resolve the name of a requested checksum algorithm. Algorithms registered in the
+/// [`ChecksumRegistry`] take precedence over the built-in algorithms.
+fn resolve_requested_checksum(
+    checksum_algorithm: Option<&str>,
+    cfg: &ConfigBag,
+) -> Result<RequestedChecksum, BoxError> {
+    let Some(checksum_algorithm) = checksum_algorithm else {
+        return Ok(RequestedChecksum::BuiltIn(None));
+    };
+    if let Some(checksum_factory) = cfg
+        .load::<ChecksumRegistry>()
+        .and_then(|registry| registry.factory(checksum_algorithm))
+    {
+        return Ok(RequestedChecksum::Registered(checksum_factory.clone()));
+    }
+    Ok(RequestedChecksum::BuiltIn(Some(
+        ChecksumAlgorithm::from_str(checksum_algorithm)?,
+    )))
+}
+
 fn incorporate_custom_default(
     checksum: Option<ChecksumAlgorithm>,
     cfg: &ConfigBag,
@@ -336,19 +377,22 @@ fn incorporate_custom_default(
 
 fn add_checksum_for_request_body(
     request: &mut HttpRequest,
-    checksum_algorithm: ChecksumAlgorithm,
+    checksum_factory: SharedChecksumFactory,
     checksum_cache: ChecksumCache,
     cfg: &mut ConfigBag,
 ) -> Result<(), BoxError> {
     match request.body().bytes() {
         // Body is in-memory: read it and insert the checksum as a header.
         Some(data) => {
-            let mut checksum = checksum_algorithm.into_impl();
+            let mut checksum = checksum_factory.new_checksum();
 
             // If the header has not already been set we set it. If it was already set by the user
             // we do nothing and maintain their set value.
             if request.headers().get(checksum.header_name()).is_none() {
-                tracing::debug!("applying {checksum_algorithm:?} of the request body as a header");
+                tracing::debug!(
+                    header_name = checksum.header_name(),
+                    "applying checksum of the request body as a header"
+                );
                 checksum.update(data);
 
                 let calculated_headers = checksum.headers();
@@ -371,47 +415,112 @@ fn add_checksum_for_request_body(
         }
         // Body is streaming: wrap the body so it will emit a checksum as a trailer.
         None => {
-            tracing::debug!("applying {checksum_algorithm:?} of the request body as a trailer");
+            tracing::debug!("applying checksum of the request body as a trailer");
+            // When the chunks of the body are signed, the signer does the aws-chunked encoding
+            // and signs the checksum trailer along with the chunks.
+            if let Some(PayloadSigningOverride::StreamingSignedPayload) =
+                cfg.load::<PayloadSigningOverride>()
+            {
+                let trailer_len =
+                    wrap_streaming_request_body_in_checksum_calculating_body_for_signing(
+                        request,
+                        checksum_factory,
+                        checksum_cache.clone(),
+                    )?;
+                if let Some(trailer_len) = trailer_len {
+                    cfg.interceptor_state().store_put(
+                        PayloadSigningOverride::StreamingSignedPayloadTrailer {
+                            trailer_lengths: vec![trailer_len],
+                        },
+                    );
+                }
+                return Ok(());
+            }
+            let is_compressed_stream = cfg.load::<CompressedStreamingBody>().is_some();
             cfg.interceptor_state()
                 .store_put(PayloadSigningOverride::StreamingUnsignedPayloadTrailer);
             wrap_streaming_request_body_in_checksum_calculating_body(
                 request,
-                checksum_algorithm,
+                checksum_factory,
                 checksum_cache.clone(),
+                is_compressed_stream,
             )?;
         }
     }
     Ok(())
 }
 
+/// Wraps a streaming body that will be sent with signed `aws-chunked` encoding so that it emits
+/// its checksum as a trailer. Returns the length of the trailer, or `None` if the user already
+/// set a checksum.
+fn wrap_streaming_request_body_in_checksum_calculating_body_for_signing(
+    request: &mut HttpRequest,
+    checksum_factory: SharedChecksumFactory,
+    checksum_cache: ChecksumCache,
+) -> Result<Option<u64>, BuildError> {
+    let checksum = checksum_factory.new_checksum();
+
+    // If the user already set the header value then do nothing and return early
+    if request.headers().get(checksum.header_name()).is_some() {
+        return Ok(None);
+    }
+
+    // Signed chunks need the size of the body to compute the encoded length up front
+    if request.body().size_hint().exact().is_none() {
+        return Err(BuildError::other(Error::UnsizedRequestBody));
+    }
+
+    let trailer_len = HttpChecksum::size(checksum.as_ref());
+    let body = mem::replace(request.body_mut(), SdkBody::taken());
+    *request.body_mut() = body.map(move |body| {
+        let checksum = checksum_factory.new_checksum();
+        let body = calculate::ChecksumBody::new(body, checksum).with_cache(checksum_cache.clone());
+        SdkBody::from_body_0_4(body)
+    });
+
+    request.headers_mut().insert(
+        http::header::HeaderName::from_static("x-amz-trailer"),
+        checksum.header_name(),
+    );
+
+    Ok(Some(trailer_len))
+}
+
 fn wrap_streaming_request_body_in_checksum_calculating_body(
     request: &mut HttpRequest,
-    checksum_algorithm: ChecksumAlgorithm,
+    checksum_factory: SharedChecksumFactory,
     checksum_cache: ChecksumCache,
+    is_compressed_stream: bool,
 ) -> Result<(), BuildError> {
-    let checksum = checksum_algorithm.into_impl();
+    let checksum = checksum_factory.new_checksum();
 
     // If the user already set the header value then do nothing and return early
     if request.headers().get(checksum.header_name()).is_some() {
         return Ok(());
     }
 
-    let original_body_size = request
-        .body()
-        .size_hint()
-        .exact()
-        .ok_or_else(|| BuildError::other(Error::UnsizedRequestBody))?;
+    // A streaming body compressed by the request compression interceptor, which runs before this
+    // one, can't know its size until it's fully sent, so it's sent as an unsized aws-chunked body.
+    // This way, the checksum covers the compressed payload. Any other unsized body is rejected.
+    let original_body_size = request.body().size_hint().exact();
+    if original_body_size.is_none() && !is_compressed_stream {
+        return Err(BuildError::other(Error::UnsizedRequestBody));
+    }
 
     let mut body = {
         let body = mem::replace(request.body_mut(), SdkBody::taken());
 
         body.map(move |body| {
-            let checksum = checksum_algorithm.into_impl();
+            let checksum = checksum_factory.new_checksum();
             let trailer_len = HttpChecksum::size(checksum.as_ref());
             let body =
                 calculate::ChecksumBody::new(body, checksum).with_cache(checksum_cache.clone());
-            let aws_chunked_body_options =
-                AwsChunkedBodyOptions::new(original_body_size, vec![trailer_len]);
+            let aws_chunked_body_options = match original_body_size {
+                Some(original_body_size) => {
+                    AwsChunkedBodyOptions::new(original_body_size, vec![trailer_len])
+                }
+                None => AwsChunkedBodyOptions::new_unsized(vec![trailer_len]),
+            };
 
             let body = AwsChunkedBody::new(body, aws_chunked_body_options);
 
@@ -419,11 +528,6 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
         })
     };
 
-    let encoded_content_length = body
-        .size_hint()
-        .exact()
-        .ok_or_else(|| BuildError::other(Error::UnsizedRequestBody))?;
-
     let headers = request.headers_mut();
 
     headers.insert(
@@ -431,14 +535,25 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
         checksum.header_name(),
     );
 
-    headers.insert(
-        http::header::CONTENT_LENGTH,
-        HeaderValue::from(encoded_content_length),
-    );
-    headers.insert(
-        http::header::HeaderName::from_static("x-amz-decoded-content-length"),
-        HeaderValue::from(original_body_size),
-    );
+    match original_body_size {
+        Some(original_body_size) => {
+            let encoded_content_length = body
+                .size_hint()
+                .exact()
+                .ok_or_else(|| BuildError::other(Error::UnsizedRequestBody))?;
+            headers.insert(
+                http::header::CONTENT_LENGTH,
+                HeaderValue::from(encoded_content_length),
+            );
+            headers.insert(
+                http::header::HeaderName::from_static("x-amz-decoded-content-length"),
+                HeaderValue::from(original_body_size),
+            );
+        }
+        None => {
+            headers.remove(http::header::CONTENT_LENGTH);
+        }
+    }
     // The target service does not depend on where `aws-chunked` appears in the `Content-Encoding` header,
     // as it will ultimately be stripped.
     headers.append(
@@ -455,13 +570,18 @@ fn wrap_streaming_request_body_in_checksum_calculating_body(
 
 #[cfg(test)]
 mod tests {
-    use crate::http_request_checksum::wrap_streaming_request_body_in_checksum_calculating_body;
+    use crate::http_request_checksum::{
+        resolve_requested_checksum, wrap_streaming_request_body_in_checksum_calculating_body,
+        RequestedChecksum,
+    };
     use aws_smithy_checksums::body::ChecksumCache;
+    use aws_smithy_checksums::registry::ChecksumRegistry;
     use aws_smithy_checksums::ChecksumAlgorithm;
     use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
     use aws_smithy_types::base64;
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::byte_stream::ByteStream;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
     use bytes::BytesMut;
     use http_body::Body;
     use tempfile::NamedTempFile;
@@ -483,8 +603,9 @@ mod tests {
         let checksum_cache = ChecksumCache::new();
         wrap_streaming_request_body_in_checksum_calculating_body(
             &mut request,
-            checksum_algorithm,
+            checksum_algorithm.into(),
             checksum_cache,
+            false,
         )
         .unwrap();
 
@@ -534,8 +655,9 @@ mod tests {
         let checksum_cache = ChecksumCache::new();
         wrap_streaming_request_body_in_checksum_calculating_body(
             &mut request,
-            checksum_algorithm,
+            checksum_algorithm.into(),
             checksum_cache,
+            false,
         )
         .unwrap();
 
@@ -554,4 +676,127 @@ mod tests {
             "expected {body} to end with '{expected}'"
         );
     }
+
+    #[tokio::test]
+    async fn test_unsized_compressed_body_is_sent_as_unsized_aws_chunked_body() {
+        let input_text = "Hello world";
+        let mut request: HttpRequest = http::Request::builder()
+            .header("content-encoding", "gzip")
+            .body(SdkBody::retryable(move || {
+                SdkBody::from_body_0_4(UnsizedBody(Some(input_text.into())))
+            }))
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
+        wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm.into(),
+            ChecksumCache::new(),
+            true,
+        )
+        .unwrap();
+
+        assert!(request.headers().get("content-length").is_none());
+        assert!(request
+            .headers()
+            .get("x-amz-decoded-content-length")
+            .is_none());
+        assert_eq!(
+            vec!["gzip", "aws-chunked"],
+            request
+                .headers()
+                .get_all("content-encoding")
+                .collect::<Vec<_>>()
+        );
+
+        let mut body = request.body().try_clone().expect("body is retryable");
+        let mut body_data = BytesMut::new();
+        while let Some(data) = body.data().await {
+            body_data.extend_from_slice(&data.unwrap())
+        }
+        let body = std::str::from_utf8(&body_data).unwrap();
+        assert_eq!(
+            "B\r\nHello world\r\n0\r\nx-amz-checksum-crc32:i9aeUg==\r\n\r\n",
+            body
+        );
+    }
+
+    #[test]
+    fn test_registered_checksum_algorithms_take_precedence() {
+        let mut layer = Layer::new("test");
+        layer.store_put(
+            ChecksumRegistry::new()
+                .with_algorithm("Custom", || ChecksumAlgorithm::Sha256.into_impl())
+                .with_algorithm("crc32c", || ChecksumAlgorithm::Sha1.into_impl()),
+        );
+        let cfg = ConfigBag::of_layers(vec![layer]);
+
+        let header_name =
+            |checksum_algorithm| match resolve_requested_checksum(Some(checksum_algorithm), &cfg)
+                .unwrap()
+            {
+                RequestedChecksum::Registered(factory) => factory.new_checksum().header_name(),
+                RequestedChecksum::BuiltIn(_) => panic!("{checksum_algorithm} is registered"),
+            };
+        assert_eq!("x-amz-checksum-sha256", header_name("custom"));
+        assert_eq!("x-amz-checksum-sha1", header_name("CRC32C"));
+
+        assert!(matches!(
+            resolve_requested_checksum(Some("crc32"), &cfg).unwrap(),
+            RequestedChecksum::BuiltIn(Some(ChecksumAlgorithm::Crc32))
+        ));
+        assert!(matches!(
+            resolve_requested_checksum(None, &cfg).unwrap(),
+            RequestedChecksum::BuiltIn(None)
+        ));
+        resolve_requested_checksum(Some("unknown"), &cfg)
+            .expect_err("unknown algorithms are an error");
+    }
+
+    #[test]
+    fn test_unsized_uncompressed_body_is_rejected() {
+        // Only compression marks a body as unsized on purpose, whatever its `Content-Encoding`
+        let mut request: HttpRequest = http::Request::builder()
+            .header("content-encoding", "custom")
+            .body(SdkBody::from_body_0_4(UnsizedBody(Some(
+                "Hello world".into(),
+            ))))
+            .unwrap()
+            .try_into()
+            .unwrap();
+
+        let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
+        let err = wrap_streaming_request_body_in_checksum_calculating_body(
+            &mut request,
+            checksum_algorithm.into(),
+            ChecksumCache::new(),
+            false,
+        )
+        .expect_err("unsized bodies must be compressed");
+        assert!(format!("{err:?}").contains("UnsizedRequestBody"), "{err:?}");
+    }
+
+    /// A body that yields a single chunk of data without reporting its size.
+    struct UnsizedBody(Option<bytes::Bytes>);
+
+    impl Body for UnsizedBody {
+        type Data = bytes::Bytes;
+        type Error = aws_smithy_types::body::Error;
+
+        fn poll_data(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Option<Result<Self::Data, Self::Error>>> {
+            std::task::Poll::Ready(self.0.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<Option<http::HeaderMap>, Self::Error>> {
+            std::task::Poll::Ready(Ok(None))
+        }
+    }
 }
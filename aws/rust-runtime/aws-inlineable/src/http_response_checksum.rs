@@ -7,6 +7,7 @@
 
 //! Interceptor for handling Smithy `@httpChecksum` response checksumming
 
+use aws_smithy_checksums::registry::{ChecksumRegistry, SharedChecksumFactory};
 use aws_smithy_checksums::ChecksumAlgorithm;
 use aws_smithy_runtime::client::sdk_feature::SmithySdkFeature;
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -136,15 +137,16 @@ where
             let maybe_checksum_headers = check_headers_for_precalculated_checksum(
                 response.headers(),
                 self.response_algorithms,
+                cfg.load::<ChecksumRegistry>(),
             );
 
-            if let Some((checksum_algorithm, precalculated_checksum)) = maybe_checksum_headers {
+            if let Some((checksum_factory, precalculated_checksum)) = maybe_checksum_headers {
                 let mut body = SdkBody::taken();
                 mem::swap(&mut body, response.body_mut());
 
                 let mut body = wrap_body_with_checksum_validator(
                     body,
-                    checksum_algorithm,
+                    checksum_factory,
                     precalculated_checksum,
                 );
                 mem::swap(&mut body, response.body_mut());
@@ -155,12 +157,12 @@ where
     }
 }
 
-/// Given an `SdkBody`, a `aws_smithy_checksums::ChecksumAlgorithm`, and a pre-calculated checksum,
-/// return an `SdkBody` where the body will processed with the checksum algorithm and checked
-/// against the pre-calculated checksum.
+/// Given an `SdkBody`, a `SharedChecksumFactory`, and a pre-calculated checksum, return an
+/// `SdkBody` where the body will processed with the checksum algorithm and checked against the
+/// pre-calculated checksum.
 pub(crate) fn wrap_body_with_checksum_validator(
     body: SdkBody,
-    checksum_algorithm: ChecksumAlgorithm,
+    checksum_factory: SharedChecksumFactory,
     precalculated_checksum: bytes::Bytes,
 ) -> SdkBody {
     use aws_smithy_checksums::body::validate;
@@ -168,7 +170,7 @@ pub(crate) fn wrap_body_with_checksum_validator(
     body.map(move |body| {
         SdkBody::from_body_0_4(validate::ChecksumBody::new(
             body,
-            checksum_algorithm.into_impl(),
+            checksum_factory.new_checksum(),
             precalculated_checksum.clone(),
         ))
     })
@@ -177,13 +179,18 @@ pub(crate) fn wrap_body_with_checksum_validator(
 /// Given a `HeaderMap`, extract any checksum included in the headers as `Some(Bytes)`.
 /// If no checksum header is set, return `None`. If multiple checksum headers are set, the one that
 /// is fastest to compute will be chosen.
+///
+/// Algorithms in the `ChecksumRegistry` replace the built-in algorithm of the same name. Headers
+/// for the remaining registered algorithms are only checked if no built-in checksum header was set.
 pub(crate) fn check_headers_for_precalculated_checksum(
     headers: &Headers,
     response_algorithms: &[&str],
-) -> Option<(ChecksumAlgorithm, bytes::Bytes)> {
+    registry: Option<&ChecksumRegistry>,
+) -> Option<(SharedChecksumFactory, bytes::Bytes)> {
     let checksum_algorithms_to_check =
-        aws_smithy_checksums::http::CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER
-            .into_iter()
+        aws_smithy_checksums::http::ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER
+            .iter()
+            .copied()
             // Process list of algorithms, from fastest to slowest, that may have been used to checksum
             // the response body, ignoring any that aren't marked as supported algorithms by the model.
             .flat_map(|algo| {
@@ -197,12 +204,31 @@ pub(crate) fn check_headers_for_precalculated_checksum(
                 None
             });
 
-    for checksum_algorithm in checksum_algorithms_to_check {
-        let checksum_algorithm: ChecksumAlgorithm = checksum_algorithm.parse().expect(
-            "CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER only contains valid checksum algorithm names",
-        );
+    let registered_factory = |name: &str| registry.and_then(|registry| registry.factory(name));
+    let built_in_factories = checksum_algorithms_to_check.map(|checksum_algorithm| {
+        match registered_factory(checksum_algorithm) {
+            Some(checksum_factory) => checksum_factory.clone(),
+            None => checksum_algorithm
+                .parse::<ChecksumAlgorithm>()
+                .expect("ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER only contains valid checksum algorithm names")
+                .into(),
+        }
+    });
+    let mut registered_names: Vec<&str> = registry
+        .map(|registry| registry.algorithm_names().collect())
+        .unwrap_or_default();
+    // Sort names so that the algorithm chosen doesn't depend on the registry's iteration order
+    registered_names.sort_unstable();
+    let registered_factories = registered_names
+        .into_iter()
+        .filter(|name| {
+            !aws_smithy_checksums::http::ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER.contains(name)
+        })
+        .filter_map(|name| registered_factory(name).cloned());
+
+    for checksum_factory in built_in_factories.chain(registered_factories) {
         if let Some(base64_encoded_precalculated_checksum) =
-            headers.get(checksum_algorithm.into_impl().header_name())
+            headers.get(checksum_factory.new_checksum().header_name())
         {
             // S3 needs special handling for checksums of objects uploaded with `MultiPartUpload`.
             if is_part_level_checksum(base64_encoded_precalculated_checksum) {
@@ -224,7 +250,7 @@ pub(crate) fn check_headers_for_precalculated_checksum(
                 }
             };
 
-            return Some((checksum_algorithm, precalculated_checksum));
+            return Some((checksum_factory, precalculated_checksum));
         }
     }
 
@@ -261,7 +287,14 @@ fn is_part_level_checksum(checksum: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::{is_part_level_checksum, wrap_body_with_checksum_validator};
+    use super::{
+        check_headers_for_precalculated_checksum, is_part_level_checksum,
+        wrap_body_with_checksum_validator,
+    };
+    use aws_smithy_checksums::http::HttpChecksum;
+    use aws_smithy_checksums::registry::ChecksumRegistry;
+    use aws_smithy_checksums::{Checksum, ChecksumAlgorithm};
+    use aws_smithy_runtime_api::http::Headers;
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::byte_stream::ByteStream;
     use aws_smithy_types::error::display::DisplayErrorContext;
@@ -269,7 +302,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_build_checksum_validated_body_works() {
-        let checksum_algorithm = "crc32".parse().unwrap();
+        let checksum_algorithm: ChecksumAlgorithm = "crc32".parse().unwrap();
         let input_text = "Hello world";
         let precalculated_checksum = Bytes::from_static(&[0x8b, 0xd6, 0x9e, 0x52]);
         let body = ByteStream::new(SdkBody::from(input_text));
@@ -277,7 +310,7 @@ mod tests {
         let body = body.map(move |sdk_body| {
             wrap_body_with_checksum_validator(
                 sdk_body,
-                checksum_algorithm,
+                checksum_algorithm.into(),
                 precalculated_checksum.clone(),
             )
         });
@@ -315,6 +348,84 @@ mod tests {
         assert!(!is_part_level_checksum("abcd==-AA"));
     }
 
+    /// A CRC32 checksum sent in a non-standard header.
+    struct CustomChecksum(Box<dyn HttpChecksum>);
+
+    impl Checksum for CustomChecksum {
+        fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+
+        fn finalize(self: Box<Self>) -> Bytes {
+            self.0.finalize()
+        }
+
+        fn size(&self) -> u64 {
+            Checksum::size(self.0.as_ref())
+        }
+    }
+
+    impl HttpChecksum for CustomChecksum {
+        fn header_name(&self) -> &'static str {
+            "x-amz-checksum-custom"
+        }
+    }
+
+    fn custom_checksum() -> Box<dyn HttpChecksum> {
+        Box::new(CustomChecksum(ChecksumAlgorithm::Crc32.into_impl()))
+    }
+
+    #[test]
+    fn test_registered_checksum_algorithms_are_checked() {
+        let registry = ChecksumRegistry::new().with_algorithm("custom", custom_checksum);
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-custom", "i9aeUg==");
+
+        let (factory, checksum) =
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], Some(&registry))
+                .expect("custom checksum header is set");
+        assert_eq!(
+            "x-amz-checksum-custom",
+            factory.new_checksum().header_name()
+        );
+        assert_eq!(&[0x8b, 0xd6, 0x9e, 0x52][..], &checksum[..]);
+
+        // Built-in checksums are preferred over registered ones
+        headers.insert("x-amz-checksum-crc32", "i9aeUg==");
+        let (factory, _) =
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], Some(&registry))
+                .unwrap();
+        assert_eq!("x-amz-checksum-crc32", factory.new_checksum().header_name());
+
+        assert!(check_headers_for_precalculated_checksum(
+            &Headers::new(),
+            &["crc32"],
+            Some(&registry)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_registered_checksum_algorithms_replace_built_in_algorithms() {
+        let registry = ChecksumRegistry::new().with_algorithm("crc32", custom_checksum);
+        let mut headers = Headers::new();
+        headers.insert("x-amz-checksum-crc32", "i9aeUg==");
+
+        // The registered implementation sends its checksum in a different header
+        assert!(
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], Some(&registry))
+                .is_none()
+        );
+        headers.insert("x-amz-checksum-custom", "i9aeUg==");
+        let (factory, _) =
+            check_headers_for_precalculated_checksum(&headers, &["crc32"], Some(&registry))
+                .unwrap();
+        assert_eq!(
+            "x-amz-checksum-custom",
+            factory.new_checksum().header_name()
+        );
+    }
+
     #[test]
     fn part_level_checksum_detection_works() {
         let a_real_checksum = is_part_level_checksum("C9A5A6878D97B48CC965C1E41859F034-14");
@@ -72,3 +72,4 @@ pub struct Client;
 
 pub mod dsql_auth_token;
 pub mod rds_auth_token;
+pub mod s3_presigned_post;
@@ -186,6 +186,10 @@ enum SigV4SigningError {
     #[cfg(feature = "sigv4a")]
     MissingSigningRegionSet,
     MissingSigningName,
+    #[cfg(feature = "http-02x")]
+    UnsizedStreamingSignedPayload,
+    #[cfg(not(feature = "http-02x"))]
+    StreamingSignedPayloadUnsupported,
     WrongIdentityType(Identity),
     BadTypeInEndpointAuthSchemeConfig(&'static str),
 }
@@ -200,6 +204,14 @@ impl fmt::Display for SigV4SigningError {
             #[cfg(feature = "sigv4a")]
             MissingSigningRegionSet => w("missing signing region set"),
             MissingSigningName => w("missing signing name"),
+            #[cfg(feature = "http-02x")]
+            UnsizedStreamingSignedPayload => {
+                w("a streaming signed payload requires a request body with a known size")
+            }
+            #[cfg(not(feature = "http-02x"))]
+            StreamingSignedPayloadUnsupported => {
+                w("a streaming signed payload requires the `http-02x` feature")
+            }
             WrongIdentityType(identity) => {
                 write!(f, "wrong identity type for SigV4/sigV4a. Expected AWS credentials but got `{identity:?}`")
             }
@@ -329,6 +341,16 @@ pub enum PayloadSigningOverride {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Send the body with `aws-chunked` encoding, and sign each chunk.
+    StreamingSignedPayload,
+
+    /// Send the body with `aws-chunked` encoding, and sign each chunk and the trailers of the
+    /// body. The length of each trailer is needed to calculate the length of the encoded body.
+    StreamingSignedPayloadTrailer {
+        /// The length of each trailer of the body
+        trailer_lengths: Vec<u64>,
+    },
 }
 
 impl PayloadSigningOverride {
@@ -338,6 +360,12 @@ impl PayloadSigningOverride {
         Self::UnsignedPayload
     }
 
+    /// Create a payload signing override that will send the payload in `aws-chunked` encoding
+    /// with signed chunks.
+    pub fn streaming_signed_payload() -> Self {
+        Self::StreamingSignedPayload
+    }
+
     /// Convert this type into the type used by the signer to determine how a
     /// request body should be signed.
     pub fn to_signable_body(self) -> SignableBody<'static> {
@@ -345,6 +373,10 @@ impl PayloadSigningOverride {
             Self::UnsignedPayload => SignableBody::UnsignedPayload,
             Self::Precomputed(checksum) => SignableBody::Precomputed(checksum),
             Self::StreamingUnsignedPayloadTrailer => SignableBody::StreamingUnsignedPayloadTrailer,
+            Self::StreamingSignedPayload => SignableBody::StreamingSignedPayload,
+            Self::StreamingSignedPayloadTrailer { .. } => {
+                SignableBody::StreamingSignedPayloadTrailer
+            }
         }
     }
 }
@@ -370,6 +402,18 @@ impl PayloadSigningOverrideRuntimePlugin {
             inner: layer.freeze(),
         }
     }
+
+    /// Create a new runtime plugin that will make the signer send the request payload in
+    /// `aws-chunked` encoding with signed chunks, for services that require the payload to be
+    /// signed without buffering it.
+    pub fn streaming_signed() -> Self {
+        let mut layer = Layer::new("PayloadSigningOverrideRuntimePlugin");
+        layer.store_put(PayloadSigningOverride::StreamingSignedPayload);
+
+        Self {
+            inner: layer.freeze(),
+        }
+    }
 }
 
 impl RuntimePlugin for PayloadSigningOverrideRuntimePlugin {
@@ -10,7 +10,7 @@ use crate::auth::{
 };
 use aws_credential_types::Credentials;
 use aws_sigv4::http_request::{
-    sign, SignableBody, SignableRequest, SigningParams, SigningSettings,
+    sign, PayloadChecksumKind, SignableBody, SignableRequest, SigningParams, SigningSettings,
 };
 use aws_sigv4::sign::v4;
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -169,7 +169,7 @@ impl Sign for SigV4Signer {
             Self::extract_operation_config(auth_scheme_endpoint_config, config_bag)?;
         let request_time = runtime_components.time_source().unwrap_or_default().now();
 
-        let settings = if let Some(session_token_name_override) =
+        let mut settings = if let Some(session_token_name_override) =
             config_bag.load::<SigV4SessionTokenNameOverride>()
         {
             let mut settings = Self::settings(&operation_config);
@@ -180,36 +180,66 @@ impl Sign for SigV4Signer {
             Self::settings(&operation_config)
         };
 
+        // The chunks of a streaming signed payload are signed after the request is, so the
+        // headers describing the encoded body must be in place before the request is signed
+        let payload_signing_override = config_bag.load::<PayloadSigningOverride>();
+        let signed_chunks_trailer_lengths = match payload_signing_override {
+            Some(PayloadSigningOverride::StreamingSignedPayloadTrailer { trailer_lengths }) => {
+                Some(trailer_lengths.clone())
+            }
+            Some(PayloadSigningOverride::StreamingSignedPayload) => Some(Vec::new()),
+            Some(_) => None,
+            None => match operation_config.signing_options.payload_override {
+                Some(
+                    SignableBody::StreamingSignedPayload
+                    | SignableBody::StreamingSignedPayloadTrailer,
+                ) => Some(Vec::new()),
+                _ => None,
+            },
+        };
+        if signed_chunks_trailer_lengths.is_some() {
+            settings.payload_checksum_kind = PayloadChecksumKind::XAmzSha256;
+        }
+        #[cfg(feature = "http-02x")]
+        let signed_chunks_options = match signed_chunks_trailer_lengths {
+            Some(trailer_lengths) => Some(signed_chunks::apply_headers(request, trailer_lengths)?),
+            None => None,
+        };
+        #[cfg(not(feature = "http-02x"))]
+        if signed_chunks_trailer_lengths.is_some() {
+            return Err(SigV4SigningError::StreamingSignedPayloadUnsupported.into());
+        }
+
+        // A body that is already in memory can be signed directly. A body that is not in memory
+        // (any sort of streaming body or presigned request) will be signed via UNSIGNED-PAYLOAD.
+        let mut signable_body = operation_config
+            .signing_options
+            .payload_override
+            .as_ref()
+            // the payload_override is a cheap clone because it contains either a
+            // reference or a short checksum (we're not cloning the entire body)
+            .cloned()
+            .unwrap_or_else(|| {
+                request
+                    .body()
+                    .bytes()
+                    .map(SignableBody::Bytes)
+                    .unwrap_or(SignableBody::UnsignedPayload)
+            });
+
+        // Sometimes it's necessary to override the payload signing scheme.
+        // If an override exists then fetch and apply it.
+        if let Some(payload_signing_override) = payload_signing_override {
+            tracing::trace!(
+                "payload signing was overridden, now set to {payload_signing_override:?}"
+            );
+            signable_body = payload_signing_override.clone().to_signable_body();
+        }
+
         let signing_params =
             Self::signing_params(settings, identity, &operation_config, request_time)?;
 
         let (signing_instructions, _signature) = {
-            // A body that is already in memory can be signed directly. A body that is not in memory
-            // (any sort of streaming body or presigned request) will be signed via UNSIGNED-PAYLOAD.
-            let mut signable_body = operation_config
-                .signing_options
-                .payload_override
-                .as_ref()
-                // the payload_override is a cheap clone because it contains either a
-                // reference or a short checksum (we're not cloning the entire body)
-                .cloned()
-                .unwrap_or_else(|| {
-                    request
-                        .body()
-                        .bytes()
-                        .map(SignableBody::Bytes)
-                        .unwrap_or(SignableBody::UnsignedPayload)
-                });
-
-            // Sometimes it's necessary to override the payload signing scheme.
-            // If an override exists then fetch and apply it.
-            if let Some(payload_signing_override) = config_bag.load::<PayloadSigningOverride>() {
-                tracing::trace!(
-                    "payload signing was overridden, now set to {payload_signing_override:?}"
-                );
-                signable_body = payload_signing_override.clone().to_signable_body();
-            }
-
             let signable_request = SignableRequest::new(
                 request.method(),
                 request.uri(),
@@ -232,7 +262,7 @@ impl Sign for SigV4Signer {
                 let name = operation_config.name.clone().unwrap();
                 signer_sender
                     .send(Box::new(SigV4MessageSigner::new(
-                        _signature,
+                        _signature.clone(),
                         identity.clone(),
                         region,
                         name,
@@ -242,10 +272,144 @@ impl Sign for SigV4Signer {
             }
         }
         auth::apply_signing_instructions(signing_instructions, request)?;
+
+        // If the payload is streamed with signed chunks, encode the body so its chunks are signed
+        #[cfg(feature = "http-02x")]
+        if let Some((stream_length, trailer_lengths)) = signed_chunks_options {
+            let chunk_signer = signed_chunks::SigV4ChunkSigner::new(
+                _signature,
+                identity.clone(),
+                operation_config.region.clone().unwrap(),
+                operation_config.name.clone().unwrap(),
+                request_time,
+            );
+            signed_chunks::wrap_body(request, stream_length, trailer_lengths, chunk_signer);
+        }
         Ok(())
     }
 }
 
+#[cfg(feature = "http-02x")]
+mod signed_chunks {
+    use crate::auth::SigV4SigningError;
+    use crate::content_encoding::header_value::AWS_CHUNKED;
+    use crate::content_encoding::{AwsChunkedBody, AwsChunkedBodyOptions, SignChunk};
+    use aws_sigv4::http_request::{sign_chunk, sign_trailers, ChunkSigningParams};
+    use aws_smithy_runtime_api::client::identity::Identity;
+    use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+    use aws_smithy_types::body::SdkBody;
+    use aws_types::region::SigningRegion;
+    use aws_types::SigningName;
+    use http_02x::HeaderMap;
+    use std::mem;
+    use std::time::SystemTime;
+
+    /// Sets the headers of a request whose body will be sent with signed `aws-chunked` encoding, and
+    /// returns the length of the body and of its trailers
+    pub(super) fn apply_headers(
+        request: &mut HttpRequest,
+        trailer_lengths: Vec<u64>,
+    ) -> Result<(u64, Vec<u64>), SigV4SigningError> {
+        let stream_length = request
+            .body()
+            .content_length()
+            .ok_or(SigV4SigningError::UnsizedStreamingSignedPayload)?;
+        let encoded_length = AwsChunkedBodyOptions::new(stream_length, trailer_lengths.clone())
+            .signed_encoded_length()
+            .expect("the stream has a size");
+        let headers = request.headers_mut();
+        headers.insert("content-length", encoded_length.to_string());
+        headers.insert("x-amz-decoded-content-length", stream_length.to_string());
+        if !headers
+            .get_all("content-encoding")
+            .any(|value| value == AWS_CHUNKED)
+        {
+            headers.append("content-encoding", AWS_CHUNKED);
+        }
+        Ok((stream_length, trailer_lengths))
+    }
+
+    /// Signs the chunks of a streaming signed payload, starting with the signature of the request
+    #[derive(Clone, Debug)]
+    pub(super) struct SigV4ChunkSigner {
+        previous_signature: String,
+        identity: Identity,
+        signing_region: SigningRegion,
+        signing_name: SigningName,
+        time: SystemTime,
+    }
+
+    impl SigV4ChunkSigner {
+        pub(super) fn new(
+            previous_signature: String,
+            identity: Identity,
+            signing_region: SigningRegion,
+            signing_name: SigningName,
+            time: SystemTime,
+        ) -> Self {
+            Self {
+                previous_signature,
+                identity,
+                signing_region,
+                signing_name,
+                time,
+            }
+        }
+
+        fn signing_params(&self) -> ChunkSigningParams<'_> {
+            ChunkSigningParams::builder()
+                .identity(&self.identity)
+                .region(self.signing_region.as_ref())
+                .name(self.signing_name.as_ref())
+                .time(self.time)
+                .settings(())
+                .build()
+                .expect("all required fields set")
+        }
+    }
+
+    impl SignChunk for SigV4ChunkSigner {
+        fn chunk_signature(
+            &mut self,
+            chunk: &[u8],
+        ) -> Result<String, aws_smithy_types::body::Error> {
+            let signature = sign_chunk(chunk, &self.previous_signature, &self.signing_params())?;
+            self.previous_signature = signature.clone();
+            Ok(signature)
+        }
+
+        fn trailer_signature(
+            &mut self,
+            trailers: &HeaderMap,
+        ) -> Result<String, aws_smithy_types::body::Error> {
+            let trailers = trailers
+                .iter()
+                .map(|(name, value)| Ok((name.as_str(), value.to_str()?)))
+                .collect::<Result<Vec<_>, http_02x::header::ToStrError>>()?;
+            let signature =
+                sign_trailers(trailers, &self.previous_signature, &self.signing_params())?;
+            self.previous_signature = signature.clone();
+            Ok(signature)
+        }
+    }
+
+    /// Replaces the body of the request with one that's `aws-chunked` encoded with signed chunks
+    pub(super) fn wrap_body(
+        request: &mut HttpRequest,
+        stream_length: u64,
+        trailer_lengths: Vec<u64>,
+        chunk_signer: SigV4ChunkSigner,
+    ) {
+        let body = mem::replace(request.body_mut(), SdkBody::taken());
+        *request.body_mut() = body.map(move |body| {
+            let options = AwsChunkedBodyOptions::new(stream_length, trailer_lengths.clone());
+            SdkBody::from_body_0_4(
+                AwsChunkedBody::new(body, options).with_chunk_signer(chunk_signer.clone()),
+            )
+        });
+    }
+}
+
 #[cfg(feature = "event-stream")]
 mod event_stream {
     use aws_sigv4::event_stream::{sign_empty_message, sign_message};
@@ -475,4 +639,38 @@ mod tests {
         assert_eq!(result.name, Some(SigningName::from_static("qldb")));
         assert!(matches!(result, Cow::Borrowed(_)));
     }
+
+    #[cfg(feature = "http-02x")]
+    #[test]
+    fn signed_chunks_headers() {
+        use crate::content_encoding::header_value::AWS_CHUNKED;
+        use crate::content_encoding::AwsChunkedBodyOptions;
+        use aws_smithy_types::body::SdkBody;
+
+        let mut request = HttpRequest::new(SdkBody::from("Hello world"));
+        request.headers_mut().insert("content-encoding", "gzip");
+        let (stream_length, trailer_lengths) =
+            signed_chunks::apply_headers(&mut request, vec![30]).unwrap();
+
+        assert_eq!(11, stream_length);
+        assert_eq!(vec![30], trailer_lengths);
+        let expected_length = AwsChunkedBodyOptions::new(11, vec![30])
+            .signed_encoded_length()
+            .unwrap();
+        assert_eq!(
+            Some(expected_length.to_string().as_str()),
+            request.headers().get("content-length")
+        );
+        assert_eq!(
+            Some("11"),
+            request.headers().get("x-amz-decoded-content-length")
+        );
+        assert_eq!(
+            vec!["gzip", AWS_CHUNKED],
+            request
+                .headers()
+                .get_all("content-encoding")
+                .collect::<Vec<_>>()
+        );
+    }
 }
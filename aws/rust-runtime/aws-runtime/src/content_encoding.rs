@@ -8,12 +8,20 @@ use http_02x::{HeaderMap, HeaderValue};
 use http_body_04x::{Body, SizeHint};
 use pin_project_lite::pin_project;
 
+use std::fmt;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{ready, Context, Poll};
 
 const CRLF: &str = "\r\n";
 const CHUNK_TERMINATOR: &str = "0\r\n";
 const TRAILER_SEPARATOR: &[u8] = b":";
+const CHUNK_SIGNATURE_EXTENSION: &str = ";chunk-signature=";
+const TRAILER_SIGNATURE: &str = "x-amz-trailer-signature";
+/// The length of a hex encoded SigV4 chunk signature
+const CHUNK_SIGNATURE_LENGTH: u64 = 64;
+/// The size of the chunks of a body with signed chunks. Only the final chunk with data may be
+/// smaller.
+const SIGNED_CHUNK_SIZE: usize = 64 * 1024;
 
 /// Content encoding header value constants
 pub mod header_value {
@@ -21,6 +29,21 @@ pub mod header_value {
     pub const AWS_CHUNKED: &str = "aws-chunked";
 }
 
+/// Signs the chunks and trailers of an [`AwsChunkedBody`].
+///
+/// Each signature incorporates the signature before it, starting with the signature of the
+/// request, so a signer must only be used for a single body.
+pub trait SignChunk: fmt::Debug + Send + Sync {
+    /// Returns the signature of the next chunk. The final chunk is empty.
+    fn chunk_signature(&mut self, chunk: &[u8]) -> Result<String, aws_smithy_types::body::Error>;
+
+    /// Returns the signature of the trailers, which are written after the final chunk.
+    fn trailer_signature(
+        &mut self,
+        trailers: &HeaderMap,
+    ) -> Result<String, aws_smithy_types::body::Error>;
+}
+
 /// Options used when constructing an [`AwsChunkedBody`].
 #[derive(Debug, Default)]
 #[non_exhaustive]
@@ -32,6 +55,9 @@ pub struct AwsChunkedBodyOptions {
     /// The length of each trailer sent within an `AwsChunkedBody`. Necessary in
     /// order to correctly calculate the total size of the body accurately.
     trailer_lengths: Vec<u64>,
+    /// Whether the total size of the stream is unknown. When it is, each frame of
+    /// the underlying payload is written as its own chunk and `stream_length` is ignored.
+    is_unsized: bool,
 }
 
 impl AwsChunkedBodyOptions {
@@ -40,6 +66,20 @@ impl AwsChunkedBodyOptions {
         Self {
             stream_length,
             trailer_lengths,
+            is_unsized: false,
+        }
+    }
+
+    /// Create a new [`AwsChunkedBodyOptions`] for a stream whose total size isn't known up front,
+    /// such as a compressed streaming body.
+    ///
+    /// Each frame of the underlying payload will be written as its own chunk, and the resulting
+    /// [`AwsChunkedBody`] will not report an exact size.
+    pub fn new_unsized(trailer_lengths: Vec<u64>) -> Self {
+        Self {
+            stream_length: 0,
+            trailer_lengths,
+            is_unsized: true,
         }
     }
 
@@ -54,6 +94,60 @@ impl AwsChunkedBodyOptions {
         self.trailer_lengths.push(trailer_len);
         self
     }
+
+    /// The length of the body once encoded with these options, or `None` if the size of the
+    /// stream isn't known.
+    pub fn encoded_length(&self) -> Option<u64> {
+        if self.is_unsized {
+            return None;
+        }
+        let mut length = 0;
+        if self.stream_length != 0 {
+            length += get_unsigned_chunk_bytes_length(self.stream_length);
+        }
+
+        // End chunk
+        length += CHUNK_TERMINATOR.len() as u64;
+
+        // Trailers
+        for len in self.trailer_lengths.iter() {
+            length += len + CRLF.len() as u64;
+        }
+
+        // Encoding terminator
+        length += CRLF.len() as u64;
+
+        Some(length)
+    }
+
+    /// The length of the body once encoded with these options and signed chunks, or `None` if
+    /// the size of the stream isn't known.
+    pub fn signed_encoded_length(&self) -> Option<u64> {
+        if self.is_unsized {
+            return None;
+        }
+        let full_chunks = self.stream_length / SIGNED_CHUNK_SIZE as u64;
+        let remainder = self.stream_length % SIGNED_CHUNK_SIZE as u64;
+        let mut length = full_chunks * get_signed_chunk_bytes_length(SIGNED_CHUNK_SIZE as u64);
+        if remainder != 0 {
+            length += get_signed_chunk_bytes_length(remainder);
+        }
+
+        // End chunk, which is empty and so isn't followed by a CRLF
+        length += get_signed_chunk_bytes_length(0) - CRLF.len() as u64;
+
+        // Trailers, followed by their signature
+        if !self.trailer_lengths.is_empty() {
+            length += self.total_trailer_length();
+            length += (TRAILER_SIGNATURE.len() + TRAILER_SEPARATOR.len() + CRLF.len()) as u64
+                + CHUNK_SIGNATURE_LENGTH;
+        }
+
+        // Encoding terminator
+        length += CRLF.len() as u64;
+
+        Some(length)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -73,8 +167,9 @@ enum AwsChunkedBodyState {
 }
 
 pin_project! {
-    /// A request body compatible with `Content-Encoding: aws-chunked`. This implementation is only
-    /// capable of writing a single chunk and does not support signed chunks.
+    /// A request body compatible with `Content-Encoding: aws-chunked`. Unless a
+    /// [chunk signer](AwsChunkedBody::with_chunk_signer) is set, this implementation writes the
+    /// payload as a single chunk, or as one chunk per frame of an unsized stream.
     ///
     /// Chunked-Body grammar is defined in [ABNF] as:
     ///
@@ -101,46 +196,97 @@ pin_project! {
         state: AwsChunkedBodyState,
         options: AwsChunkedBodyOptions,
         inner_body_bytes_read_so_far: usize,
+        chunk_signer: Option<Box<dyn SignChunk>>,
+        // Data read from the inner body that hasn't been written as a signed chunk yet
+        buffer: BytesMut,
     }
 }
 
 impl<Inner> AwsChunkedBody<Inner> {
     /// Wrap the given body in an outer body compatible with `Content-Encoding: aws-chunked`
     pub fn new(body: Inner, options: AwsChunkedBodyOptions) -> Self {
+        // The size of each chunk of an unsized stream is written along with its data
+        let state = if options.is_unsized {
+            AwsChunkedBodyState::WritingChunk
+        } else {
+            AwsChunkedBodyState::WritingChunkSize
+        };
         Self {
             inner: body,
-            state: AwsChunkedBodyState::WritingChunkSize,
+            state,
             options,
             inner_body_bytes_read_so_far: 0,
+            chunk_signer: None,
+            buffer: BytesMut::new(),
         }
     }
 
-    fn encoded_length(&self) -> u64 {
-        let mut length = 0;
-        if self.options.stream_length != 0 {
-            length += get_unsigned_chunk_bytes_length(self.options.stream_length);
-        }
-
-        // End chunk
-        length += CHUNK_TERMINATOR.len() as u64;
+    /// Signs each chunk and the trailers of the body with the given `signer`, for requests
+    /// signed with a streaming signed payload.
+    ///
+    /// The payload is written in chunks of 64 KiB, followed by a final chunk with the rest of it.
+    pub fn with_chunk_signer(mut self, signer: impl SignChunk + 'static) -> Self {
+        self.chunk_signer = Some(Box::new(signer));
+        self
+    }
 
-        // Trailers
-        for len in self.options.trailer_lengths.iter() {
-            length += len + CRLF.len() as u64;
+    fn encoded_length(&self) -> Option<u64> {
+        if self.chunk_signer.is_some() {
+            self.options.signed_encoded_length()
+        } else {
+            self.options.encoded_length()
         }
-
-        // Encoding terminator
-        length += CRLF.len() as u64;
-
-        length
     }
 }
 
+/// Writes out a complete chunk, prefixed by its size and followed by a CRLF.
+fn unsigned_chunk(data: &[u8]) -> Bytes {
+    let mut chunk =
+        BytesMut::with_capacity(get_unsigned_chunk_bytes_length(data.len() as u64) as usize);
+    chunk.extend_from_slice(format!("{:X?}{CRLF}", data.len()).as_bytes());
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(CRLF.as_bytes());
+    chunk.freeze()
+}
+
 fn get_unsigned_chunk_bytes_length(payload_length: u64) -> u64 {
     let hex_repr_len = int_log16(payload_length);
     hex_repr_len + CRLF.len() as u64 + payload_length + CRLF.len() as u64
 }
 
+/// Writes out a complete chunk, prefixed by its size and signature. The final, empty chunk isn't
+/// followed by a CRLF.
+fn signed_chunk(
+    signer: &mut dyn SignChunk,
+    data: &[u8],
+    chunk: &mut BytesMut,
+) -> Result<(), aws_smithy_types::body::Error> {
+    let signature = signer.chunk_signature(data)?;
+    chunk.extend_from_slice(
+        format!(
+            "{:X?}{CHUNK_SIGNATURE_EXTENSION}{signature}{CRLF}",
+            data.len()
+        )
+        .as_bytes(),
+    );
+    if !data.is_empty() {
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(CRLF.as_bytes());
+    }
+    Ok(())
+}
+
+fn get_signed_chunk_bytes_length(payload_length: u64) -> u64 {
+    // The size of an empty chunk is written as a single zero
+    let hex_repr_len = int_log16(payload_length).max(1);
+    hex_repr_len
+        + CHUNK_SIGNATURE_EXTENSION.len() as u64
+        + CHUNK_SIGNATURE_LENGTH
+        + CRLF.len() as u64
+        + payload_length
+        + CRLF.len() as u64
+}
+
 /// Writes trailers out into a `string` and then converts that `String` to a `Bytes` before
 /// returning.
 ///
@@ -212,6 +358,57 @@ where
         let mut this = self.project();
 
         match *this.state {
+            AwsChunkedBodyState::WritingChunkSize | AwsChunkedBodyState::WritingChunk
+                if this.chunk_signer.is_some() =>
+            {
+                let signer = this.chunk_signer.as_deref_mut().expect("checked above");
+                loop {
+                    if this.buffer.len() >= SIGNED_CHUNK_SIZE {
+                        let data = this.buffer.split_to(SIGNED_CHUNK_SIZE);
+                        tracing::trace!(len = data.len(), "writing signed chunk");
+                        let mut chunk = BytesMut::new();
+                        if let Err(e) = signed_chunk(signer, &data, &mut chunk) {
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                        return Poll::Ready(Some(Ok(chunk.freeze())));
+                    }
+                    match ready!(this.inner.as_mut().poll_data(cx)) {
+                        Some(Ok(data)) => {
+                            *this.inner_body_bytes_read_so_far += data.len();
+                            this.buffer.extend_from_slice(&data);
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            let actual_stream_length = *this.inner_body_bytes_read_so_far as u64;
+                            let expected_stream_length = this.options.stream_length;
+                            if !this.options.is_unsized
+                                && actual_stream_length != expected_stream_length
+                            {
+                                let err = Box::new(AwsChunkedBodyError::StreamLengthMismatch {
+                                    actual: actual_stream_length,
+                                    expected: expected_stream_length,
+                                });
+                                return Poll::Ready(Some(Err(err)));
+                            }
+
+                            tracing::trace!(
+                                len = this.buffer.len(),
+                                "no more chunk data, writing the rest of it and the final signed chunk"
+                            );
+                            *this.state = AwsChunkedBodyState::WritingTrailers;
+                            let mut chunks = BytesMut::new();
+                            let data = this.buffer.split();
+                            let result = if data.is_empty() {
+                                signed_chunk(signer, &[], &mut chunks)
+                            } else {
+                                signed_chunk(signer, &data, &mut chunks)
+                                    .and_then(|_| signed_chunk(signer, &[], &mut chunks))
+                            };
+                            return Poll::Ready(Some(result.map(|_| chunks.freeze())));
+                        }
+                    }
+                }
+            }
             AwsChunkedBodyState::WritingChunkSize => {
                 if this.options.stream_length == 0 {
                     // If the stream is empty, we skip to writing trailers after writing the CHUNK_TERMINATOR.
@@ -227,6 +424,23 @@ where
                     Poll::Ready(Some(Ok(chunk_size)))
                 }
             }
+            AwsChunkedBodyState::WritingChunk if this.options.is_unsized => loop {
+                match ready!(this.inner.as_mut().poll_data(cx)) {
+                    // An empty chunk would terminate the body, so skip empty frames.
+                    Some(Ok(data)) if data.is_empty() => continue,
+                    Some(Ok(data)) => {
+                        tracing::trace!(len = data.len(), "writing chunk of unsized stream");
+                        *this.inner_body_bytes_read_so_far += data.len();
+                        return Poll::Ready(Some(Ok(unsigned_chunk(&data))));
+                    }
+                    Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                    None => {
+                        tracing::trace!("no more chunk data, writing chunk terminator");
+                        *this.state = AwsChunkedBodyState::WritingTrailers;
+                        return Poll::Ready(Some(Ok(Bytes::from(CHUNK_TERMINATOR))));
+                    }
+                }
+            },
             AwsChunkedBodyState::WritingChunk => match this.inner.poll_data(cx) {
                 Poll::Ready(Some(Ok(data))) => {
                     tracing::trace!(len = data.len(), "writing chunk data");
@@ -269,8 +483,24 @@ where
                             return Poll::Ready(Some(Err(err)));
                         }
 
+                        let trailer_signature = match (this.chunk_signer.as_deref_mut(), &trailers)
+                        {
+                            (Some(signer), Some(trailers)) if !trailers.is_empty() => {
+                                match signer.trailer_signature(trailers) {
+                                    Ok(signature) => Some(signature),
+                                    Err(e) => return Poll::Ready(Some(Err(e))),
+                                }
+                            }
+                            _ => None,
+                        };
                         let mut trailers =
                             trailers_as_aws_chunked_bytes(trailers, actual_length + 1);
+                        if let Some(signature) = trailer_signature {
+                            trailers.extend_from_slice(TRAILER_SIGNATURE.as_bytes());
+                            trailers.extend_from_slice(TRAILER_SEPARATOR);
+                            trailers.extend_from_slice(signature.as_bytes());
+                            trailers.extend_from_slice(CRLF.as_bytes());
+                        }
                         // Insert the final CRLF to close the body
                         trailers.extend_from_slice(CRLF.as_bytes());
 
@@ -297,7 +527,10 @@ where
     }
 
     fn size_hint(&self) -> SizeHint {
-        SizeHint::with_exact(self.encoded_length())
+        match self.encoded_length() {
+            Some(length) => SizeHint::with_exact(length),
+            None => SizeHint::new(),
+        }
     }
 }
 
@@ -351,7 +584,7 @@ where
 mod tests {
     use super::{
         total_rendered_length_of_trailers, trailers_as_aws_chunked_bytes, AwsChunkedBody,
-        AwsChunkedBodyOptions, CHUNK_TERMINATOR, CRLF,
+        AwsChunkedBodyOptions, SignChunk, CHUNK_TERMINATOR, CRLF,
     };
 
     use aws_smithy_types::body::SdkBody;
@@ -526,6 +759,157 @@ mod tests {
         }
     }
 
+    // Signs each chunk with a signature made of the chunk's index
+    #[derive(Debug, Default)]
+    struct CountingSigner {
+        count: usize,
+    }
+
+    impl CountingSigner {
+        fn next_signature(&mut self) -> String {
+            self.count += 1;
+            format!("{:064}", self.count)
+        }
+    }
+
+    impl SignChunk for CountingSigner {
+        fn chunk_signature(
+            &mut self,
+            _chunk: &[u8],
+        ) -> Result<String, aws_smithy_types::body::Error> {
+            Ok(self.next_signature())
+        }
+
+        fn trailer_signature(
+            &mut self,
+            _trailers: &HeaderMap,
+        ) -> Result<String, aws_smithy_types::body::Error> {
+            Ok(self.next_signature())
+        }
+    }
+
+    struct TrailersBody {
+        data: Option<Bytes>,
+        trailers: Option<HeaderMap>,
+    }
+
+    impl Body for TrailersBody {
+        type Data = Bytes;
+        type Error = aws_smithy_types::body::Error;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+            Poll::Ready(self.data.take().map(Ok))
+        }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap<HeaderValue>>, Self::Error>> {
+            Poll::Ready(Ok(self.trailers.take()))
+        }
+    }
+
+    async fn read_to_string<B>(mut body: B) -> String
+    where
+        B: Body<Data = Bytes, Error = aws_smithy_types::body::Error> + Unpin,
+    {
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+        actual_output
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_chunks() {
+        let input = "a".repeat(64 * 1024 + 10);
+        let opts = AwsChunkedBodyOptions::new(input.len() as u64, Vec::new());
+        let body = AwsChunkedBody::new(SdkBody::from(input.as_str()), opts)
+            .with_chunk_signer(CountingSigner::default());
+        let expected_length = body.size_hint().exact().unwrap();
+
+        let actual_output = read_to_string(body).await;
+        let expected_output = format!(
+            "10000;chunk-signature={:064}\r\n{}\r\n\
+             A;chunk-signature={:064}\r\n{}\r\n\
+             0;chunk-signature={:064}\r\n\r\n",
+            1,
+            &input[..64 * 1024],
+            2,
+            &input[64 * 1024..],
+            3
+        );
+        assert_eq!(expected_output, actual_output);
+        assert_eq!(expected_length, actual_output.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_signed_chunks_with_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("x-amz-checksum-crc32", HeaderValue::from_static("i9aeUg=="));
+        let trailer_len = total_rendered_length_of_trailers(Some(&trailers)) - CRLF.len() as u64;
+        let input = TrailersBody {
+            data: Some(Bytes::from_static(b"Hello world")),
+            trailers: Some(trailers),
+        };
+        let opts = AwsChunkedBodyOptions::new(11, vec![trailer_len]);
+        let body = AwsChunkedBody::new(input, opts).with_chunk_signer(CountingSigner::default());
+        let expected_length = body.size_hint().exact().unwrap();
+
+        let actual_output = read_to_string(body).await;
+        let expected_output = format!(
+            "B;chunk-signature={:064}\r\nHello world\r\n\
+             0;chunk-signature={:064}\r\n\
+             x-amz-checksum-crc32:i9aeUg==\r\n\
+             x-amz-trailer-signature:{:064}\r\n\r\n",
+            1, 2, 3
+        );
+        assert_eq!(expected_output, actual_output);
+        assert_eq!(expected_length, actual_output.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_aws_chunked_encoding_unsized_sputtering_body() {
+        let input = SputteringBody {
+            parts: vec![
+                Some(Bytes::from_static(b"chunk 1, ")),
+                None,
+                Some(Bytes::new()),
+                Some(Bytes::from_static(b"chunk 2, ")),
+                None,
+                Some(Bytes::from_static(b"chunk 3")),
+            ],
+            cursor: 0,
+            delay_in_millis: 10,
+        };
+        let opts = AwsChunkedBodyOptions::new_unsized(Vec::new());
+        let mut body = AwsChunkedBody::new(input, opts);
+        assert_eq!(None, body.size_hint().exact());
+
+        let mut output = SegmentedBuf::new();
+        while let Some(buf) = body.data().await {
+            output.push(buf.unwrap());
+        }
+
+        let mut actual_output = String::new();
+        output
+            .reader()
+            .read_to_string(&mut actual_output)
+            .expect("Doesn't cause IO errors");
+
+        let expected_output = "9\r\nchunk 1, \r\n9\r\nchunk 2, \r\n7\r\nchunk 3\r\n0\r\n\r\n";
+
+        assert_eq!(expected_output, actual_output);
+    }
+
     #[tokio::test]
     #[should_panic = "called `Result::unwrap()` on an `Err` value: ReportedTrailerLengthMismatch { actual: 44, expected: 0 }"]
     async fn test_aws_chunked_encoding_incorrect_trailer_length_panic() {
@@ -0,0 +1,122 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Support for caching AWS credentials with a
+//! [persistent identity cache](aws_smithy_runtime::client::identity::IdentityCache::persistent).
+
+use aws_credential_types::Credentials;
+use aws_smithy_runtime::client::identity::IdentitySerializer;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::Identity;
+use std::time::SystemTime;
+
+const PROVIDER_NAME: &str = "PersistentIdentityCache";
+
+/// Serializer for AWS [`Credentials`] identities.
+///
+/// The access key ID, secret access key, session token and account ID of the credentials are
+/// stored. Credentials loaded from the cache report `PersistentIdentityCache` as their provider.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct CredentialsSerializer;
+
+impl CredentialsSerializer {
+    /// Creates a new `CredentialsSerializer`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl IdentitySerializer for CredentialsSerializer {
+    fn serialize(&self, identity: &Identity) -> Result<Vec<u8>, BoxError> {
+        let credentials = identity
+            .data::<Credentials>()
+            .ok_or("the identity isn't AWS credentials")?;
+        // One field per line, with empty lines for missing optional fields
+        let fields = [
+            credentials.access_key_id(),
+            credentials.secret_access_key(),
+            credentials.session_token().unwrap_or_default(),
+            credentials
+                .account_id()
+                .map(|account_id| account_id.as_str())
+                .unwrap_or_default(),
+        ];
+        if fields.iter().any(|field| field.contains('\n')) {
+            return Err("the credentials contain a line break".into());
+        }
+        Ok(fields.join("\n").into_bytes())
+    }
+
+    fn deserialize(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError> {
+        let data = std::str::from_utf8(data)?;
+        let mut fields = data.split('\n');
+        let mut next = || fields.next().ok_or("missing credentials field");
+        let (access_key_id, secret_access_key) = (next()?, next()?);
+        let (session_token, account_id) = (next()?, next()?);
+
+        let mut builder = Credentials::builder()
+            .access_key_id(access_key_id)
+            .secret_access_key(secret_access_key)
+            .expiry(expiration)
+            .provider_name(PROVIDER_NAME);
+        if !session_token.is_empty() {
+            builder = builder.session_token(session_token);
+        }
+        if !account_id.is_empty() {
+            builder = builder.account_id(account_id);
+        }
+        Ok(builder.build().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CredentialsSerializer;
+    use aws_credential_types::Credentials;
+    use aws_smithy_runtime::client::identity::IdentitySerializer;
+    use aws_smithy_runtime_api::client::identity::Identity;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn credentials_round_trip() {
+        let expiration = UNIX_EPOCH + Duration::from_secs(1000);
+        let serializer = CredentialsSerializer::new();
+        let credentials = [
+            Credentials::for_tests(),
+            Credentials::builder()
+                .access_key_id("AKID")
+                .secret_access_key("secret")
+                .session_token("token")
+                .account_id("123456789012")
+                .expiry(expiration)
+                .provider_name("test")
+                .build(),
+        ];
+        for credentials in credentials {
+            let data = serializer
+                .serialize(&Identity::from(credentials.clone()))
+                .unwrap();
+            let identity = serializer.deserialize(&data, expiration).unwrap();
+            assert_eq!(Some(expiration), identity.expiration());
+            let deserialized = identity.data::<Credentials>().unwrap();
+            assert_eq!(credentials.access_key_id(), deserialized.access_key_id());
+            assert_eq!(
+                credentials.secret_access_key(),
+                deserialized.secret_access_key()
+            );
+            assert_eq!(credentials.session_token(), deserialized.session_token());
+            assert_eq!(credentials.account_id(), deserialized.account_id());
+        }
+    }
+
+    #[test]
+    fn invalid_data() {
+        let serializer = CredentialsSerializer::new();
+        assert!(serializer.deserialize(b"AKID\nsecret", UNIX_EPOCH).is_err());
+        let token = Identity::new("not credentials", None);
+        assert!(serializer.serialize(&token).is_err());
+    }
+}
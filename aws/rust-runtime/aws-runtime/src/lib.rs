@@ -51,3 +51,5 @@ pub mod fs_util;
 /// Supporting code for parsing AWS config values set in a user's environment or
 /// in a shared config file.
 pub mod env_config;
+
+pub mod identity;
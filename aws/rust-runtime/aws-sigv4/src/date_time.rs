@@ -7,7 +7,7 @@
 #![allow(dead_code)]
 
 use std::time::SystemTime;
-use time::{OffsetDateTime, Time};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
 
 /// Truncates the subseconds from the given `SystemTime` to zero.
 pub(crate) fn truncate_subsecs(time: SystemTime) -> SystemTime {
@@ -45,6 +45,35 @@ pub(crate) fn format_date_time(time: SystemTime) -> String {
     )
 }
 
+/// Parses a `YYYYMMDD'T'HHMMSS'Z'` formatted date time, such as the `x-amz-date` of a signed
+/// request. Returns `None` if it isn't in that format.
+pub(crate) fn parse_signing_date_time(date_time_str: &str) -> Option<SystemTime> {
+    let bytes = date_time_str.as_bytes();
+    if bytes.len() != 16 || bytes[8] != b'T' || bytes[15] != b'Z' {
+        return None;
+    }
+    let digits = |range: std::ops::Range<usize>| -> Option<u16> {
+        let digits = &date_time_str[range];
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+    let date = Date::from_calendar_date(
+        digits(0..4)?.into(),
+        Month::try_from(digits(4..6)? as u8).ok()?,
+        digits(6..8)? as u8,
+    )
+    .ok()?;
+    let time = Time::from_hms(
+        digits(9..11)? as u8,
+        digits(11..13)? as u8,
+        digits(13..15)? as u8,
+    )
+    .ok()?;
+    Some(PrimitiveDateTime::new(date, time).assume_utc().into())
+}
+
 /// Parse functions that are only needed for unit tests.
 #[cfg(test)]
 pub(crate) mod test_parsers {
@@ -129,6 +158,15 @@ mod tests {
         assert_eq!("20150830T123600Z", format_date_time(time));
     }
 
+    #[test]
+    fn parse_signing_date_time_roundtrip() {
+        let time = parse_signing_date_time("20150830T123600Z").unwrap();
+        assert_eq!("20150830T123600Z", format_date_time(time));
+        assert_eq!(None, parse_signing_date_time("2015-08-30T12:36:00Z"));
+        assert_eq!(None, parse_signing_date_time("20151330T123600Z"));
+        assert_eq!(None, parse_signing_date_time("2015083+T123600Z"));
+    }
+
     #[test]
     fn date_roundtrip() {
         let time = parse_date("20150830").unwrap();
@@ -56,11 +56,13 @@
 //! ```
 
 mod canonical_request;
+mod chunk;
 mod error;
 mod settings;
 mod sign;
 mod uri_path_normalization;
 mod url_escape;
+mod verify;
 
 #[cfg(test)]
 pub(crate) mod test;
@@ -70,6 +72,7 @@ use crate::sign::v4;
 use crate::sign::v4a;
 use crate::SignatureVersion;
 use aws_credential_types::Credentials;
+pub use chunk::{sign_chunk, sign_trailers, ChunkSigningParams};
 pub use error::SigningError;
 pub use settings::{
     PayloadChecksumKind, PercentEncodingMode, SessionTokenMode, SignatureLocation, SigningSettings,
@@ -77,6 +80,10 @@ pub use settings::{
 };
 pub use sign::{sign, SignableBody, SignableRequest, SigningInstructions};
 use std::time::SystemTime;
+pub use verify::{
+    verification_params, verify, CredentialsLookup, RejectionReason, VerificationError,
+    VerificationParams, VerifiedRequest,
+};
 
 // Individual Debug impls are responsible for redacting sensitive fields.
 #[derive(Debug)]
@@ -95,6 +95,18 @@ pub enum SignableBody<'a> {
 
     /// Set when a streaming body has checksum trailers.
     StreamingUnsignedPayloadTrailer,
+
+    /// Set when a streaming body is sent with `aws-chunked` encoding and each chunk is signed.
+    ///
+    /// The signature of the request is the seed for the signature of the first chunk, which is
+    /// calculated with [`sign_chunk`](crate::http_request::sign_chunk).
+    StreamingSignedPayload,
+
+    /// Set when a streaming body is sent with `aws-chunked` encoding, each chunk is signed, and
+    /// the body has signed trailers.
+    ///
+    /// The trailers are signed with [`sign_trailers`](crate::http_request::sign_trailers).
+    StreamingSignedPayloadTrailer,
 }
 
 /// Formats the value using the given formatter. To print the body data, set the environment variable `LOG_SIGNABLE_BODY=true`.
@@ -117,6 +129,8 @@ impl Debug for SignableBody<'_> {
             Self::StreamingUnsignedPayloadTrailer => {
                 write!(f, "StreamingUnsignedPayloadTrailer")
             }
+            Self::StreamingSignedPayload => write!(f, "StreamingSignedPayload"),
+            Self::StreamingSignedPayloadTrailer => write!(f, "StreamingSignedPayloadTrailer"),
         }
     }
 }
@@ -1054,6 +1068,9 @@ mod tests {
 
         let sut = SignableBody::StreamingUnsignedPayloadTrailer;
         assert_eq!("StreamingUnsignedPayloadTrailer", format!("{sut:?}"));
+
+        let sut = SignableBody::StreamingSignedPayload;
+        assert_eq!("StreamingSignedPayload", format!("{sut:?}"));
     }
 
     // v4 test suite
@@ -0,0 +1,1135 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use super::error::SigningError;
+use super::{PayloadChecksumKind, SessionTokenMode, SignatureLocation, SigningSettings};
+use crate::date_time::{format_date, parse_signing_date_time};
+use crate::http_request::canonical_request::{header, param, HMAC_256, UNSIGNED_PAYLOAD};
+use crate::http_request::canonical_request::{CanonicalRequest, StringToSign};
+use crate::http_request::sign::{SignableBody, SignableRequest};
+use crate::http_request::SigningParams;
+use crate::sign::v4;
+#[cfg(feature = "sigv4a")]
+use crate::sign::v4a;
+use crate::SignatureVersion;
+use aws_credential_types::Credentials;
+use aws_smithy_runtime_api::client::identity::Identity;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+const DEFAULT_MAX_EXPIRES_IN: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const AUTHORIZATION: &str = "authorization";
+const HOST: &str = "host";
+
+/// Looks up the credentials that requests are verified against.
+pub trait CredentialsLookup: fmt::Debug + Send + Sync {
+    /// Returns the credentials for `access_key_id`, or `None` if the access key isn't known.
+    ///
+    /// `session_token` is the session token sent with the request, if any. Implementations that
+    /// issue temporary credentials are responsible for checking that it belongs to the access key.
+    fn lookup(&self, access_key_id: &str, session_token: Option<&str>) -> Option<Credentials>;
+}
+
+impl CredentialsLookup for Credentials {
+    fn lookup(&self, access_key_id: &str, session_token: Option<&str>) -> Option<Credentials> {
+        (self.access_key_id() == access_key_id && self.session_token() == session_token)
+            .then(|| self.clone())
+    }
+}
+
+/// Parameters for verifying the signature of an HTTP request.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct VerificationParams<'a> {
+    credentials_lookup: &'a dyn CredentialsLookup,
+    region: &'a str,
+    name: &'a str,
+    time: SystemTime,
+    max_clock_skew: Duration,
+    max_expires_in: Duration,
+    settings: SigningSettings,
+}
+
+impl<'a> VerificationParams<'a> {
+    /// Returns a builder that can create new `VerificationParams`.
+    pub fn builder() -> verification_params::Builder<'a> {
+        Default::default()
+    }
+}
+
+/// Builder and error for creating [`VerificationParams`]
+pub mod verification_params {
+    use super::{CredentialsLookup, VerificationParams};
+    use super::{DEFAULT_MAX_CLOCK_SKEW, DEFAULT_MAX_EXPIRES_IN};
+    use crate::http_request::SigningSettings;
+    use std::error::Error;
+    use std::fmt;
+    use std::time::{Duration, SystemTime};
+
+    /// [`VerificationParams`] builder error
+    #[derive(Debug)]
+    pub struct BuildError {
+        reason: &'static str,
+    }
+
+    impl fmt::Display for BuildError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.reason)
+        }
+    }
+
+    impl Error for BuildError {}
+
+    /// Builder that can create new [`VerificationParams`]
+    #[derive(Debug, Default)]
+    pub struct Builder<'a> {
+        credentials_lookup: Option<&'a dyn CredentialsLookup>,
+        region: Option<&'a str>,
+        name: Option<&'a str>,
+        time: Option<SystemTime>,
+        max_clock_skew: Option<Duration>,
+        max_expires_in: Option<Duration>,
+        settings: Option<SigningSettings>,
+    }
+
+    impl<'a> Builder<'a> {
+        /// Sets the lookup for the credentials of signed requests (required)
+        pub fn credentials_lookup(mut self, credentials_lookup: &'a dyn CredentialsLookup) -> Self {
+            self.credentials_lookup = Some(credentials_lookup);
+            self
+        }
+
+        /// Sets the lookup for the credentials of signed requests (required)
+        pub fn set_credentials_lookup(
+            &mut self,
+            credentials_lookup: Option<&'a dyn CredentialsLookup>,
+        ) -> &mut Self {
+            self.credentials_lookup = credentials_lookup;
+            self
+        }
+
+        /// Sets the region requests must be signed for (required)
+        pub fn region(mut self, region: &'a str) -> Self {
+            self.region = Some(region);
+            self
+        }
+
+        /// Sets the region requests must be signed for (required)
+        pub fn set_region(&mut self, region: Option<&'a str>) -> &mut Self {
+            self.region = region;
+            self
+        }
+
+        /// Sets the name requests must be signed for (required)
+        pub fn name(mut self, name: &'a str) -> Self {
+            self.name = Some(name);
+            self
+        }
+
+        /// Sets the name requests must be signed for (required)
+        pub fn set_name(&mut self, name: Option<&'a str>) -> &mut Self {
+            self.name = name;
+            self
+        }
+
+        /// Sets the current time that signing times are checked against (required)
+        pub fn time(mut self, time: SystemTime) -> Self {
+            self.time = Some(time);
+            self
+        }
+
+        /// Sets the current time that signing times are checked against (required)
+        pub fn set_time(&mut self, time: Option<SystemTime>) -> &mut Self {
+            self.time = time;
+            self
+        }
+
+        /// Sets how far the signing time of a request may be from the current time. Defaults to
+        /// five minutes.
+        pub fn max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+            self.max_clock_skew = Some(max_clock_skew);
+            self
+        }
+
+        /// Sets how far the signing time of a request may be from the current time. Defaults to
+        /// five minutes.
+        pub fn set_max_clock_skew(&mut self, max_clock_skew: Option<Duration>) -> &mut Self {
+            self.max_clock_skew = max_clock_skew;
+            self
+        }
+
+        /// Sets the longest expiration accepted for presigned requests. Defaults to seven days.
+        pub fn max_expires_in(mut self, max_expires_in: Duration) -> Self {
+            self.max_expires_in = Some(max_expires_in);
+            self
+        }
+
+        /// Sets the longest expiration accepted for presigned requests. Defaults to seven days.
+        pub fn set_max_expires_in(&mut self, max_expires_in: Option<Duration>) -> &mut Self {
+            self.max_expires_in = max_expires_in;
+            self
+        }
+
+        /// Sets the settings requests were signed with. Only the settings that aren't sent with
+        /// a signature, such as the percent encoding and URI path normalization modes, are used.
+        /// Defaults to [`SigningSettings::default`].
+        pub fn settings(mut self, settings: SigningSettings) -> Self {
+            self.settings = Some(settings);
+            self
+        }
+
+        /// Sets the settings requests were signed with. Only the settings that aren't sent with
+        /// a signature, such as the percent encoding and URI path normalization modes, are used.
+        /// Defaults to [`SigningSettings::default`].
+        pub fn set_settings(&mut self, settings: Option<SigningSettings>) -> &mut Self {
+            self.settings = settings;
+            self
+        }
+
+        /// Builds an instance of [`VerificationParams`]. Will yield a [`BuildError`] if
+        /// a required argument was not given.
+        pub fn build(self) -> Result<VerificationParams<'a>, BuildError> {
+            let missing = |reason| BuildError { reason };
+            Ok(VerificationParams {
+                credentials_lookup: self
+                    .credentials_lookup
+                    .ok_or_else(|| missing("credentials lookup is required"))?,
+                region: self.region.ok_or_else(|| missing("region is required"))?,
+                name: self.name.ok_or_else(|| missing("name is required"))?,
+                time: self.time.ok_or_else(|| missing("time is required"))?,
+                max_clock_skew: self.max_clock_skew.unwrap_or(DEFAULT_MAX_CLOCK_SKEW),
+                max_expires_in: self.max_expires_in.unwrap_or(DEFAULT_MAX_EXPIRES_IN),
+                settings: self.settings.unwrap_or_default(),
+            })
+        }
+    }
+}
+
+/// A request whose signature was verified
+#[derive(Debug)]
+pub struct VerifiedRequest {
+    credentials: Credentials,
+    signature_version: SignatureVersion,
+    signature_location: SignatureLocation,
+    signing_time: SystemTime,
+}
+
+impl VerifiedRequest {
+    /// The credentials the request was signed with, as returned by the [`CredentialsLookup`]
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
+    /// The access key ID the request was signed with
+    pub fn access_key_id(&self) -> &str {
+        self.credentials.access_key_id()
+    }
+
+    /// The signing algorithm the request was signed with
+    pub fn signature_version(&self) -> SignatureVersion {
+        self.signature_version
+    }
+
+    /// Whether the signature was in the headers, or in the query params of a presigned request
+    pub fn signature_location(&self) -> SignatureLocation {
+        self.signature_location
+    }
+
+    /// The time the request was signed at
+    pub fn signing_time(&self) -> SystemTime {
+        self.signing_time
+    }
+}
+
+/// The reason a request was rejected by [`verify`]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RejectionReason {
+    /// The request has neither an `authorization` header nor an `X-Amz-Signature` query param
+    MissingSignature,
+    /// The signature, or a value signed along with it, couldn't be parsed
+    MalformedSignature,
+    /// The request was signed with an algorithm other than SigV4, or SigV4a when the `sigv4a`
+    /// feature is enabled
+    UnsupportedAlgorithm,
+    /// The request was signed for a different date, region, or service
+    CredentialScopeMismatch,
+    /// The signing time is further from the current time than the allowed clock skew
+    RequestTimeTooSkewed,
+    /// The presigned request has expired
+    RequestExpired,
+    /// The presigned request expires later than allowed
+    ExpiresTooLong,
+    /// The [`CredentialsLookup`] doesn't know the access key
+    UnknownAccessKey,
+    /// The body doesn't match the signed `x-amz-content-sha256` header
+    PayloadHashMismatch,
+    /// The signature doesn't match the one calculated for the request
+    SignatureMismatch,
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RejectionReason::*;
+        match self {
+            MissingSignature => write!(f, "the request isn't signed"),
+            MalformedSignature => write!(f, "the request signature is malformed"),
+            UnsupportedAlgorithm => write!(f, "the signing algorithm isn't supported"),
+            CredentialScopeMismatch => {
+                write!(f, "the credential scope doesn't match the request")
+            }
+            RequestTimeTooSkewed => write!(
+                f,
+                "the difference between the signing time and the current time is too large"
+            ),
+            RequestExpired => write!(f, "the presigned request has expired"),
+            ExpiresTooLong => write!(f, "the presigned request expiration is too long"),
+            UnknownAccessKey => write!(f, "the access key is unknown"),
+            PayloadHashMismatch => {
+                write!(f, "the payload hash doesn't match the request body")
+            }
+            SignatureMismatch => write!(
+                f,
+                "the request signature doesn't match the calculated signature"
+            ),
+        }
+    }
+}
+
+/// Error verifying the signature of a request
+#[derive(Debug)]
+pub struct VerificationError {
+    reason: RejectionReason,
+    source: Option<SigningError>,
+}
+
+impl VerificationError {
+    fn new(reason: RejectionReason) -> Self {
+        Self {
+            reason,
+            source: None,
+        }
+    }
+
+    /// The reason the request was rejected
+    pub fn reason(&self) -> RejectionReason {
+        self.reason
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "signature verification failed: {}", self.reason)
+    }
+}
+
+impl Error for VerificationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|err| err as _)
+    }
+}
+
+impl From<RejectionReason> for VerificationError {
+    fn from(reason: RejectionReason) -> Self {
+        Self::new(reason)
+    }
+}
+
+impl From<SigningError> for VerificationError {
+    fn from(source: SigningError) -> Self {
+        Self {
+            reason: RejectionReason::MalformedSignature,
+            source: Some(source),
+        }
+    }
+}
+
+/// The values sent along with a signature
+#[derive(Debug)]
+struct SignatureValues {
+    location: SignatureLocation,
+    version: SignatureVersion,
+    credential: Credential,
+    signed_headers: Vec<String>,
+    signature: String,
+    time: SystemTime,
+    expires_in: Option<Duration>,
+    session_token: Option<String>,
+    // Only SigV4a signatures have a region set
+    region_set: Option<String>,
+}
+
+/// The access key ID and scope of a signature
+#[derive(Debug)]
+struct Credential {
+    access_key_id: String,
+    date: String,
+    // Only SigV4 scopes have a region
+    region: Option<String>,
+    service: String,
+}
+
+/// Verifies the signature of a `request` signed with SigV4 or SigV4a, either in its headers or
+/// in the query params of a presigned request.
+///
+/// The canonical request is rebuilt the same way it is when signing, from the headers listed as
+/// signed and the URI without the signature query params, so the request must be given as it was
+/// received. The body of the request is only hashed if it's given as [`SignableBody::Bytes`] and
+/// the `x-amz-content-sha256` header wasn't signed; otherwise the signed header is checked
+/// against the body. A signed `UNSIGNED-PAYLOAD` or `STREAMING-*` header isn't a hash of the body,
+/// so it's used as the precomputed payload hash without checking the body.
+///
+/// Returns the credentials and signing details of the request if the signature is valid, or the
+/// [reason](RejectionReason) it was rejected.
+pub fn verify(
+    request: SignableRequest<'_>,
+    params: &VerificationParams<'_>,
+) -> Result<VerifiedRequest, VerificationError> {
+    tracing::trace!(request = ?request, "verifying request signature");
+    let token_name = params.settings.session_token_name_override;
+    let values = match find_header(&request, AUTHORIZATION) {
+        Some(authorization) => header_values(&request, authorization, token_name)?,
+        None => query_values(&request, token_name)?,
+    };
+
+    // The signer always signs the host, and the canonical request can't be built without it
+    // when the request URI has no authority
+    if !values.signed_headers.iter().any(|it| it == HOST) {
+        return Err(RejectionReason::MalformedSignature.into());
+    }
+    check_scope(&values, params)?;
+    check_time(&values, params)?;
+
+    let credentials = params
+        .credentials_lookup
+        .lookup(
+            &values.credential.access_key_id,
+            values.session_token.as_deref(),
+        )
+        .ok_or(RejectionReason::UnknownAccessKey)?;
+
+    let signs_header = |name: &str| values.signed_headers.iter().any(|it| it == name);
+    let body = match find_header(&request, header::X_AMZ_CONTENT_SHA_256) {
+        Some(content_sha256) if signs_header(header::X_AMZ_CONTENT_SHA_256) => {
+            if let (SignableBody::Bytes(data), false) =
+                (request.body(), is_unhashed_payload(content_sha256))
+            {
+                if !constant_time_eq(&v4::sha256_hex_string(data), content_sha256) {
+                    return Err(RejectionReason::PayloadHashMismatch.into());
+                }
+            }
+            SignableBody::Precomputed(content_sha256.into())
+        }
+        _ => request.body().clone(),
+    };
+
+    // The request is rebuilt from only the signed headers, because the signer signs every
+    // header it's given
+    let mut headers = Vec::with_capacity(values.signed_headers.len());
+    for name in &values.signed_headers {
+        let mut found = false;
+        for (key, value) in request.headers() {
+            if key.eq_ignore_ascii_case(name) {
+                headers.push((*key, *value));
+                found = true;
+            }
+        }
+        if !found && (name != HOST || request.uri().authority().is_none()) {
+            tracing::debug!(header = %name, "a signed header is missing from the request");
+            return Err(RejectionReason::SignatureMismatch.into());
+        }
+    }
+    let uri = match values.location {
+        SignatureLocation::Headers => Cow::Owned(request.uri().to_string()),
+        SignatureLocation::QueryParams => Cow::Owned(uri_without_signature(&request, token_name)),
+    };
+    let signable = SignableRequest::new(request.method(), uri, headers.into_iter(), body)?;
+
+    let mut settings = params.settings.clone();
+    settings.signature_location = values.location;
+    settings.expires_in = values.expires_in;
+    settings.excluded_headers = None;
+    settings.payload_checksum_kind = if values.location == SignatureLocation::Headers
+        && signs_header(header::X_AMZ_CONTENT_SHA_256)
+    {
+        PayloadChecksumKind::XAmzSha256
+    } else {
+        PayloadChecksumKind::NoHeader
+    };
+    if values.location == SignatureLocation::Headers {
+        let token_header = token_name.unwrap_or(header::X_AMZ_SECURITY_TOKEN);
+        settings.session_token_mode = if signs_header(token_header) {
+            SessionTokenMode::Include
+        } else {
+            SessionTokenMode::Exclude
+        };
+    }
+
+    let identity: Identity = Credentials::new(
+        values.credential.access_key_id.as_str(),
+        credentials.secret_access_key(),
+        values.session_token.as_deref().map(str::to_owned),
+        None,
+        "SigV4 verification",
+    )
+    .into();
+    let valid = match values.version {
+        SignatureVersion::V4 => {
+            let region = values.credential.region.as_deref().unwrap_or(params.region);
+            let signing_params: SigningParams<'_> = v4::SigningParams::builder()
+                .identity(&identity)
+                .region(region)
+                .name(&values.credential.service)
+                .time(values.time)
+                .settings(settings)
+                .build()
+                .expect("all required fields set")
+                .into();
+            let creq =
+                CanonicalRequest::from(&signable, &signing_params).map_err(SigningError::from)?;
+            let encoded_creq = v4::sha256_hex_string(creq.to_string().as_bytes());
+            let string_to_sign = StringToSign::new_v4(
+                values.time,
+                region,
+                &values.credential.service,
+                &encoded_creq,
+            )
+            .to_string();
+            tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "calculated canonical request");
+            let signing_key = v4::generate_signing_key(
+                credentials.secret_access_key(),
+                values.time,
+                region,
+                &values.credential.service,
+            );
+            let signature = v4::calculate_signature(signing_key, string_to_sign.as_bytes());
+            constant_time_eq(&signature, &values.signature)
+        }
+        #[cfg(feature = "sigv4a")]
+        SignatureVersion::V4a => {
+            let region_set = values
+                .region_set
+                .as_deref()
+                .ok_or(RejectionReason::MalformedSignature)?;
+            let signing_params: SigningParams<'_> = v4a::SigningParams::builder()
+                .identity(&identity)
+                .region_set(region_set)
+                .name(&values.credential.service)
+                .time(values.time)
+                .settings(settings)
+                .build()
+                .expect("all required fields set")
+                .into();
+            let creq =
+                CanonicalRequest::from(&signable, &signing_params).map_err(SigningError::from)?;
+            let encoded_creq = v4::sha256_hex_string(creq.to_string().as_bytes());
+            let string_to_sign = StringToSign::new_v4a(
+                values.time,
+                region_set,
+                &values.credential.service,
+                &encoded_creq,
+            )
+            .to_string();
+            tracing::trace!(canonical_request = %creq, string_to_sign = %string_to_sign, "calculated canonical request");
+            let signing_key = v4a::generate_signing_key(
+                credentials.access_key_id(),
+                credentials.secret_access_key(),
+            );
+            v4a::verify_signature(signing_key, string_to_sign.as_bytes(), &values.signature)
+        }
+        #[allow(unreachable_patterns)]
+        _ => return Err(RejectionReason::UnsupportedAlgorithm.into()),
+    };
+    if !valid {
+        return Err(RejectionReason::SignatureMismatch.into());
+    }
+
+    Ok(VerifiedRequest {
+        credentials,
+        signature_version: values.version,
+        signature_location: values.location,
+        signing_time: values.time,
+    })
+}
+
+fn find_header<'a>(request: &'a SignableRequest<'_>, name: &str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+}
+
+/// Whether `content_sha256` stands in for the payload hash of an unsigned or streaming payload,
+/// rather than being the hash of the body
+fn is_unhashed_payload(content_sha256: &str) -> bool {
+    content_sha256 == UNSIGNED_PAYLOAD || content_sha256.starts_with("STREAMING-")
+}
+
+fn signature_version(algorithm: &str) -> Result<SignatureVersion, RejectionReason> {
+    match algorithm {
+        HMAC_256 => Ok(SignatureVersion::V4),
+        #[cfg(feature = "sigv4a")]
+        v4a::ECDSA_256 => Ok(SignatureVersion::V4a),
+        _ => Err(RejectionReason::UnsupportedAlgorithm),
+    }
+}
+
+impl Credential {
+    fn parse(credential: &str, version: SignatureVersion) -> Result<Self, RejectionReason> {
+        let parts = credential.split('/').collect::<Vec<_>>();
+        let (access_key_id, date, region, service) = match (version, parts.as_slice()) {
+            (SignatureVersion::V4, [access_key_id, date, region, service, "aws4_request"]) => {
+                (access_key_id, date, Some(region), service)
+            }
+            (SignatureVersion::V4a, [access_key_id, date, service, "aws4_request"]) => {
+                (access_key_id, date, None, service)
+            }
+            _ => return Err(RejectionReason::MalformedSignature),
+        };
+        Ok(Self {
+            access_key_id: access_key_id.to_string(),
+            date: date.to_string(),
+            region: region.map(|region| region.to_string()),
+            service: service.to_string(),
+        })
+    }
+}
+
+fn header_values(
+    request: &SignableRequest<'_>,
+    authorization: &str,
+    token_name: Option<&'static str>,
+) -> Result<SignatureValues, RejectionReason> {
+    let (algorithm, components) = authorization
+        .split_once(' ')
+        .ok_or(RejectionReason::MalformedSignature)?;
+    let version = signature_version(algorithm)?;
+    let (mut credential, mut signed_headers, mut signature) = (None, None, None);
+    for component in components.split(',') {
+        match component.trim().split_once('=') {
+            Some(("Credential", value)) => credential = Some(value),
+            Some(("SignedHeaders", value)) => signed_headers = Some(value),
+            Some(("Signature", value)) => signature = Some(value),
+            _ => return Err(RejectionReason::MalformedSignature),
+        }
+    }
+    let (Some(credential), Some(signed_headers), Some(signature)) =
+        (credential, signed_headers, signature)
+    else {
+        return Err(RejectionReason::MalformedSignature);
+    };
+    let credential = Credential::parse(credential, version)?;
+    let time = find_header(request, header::X_AMZ_DATE)
+        .and_then(parse_signing_date_time)
+        .ok_or(RejectionReason::MalformedSignature)?;
+    let signed_headers = signed_headers
+        .split(';')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if !signed_headers.iter().any(|it| it == header::X_AMZ_DATE) {
+        return Err(RejectionReason::MalformedSignature);
+    }
+    Ok(SignatureValues {
+        location: SignatureLocation::Headers,
+        version,
+        credential,
+        signed_headers,
+        signature: signature.to_string(),
+        time,
+        expires_in: None,
+        session_token: find_header(request, token_name.unwrap_or(header::X_AMZ_SECURITY_TOKEN))
+            .map(str::to_string),
+        region_set: region_set_header(request).map(str::to_string),
+    })
+}
+
+#[cfg(feature = "sigv4a")]
+fn region_set_header<'a>(request: &'a SignableRequest<'_>) -> Option<&'a str> {
+    use crate::http_request::canonical_request::sigv4a;
+    find_header(request, sigv4a::header::X_AMZ_REGION_SET)
+}
+
+#[cfg(not(feature = "sigv4a"))]
+fn region_set_header<'a>(_request: &'a SignableRequest<'_>) -> Option<&'a str> {
+    None
+}
+
+fn query_values(
+    request: &SignableRequest<'_>,
+    token_name: Option<&'static str>,
+) -> Result<SignatureValues, RejectionReason> {
+    let query = request.uri().query().unwrap_or_default();
+    let find = |name: &str| {
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    let signature = find(param::X_AMZ_SIGNATURE).ok_or(RejectionReason::MissingSignature)?;
+    let algorithm = find(param::X_AMZ_ALGORITHM).ok_or(RejectionReason::MalformedSignature)?;
+    let version = signature_version(&algorithm)?;
+    let credential = find(param::X_AMZ_CREDENTIAL).ok_or(RejectionReason::MalformedSignature)?;
+    let credential = Credential::parse(&credential, version)?;
+    let time = find(param::X_AMZ_DATE)
+        .and_then(|date| parse_signing_date_time(&date))
+        .ok_or(RejectionReason::MalformedSignature)?;
+    let expires_in = find(param::X_AMZ_EXPIRES)
+        .and_then(|expires| expires.parse().ok())
+        .map(Duration::from_secs)
+        .ok_or(RejectionReason::MalformedSignature)?;
+    let signed_headers = find(param::X_AMZ_SIGNED_HEADERS)
+        .ok_or(RejectionReason::MalformedSignature)?
+        .split(';')
+        .map(str::to_string)
+        .collect();
+    let session_token = find(token_name.unwrap_or(param::X_AMZ_SECURITY_TOKEN));
+    #[cfg(feature = "sigv4a")]
+    let region_set = find(crate::http_request::canonical_request::sigv4a::param::X_AMZ_REGION_SET);
+    #[cfg(not(feature = "sigv4a"))]
+    let region_set = None;
+    Ok(SignatureValues {
+        location: SignatureLocation::QueryParams,
+        version,
+        credential,
+        signed_headers,
+        signature,
+        time,
+        expires_in: Some(expires_in),
+        session_token,
+        region_set,
+    })
+}
+
+/// Returns the URI of a presigned request without the query params added by the signer
+fn uri_without_signature(
+    request: &SignableRequest<'_>,
+    token_name: Option<&'static str>,
+) -> String {
+    let uri = request.uri();
+    let signing_params = [
+        param::X_AMZ_ALGORITHM,
+        param::X_AMZ_CREDENTIAL,
+        param::X_AMZ_DATE,
+        param::X_AMZ_EXPIRES,
+        param::X_AMZ_SIGNED_HEADERS,
+        param::X_AMZ_SIGNATURE,
+        token_name.unwrap_or(param::X_AMZ_SECURITY_TOKEN),
+        #[cfg(feature = "sigv4a")]
+        crate::http_request::canonical_request::sigv4a::param::X_AMZ_REGION_SET,
+    ];
+    let query = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| {
+            let (key, _) = form_urlencoded::parse(pair.as_bytes())
+                .next()
+                .unwrap_or_default();
+            !pair.is_empty() && !signing_params.contains(&key.as_ref())
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    let mut result = String::new();
+    if let (Some(scheme), Some(authority)) = (uri.scheme_str(), uri.authority()) {
+        result.push_str(scheme);
+        result.push_str("://");
+        result.push_str(authority.as_str());
+    }
+    result.push_str(uri.path());
+    if !query.is_empty() {
+        result.push('?');
+        result.push_str(&query);
+    }
+    result
+}
+
+fn check_scope(
+    values: &SignatureValues,
+    params: &VerificationParams<'_>,
+) -> Result<(), RejectionReason> {
+    let region_matches = match (&values.credential.region, &values.region_set) {
+        (Some(region), _) => region == params.region,
+        (None, Some(region_set)) => region_set.split(',').any(|pattern| {
+            let pattern = pattern.trim();
+            match pattern.strip_suffix('*') {
+                Some(prefix) => params.region.starts_with(prefix),
+                None => pattern == params.region,
+            }
+        }),
+        (None, None) => false,
+    };
+    if values.credential.date != format_date(values.time)
+        || values.credential.service != params.name
+        || !region_matches
+    {
+        return Err(RejectionReason::CredentialScopeMismatch);
+    }
+    Ok(())
+}
+
+fn check_time(
+    values: &SignatureValues,
+    params: &VerificationParams<'_>,
+) -> Result<(), RejectionReason> {
+    // The signing time may be ahead of the current time by the allowed clock skew
+    if values.time > params.time + params.max_clock_skew {
+        return Err(RejectionReason::RequestTimeTooSkewed);
+    }
+    match values.expires_in {
+        None => {
+            if values.time + params.max_clock_skew < params.time {
+                return Err(RejectionReason::RequestTimeTooSkewed);
+            }
+        }
+        Some(expires_in) => {
+            if expires_in > params.max_expires_in {
+                return Err(RejectionReason::ExpiresTooLong);
+            }
+            if values.time + expires_in < params.time {
+                return Err(RejectionReason::RequestExpired);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compares two strings in time that only depends on their length, so that a signature can't
+/// be guessed from how long the comparison takes
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify, RejectionReason, VerificationParams};
+    use crate::date_time::test_parsers::parse_date_time;
+    use crate::http_request::{
+        sign, PayloadChecksumKind, SignableBody, SignableRequest, SignatureLocation, SigningParams,
+        SigningSettings,
+    };
+    use crate::sign::v4;
+    use aws_credential_types::Credentials;
+    use aws_smithy_runtime_api::client::identity::Identity;
+    use std::time::{Duration, SystemTime};
+
+    const SIGNING_TIME: &str = "20150830T123600Z";
+
+    fn credentials() -> Credentials {
+        Credentials::for_tests_with_session_token()
+    }
+
+    fn signing_time() -> SystemTime {
+        parse_date_time(SIGNING_TIME).unwrap()
+    }
+
+    fn signed_request(settings: SigningSettings, body: &[u8]) -> http::Request<&[u8]> {
+        signed_request_with_payload(settings, body, SignableBody::Bytes(body))
+    }
+
+    fn signed_request_with_payload<'a>(
+        settings: SigningSettings,
+        body: &'a [u8],
+        payload: SignableBody<'_>,
+    ) -> http::Request<&'a [u8]> {
+        let mut request = http::Request::builder()
+            .method("PUT")
+            .uri("https://example.amazonaws.com/some/path?b=2&a=1")
+            .header("host", "example.amazonaws.com")
+            .header("content-type", "text/plain")
+            .body(body)
+            .unwrap();
+        let identity: Identity = credentials().into();
+        let params: SigningParams<'_> = v4::SigningParams::builder()
+            .identity(&identity)
+            .region("us-east-1")
+            .name("service")
+            .time(signing_time())
+            .settings(settings)
+            .build()
+            .unwrap()
+            .into();
+        let signable = SignableRequest::new(
+            request.method().as_str(),
+            request.uri().to_string(),
+            request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.to_str().unwrap())),
+            payload,
+        )
+        .unwrap();
+        let (instructions, _) = sign(signable, &params).unwrap().into_parts();
+        instructions.apply_to_request_http1x(&mut request);
+        request
+    }
+
+    fn verify_request(
+        request: &http::Request<&[u8]>,
+        time: SystemTime,
+    ) -> Result<super::VerifiedRequest, super::VerificationError> {
+        let uri = request.uri().to_string();
+        let signable = SignableRequest::new(
+            request.method().as_str(),
+            uri,
+            request
+                .headers()
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.to_str().unwrap())),
+            SignableBody::Bytes(request.body()),
+        )
+        .unwrap();
+        let credentials = credentials();
+        let params = VerificationParams::builder()
+            .credentials_lookup(&credentials)
+            .region("us-east-1")
+            .name("service")
+            .time(time)
+            .build()
+            .unwrap();
+        verify(signable, &params)
+    }
+
+    fn rejection(
+        result: Result<super::VerifiedRequest, super::VerificationError>,
+    ) -> RejectionReason {
+        result.expect_err("verification should fail").reason()
+    }
+
+    #[test]
+    fn verify_header_signature() {
+        let settings = SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        let request = signed_request(settings, b"hello");
+
+        let verified = verify_request(&request, signing_time() + Duration::from_secs(60))
+            .expect("signature is valid");
+        assert_eq!("ANOTREAL", verified.access_key_id());
+        assert_eq!(SignatureLocation::Headers, verified.signature_location());
+        assert_eq!(signing_time(), verified.signing_time());
+
+        let mut tampered = request.clone();
+        tampered
+            .headers_mut()
+            .insert("content-type", "application/json".parse().unwrap());
+        assert_eq!(
+            RejectionReason::SignatureMismatch,
+            rejection(verify_request(&tampered, signing_time()))
+        );
+
+        let mut tampered = request.clone();
+        *tampered.body_mut() = b"goodbye";
+        assert_eq!(
+            RejectionReason::PayloadHashMismatch,
+            rejection(verify_request(&tampered, signing_time()))
+        );
+
+        assert_eq!(
+            RejectionReason::RequestTimeTooSkewed,
+            rejection(verify_request(
+                &request,
+                signing_time() + Duration::from_secs(301)
+            ))
+        );
+    }
+
+    #[test]
+    fn verify_unsigned_and_streaming_payloads() {
+        let settings = || SigningSettings {
+            payload_checksum_kind: PayloadChecksumKind::XAmzSha256,
+            ..Default::default()
+        };
+        for payload in [
+            SignableBody::UnsignedPayload,
+            SignableBody::Precomputed("STREAMING-AWS4-HMAC-SHA256-PAYLOAD".into()),
+            SignableBody::StreamingUnsignedPayloadTrailer,
+        ] {
+            let request = signed_request_with_payload(settings(), b"hello", payload);
+            // The body is given as bytes, but isn't what the header stands for
+            verify_request(&request, signing_time()).expect("signature is valid");
+
+            let mut tampered = request.clone();
+            tampered.headers_mut().insert(
+                "x-amz-content-sha256",
+                v4::sha256_hex_string(b"hello").parse().unwrap(),
+            );
+            assert_eq!(
+                RejectionReason::SignatureMismatch,
+                rejection(verify_request(&tampered, signing_time()))
+            );
+        }
+    }
+
+    #[test]
+    fn verify_presigned_request() {
+        let settings = SigningSettings {
+            signature_location: SignatureLocation::QueryParams,
+            expires_in: Some(Duration::from_secs(900)),
+            ..Default::default()
+        };
+        let request = signed_request(settings, b"");
+
+        let verified = verify_request(&request, signing_time() + Duration::from_secs(900))
+            .expect("signature is valid");
+        assert_eq!(
+            SignatureLocation::QueryParams,
+            verified.signature_location()
+        );
+
+        assert_eq!(
+            RejectionReason::RequestExpired,
+            rejection(verify_request(
+                &request,
+                signing_time() + Duration::from_secs(901)
+            ))
+        );
+
+        let mut tampered = request.clone();
+        *tampered.uri_mut() = request
+            .uri()
+            .to_string()
+            .replace("a=1", "a=3")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            RejectionReason::SignatureMismatch,
+            rejection(verify_request(&tampered, signing_time()))
+        );
+    }
+
+    #[test]
+    fn verify_origin_form_request() {
+        let request = signed_request(SigningSettings::default(), b"");
+        // Servers receive the request target without the scheme and authority
+        let mut origin_form = request.clone();
+        *origin_form.uri_mut() = "/some/path?b=2&a=1".parse().unwrap();
+        verify_request(&origin_form, signing_time()).expect("signature is valid");
+
+        let mut without_host = origin_form.clone();
+        without_host.headers_mut().remove("host");
+        assert_eq!(
+            RejectionReason::SignatureMismatch,
+            rejection(verify_request(&without_host, signing_time()))
+        );
+
+        let mut host_not_signed = origin_form.clone();
+        let authorization = host_not_signed.headers()["authorization"]
+            .to_str()
+            .unwrap()
+            .replace("host;", "");
+        host_not_signed
+            .headers_mut()
+            .insert("authorization", authorization.parse().unwrap());
+        assert_eq!(
+            RejectionReason::MalformedSignature,
+            rejection(verify_request(&host_not_signed, signing_time()))
+        );
+    }
+
+    #[test]
+    fn reject_unknown_access_keys_and_scopes() {
+        let request = signed_request(SigningSettings::default(), b"");
+        let signable = || {
+            SignableRequest::new(
+                request.method().as_str(),
+                request.uri().to_string(),
+                request
+                    .headers()
+                    .iter()
+                    .map(|(k, v)| (k.as_str(), v.to_str().unwrap())),
+                SignableBody::empty(),
+            )
+            .unwrap()
+        };
+
+        let other_credentials = Credentials::new("AKIDOTHER", "secret", None, None, "test");
+        let params = VerificationParams::builder()
+            .credentials_lookup(&other_credentials)
+            .region("us-east-1")
+            .name("service")
+            .time(signing_time())
+            .build()
+            .unwrap();
+        assert_eq!(
+            RejectionReason::UnknownAccessKey,
+            rejection(verify(signable(), &params))
+        );
+
+        let credentials = credentials();
+        let params = VerificationParams::builder()
+            .credentials_lookup(&credentials)
+            .region("us-west-2")
+            .name("service")
+            .time(signing_time())
+            .build()
+            .unwrap();
+        assert_eq!(
+            RejectionReason::CredentialScopeMismatch,
+            rejection(verify(signable(), &params))
+        );
+
+        let unsigned = SignableRequest::new(
+            "GET",
+            "https://example.amazonaws.com/",
+            std::iter::empty(),
+            SignableBody::empty(),
+        )
+        .unwrap();
+        assert_eq!(
+            RejectionReason::MissingSignature,
+            rejection(verify(unsigned, &params))
+        );
+    }
+
+    #[cfg(feature = "sigv4a")]
+    #[test]
+    fn verify_sigv4a_header_signature() {
+        use crate::sign::v4a;
+
+        let identity: Identity = credentials().into();
+        let params: SigningParams<'_> = v4a::SigningParams::builder()
+            .identity(&identity)
+            .region_set("us-*")
+            .name("service")
+            .time(signing_time())
+            .settings(SigningSettings::default())
+            .build()
+            .unwrap()
+            .into();
+        let mut request = http::Request::builder()
+            .method("GET")
+            .uri("https://example.amazonaws.com/")
+            .body(&b""[..])
+            .unwrap();
+        let signable = SignableRequest::new(
+            "GET",
+            "https://example.amazonaws.com/",
+            std::iter::empty(),
+            SignableBody::empty(),
+        )
+        .unwrap();
+        let (instructions, _) = sign(signable, &params).unwrap().into_parts();
+        instructions.apply_to_request_http1x(&mut request);
+
+        let verified = verify_request(&request, signing_time()).expect("signature is valid");
+        assert_eq!(crate::SignatureVersion::V4a, verified.signature_version());
+
+        request
+            .headers_mut()
+            .insert("x-amz-region-set", "eu-*".parse().unwrap());
+        assert_eq!(
+            RejectionReason::CredentialScopeMismatch,
+            rejection(verify_request(&request, signing_time()))
+        );
+    }
+}
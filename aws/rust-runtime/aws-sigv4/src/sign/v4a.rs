@@ -6,7 +6,7 @@
 use aws_smithy_runtime_api::client::identity::Identity;
 use bytes::{BufMut, BytesMut};
 use crypto_bigint::{CheckedAdd, CheckedSub, Encoding, U256};
-use p256::ecdsa::signature::Signer;
+use p256::ecdsa::signature::{Signer, Verifier};
 use p256::ecdsa::{Signature, SigningKey};
 use std::io::Write;
 use std::sync::LazyLock;
@@ -35,6 +35,27 @@ pub fn calculate_signature(signing_key: impl AsRef<[u8]>, string_to_sign: &[u8])
     hex::encode(signature.as_ref())
 }
 
+/// Verifies a hex encoded Sigv4a signature of `string_to_sign` made with `signing_key`
+pub fn verify_signature(
+    signing_key: impl AsRef<[u8]>,
+    string_to_sign: &[u8],
+    signature: &str,
+) -> bool {
+    let (Ok(signing_key), Ok(signature)) = (
+        SigningKey::from_bytes(signing_key.as_ref()),
+        hex::decode(signature),
+    ) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_der(&signature) else {
+        return false;
+    };
+    signing_key
+        .verifying_key()
+        .verify(string_to_sign, &signature)
+        .is_ok()
+}
+
 /// Generates a signing key for Sigv4a signing.
 pub fn generate_signing_key(access_key: &str, secret_access_key: &str) -> impl AsRef<[u8]> {
     // Capacity is the secret access key length plus the length of "AWS4A"
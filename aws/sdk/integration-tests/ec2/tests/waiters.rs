@@ -9,9 +9,13 @@ use aws_smithy_async::test_util::tick_advance_sleep::{
     tick_advance_time_and_sleep, TickAdvanceTime,
 };
 use aws_smithy_http_client::test_util::dvr::ReplayingClient;
+use aws_smithy_runtime::client::waiters::{
+    AcceptorState, WaiterCancellationToken, WaiterOptions, WaiterPoll,
+};
 use aws_smithy_runtime::test_util::capture_test_logs::show_test_logs;
 use aws_smithy_runtime_api::client::waiters::error::WaiterError;
 use aws_smithy_types::retry::RetryConfig;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 async fn prerequisites() -> (Client, ReplayingClient, TickAdvanceTime) {
@@ -123,3 +127,63 @@ async fn should_emit_business_metric_for_waiter_in_user_agent() {
         .unwrap();
     assert_ua_contains_metric_values(user_agent_in_last_request, &["B"]);
 }
+
+#[tokio::test]
+async fn waiters_stream_each_poll() {
+    let _logs = show_test_logs();
+
+    let (ec2, _, time_source) = prerequisites().await;
+
+    ec2.start_instances()
+        .instance_ids("i-09fb4224219ac6902")
+        .send()
+        .await
+        .unwrap();
+
+    let polled_states = Arc::new(Mutex::new(Vec::new()));
+    let options = WaiterOptions::new().on_poll({
+        let polled_states = polled_states.clone();
+        move |poll: &WaiterPoll<_, _>| polled_states.lock().unwrap().push(poll.acceptor_state())
+    });
+    let stream = ec2
+        .wait_until_instance_status_ok()
+        .instance_ids("i-09fb4224219ac6902")
+        .wait_stream(Duration::from_secs(300), options)
+        .unwrap();
+    let waiter_task = tokio::spawn(stream.collect::<Vec<_>>());
+
+    time_source.tick(Duration::from_secs(305)).await;
+    let states = waiter_task
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|poll| poll.expect("no errors").acceptor_state())
+        .collect::<Vec<_>>();
+    assert_eq!(Some(&AcceptorState::Success), states.last());
+    assert!(states.len() > 1);
+    assert_eq!(states, *polled_states.lock().unwrap());
+}
+
+#[tokio::test]
+async fn waiters_can_be_canceled() {
+    let _logs = show_test_logs();
+
+    let (ec2, _, _) = prerequisites().await;
+
+    let token = WaiterCancellationToken::new();
+    token.cancel();
+    let err = ec2
+        .wait_until_instance_status_ok()
+        .instance_ids("i-09fb4224219ac6902")
+        .wait_with(
+            Duration::from_secs(300),
+            WaiterOptions::new().cancellation_token(token),
+        )
+        .await
+        .err()
+        .expect("should be canceled");
+    match err {
+        WaiterError::Canceled(context) => assert_eq!(0, context.poll_count()),
+        err => panic!("unexpected error: {}", DisplayErrorContext(&err)),
+    }
+}
@@ -75,6 +75,7 @@ object CrateSet {
             "aws-smithy-mocks-experimental",
             "aws-smithy-observability",
             "aws-smithy-observability-otel",
+            "aws-smithy-observability-prometheus",
             "aws-smithy-protocol-test",
             "aws-smithy-query",
             "aws-smithy-runtime",
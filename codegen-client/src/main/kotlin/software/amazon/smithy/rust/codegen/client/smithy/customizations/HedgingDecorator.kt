@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.IdempotentTrait
+import software.amazon.smithy.model.traits.ReadonlyTrait
+import software.amazon.smithy.rust.codegen.client.smithy.ClientCodegenContext
+import software.amazon.smithy.rust.codegen.client.smithy.ClientRustModule
+import software.amazon.smithy.rust.codegen.client.smithy.customize.ClientCodegenDecorator
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ConfigCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.generators.config.ServiceConfig
+import software.amazon.smithy.rust.codegen.core.rustlang.Writable
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
+import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
+
+/**
+ * Allows requests to `@idempotent` and `@readonly` operations to be hedged.
+ *
+ * Those operations are marked with `IdempotentOperation`, and the service config gets a `hedging_config` setting that
+ * enables hedging for them.
+ */
+class HedgingDecorator : ClientCodegenDecorator {
+    override val name: String get() = "HedgingDecorator"
+    override val order: Byte get() = 0
+
+    override fun configCustomizations(
+        codegenContext: ClientCodegenContext,
+        baseCustomizations: List<ConfigCustomization>,
+    ): List<ConfigCustomization> = baseCustomizations + HedgingConfigCustomization(codegenContext)
+
+    override fun operationCustomizations(
+        codegenContext: ClientCodegenContext,
+        operation: OperationShape,
+        baseCustomizations: List<OperationCustomization>,
+    ): List<OperationCustomization> = baseCustomizations + IdempotentOperationCustomization(codegenContext, operation)
+
+    override fun extras(
+        codegenContext: ClientCodegenContext,
+        rustCrate: RustCrate,
+    ) {
+        rustCrate.withModule(ClientRustModule.Config.retry) {
+            rustTemplate(
+                "pub use #{hedging}::{HedgingConfig, HedgingConfigBuilder, HedgingConfigBuilderError};",
+                "hedging" to RuntimeType.smithyRuntime(codegenContext.runtimeConfig).resolve("client::retries::hedging"),
+            )
+        }
+    }
+}
+
+private class HedgingConfigCustomization(codegenContext: ClientCodegenContext) : ConfigCustomization() {
+    private val moduleUseName = codegenContext.moduleUseName()
+    private val codegenScope =
+        arrayOf(
+            *preludeScope,
+            "HedgingConfig" to
+                RuntimeType.smithyRuntime(codegenContext.runtimeConfig)
+                    .resolve("client::retries::hedging::HedgingConfig"),
+        )
+
+    override fun section(section: ServiceConfig) =
+        writable {
+            when (section) {
+                is ServiceConfig.ConfigImpl -> {
+                    rustTemplate(
+                        """
+                        /// Return a reference to the hedging configuration contained in this config, if any.
+                        pub fn hedging_config(&self) -> #{Option}<&#{HedgingConfig}> {
+                            self.config.load::<#{HedgingConfig}>()
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is ServiceConfig.BuilderImpl -> {
+                    rustTemplate(
+                        """
+                        /// Enables hedging requests to idempotent operations with the given configuration.
+                        ///
+                        /// When a request to an operation modeled as `@idempotent` or `@readonly` doesn't get a
+                        /// response within a percentile of recently observed latencies, a second copy of it is sent,
+                        /// and whichever response succeeds first is used. Hedges spend retry quota, so hedging backs
+                        /// off when requests are failing.
+                        ///
+                        /// ## Examples
+                        /// ```no_run
+                        /// use $moduleUseName::config::Config;
+                        /// use $moduleUseName::config::retry::HedgingConfig;
+                        ///
+                        /// let hedging_config = HedgingConfig::builder().percentile(99.0).build().unwrap();
+                        /// let config = Config::builder().hedging_config(hedging_config).build();
+                        /// ```
+                        pub fn hedging_config(mut self, hedging_config: #{HedgingConfig}) -> Self {
+                            self.set_hedging_config(#{Some}(hedging_config));
+                            self
+                        }
+
+                        /// Like [`Self::hedging_config`], but takes a mutable reference to the builder and an optional `HedgingConfig`
+                        pub fn set_hedging_config(&mut self, hedging_config: #{Option}<#{HedgingConfig}>) -> &mut Self {
+                            self.config.store_or_unset(hedging_config);
+                            self
+                        }
+                        """,
+                        *codegenScope,
+                    )
+                }
+
+                is ServiceConfig.BuilderFromConfigBag -> {
+                    rustTemplate(
+                        "${section.builder}.set_hedging_config(${section.configBag}.load::<#{HedgingConfig}>().cloned());",
+                        *codegenScope,
+                    )
+                }
+
+                else -> emptySection
+            }
+        }
+}
+
+private class IdempotentOperationCustomization(
+    private val codegenContext: ClientCodegenContext,
+    private val operation: OperationShape,
+) : OperationCustomization() {
+    private val isIdempotent = operation.hasTrait<IdempotentTrait>() || operation.hasTrait<ReadonlyTrait>()
+
+    override fun section(section: OperationSection): Writable =
+        writable {
+            if (section is OperationSection.AdditionalRuntimePluginConfig && isIdempotent) {
+                rustTemplate(
+                    """
+                    ${section.newLayerName}.store_put(#{IdempotentOperation});
+                    """,
+                    "IdempotentOperation" to
+                        RuntimeType.smithyRuntime(codegenContext.runtimeConfig)
+                            .resolve("client::retries::hedging::IdempotentOperation"),
+                )
+            }
+        }
+}
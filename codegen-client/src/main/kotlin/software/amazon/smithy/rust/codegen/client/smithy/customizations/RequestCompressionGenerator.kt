@@ -9,18 +9,20 @@ import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.model.traits.RequestCompressionTrait
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.generators.OperationSection
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.util.dq
 import software.amazon.smithy.rust.codegen.core.util.getTrait
 import java.util.logging.Logger
 
-// Currently, gzip is the only supported encoding.
-fun isSupportedEncoding(encoding: String): Boolean = encoding == "gzip"
+// Maps each supported encoding to the `aws-smithy-compression` feature it requires, if any.
+private val supportedEncodings = mapOf("gzip" to null, "zstd" to "zstd", "br" to "brotli")
 
-fun firstSupportedEncoding(encodings: List<String>): String? = encodings.firstOrNull { isSupportedEncoding(it) }
+fun isSupportedEncoding(encoding: String): Boolean = supportedEncodings.containsKey(encoding)
 
 // This generator was implemented based on this spec:
 // https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait
@@ -35,10 +37,11 @@ class RequestCompressionGenerator(
                 logger.warning { "No encodings were specified for the requestCompressionTrait on ${operationShape.id}" }
                 return emptySection
             }
-            val encoding = firstSupportedEncoding(requestCompressionTrait.encodings) ?: return emptySection
-            // We can remove this once we start supporting other algorithms.
-            // Until then, we shouldn't see anything else coming up here.
-            assert(encoding == "gzip") { "Only gzip is supported but encoding was `$encoding`" }
+            // The runtime picks the first of these encodings, preserving the model's order of preference.
+            val encodings = requestCompressionTrait.encodings.filter { isSupportedEncoding(it) }
+            if (encodings.isEmpty()) {
+                return emptySection
+            }
             val runtimeConfig = codegenContext.runtimeConfig
             val compression = RuntimeType.clientRequestCompression(runtimeConfig)
 
@@ -46,7 +49,13 @@ class RequestCompressionGenerator(
                 when (section) {
                     is OperationSection.AdditionalRuntimePlugins ->
                         section.addOperationRuntimePlugin(this) {
-                            rust("#T::new()", compression.resolve("RequestCompressionRuntimePlugin"))
+                            encodings.mapNotNull { supportedEncodings[it] }.forEach { feature ->
+                                addDependency(CargoDependency.smithyCompression(runtimeConfig).withFeature(feature))
+                            }
+                            rust(
+                                "#T::new(&[${encodings.joinToString { it.dq() }}])",
+                                compression.resolve("RequestCompressionRuntimePlugin"),
+                            )
                         }
 
                     else -> {}
@@ -183,6 +183,34 @@ class RetryClassifierServiceRuntimePluginCustomization(codegenContext: ClientCod
         }
 }
 
+/**
+ * Registers `RetryAfterClassifier`, which honors the `Retry-After` header of throttled and unavailable responses.
+ *
+ * This is only registered for generic clients. The AWS SDK removes it and keeps retrying according to its own
+ * error classification.
+ */
+class RetryAfterClassifierServiceRuntimePluginCustomization(codegenContext: ClientCodegenContext) :
+    ServiceRuntimePluginCustomization() {
+    private val runtimeConfig = codegenContext.runtimeConfig
+    private val retries = RuntimeType.smithyRuntime(runtimeConfig).resolve("client::retries")
+
+    override fun section(section: ServiceRuntimePluginSection): Writable =
+        writable {
+            when (section) {
+                is ServiceRuntimePluginSection.RegisterRuntimeComponents -> {
+                    section.registerRetryClassifier(this) {
+                        rustTemplate(
+                            "#{RetryAfterClassifier}::new()",
+                            "RetryAfterClassifier" to retries.resolve("classifiers::RetryAfterClassifier"),
+                        )
+                    }
+                }
+
+                else -> emptySection
+            }
+        }
+}
+
 class RetryClassifierOperationCustomization(
     codegenContext: ClientCodegenContext,
     val operation: OperationShape,
@@ -19,6 +19,7 @@ import software.amazon.smithy.rust.codegen.client.smithy.customizations.Resilien
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.ResiliencyReExportCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierConfigCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierOperationCustomization
+import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryAfterClassifierServiceRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryClassifierServiceRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.RetryModeFeatureTrackerRuntimePluginCustomization
 import software.amazon.smithy.rust.codegen.client.smithy.customizations.TimeSourceCustomization
@@ -137,6 +138,7 @@ class RequiredCustomizations : ClientCodegenDecorator {
         baseCustomizations +
             ConnectionPoisoningRuntimePluginCustomization(codegenContext) +
             RetryClassifierServiceRuntimePluginCustomization(codegenContext) +
+            RetryAfterClassifierServiceRuntimePluginCustomization(codegenContext) +
             RetryModeFeatureTrackerRuntimePluginCustomization(codegenContext) +
             AuthEndpointOrchestrationV2MarkerCustomization(codegenContext)
 }
@@ -169,7 +169,10 @@ private class WaiterFluentBuilderConfig(
             "WaiterError" to
                 RuntimeType.smithyRuntimeApiClient(runtimeConfig)
                     .resolve("client::waiters::error::WaiterError"),
+            "WaiterOptions" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::waiters::WaiterOptions"),
             "WaiterOrchestrator" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::waiters::WaiterOrchestrator"),
+            "WaiterPoll" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::waiters::WaiterPoll"),
+            "FnStream" to RuntimeType.smithyAsync(runtimeConfig).resolve("future::pagination_stream::fn_stream::FnStream"),
             "attach_waiter_tracing_span" to RuntimeType.smithyRuntime(runtimeConfig).resolve("client::waiters::attach_waiter_tracing_span"),
         )
 
@@ -201,47 +204,83 @@ private class WaiterFluentBuilderConfig(
             rustTemplate(
                 """
                 pub async fn wait(self, max_wait: #{Duration}) -> #{Result}<#{FinalPollAlias}, #{WaiterErrorAlias}> {
-                    let input = self.inner.build()
-                        .map_err(#{WaiterError}::construction_failure)?;
-                    let runtime_plugins = #{Operation}::operation_runtime_plugins(
-                        self.handle.runtime_plugins.clone(),
-                        &self.handle.conf,
-                        #{None},
-                    ).with_operation_plugin(#{WaiterFeatureTrackerRuntimePlugin}::new());
-                    let mut cfg = #{ConfigBag}::base();
-                    let runtime_components_builder = runtime_plugins.apply_client_configuration(&mut cfg)
-                        .map_err(#{WaiterError}::construction_failure)?;
-                    let time_components = runtime_components_builder.into_time_components();
-                    let sleep_impl = time_components.sleep_impl().expect("a sleep impl is required by waiters");
-                    let time_source = time_components.time_source().expect("a time source is required by waiters");
+                    self.wait_with(max_wait, #{WaiterOptions}::new()).await
+                }
 
-                    #{acceptor}
-                    let operation = move || {
-                        let input = input.clone();
-                        let runtime_plugins = runtime_plugins.clone();
-                        async move {
-                            #{Operation}::orchestrate(&runtime_plugins, input).await
-                        }
-                    };
-                    let orchestrator = #{WaiterOrchestrator}::builder()
-                        .min_delay(#{Duration}::from_secs(${waiter.minDelay}))
-                        .max_delay(#{Duration}::from_secs(${waiter.maxDelay}))
-                        .max_wait(max_wait)
-                        .time_source(time_source)
-                        .sleep_impl(sleep_impl)
-                        .acceptor(acceptor)
-                        .operation(operation)
-                        .build();
+                /// Like [`wait`](Self::wait), but with options to cancel the waiter, resume it from a
+                /// checkpoint saved by an earlier waiter, or observe each poll it makes.
+                pub async fn wait_with(
+                    self,
+                    max_wait: #{Duration},
+                    options: #{WaiterOptions}<#{OperationOutput}, #{OperationError}>,
+                ) -> #{Result}<#{FinalPollAlias}, #{WaiterErrorAlias}> {
+                    #{orchestrator}
                     #{attach_waiter_tracing_span}(orchestrator.orchestrate()).await
                 }
+
+                /// Like [`wait_with`](Self::wait_with), but returns a stream that yields each poll the waiter makes.
+                ///
+                /// The stream ends after the poll that matched a success or failure state. A waiter that ends
+                /// without matching either of them yields its error as the last item.
+                pub fn wait_stream(
+                    self,
+                    max_wait: #{Duration},
+                    options: #{WaiterOptions}<#{OperationOutput}, #{OperationError}>,
+                ) -> #{Result}<#{FnStream}<#{Result}<#{WaiterPoll}<#{OperationOutput}, #{OperationError}>, #{WaiterErrorAlias}>>, #{WaiterErrorAlias}> {
+                    #{orchestrator}
+                    #{Ok}(orchestrator.orchestrate_stream())
+                }
+                """,
+                *scope,
+                "orchestrator" to orchestrator(),
+                "FinalPollAlias" to finalPollTypeAlias(),
+                "WaiterErrorAlias" to waiterErrorTypeAlias(),
+            )
+        }
+
+    /** Builds the waiter orchestrator from `self`, `max_wait`, and `options` */
+    private fun orchestrator(): Writable =
+        writable {
+            rustTemplate(
+                """
+                let input = self.inner.build()
+                    .map_err(#{WaiterError}::construction_failure)?;
+                let runtime_plugins = #{Operation}::operation_runtime_plugins(
+                    self.handle.runtime_plugins.clone(),
+                    &self.handle.conf,
+                    #{None},
+                ).with_operation_plugin(#{WaiterFeatureTrackerRuntimePlugin}::new());
+                let mut cfg = #{ConfigBag}::base();
+                let runtime_components_builder = runtime_plugins.apply_client_configuration(&mut cfg)
+                    .map_err(#{WaiterError}::construction_failure)?;
+                let time_components = runtime_components_builder.into_time_components();
+                let sleep_impl = time_components.sleep_impl().expect("a sleep impl is required by waiters");
+                let time_source = time_components.time_source().expect("a time source is required by waiters");
+
+                #{acceptor}
+                let operation = move || {
+                    let input = input.clone();
+                    let runtime_plugins = runtime_plugins.clone();
+                    async move {
+                        #{Operation}::orchestrate(&runtime_plugins, input).await
+                    }
+                };
+                let orchestrator = #{WaiterOrchestrator}::builder()
+                    .min_delay(#{Duration}::from_secs(${waiter.minDelay}))
+                    .max_delay(#{Duration}::from_secs(${waiter.maxDelay}))
+                    .max_wait(max_wait)
+                    .time_source(time_source)
+                    .sleep_impl(sleep_impl)
+                    .acceptor(acceptor)
+                    .operation(operation)
+                    .options(options)
+                    .build();
                 """,
                 *scope,
                 "acceptor" to
                     writable {
                         WaiterAcceptorGenerator(codegenContext, operation, waiter, "input").render(this)
                     },
-                "FinalPollAlias" to finalPollTypeAlias(),
-                "WaiterErrorAlias" to waiterErrorTypeAlias(),
                 "WaiterFeatureTrackerRuntimePlugin" to
                     RuntimeType.forInlineDependency(
                         InlineDependency.sdkFeatureTracker(runtimeConfig),
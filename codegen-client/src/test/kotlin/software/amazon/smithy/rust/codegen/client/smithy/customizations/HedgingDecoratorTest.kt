@@ -0,0 +1,120 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.client.smithy.customizations
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.client.testutil.clientIntegrationTest
+import software.amazon.smithy.rust.codegen.core.rustlang.CargoDependency
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.integrationTest
+
+class HedgingDecoratorTest {
+    private fun codegenScope(runtimeConfig: RuntimeConfig): Array<Pair<String, Any>> {
+        val api = RuntimeType.smithyRuntimeApiClient(runtimeConfig)
+        return arrayOf(
+            "http_client_fn" to api.resolve("client::http::http_client_fn"),
+            "HttpConnector" to api.resolve("client::http::HttpConnector"),
+            "HttpConnectorFuture" to api.resolve("client::http::HttpConnectorFuture"),
+            "HttpRequest" to api.resolve("client::orchestrator::HttpRequest"),
+            "HttpResponse" to api.resolve("client::orchestrator::HttpResponse"),
+            "SdkBody" to RuntimeType.sdkBody(runtimeConfig),
+            "SharedHttpConnector" to api.resolve("client::http::SharedHttpConnector"),
+            "http_1x" to CargoDependency.Http1x.toType(),
+            "tokio" to CargoDependency.Tokio.toType(),
+        )
+    }
+
+    private val model =
+        """
+        namespace com.example
+        use aws.protocols#awsJson1_0
+        @awsJson1_0
+        service HelloService {
+            operations: [GetGreeting, SayHello],
+            version: "1"
+        }
+
+        @optionalAuth
+        @readonly
+        operation GetGreeting {}
+
+        @optionalAuth
+        operation SayHello {}
+        """.asSmithyModel()
+
+    @Test
+    fun `requests to idempotent operations are hedged`() {
+        clientIntegrationTest(model) { codegenContext, rustCrate ->
+            rustCrate.integrationTest("hedging") {
+                val moduleName = codegenContext.moduleUseName()
+                rustTemplate(
+                    """
+                    use std::sync::atomic::{AtomicUsize, Ordering};
+                    use std::sync::Arc;
+                    use std::time::Duration;
+                    use $moduleName::config::retry::HedgingConfig;
+
+                    /// Connector whose first call takes a minute to respond
+                    ##[derive(Clone, Debug, Default)]
+                    struct SlowFirstCall(Arc<AtomicUsize>);
+
+                    impl #{HttpConnector} for SlowFirstCall {
+                        fn call(&self, _request: #{HttpRequest}) -> #{HttpConnectorFuture} {
+                            let call = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                            #{HttpConnectorFuture}::new(async move {
+                                if call == 1 {
+                                    #{tokio}::time::sleep(Duration::from_secs(60)).await;
+                                }
+                                let response = #{http_1x}::Response::builder()
+                                    .status(200)
+                                    .body(#{SdkBody}::from("{}"))
+                                    .unwrap();
+                                Ok(#{HttpResponse}::try_from(response).unwrap())
+                            })
+                        }
+                    }
+
+                    fn client(connector: &SlowFirstCall) -> $moduleName::Client {
+                        let connector = connector.clone();
+                        let hedging_config = HedgingConfig::builder()
+                            .initial_delay(Duration::from_millis(100))
+                            .build()
+                            .unwrap();
+                        let config = $moduleName::Config::builder()
+                            .endpoint_url("http://localhost:1234")
+                            .http_client(#{http_client_fn}(move |_, _| #{SharedHttpConnector}::new(connector.clone())))
+                            .hedging_config(hedging_config)
+                            .build();
+                        $moduleName::Client::from_conf(config)
+                    }
+
+                    ##[#{tokio}::test(start_paused = true)]
+                    async fn readonly_operation_is_hedged() {
+                        let connector = SlowFirstCall::default();
+                        let start = #{tokio}::time::Instant::now();
+                        client(&connector).get_greeting().send().await.expect("success");
+                        assert_eq!(Duration::from_millis(100), start.elapsed());
+                        assert_eq!(2, connector.0.load(Ordering::SeqCst));
+                    }
+
+                    ##[#{tokio}::test(start_paused = true)]
+                    async fn other_operations_are_not_hedged() {
+                        let connector = SlowFirstCall::default();
+                        let start = #{tokio}::time::Instant::now();
+                        client(&connector).say_hello().send().await.expect("success");
+                        assert_eq!(Duration::from_secs(60), start.elapsed());
+                        assert_eq!(1, connector.0.load(Ordering::SeqCst));
+                    }
+                    """,
+                    *codegenScope(codegenContext.runtimeConfig),
+                )
+            }
+        }
+    }
+}
@@ -5,7 +5,13 @@
 
 package software.amazon.smithy.rust.codegen.server.smithy.generators
 
+import software.amazon.smithy.aws.traits.auth.SigV4Trait
+import software.amazon.smithy.model.knowledge.ServiceIndex
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.HttpApiKeyAuthTrait
+import software.amazon.smithy.model.traits.HttpBasicAuthTrait
+import software.amazon.smithy.model.traits.HttpBearerAuthTrait
+import software.amazon.smithy.model.traits.OptionalAuthTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.documentShape
@@ -14,6 +20,7 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.util.dq
+import software.amazon.smithy.rust.codegen.core.util.hasTrait
 import software.amazon.smithy.rust.codegen.core.util.toPascalCase
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 
@@ -29,6 +36,7 @@ class ServerOperationGenerator(
         )
     private val symbolProvider = codegenContext.symbolProvider
     private val model = codegenContext.model
+    private val serviceShape = codegenContext.serviceShape
 
     private val operationName = symbolProvider.toSymbol(operation).name.toPascalCase()
     private val operationId = operation.id
@@ -44,6 +52,45 @@ class ServerOperationGenerator(
             }
         }
 
+    /** Renders the operation's effective auth schemes, in priority order, as `AuthScheme`s. */
+    private fun authSchemes(): Writable =
+        writable {
+            val authSchemes = ServiceIndex.of(model).getEffectiveAuthSchemes(serviceShape, operation).values
+            rust("&[")
+            for (authScheme in authSchemes) {
+                when (authScheme) {
+                    is HttpBearerAuthTrait -> rustTemplate("#{SmithyHttpServer}::auth::AuthScheme::HttpBearer,", *codegenScope)
+                    is HttpBasicAuthTrait -> rustTemplate("#{SmithyHttpServer}::auth::AuthScheme::HttpBasic,", *codegenScope)
+                    is HttpApiKeyAuthTrait -> {
+                        val location =
+                            when (authScheme.`in`!!) {
+                                HttpApiKeyAuthTrait.Location.HEADER -> "Header"
+                                HttpApiKeyAuthTrait.Location.QUERY -> "Query"
+                            }
+                        val scheme = authScheme.scheme.map { "Some(${it.dq()})" }.orElse("None")
+                        rustTemplate(
+                            """
+                            #{SmithyHttpServer}::auth::AuthScheme::HttpApiKey {
+                                name: ${authScheme.name.dq()},
+                                location: #{SmithyHttpServer}::auth::ApiKeyLocation::$location,
+                                scheme: $scheme,
+                            },
+                            """,
+                            *codegenScope,
+                        )
+                    }
+                    is SigV4Trait ->
+                        rustTemplate(
+                            "#{SmithyHttpServer}::auth::AuthScheme::SigV4 { name: ${authScheme.name.dq()} },",
+                            *codegenScope,
+                        )
+                    // The server runtime can't read the credentials of other auth schemes.
+                    else -> {}
+                }
+            }
+            rust("]")
+        }
+
     fun render(writer: RustWriter) {
         writer.documentShape(operation, model)
 
@@ -76,12 +123,18 @@ class ServerOperationGenerator(
                     #{ResponseValue:W}
                 }
             }
+
+            impl #{SmithyHttpServer}::auth::OperationAuth for $operationName {
+                const AUTH_SCHEMES: &'static [#{SmithyHttpServer}::auth::AuthScheme] = #{AuthSchemes:W};
+                const OPTIONAL_AUTH: bool = ${operation.hasTrait<OptionalAuthTrait>()};
+            }
             """,
             "Error" to operationError(),
             "RequestValue" to requestFmt.value,
             "RequestType" to requestFmt.type,
             "ResponseValue" to responseFmt.value,
             "ResponseType" to responseFmt.type,
+            "AuthSchemes" to authSchemes(),
             *codegenScope,
         )
         // Adds newline to end of render
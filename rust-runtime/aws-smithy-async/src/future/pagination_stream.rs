@@ -71,6 +71,59 @@ impl<Item> PaginationStream<Item> {
     pub async fn collect<T: Collectable<Item>>(self) -> T {
         self.0.collect().await
     }
+
+    /// Produces a new stream that fetches up to `depth` elements of this stream ahead of the
+    /// consumer.
+    ///
+    /// A paginator only requests the next page once the current page has been consumed, so
+    /// listing many pages is dominated by round-trip latency. With prefetching, this stream is
+    /// driven by a spawned task that polls it as soon as the previous element is received, and
+    /// buffers up to `depth` elements while the consumer processes the current one. The task is
+    /// aborted when the returned stream is dropped.
+    ///
+    /// `depth` counts the elements of this stream: pages for a stream of pages, and individual
+    /// items for a stream of items, such as one returned by a paginator's `items()`.
+    ///
+    /// This must be called from within a Tokio runtime.
+    ///
+    /// # Panics
+    /// If `depth` is zero, or if called outside of a Tokio runtime.
+    #[cfg(feature = "rt-tokio")]
+    pub fn prefetch(mut self, depth: usize) -> PaginationStream<Item>
+    where
+        Item: Send + 'static,
+    {
+        assert!(depth > 0, "prefetch depth must be greater than zero");
+        let (buffer_tx, mut buffer_rx) = tokio::sync::mpsc::channel(depth);
+        let task = AbortOnDrop(tokio::spawn(async move {
+            while let Some(item) = self.next().await {
+                if buffer_tx.send(item).await.is_err() {
+                    return;
+                }
+            }
+        }));
+        PaginationStream::new(FnStream::new(move |tx| {
+            Box::pin(async move {
+                let _task = task;
+                while let Some(item) = buffer_rx.recv().await {
+                    if tx.send(item).await.is_err() {
+                        return;
+                    }
+                }
+            }) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }))
+    }
+}
+
+/// Aborts the prefetching task when the stream reading from it is dropped
+#[cfg(feature = "rt-tokio")]
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+#[cfg(feature = "rt-tokio")]
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 impl<T, E> PaginationStream<Result<T, E>> {
@@ -83,6 +136,52 @@ impl<T, E> PaginationStream<Result<T, E>> {
     pub async fn try_collect(self) -> Result<Vec<T>, E> {
         self.collect::<Result<Vec<T>, E>>().await
     }
+
+    /// Gathers at most `limit` items into a `Vec`, or returns the first error encountered.
+    ///
+    /// The stream is dropped once `limit` items have been gathered, so no further pages are
+    /// requested.
+    pub async fn try_collect_with_limit(mut self, limit: usize) -> Result<Vec<T>, E> {
+        let mut items = Vec::with_capacity(limit.min(1024));
+        while items.len() < limit {
+            match self.try_next().await? {
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Produces a new stream that yields at most `n` successful items.
+    ///
+    /// The returned stream ends once `n` items have been yielded, or after the first error, and
+    /// stops polling this stream at that point so no further pages are requested.
+    pub fn take_items(mut self, n: usize) -> PaginationStream<Result<T, E>>
+    where
+        T: Send + 'static,
+        E: Send + 'static,
+    {
+        PaginationStream::new(FnStream::new(move |tx| {
+            Box::pin(async move {
+                let mut remaining = n;
+                while remaining > 0 {
+                    match self.next().await {
+                        Some(Ok(item)) => {
+                            remaining -= 1;
+                            if tx.send(Ok(item)).await.is_err() {
+                                return;
+                            }
+                        }
+                        Some(Err(err)) => {
+                            let _ = tx.send(Err(err)).await;
+                            return;
+                        }
+                        None => return,
+                    }
+                }
+            }) as Pin<Box<dyn Future<Output = ()> + Send>>
+        }))
+    }
 }
 
 /// Utility wrapper to flatten paginated results
@@ -306,4 +405,93 @@ mod test {
                 .await
         )
     }
+
+    /// Creates a stream of `pages` pages, recording the number of pages requested
+    #[cfg(feature = "rt-tokio")]
+    fn counted_pages(pages: usize, requested: Arc<Mutex<usize>>) -> PaginationStream<usize> {
+        PaginationStream::new(FnStream::new(move |tx| {
+            Box::pin(async move {
+                for page in 1..=pages {
+                    *requested.lock().unwrap() = page;
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    if tx.send(page).await.is_err() {
+                        return;
+                    }
+                }
+            })
+        }))
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn prefetch_requests_pages_ahead_of_the_reader() {
+        tokio::time::pause();
+        let requested = Arc::new(Mutex::new(0));
+        let mut stream = counted_pages(5, requested.clone()).prefetch(2);
+
+        let start = tokio::time::Instant::now();
+        assert_eq!(Some(1), stream.next().await);
+        // process the first page while the following pages are fetched
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        // two pages are buffered, and a third is waiting for room in the buffer
+        assert_eq!(4, *requested.lock().unwrap());
+        assert_eq!(Some(2), stream.next().await);
+        assert_eq!(Some(3), stream.next().await);
+        assert_eq!(Some(4), stream.next().await);
+        assert_eq!(Some(5), stream.next().await);
+        assert_eq!(None, stream.next().await);
+        assert_eq!(7, start.elapsed().as_secs());
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn dropping_a_prefetching_stream_stops_requests() {
+        tokio::time::pause();
+        let requested = Arc::new(Mutex::new(0));
+        let mut stream = counted_pages(10, requested.clone()).prefetch(1);
+        assert_eq!(Some(1), stream.next().await);
+        drop(stream);
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(2, *requested.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn take_items_stops_requesting_pages() {
+        tokio::time::pause();
+        let requested = Arc::new(Mutex::new(0));
+        let stream = PaginationStream::new(FnStream::new({
+            let requested = requested.clone();
+            move |tx| {
+                Box::pin(async move {
+                    for page in 0..10 {
+                        *requested.lock().unwrap() = page + 1;
+                        if tx.send(Result::<_, &str>::Ok(page)).await.is_err() {
+                            return;
+                        }
+                    }
+                })
+            }
+        }));
+        assert_eq!(Ok(vec![0, 1, 2]), stream.take_items(3).try_collect().await);
+        assert_eq!(3, *requested.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn try_collect_with_limit() {
+        let stream = || {
+            PaginationStream::new(FnStream::new(|tx| {
+                Box::pin(async move {
+                    for i in 0..3 {
+                        if tx.send(Ok(i)).await.is_err() {
+                            return;
+                        }
+                    }
+                    let _ = tx.send(Err("bummer")).await;
+                })
+            }))
+        };
+        assert_eq!(Ok(vec![0, 1]), stream().try_collect_with_limit(2).await);
+        assert_eq!(Err("bummer"), stream().try_collect_with_limit(5).await);
+        assert_eq!(Ok(vec![]), stream().try_collect_with_limit(0).await);
+    }
 }
@@ -220,4 +220,22 @@ mod tests {
         // Verify data is complete and unaltered
         assert_eq!(input_text, output_text);
     }
+
+    #[tokio::test]
+    async fn test_xxhash128_checksum_validated_body_succeeds_on_match() {
+        let input_text = "This is some test text for an SdkBody";
+        let actual_checksum = Bytes::copy_from_slice(
+            &xxhash_rust::xxh3::xxh3_128(input_text.as_bytes()).to_be_bytes(),
+        );
+        let body = SdkBody::from(input_text);
+        let http_checksum = "xxhash128"
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        let mut body = ChecksumBody::new(body, http_checksum, actual_checksum);
+
+        while let Some(buf) = body.data().await {
+            buf.expect("checksum matches");
+        }
+    }
 }
@@ -10,8 +10,9 @@ use http::header::{HeaderMap, HeaderValue};
 
 use crate::Crc64Nvme;
 use crate::{
-    Checksum, Crc32, Crc32c, Md5, Sha1, Sha256, CRC_32_C_NAME, CRC_32_NAME, CRC_64_NVME_NAME,
-    SHA_1_NAME, SHA_256_NAME,
+    Checksum, Crc32, Crc32c, Md5, Sha1, Sha256, Sha512, Xxhash128, Xxhash3, CRC_32_C_NAME,
+    CRC_32_NAME, CRC_64_NVME_NAME, SHA_1_NAME, SHA_256_NAME, SHA_512_NAME, XXHASH_128_NAME,
+    XXHASH_3_NAME,
 };
 
 pub const CRC_32_HEADER_NAME: &str = "x-amz-checksum-crc32";
@@ -19,6 +20,9 @@ pub const CRC_32_C_HEADER_NAME: &str = "x-amz-checksum-crc32c";
 pub const SHA_1_HEADER_NAME: &str = "x-amz-checksum-sha1";
 pub const SHA_256_HEADER_NAME: &str = "x-amz-checksum-sha256";
 pub const CRC_64_NVME_HEADER_NAME: &str = "x-amz-checksum-crc64nvme";
+pub const SHA_512_HEADER_NAME: &str = "x-amz-checksum-sha512";
+pub const XXHASH_3_HEADER_NAME: &str = "x-amz-checksum-xxhash3";
+pub const XXHASH_128_HEADER_NAME: &str = "x-amz-checksum-xxhash128";
 
 // Preserved for compatibility purposes. This should never be used by users, only within smithy-rs
 #[warn(dead_code)]
@@ -27,6 +31,9 @@ pub(crate) static MD5_HEADER_NAME: &str = "content-md5";
 /// When a response has to be checksum-verified, we have to check possible headers until we find the
 /// header with the precalculated checksum. Because a service may send back multiple headers, we have
 /// to check them in order based on how fast each checksum is to calculate.
+///
+/// This only lists the original checksum algorithms. See
+/// [`ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER`] for every supported algorithm.
 pub const CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER: [&str; 5] = [
     CRC_64_NVME_NAME,
     CRC_32_C_NAME,
@@ -35,6 +42,21 @@ pub const CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER: [&str; 5] = [
     SHA_256_NAME,
 ];
 
+/// Every supported checksum algorithm, in the order they should be checked in when verifying a
+/// response, from fastest to slowest to calculate.
+///
+/// This is a slice so that supporting more algorithms doesn't change its type.
+pub const ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER: &[&str] = &[
+    CRC_64_NVME_NAME,
+    CRC_32_C_NAME,
+    CRC_32_NAME,
+    XXHASH_3_NAME,
+    XXHASH_128_NAME,
+    SHA_1_NAME,
+    SHA_256_NAME,
+    SHA_512_NAME,
+];
+
 /// Checksum algorithms are use to validate the integrity of data. Structs that implement this trait
 /// can be used as checksum calculators. This trait requires Send + Sync because these checksums are
 /// often used in a threaded context.
@@ -107,6 +129,24 @@ impl HttpChecksum for Sha256 {
     }
 }
 
+impl HttpChecksum for Sha512 {
+    fn header_name(&self) -> &'static str {
+        SHA_512_HEADER_NAME
+    }
+}
+
+impl HttpChecksum for Xxhash3 {
+    fn header_name(&self) -> &'static str {
+        XXHASH_3_HEADER_NAME
+    }
+}
+
+impl HttpChecksum for Xxhash128 {
+    fn header_name(&self) -> &'static str {
+        XXHASH_128_HEADER_NAME
+    }
+}
+
 impl HttpChecksum for Md5 {
     fn header_name(&self) -> &'static str {
         MD5_HEADER_NAME
@@ -120,9 +160,24 @@ mod tests {
 
     use crate::{
         ChecksumAlgorithm, CRC_32_C_NAME, CRC_32_NAME, CRC_64_NVME_NAME, SHA_1_NAME, SHA_256_NAME,
+        SHA_512_NAME, XXHASH_128_NAME, XXHASH_3_NAME,
     };
 
-    use super::HttpChecksum;
+    use super::{
+        HttpChecksum, ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER,
+        CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER,
+    };
+
+    #[test]
+    fn priority_order_lists_every_algorithm() {
+        for name in ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER {
+            name.parse::<ChecksumAlgorithm>().unwrap();
+        }
+        assert_eq!(8, ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER.len());
+        for name in CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER {
+            assert!(ALL_CHECKSUM_ALGORITHMS_IN_PRIORITY_ORDER.contains(&name));
+        }
+    }
 
     #[test]
     fn test_trailer_length_of_crc32_checksum_body() {
@@ -244,4 +299,85 @@ mod tests {
         let actual_value = checksum.header_value();
         assert_eq!(expected_value, actual_value)
     }
+
+    #[test]
+    fn test_trailer_length_of_sha512_checksum_body() {
+        let checksum = SHA_512_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        let expected_size = 110;
+        let actual_size = HttpChecksum::size(&*checksum);
+        assert_eq!(expected_size, actual_size)
+    }
+
+    #[test]
+    fn test_trailer_value_of_sha512_checksum_body() {
+        let checksum = SHA_512_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        // The SHA512 of an empty string is cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e
+        let expected_value = Bytes::from_static(&[
+            0xcf, 0x83, 0xe1, 0x35, 0x7e, 0xef, 0xb8, 0xbd, 0xf1, 0x54, 0x28, 0x50, 0xd6, 0x6d,
+            0x80, 0x07, 0xd6, 0x20, 0xe4, 0x05, 0x0b, 0x57, 0x15, 0xdc, 0x83, 0xf4, 0xa9, 0x21,
+            0xd3, 0x6c, 0xe9, 0xce, 0x47, 0xd0, 0xd1, 0x3c, 0x5d, 0x85, 0xf2, 0xb0, 0xff, 0x83,
+            0x18, 0xd2, 0x87, 0x7e, 0xec, 0x2f, 0x63, 0xb9, 0x31, 0xbd, 0x47, 0x41, 0x7a, 0x81,
+            0xa5, 0x38, 0x32, 0x7a, 0xf9, 0x27, 0xda, 0x3e,
+        ]);
+        let expected_value = base64::encode(&expected_value);
+        let actual_value = checksum.header_value();
+        assert_eq!(expected_value, actual_value)
+    }
+
+    #[test]
+    fn test_trailer_length_of_xxhash3_checksum_body() {
+        let checksum = XXHASH_3_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        let expected_size = 35;
+        let actual_size = HttpChecksum::size(&*checksum);
+        assert_eq!(expected_size, actual_size)
+    }
+
+    #[test]
+    fn test_trailer_value_of_xxhash3_checksum_body() {
+        let checksum = XXHASH_3_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        // The XXH3-64 of an empty string is 2d06800538d394c2
+        let expected_value = Bytes::from_static(&[0x2d, 0x06, 0x80, 0x05, 0x38, 0xd3, 0x94, 0xc2]);
+        let expected_value = base64::encode(&expected_value);
+        let actual_value = checksum.header_value();
+        assert_eq!(expected_value, actual_value)
+    }
+
+    #[test]
+    fn test_trailer_length_of_xxhash128_checksum_body() {
+        let checksum = XXHASH_128_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        let expected_size = 49;
+        let actual_size = HttpChecksum::size(&*checksum);
+        assert_eq!(expected_size, actual_size)
+    }
+
+    #[test]
+    fn test_trailer_value_of_xxhash128_checksum_body() {
+        let checksum = XXHASH_128_NAME
+            .parse::<ChecksumAlgorithm>()
+            .unwrap()
+            .into_impl();
+        // The XXH3-128 of an empty string is 99aa06d3014798d86001c324468d497f
+        let expected_value = Bytes::from_static(&[
+            0x99, 0xaa, 0x06, 0xd3, 0x01, 0x47, 0x98, 0xd8, 0x60, 0x01, 0xc3, 0x24, 0x46, 0x8d,
+            0x49, 0x7f,
+        ]);
+        let expected_value = base64::encode(&expected_value);
+        let actual_value = checksum.header_value();
+        assert_eq!(expected_value, actual_value)
+    }
 }
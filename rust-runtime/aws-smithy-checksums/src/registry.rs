@@ -0,0 +1,189 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A registry of user-defined checksum algorithms.
+//!
+//! Storing a [`ChecksumRegistry`] in the config bag makes the algorithms registered in it
+//! available to request checksum calculation and response checksum validation. A registered
+//! algorithm is looked up by name before the built-in [`ChecksumAlgorithm`]s, so a registry
+//! can both add new algorithms and replace the implementation of a built-in one.
+
+use crate::error::UnknownChecksumAlgorithmError;
+use crate::http::HttpChecksum;
+use crate::ChecksumAlgorithm;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Creates new instances of a checksum algorithm's [`HttpChecksum`] implementation.
+///
+/// A new checksum is created for every request or response body that gets checksummed,
+/// including each retry attempt.
+#[derive(Clone)]
+pub struct SharedChecksumFactory(Arc<dyn Fn() -> Box<dyn HttpChecksum> + Send + Sync>);
+
+impl SharedChecksumFactory {
+    /// Create a new `SharedChecksumFactory` from a function that returns a new checksum.
+    pub fn new(factory: impl Fn() -> Box<dyn HttpChecksum> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(factory))
+    }
+
+    /// Return a new `HttpChecksum` that hasn't been updated with any data.
+    pub fn new_checksum(&self) -> Box<dyn HttpChecksum> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for SharedChecksumFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedChecksumFactory").finish()
+    }
+}
+
+impl From<ChecksumAlgorithm> for SharedChecksumFactory {
+    fn from(checksum_algorithm: ChecksumAlgorithm) -> Self {
+        Self::new(move || checksum_algorithm.into_impl())
+    }
+}
+
+/// A registry of checksum algorithms, keyed by algorithm name.
+///
+/// Algorithm names are case-insensitive. See the [module docs](self) for more information.
+#[derive(Clone, Debug, Default)]
+pub struct ChecksumRegistry {
+    factories: HashMap<String, SharedChecksumFactory>,
+}
+
+impl ChecksumRegistry {
+    /// Create a new, empty `ChecksumRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a checksum algorithm under the given name, replacing any algorithm previously
+    /// registered under that name.
+    pub fn register(
+        &mut self,
+        name: impl AsRef<str>,
+        factory: impl Fn() -> Box<dyn HttpChecksum> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.factories.insert(
+            name.as_ref().to_ascii_lowercase(),
+            SharedChecksumFactory::new(factory),
+        );
+        self
+    }
+
+    /// Register a checksum algorithm under the given name, replacing any algorithm previously
+    /// registered under that name.
+    pub fn with_algorithm(
+        mut self,
+        name: impl AsRef<str>,
+        factory: impl Fn() -> Box<dyn HttpChecksum> + Send + Sync + 'static,
+    ) -> Self {
+        self.register(name, factory);
+        self
+    }
+
+    /// Return the factory registered under the given name, if any.
+    ///
+    /// This doesn't consider the built-in algorithms. Use [`resolve`](Self::resolve) for that.
+    pub fn factory(&self, name: &str) -> Option<&SharedChecksumFactory> {
+        self.factories.get(&name.to_ascii_lowercase())
+    }
+
+    /// Return the names of all registered algorithms, in lowercase and in no particular order.
+    pub fn algorithm_names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Return a factory for the given algorithm name.
+    ///
+    /// Registered algorithms take precedence over the built-in ones. If the name is neither
+    /// registered nor a built-in algorithm, an error is returned.
+    pub fn resolve(
+        &self,
+        name: &str,
+    ) -> Result<SharedChecksumFactory, UnknownChecksumAlgorithmError> {
+        match self.factory(name) {
+            Some(factory) => Ok(factory.clone()),
+            None => ChecksumAlgorithm::from_str(name).map(Into::into),
+        }
+    }
+}
+
+impl Storable for ChecksumRegistry {
+    type Storer = StoreReplace<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChecksumRegistry;
+    use crate::http::HttpChecksum;
+    use crate::{Checksum, ChecksumAlgorithm};
+    use bytes::Bytes;
+
+    /// A "checksum" that counts the number of bytes it was updated with.
+    #[derive(Debug, Default)]
+    struct ByteCount(u32);
+
+    impl Checksum for ByteCount {
+        fn update(&mut self, bytes: &[u8]) {
+            self.0 += bytes.len() as u32;
+        }
+
+        fn finalize(self: Box<Self>) -> Bytes {
+            Bytes::copy_from_slice(&self.0.to_be_bytes())
+        }
+
+        fn size(&self) -> u64 {
+            4
+        }
+    }
+
+    impl HttpChecksum for ByteCount {
+        fn header_name(&self) -> &'static str {
+            "x-amz-checksum-bytecount"
+        }
+    }
+
+    #[test]
+    fn test_registered_algorithm_is_resolved_case_insensitively() {
+        let registry =
+            ChecksumRegistry::new().with_algorithm("ByteCount", || Box::<ByteCount>::default());
+        assert_eq!(
+            vec!["bytecount"],
+            registry.algorithm_names().collect::<Vec<_>>()
+        );
+
+        let mut checksum = registry.resolve("BYTECOUNT").unwrap().new_checksum();
+        checksum.update(b"test data");
+        assert_eq!("x-amz-checksum-bytecount", checksum.header_name());
+        assert_eq!(&[0, 0, 0, 9][..], &checksum.finalize()[..]);
+    }
+
+    #[test]
+    fn test_registered_algorithm_overrides_built_in_algorithm() {
+        let mut registry = ChecksumRegistry::new();
+        assert!(registry.factory("crc32").is_none());
+        let crc32 = registry.resolve("crc32").unwrap().new_checksum();
+        assert_eq!(
+            ChecksumAlgorithm::Crc32.into_impl().header_name(),
+            crc32.header_name()
+        );
+
+        registry.register("crc32", || Box::<ByteCount>::default());
+        let crc32 = registry.resolve("crc32").unwrap().new_checksum();
+        assert_eq!("x-amz-checksum-bytecount", crc32.header_name());
+    }
+
+    #[test]
+    fn test_unknown_algorithm_is_an_error() {
+        let err = ChecksumRegistry::new().resolve("bytecount").unwrap_err();
+        assert_eq!("bytecount", err.checksum_algorithm());
+    }
+}
@@ -3,12 +3,11 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! HTTP body-wrappers that perform request compression
+//! HTTP body-wrappers that perform request compression and response decompression
 
-// Putting this in a `mod` since I expect we'll have to handle response
-// decompression some day.
 /// Functionality for compressing an HTTP request body.
 pub mod compress {
+    use crate::CompressStream;
     use aws_smithy_types::body::SdkBody;
     use pin_project_lite::pin_project;
 
@@ -36,17 +35,61 @@ pub mod compress {
         }
     }
 
+    /// A `Body` that compresses its data as a single continuous stream with a
+    /// [`CompressStream`] implementor.
+    ///
+    /// Unlike [`CompressedBody`], which compresses each frame of data independently, this
+    /// body keeps the compressor's state across frames and finishes the compressed stream
+    /// once the inner body is exhausted. This makes it suitable for large streaming bodies
+    /// whose size isn't known up front. Trailers are passed through uncompressed after
+    /// all compressed data has been emitted.
+    #[cfg_attr(
+        not(any(feature = "http-body-0-4-x", feature = "http-body-1-x")),
+        allow(dead_code)
+    )]
+    pub struct StreamingCompressedBody {
+        body: SdkBody,
+        compressor: Box<dyn CompressStream>,
+        // Set once the compressor has been finished and all compressed data was emitted.
+        is_compression_finished: bool,
+        // Trailers received from the inner body before the compressed stream was finished.
+        #[cfg(feature = "http-body-1-x")]
+        pending_trailers: Option<http_1_0::HeaderMap>,
+    }
+
+    impl StreamingCompressedBody {
+        /// Given an [`SdkBody`] and a `Box<dyn CompressStream>`, create a new `StreamingCompressedBody`.
+        pub fn new(body: SdkBody, compressor: Box<dyn CompressStream>) -> Self {
+            Self {
+                body,
+                compressor,
+                is_compression_finished: false,
+                #[cfg(feature = "http-body-1-x")]
+                pending_trailers: None,
+            }
+        }
+    }
+
+    impl std::fmt::Debug for StreamingCompressedBody {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("StreamingCompressedBody")
+                .field("body", &self.body)
+                .field("is_compression_finished", &self.is_compression_finished)
+                .finish()
+        }
+    }
+
     /// Support for the `http-body-0-4` and `http-0-2` crates.
     #[cfg(feature = "http-body-0-4-x")]
     pub mod http_body_0_4_x {
-        use super::CompressedBody;
+        use super::{CompressedBody, StreamingCompressedBody};
         use crate::http::http_body_0_4_x::CompressRequest;
         use aws_smithy_runtime_api::box_error::BoxError;
         use aws_smithy_types::body::SdkBody;
         use http_0_2::HeaderMap;
         use http_body_0_4::{Body, SizeHint};
         use std::pin::Pin;
-        use std::task::{Context, Poll};
+        use std::task::{ready, Context, Poll};
 
         impl Body for CompressedBody<SdkBody, Box<dyn CompressRequest>> {
             type Data = bytes::Bytes;
@@ -104,12 +147,61 @@ pub mod compress {
                 Ok(SdkBody::from(compressed_body))
             }
         }
+
+        impl Body for StreamingCompressedBody {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_data(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+                let this = self.get_mut();
+                if this.is_compression_finished {
+                    return Poll::Ready(None);
+                }
+                loop {
+                    match ready!(Pin::new(&mut this.body).poll_data(cx)) {
+                        Some(Ok(data)) => {
+                            let compressed = this.compressor.compress_chunk(&data)?;
+                            // The compressor may buffer its input; keep polling until it produces output.
+                            if !compressed.is_empty() {
+                                return Poll::Ready(Some(Ok(compressed)));
+                            }
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            this.is_compression_finished = true;
+                            let compressed = this.compressor.finish()?;
+                            return Poll::Ready((!compressed.is_empty()).then_some(Ok(compressed)));
+                        }
+                    }
+                }
+            }
+
+            fn poll_trailers(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+                Pin::new(&mut self.get_mut().body).poll_trailers(cx)
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_compression_finished && self.body.is_end_stream()
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know exactly how
+                // compression will affect the content length
+                SizeHint::default()
+            }
+        }
     }
 
     /// Support for the `http-body-1-0` and `http-1-0` crates.
     #[cfg(feature = "http-body-1-x")]
     pub mod http_body_1_x {
-        use crate::body::compress::CompressedBody;
+        use crate::body::compress::{CompressedBody, StreamingCompressedBody};
         use crate::http::http_body_1_x::CompressRequest;
         use aws_smithy_types::body::SdkBody;
         use http_body_1_0::{Body, Frame, SizeHint};
@@ -157,6 +249,266 @@ pub mod compress {
                 SizeHint::default()
             }
         }
+
+        impl Body for StreamingCompressedBody {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_frame(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+                let this = self.get_mut();
+                if this.is_compression_finished {
+                    return Poll::Ready(
+                        this.pending_trailers.take().map(|t| Ok(Frame::trailers(t))),
+                    );
+                }
+                loop {
+                    match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                        Some(Ok(frame)) => match frame.into_data() {
+                            Ok(data) => {
+                                let compressed = this.compressor.compress_chunk(&data)?;
+                                // The compressor may buffer its input; keep polling until it produces output.
+                                if !compressed.is_empty() {
+                                    return Poll::Ready(Some(Ok(Frame::data(compressed))));
+                                }
+                            }
+                            // Trailers are the last frame, so the compressed stream must be
+                            // finished before they're emitted.
+                            Err(frame) => {
+                                this.is_compression_finished = true;
+                                let trailers = frame.into_trailers().ok();
+                                let compressed = this.compressor.finish()?;
+                                if compressed.is_empty() {
+                                    return Poll::Ready(trailers.map(|t| Ok(Frame::trailers(t))));
+                                }
+                                this.pending_trailers = trailers;
+                                return Poll::Ready(Some(Ok(Frame::data(compressed))));
+                            }
+                        },
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            this.is_compression_finished = true;
+                            let compressed = this.compressor.finish()?;
+                            return Poll::Ready(
+                                (!compressed.is_empty()).then_some(Ok(Frame::data(compressed))),
+                            );
+                        }
+                    }
+                }
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_compression_finished && self.pending_trailers.is_none()
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know exactly how
+                // compression will affect the content length
+                SizeHint::default()
+            }
+        }
+    }
+}
+
+/// Functionality for decompressing an HTTP response body.
+pub mod decompress {
+    use crate::DecompressStream;
+    use aws_smithy_types::body::SdkBody;
+
+    /// A `Body` that decompresses its data with a [`DecompressStream`] implementor.
+    ///
+    /// The inner body is decompressed as a single continuous stream. Trailers are passed through
+    /// as-is after all decompressed data has been emitted. A body without any data is treated as
+    /// empty rather than as a truncated compressed stream, since responses to `HEAD` requests may
+    /// carry a `Content-Encoding` header without a body.
+    #[cfg_attr(
+        not(any(feature = "http-body-0-4-x", feature = "http-body-1-x")),
+        allow(dead_code)
+    )]
+    pub struct DecompressedBody {
+        body: SdkBody,
+        decompressor: Box<dyn DecompressStream>,
+        // Set once any data has been passed to the decompressor.
+        has_received_data: bool,
+        // Set once the decompressor has been finished.
+        is_decompression_finished: bool,
+        // Trailers received from the inner body before the decompressed stream was finished.
+        #[cfg(feature = "http-body-1-x")]
+        pending_trailers: Option<http_1_0::HeaderMap>,
+    }
+
+    impl DecompressedBody {
+        /// Given an [`SdkBody`] and a `Box<dyn DecompressStream>`, create a new `DecompressedBody`.
+        pub fn new(body: SdkBody, decompressor: Box<dyn DecompressStream>) -> Self {
+            Self {
+                body,
+                decompressor,
+                has_received_data: false,
+                is_decompression_finished: false,
+                #[cfg(feature = "http-body-1-x")]
+                pending_trailers: None,
+            }
+        }
+
+        #[cfg(any(feature = "http-body-0-4-x", feature = "http-body-1-x"))]
+        fn decompress_chunk(
+            &mut self,
+            data: &[u8],
+        ) -> Result<bytes::Bytes, aws_smithy_types::body::Error> {
+            if data.is_empty() {
+                return Ok(bytes::Bytes::new());
+            }
+            self.has_received_data = true;
+            self.decompressor.decompress_chunk(data)
+        }
+
+        #[cfg(any(feature = "http-body-0-4-x", feature = "http-body-1-x"))]
+        fn finish(&mut self) -> Result<bytes::Bytes, aws_smithy_types::body::Error> {
+            self.is_decompression_finished = true;
+            if self.has_received_data {
+                self.decompressor.finish()
+            } else {
+                Ok(bytes::Bytes::new())
+            }
+        }
+    }
+
+    impl std::fmt::Debug for DecompressedBody {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("DecompressedBody")
+                .field("body", &self.body)
+                .field("is_decompression_finished", &self.is_decompression_finished)
+                .finish()
+        }
+    }
+
+    /// Support for the `http-body-0-4` and `http-0-2` crates.
+    #[cfg(feature = "http-body-0-4-x")]
+    pub mod http_body_0_4_x {
+        use super::DecompressedBody;
+        use http_0_2::HeaderMap;
+        use http_body_0_4::{Body, SizeHint};
+        use std::pin::Pin;
+        use std::task::{ready, Context, Poll};
+
+        impl Body for DecompressedBody {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_data(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+                let this = self.get_mut();
+                if this.is_decompression_finished {
+                    return Poll::Ready(None);
+                }
+                loop {
+                    match ready!(Pin::new(&mut this.body).poll_data(cx)) {
+                        Some(Ok(data)) => {
+                            let decompressed = this.decompress_chunk(&data)?;
+                            // The decompressor may need more input; keep polling until it produces output.
+                            if !decompressed.is_empty() {
+                                return Poll::Ready(Some(Ok(decompressed)));
+                            }
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            let decompressed = this.finish()?;
+                            return Poll::Ready(
+                                (!decompressed.is_empty()).then_some(Ok(decompressed)),
+                            );
+                        }
+                    }
+                }
+            }
+
+            fn poll_trailers(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+                Pin::new(&mut self.get_mut().body).poll_trailers(cx)
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_decompression_finished && self.body.is_end_stream()
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know how large
+                // the decompressed data will be
+                SizeHint::default()
+            }
+        }
+    }
+
+    /// Support for the `http-body-1-0` and `http-1-0` crates.
+    #[cfg(feature = "http-body-1-x")]
+    pub mod http_body_1_x {
+        use super::DecompressedBody;
+        use http_body_1_0::{Body, Frame, SizeHint};
+        use std::pin::Pin;
+        use std::task::{ready, Context, Poll};
+
+        impl Body for DecompressedBody {
+            type Data = bytes::Bytes;
+            type Error = aws_smithy_types::body::Error;
+
+            fn poll_frame(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+                let this = self.get_mut();
+                if this.is_decompression_finished {
+                    return Poll::Ready(
+                        this.pending_trailers.take().map(|t| Ok(Frame::trailers(t))),
+                    );
+                }
+                loop {
+                    match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                        Some(Ok(frame)) => match frame.into_data() {
+                            Ok(data) => {
+                                let decompressed = this.decompress_chunk(&data)?;
+                                // The decompressor may need more input; keep polling until it produces output.
+                                if !decompressed.is_empty() {
+                                    return Poll::Ready(Some(Ok(Frame::data(decompressed))));
+                                }
+                            }
+                            // Trailers are the last frame, so the decompressed stream must be
+                            // finished before they're emitted.
+                            Err(frame) => {
+                                let trailers = frame.into_trailers().ok();
+                                let decompressed = this.finish()?;
+                                if decompressed.is_empty() {
+                                    return Poll::Ready(trailers.map(|t| Ok(Frame::trailers(t))));
+                                }
+                                this.pending_trailers = trailers;
+                                return Poll::Ready(Some(Ok(Frame::data(decompressed))));
+                            }
+                        },
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            let decompressed = this.finish()?;
+                            return Poll::Ready(
+                                (!decompressed.is_empty()).then_some(Ok(Frame::data(decompressed))),
+                            );
+                        }
+                    }
+                }
+            }
+
+            fn is_end_stream(&self) -> bool {
+                self.is_decompression_finished && self.pending_trailers.is_none()
+            }
+
+            fn size_hint(&self) -> SizeHint {
+                // We can't return a hint because we don't know how large
+                // the decompressed data will be
+                SizeHint::default()
+            }
+        }
     }
 }
 
@@ -164,6 +516,9 @@ pub mod compress {
 #[cfg(test)]
 mod test {
     use crate::body::compress::CompressedBody;
+    #[cfg(feature = "http-body-1-x")]
+    use crate::body::compress::StreamingCompressedBody;
+    use crate::body::decompress::DecompressedBody;
     use crate::{CompressionAlgorithm, CompressionOptions};
     use aws_smithy_types::body::SdkBody;
     use bytes::Buf;
@@ -175,6 +530,35 @@ mod test {
         133, 17, 74, 13, 11, 0, 0, 0,
     ];
 
+    #[cfg(feature = "http-body-1-x")]
+    fn gettysburg_address() -> &'static [u8] {
+        include_bytes!("../test-data/gettysburg_address.txt")
+    }
+
+    #[cfg(feature = "http-body-1-x")]
+    fn gunzip(compressed: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(compressed)
+            .read_to_end(&mut out)
+            .unwrap();
+        out
+    }
+
+    /// A streaming body that yields the Gettysburg Address in many small frames, followed by trailers.
+    #[cfg(feature = "http-body-1-x")]
+    fn streaming_gettysburg_address() -> SdkBody {
+        use http_body_1_0::Frame;
+        let mut trailers = http_1_0::HeaderMap::new();
+        trailers.insert("x-test-trailer", "some value".parse().unwrap());
+        let frames = gettysburg_address()
+            .chunks(64)
+            .map(|chunk| Ok(Frame::data(bytes::Bytes::from_static(chunk))))
+            .chain(std::iter::once(Ok(Frame::trailers(trailers))));
+        SdkBody::from_body_1_x(http_body_util::StreamBody::new(futures_util::stream::iter(
+            frames.collect::<Vec<Result<_, aws_smithy_types::body::Error>>>(),
+        )))
+    }
+
     #[cfg(feature = "http-body-0-4-x")]
     mod http_body_0_4_x {
         use super::*;
@@ -222,6 +606,61 @@ mod test {
                 compressed_sdk_body.bytes().expect("body is in-memory")
             );
         }
+
+        #[tokio::test]
+        async fn test_body_is_decompressed() {
+            let decompressor = CompressionAlgorithm::Gzip.into_decompress_impl(None);
+            let mut decompressed_body =
+                DecompressedBody::new(SdkBody::from(COMPRESSED_OUTPUT), decompressor);
+
+            let mut output = Vec::new();
+            while let Some(buf) = decompressed_body.data().await {
+                output.extend_from_slice(&buf.unwrap());
+            }
+            assert_eq!(UNCOMPRESSED_INPUT, output);
+            assert!(decompressed_body.is_end_stream());
+        }
+
+        #[tokio::test]
+        async fn test_empty_body_is_not_decompressed() {
+            let decompressor = CompressionAlgorithm::Gzip.into_decompress_impl(None);
+            let mut decompressed_body = DecompressedBody::new(SdkBody::empty(), decompressor);
+
+            assert!(decompressed_body.data().await.is_none());
+        }
+
+        #[tokio::test]
+        async fn test_decompressed_body_enforces_max_size() {
+            let max_size = UNCOMPRESSED_INPUT.len() as u64 - 1;
+            let decompressor = CompressionAlgorithm::Gzip.into_decompress_impl(Some(max_size));
+            let mut decompressed_body =
+                DecompressedBody::new(SdkBody::from(COMPRESSED_OUTPUT), decompressor);
+
+            let err = decompressed_body
+                .data()
+                .await
+                .unwrap()
+                .expect_err("decompressed data is larger than the max size");
+            assert!(err.is::<crate::MaxDecompressedSizeExceeded>());
+        }
+
+        #[cfg(feature = "http-body-1-x")]
+        #[tokio::test]
+        async fn test_streaming_body_is_compressed_as_a_single_stream() {
+            let compressor =
+                CompressionAlgorithm::Gzip.into_streaming_impl(&CompressionOptions::default());
+            let mut compressed_body =
+                StreamingCompressedBody::new(streaming_gettysburg_address(), compressor);
+
+            let mut output = Vec::new();
+            while let Some(buf) = compressed_body.data().await {
+                output.extend_from_slice(&buf.unwrap());
+            }
+            let trailers = compressed_body.trailers().await.unwrap().unwrap();
+
+            assert_eq!(gettysburg_address(), gunzip(&output));
+            assert_eq!("some value", trailers.get("x-test-trailer").unwrap());
+        }
     }
 
     #[cfg(feature = "http-body-1-x")]
@@ -260,5 +699,59 @@ mod test {
             // Verify data is compressed as expected
             assert_eq!(COMPRESSED_OUTPUT, actual_output);
         }
+
+        #[tokio::test]
+        async fn test_streaming_body_is_compressed_as_a_single_stream() {
+            let compressor =
+                CompressionAlgorithm::Gzip.into_streaming_impl(&CompressionOptions::default());
+            let mut compressed_body =
+                StreamingCompressedBody::new(streaming_gettysburg_address(), compressor);
+
+            let mut output = Vec::new();
+            let mut trailers = None;
+            while let Some(frame) = compressed_body.frame().await {
+                let frame = frame.unwrap();
+                assert!(trailers.is_none(), "trailers must be the last frame");
+                match frame.into_data() {
+                    Ok(data) => output.extend_from_slice(&data),
+                    Err(frame) => trailers = frame.into_trailers().ok(),
+                }
+            }
+
+            assert_eq!(gettysburg_address(), gunzip(&output));
+            assert_eq!(
+                "some value",
+                trailers.unwrap().get("x-test-trailer").unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_streaming_body_is_decompressed() {
+            let compressor =
+                CompressionAlgorithm::Gzip.into_streaming_impl(&CompressionOptions::default());
+            let compressed_body = SdkBody::from_body_1_x(StreamingCompressedBody::new(
+                streaming_gettysburg_address(),
+                compressor,
+            ));
+            let decompressor = CompressionAlgorithm::Gzip.into_decompress_impl(None);
+            let mut decompressed_body = DecompressedBody::new(compressed_body, decompressor);
+
+            let mut output = Vec::new();
+            let mut trailers = None;
+            while let Some(frame) = decompressed_body.frame().await {
+                let frame = frame.unwrap();
+                assert!(trailers.is_none(), "trailers must be the last frame");
+                match frame.into_data() {
+                    Ok(data) => output.extend_from_slice(&data),
+                    Err(frame) => trailers = frame.into_trailers().ok(),
+                }
+            }
+
+            assert_eq!(gettysburg_address(), &output[..]);
+            assert_eq!(
+                "some value",
+                trailers.unwrap().get("x-test-trailer").unwrap()
+            );
+        }
     }
 }
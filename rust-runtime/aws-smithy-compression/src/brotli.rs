@@ -0,0 +1,265 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::limited::{into_box_error, LimitedBuffer};
+use crate::{Compress, CompressStream, CompressionOptions, DecompressStream};
+use aws_smithy_runtime_api::box_error::BoxError;
+use brotli::enc::BrotliEncoderParams;
+use brotli::{CompressorWriter, DecompressorWriter};
+use bytes::Bytes;
+use std::io::prelude::*;
+use std::mem;
+
+/// The size of the internal buffer used by the brotli encoder.
+const BUFFER_SIZE: usize = 4096;
+/// The base-2 logarithm of the sliding window size. 22 is the brotli default.
+const LG_WINDOW_SIZE: u32 = 22;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Brotli {
+    level: u32,
+}
+
+impl Brotli {
+    fn compress_bytes(&self, mut bytes: &[u8], mut writer: impl Write) -> Result<(), BoxError> {
+        // Unlike `CompressorWriter::into_inner`, this reports errors writing the end of the stream.
+        let params = BrotliEncoderParams {
+            quality: self.level as i32,
+            lgwin: LG_WINDOW_SIZE as i32,
+            ..Default::default()
+        };
+        brotli::BrotliCompress(&mut bytes, &mut writer, &params)?;
+
+        Ok(())
+    }
+}
+
+impl Compress for Brotli {
+    fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        Brotli::compress_bytes(self, bytes, writer)
+    }
+}
+
+/// A brotli compressor that maintains its state across chunks of a stream.
+pub(crate) struct BrotliStream {
+    // The encoder can only be finished by consuming it, so it's taken in `finish`.
+    encoder: Option<CompressorWriter<Vec<u8>>>,
+}
+
+impl CompressStream for BrotliStream {
+    fn compress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        let encoder = self
+            .encoder
+            .as_mut()
+            .ok_or("cannot compress data after the brotli stream was finished")?;
+        encoder.write_all(bytes)?;
+        Ok(mem::take(encoder.get_mut()).into())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        match self.encoder.take() {
+            Some(mut encoder) => {
+                // `into_inner` ignores errors, so the buffered data is flushed first to report
+                // them. Only the last, empty block of the stream is written after that.
+                encoder.flush()?;
+                Ok(encoder.into_inner().into())
+            }
+            None => Ok(Bytes::new()),
+        }
+    }
+}
+
+impl From<&CompressionOptions> for BrotliStream {
+    fn from(options: &CompressionOptions) -> Self {
+        BrotliStream {
+            encoder: Some(CompressorWriter::new(
+                Vec::new(),
+                BUFFER_SIZE,
+                options.brotli_level,
+                LG_WINDOW_SIZE,
+            )),
+        }
+    }
+}
+
+/// A brotli decompressor that maintains its state across chunks of a stream.
+pub(crate) struct BrotliDecompressStream {
+    decoder: DecompressorWriter<LimitedBuffer>,
+}
+
+impl BrotliDecompressStream {
+    pub(crate) fn new(max_decompressed_size_bytes: Option<u64>) -> Self {
+        Self {
+            decoder: DecompressorWriter::new(
+                LimitedBuffer::new(max_decompressed_size_bytes),
+                BUFFER_SIZE,
+            ),
+        }
+    }
+}
+
+impl DecompressStream for BrotliDecompressStream {
+    fn decompress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        self.decoder.write_all(bytes).map_err(into_box_error)?;
+        Ok(self.decoder.get_mut().take())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        self.decoder.close().map_err(into_box_error)?;
+        Ok(self.decoder.get_mut().take())
+    }
+}
+
+#[cfg(feature = "http-body-0-4-x")]
+mod http_body_0_4_x {
+    use crate::http::http_body_0_4_x::CompressRequest;
+
+    impl CompressRequest for super::Brotli {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static(crate::BROTLI_NAME)
+        }
+    }
+}
+
+#[cfg(feature = "http-body-1-x")]
+mod http_body_1_x {
+    use crate::http::http_body_1_x::CompressRequest;
+
+    impl CompressRequest for super::Brotli {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static(crate::BROTLI_NAME)
+        }
+    }
+}
+
+impl From<&CompressionOptions> for Brotli {
+    fn from(options: &CompressionOptions) -> Self {
+        Brotli {
+            level: options.brotli_level,
+        }
+    }
+}
+
+impl From<CompressionOptions> for Brotli {
+    fn from(options: CompressionOptions) -> Self {
+        Brotli::from(&options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Brotli, BrotliDecompressStream, BrotliStream};
+    use crate::{CompressStream, CompressionOptions, DecompressStream};
+    use pretty_assertions::assert_eq;
+    use std::io::Read;
+
+    fn gettysburg_address() -> &'static [u8] {
+        include_bytes!("../test-data/gettysburg_address.txt")
+    }
+
+    fn decompress(compressed: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        brotli::Decompressor::new(compressed, 4096)
+            .read_to_end(&mut out)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_brotli_compression() {
+        let brotli = Brotli::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        brotli
+            .compress_bytes(gettysburg_address(), &mut compressed_output)
+            .expect("compression succeeds");
+        assert!(compressed_output.len() < gettysburg_address().len());
+
+        assert_eq!(gettysburg_address(), &decompress(&compressed_output)[..]);
+    }
+
+    #[test]
+    fn test_brotli_compression_respects_level() {
+        for level in [0, 11] {
+            let options = CompressionOptions::default()
+                .with_brotli_level(level)
+                .unwrap();
+            let brotli = Brotli::from(&options);
+            let mut compressed_output = Vec::new();
+            brotli
+                .compress_bytes(gettysburg_address(), &mut compressed_output)
+                .expect("compression succeeds");
+
+            assert_eq!(gettysburg_address(), &decompress(&compressed_output)[..]);
+        }
+    }
+
+    /// A writer that fails once it's given more than `capacity` bytes.
+    struct FullWriter {
+        capacity: usize,
+    }
+
+    impl std::io::Write for FullWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if buf.len() > self.capacity {
+                return Err(std::io::Error::other("the writer is full"));
+            }
+            self.capacity -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_brotli_compression_reports_errors_writing_the_end_of_the_stream() {
+        let brotli = Brotli::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        brotli
+            .compress_bytes(gettysburg_address(), &mut compressed_output)
+            .expect("compression succeeds");
+
+        let mut writer = FullWriter {
+            capacity: compressed_output.len() - 1,
+        };
+        brotli
+            .compress_bytes(gettysburg_address(), &mut writer)
+            .expect_err("the last byte of the stream can't be written");
+    }
+
+    #[test]
+    fn test_brotli_stream_compression() {
+        let mut brotli = BrotliStream::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        for chunk in gettysburg_address().chunks(100) {
+            let compressed = brotli.compress_chunk(chunk).expect("compression succeeds");
+            compressed_output.extend_from_slice(&compressed);
+        }
+        compressed_output.extend_from_slice(&brotli.finish().expect("compression succeeds"));
+
+        assert_eq!(gettysburg_address(), &decompress(&compressed_output)[..]);
+    }
+
+    #[test]
+    fn test_brotli_stream_decompression() {
+        let mut compressed_input = Vec::new();
+        Brotli::from(&CompressionOptions::default())
+            .compress_bytes(gettysburg_address(), &mut compressed_input)
+            .expect("compression succeeds");
+
+        let mut brotli = BrotliDecompressStream::new(None);
+        let mut decompressed_output = Vec::new();
+        for chunk in compressed_input.chunks(100) {
+            let decompressed = brotli
+                .decompress_chunk(chunk)
+                .expect("decompression succeeds");
+            decompressed_output.extend_from_slice(&decompressed);
+        }
+        decompressed_output.extend_from_slice(&brotli.finish().expect("decompression succeeds"));
+
+        assert_eq!(gettysburg_address(), &decompressed_output[..]);
+    }
+}
@@ -3,10 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::{Compress, CompressionOptions};
+use crate::limited::{into_box_error, LimitedBuffer};
+use crate::{Compress, CompressStream, CompressionOptions, DecompressStream};
 use aws_smithy_runtime_api::box_error::BoxError;
-use flate2::write::GzEncoder;
+use bytes::Bytes;
+use flate2::write::{GzEncoder, MultiGzDecoder};
 use std::io::prelude::*;
+use std::mem;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub(crate) struct Gzip {
@@ -29,6 +32,57 @@ impl Compress for Gzip {
     }
 }
 
+/// A gzip compressor that maintains its state across chunks of a stream.
+pub(crate) struct GzipStream {
+    encoder: GzEncoder<Vec<u8>>,
+}
+
+impl CompressStream for GzipStream {
+    fn compress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        self.encoder.write_all(bytes)?;
+        Ok(mem::take(self.encoder.get_mut()).into())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        self.encoder.try_finish()?;
+        Ok(mem::take(self.encoder.get_mut()).into())
+    }
+}
+
+impl From<&CompressionOptions> for GzipStream {
+    fn from(options: &CompressionOptions) -> Self {
+        GzipStream {
+            encoder: GzEncoder::new(Vec::new(), flate2::Compression::new(options.level)),
+        }
+    }
+}
+
+/// A gzip decompressor that maintains its state across chunks of a stream.
+pub(crate) struct GzipDecompressStream {
+    // Servers may concatenate several gzip members into a single body, so decode all of them.
+    decoder: MultiGzDecoder<LimitedBuffer>,
+}
+
+impl GzipDecompressStream {
+    pub(crate) fn new(max_decompressed_size_bytes: Option<u64>) -> Self {
+        Self {
+            decoder: MultiGzDecoder::new(LimitedBuffer::new(max_decompressed_size_bytes)),
+        }
+    }
+}
+
+impl DecompressStream for GzipDecompressStream {
+    fn decompress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        self.decoder.write_all(bytes).map_err(into_box_error)?;
+        Ok(self.decoder.get_mut().take())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        self.decoder.try_finish().map_err(into_box_error)?;
+        Ok(self.decoder.get_mut().take())
+    }
+}
+
 #[cfg(feature = "http-body-0-4-x")]
 mod http_body_0_4_x {
     use crate::http::http_body_0_4_x::CompressRequest;
@@ -70,8 +124,10 @@ impl From<CompressionOptions> for Gzip {
 // Windows line-endings will cause the compression test to fail.
 #[cfg(all(test, not(windows)))]
 mod tests {
-    use super::Gzip;
-    use crate::CompressionOptions;
+    use super::{Gzip, GzipDecompressStream, GzipStream};
+    use crate::{
+        CompressStream, CompressionOptions, DecompressStream, MaxDecompressedSizeExceeded,
+    };
     use flate2::read::GzDecoder;
     use pretty_assertions::assert_eq;
     use std::io::Read;
@@ -110,4 +166,51 @@ mod tests {
 
         assert_eq!(uncompressed_expected, uncompressed_actual);
     }
+
+    #[test]
+    fn test_gzip_stream_compression() {
+        let mut gzip = GzipStream::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        for chunk in gettysburg_address().chunks(100) {
+            let compressed = gzip.compress_chunk(chunk).expect("compression succeeds");
+            compressed_output.extend_from_slice(&compressed);
+        }
+        compressed_output.extend_from_slice(&gzip.finish().expect("compression succeeds"));
+
+        let mut uncompressed_actual = Vec::new();
+        GzDecoder::new(&compressed_output[..])
+            .read_to_end(&mut uncompressed_actual)
+            .unwrap();
+        assert_eq!(gettysburg_address(), &uncompressed_actual[..]);
+    }
+
+    #[test]
+    fn test_gzip_stream_decompression() {
+        let mut gzip = GzipDecompressStream::new(None);
+        let mut decompressed_output = Vec::new();
+        for chunk in gzip_compressed_gettysburg_address().chunks(100) {
+            let decompressed = gzip
+                .decompress_chunk(chunk)
+                .expect("decompression succeeds");
+            decompressed_output.extend_from_slice(&decompressed);
+        }
+        decompressed_output.extend_from_slice(&gzip.finish().expect("decompression succeeds"));
+
+        assert_eq!(gettysburg_address(), &decompressed_output[..]);
+    }
+
+    #[test]
+    fn test_gzip_stream_decompression_enforces_max_size() {
+        let max_size = gettysburg_address().len() as u64 - 1;
+        let mut gzip = GzipDecompressStream::new(Some(max_size));
+        let err = gzip
+            .decompress_chunk(gzip_compressed_gettysburg_address())
+            .and_then(|_| gzip.finish())
+            .expect_err("decompressed data is larger than the max size");
+
+        let err = err
+            .downcast_ref::<MaxDecompressedSizeExceeded>()
+            .expect("correct error type");
+        assert_eq!(max_size, err.max_size_bytes());
+    }
 }
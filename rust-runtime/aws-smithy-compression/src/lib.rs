@@ -18,16 +18,28 @@
 
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use bytes::Bytes;
+use std::error::Error;
+use std::fmt;
 use std::io::Write;
 use std::str::FromStr;
 
 pub mod body;
+#[cfg(feature = "brotli")]
+mod brotli;
 mod gzip;
 pub mod http;
+mod limited;
+#[cfg(feature = "zstd")]
+mod zstd;
 
 // Valid compression algorithm names
 /// The name of the `gzip` algorithm.
 pub const GZIP_NAME: &str = "gzip";
+/// The name of the `zstd` algorithm.
+pub const ZSTD_NAME: &str = "zstd";
+/// The name of the `brotli` algorithm, as it appears in the `Content-Encoding` header.
+pub const BROTLI_NAME: &str = "br";
 
 /// The maximum-allowable value per internal standards is 10 Megabytes.
 const MAX_MIN_COMPRESSION_SIZE_BYTES: u32 = 10_485_760;
@@ -44,12 +56,79 @@ pub trait Compress: Send + Sync {
     fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError>;
 }
 
+/// Types implementing this trait can compress a stream of data incrementally.
+///
+/// Unlike [`Compress`], which produces a complete compressed payload on every call, a streaming
+/// compressor keeps its state between calls. The output of every call to
+/// [`compress_chunk`](CompressStream::compress_chunk) followed by the output of
+/// [`finish`](CompressStream::finish) forms a single compressed payload.
+pub trait CompressStream: Send + Sync {
+    /// Compress a chunk of data, returning any compressed output that is ready.
+    ///
+    /// The returned bytes may be empty if the compressor is still buffering input.
+    fn compress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError>;
+
+    /// Finish the compressed stream, returning any remaining compressed output.
+    ///
+    /// No more data may be compressed after calling this method.
+    fn finish(&mut self) -> Result<Bytes, BoxError>;
+}
+
+/// Types implementing this trait can decompress a stream of data incrementally.
+///
+/// The output of every call to [`decompress_chunk`](DecompressStream::decompress_chunk) followed by
+/// the output of [`finish`](DecompressStream::finish) forms the complete decompressed payload.
+pub trait DecompressStream: Send + Sync {
+    /// Decompress a chunk of data, returning any decompressed output that is ready.
+    ///
+    /// The returned bytes may be empty if the decompressor needs more input.
+    fn decompress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError>;
+
+    /// Finish the decompressed stream, returning any remaining decompressed output.
+    ///
+    /// No more data may be decompressed after calling this method.
+    fn finish(&mut self) -> Result<Bytes, BoxError>;
+}
+
+/// An error returned when decompressed data grows larger than the allowed maximum size.
+///
+/// This protects against "decompression bombs": small payloads that decompress to a huge amount
+/// of data.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct MaxDecompressedSizeExceeded {
+    max_size_bytes: u64,
+}
+
+impl MaxDecompressedSizeExceeded {
+    /// The maximum number of decompressed bytes that were allowed.
+    pub fn max_size_bytes(&self) -> u64 {
+        self.max_size_bytes
+    }
+}
+
+impl fmt::Display for MaxDecompressedSizeExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "decompressed data exceeded the maximum allowed size of {} bytes",
+            self.max_size_bytes
+        )
+    }
+}
+
+impl Error for MaxDecompressedSizeExceeded {}
+
 /// Options for configuring request compression.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
 pub struct CompressionOptions {
     /// Valid values are 0-9 with lower values configuring less (but faster) compression
     level: u32,
+    /// Valid values are 1-22 with lower values configuring less (but faster) compression
+    zstd_level: i32,
+    /// Valid values are 0-11 with lower values configuring less (but faster) compression
+    brotli_level: u32,
     min_compression_size_bytes: u32,
     enabled: bool,
 }
@@ -58,6 +137,8 @@ impl Default for CompressionOptions {
     fn default() -> Self {
         Self {
             level: 6,
+            zstd_level: 3,
+            brotli_level: 5,
             min_compression_size_bytes: 10240,
             enabled: true,
         }
@@ -65,11 +146,21 @@ impl Default for CompressionOptions {
 }
 
 impl CompressionOptions {
-    /// The compression level to use.
+    /// The compression level to use for `gzip`.
     pub fn level(&self) -> u32 {
         self.level
     }
 
+    /// The compression level to use for `zstd`.
+    pub fn zstd_level(&self) -> i32 {
+        self.zstd_level
+    }
+
+    /// The compression level to use for `brotli`.
+    pub fn brotli_level(&self) -> u32 {
+        self.brotli_level
+    }
+
     /// The minimum size of data to compress.
     ///
     /// Data smaller than this will not be compressed.
@@ -87,7 +178,7 @@ impl CompressionOptions {
         Self { enabled, ..self }
     }
 
-    /// Set the compression level.
+    /// Set the compression level for `gzip`.
     ///
     /// Valid values are `0..=9` with lower values configuring less _(but faster)_ compression
     pub fn with_level(self, level: u32) -> Result<Self, BoxError> {
@@ -95,6 +186,27 @@ impl CompressionOptions {
         Ok(Self { level, ..self })
     }
 
+    /// Set the compression level for `zstd`.
+    ///
+    /// Valid values are `1..=22` with lower values configuring less _(but faster)_ compression.
+    /// The default is `3`.
+    pub fn with_zstd_level(self, zstd_level: i32) -> Result<Self, BoxError> {
+        Self::validate_zstd_level(zstd_level)?;
+        Ok(Self { zstd_level, ..self })
+    }
+
+    /// Set the compression level for `brotli`.
+    ///
+    /// Valid values are `0..=11` with lower values configuring less _(but faster)_ compression.
+    /// The default is `5`.
+    pub fn with_brotli_level(self, brotli_level: u32) -> Result<Self, BoxError> {
+        Self::validate_brotli_level(brotli_level)?;
+        Ok(Self {
+            brotli_level,
+            ..self
+        })
+    }
+
     /// Set the minimum size of data to compress.
     ///
     /// Data smaller than this will not be compressed.
@@ -121,6 +233,28 @@ impl CompressionOptions {
         Ok(())
     }
 
+    fn validate_zstd_level(zstd_level: i32) -> Result<(), BoxError> {
+        if !(1..=22).contains(&zstd_level) {
+            return Err(format!(
+                "zstd compression level `{}` is invalid, valid values are 1..=22",
+                zstd_level
+            )
+            .into());
+        };
+        Ok(())
+    }
+
+    fn validate_brotli_level(brotli_level: u32) -> Result<(), BoxError> {
+        if brotli_level > 11 {
+            return Err(format!(
+                "brotli compression level `{}` is invalid, valid values are 0..=11",
+                brotli_level
+            )
+            .into());
+        };
+        Ok(())
+    }
+
     fn validate_min_compression_size_bytes(
         min_compression_size_bytes: u32,
     ) -> Result<(), BoxError> {
@@ -145,6 +279,12 @@ impl Storable for CompressionOptions {
 pub enum CompressionAlgorithm {
     /// The [gzip](https://en.wikipedia.org/wiki/Gzip) compression algorithm
     Gzip,
+    /// The [zstd](https://en.wikipedia.org/wiki/Zstd) compression algorithm
+    #[cfg(feature = "zstd")]
+    Zstd,
+    /// The [brotli](https://en.wikipedia.org/wiki/Brotli) compression algorithm
+    #[cfg(feature = "brotli")]
+    Brotli,
 }
 
 impl FromStr for CompressionAlgorithm {
@@ -154,14 +294,23 @@ impl FromStr for CompressionAlgorithm {
     ///
     /// Valid algorithm names are:
     /// - "gzip"
+    /// - "zstd" _(requires the `zstd` feature)_
+    /// - "br" _(requires the `brotli` feature)_
     ///
     /// Passing an invalid name will return an error.
     fn from_str(compression_algorithm: &str) -> Result<Self, Self::Err> {
         if compression_algorithm.eq_ignore_ascii_case(GZIP_NAME) {
-            Ok(Self::Gzip)
-        } else {
-            Err(format!("unknown compression algorithm `{compression_algorithm}`").into())
+            return Ok(Self::Gzip);
+        }
+        #[cfg(feature = "zstd")]
+        if compression_algorithm.eq_ignore_ascii_case(ZSTD_NAME) {
+            return Ok(Self::Zstd);
         }
+        #[cfg(feature = "brotli")]
+        if compression_algorithm.eq_ignore_ascii_case(BROTLI_NAME) {
+            return Ok(Self::Brotli);
+        }
+        Err(format!("unknown compression algorithm `{compression_algorithm}`").into())
     }
 }
 
@@ -174,6 +323,10 @@ impl CompressionAlgorithm {
     ) -> Box<dyn http::http_body_0_4_x::CompressRequest> {
         match self {
             Self::Gzip => Box::new(gzip::Gzip::from(options)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::Zstd::from(options)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::Brotli::from(options)),
         }
     }
 
@@ -185,6 +338,43 @@ impl CompressionAlgorithm {
     ) -> Box<dyn http::http_body_1_x::CompressRequest> {
         match self {
             Self::Gzip => Box::new(gzip::Gzip::from(options)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::Zstd::from(options)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::Brotli::from(options)),
+        }
+    }
+
+    /// Return a streaming compressor for this algorithm.
+    ///
+    /// Use this when the data to be compressed isn't available all at once, for example, when
+    /// compressing a streaming request body frame by frame.
+    pub fn into_streaming_impl(self, options: &CompressionOptions) -> Box<dyn CompressStream> {
+        match self {
+            Self::Gzip => Box::new(gzip::GzipStream::from(options)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::ZstdStream::from(options)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::BrotliStream::from(options)),
+        }
+    }
+
+    /// Return a streaming decompressor for this algorithm.
+    ///
+    /// If `max_decompressed_size_bytes` is set, the decompressor will return a
+    /// [`MaxDecompressedSizeExceeded`] error once its total output would exceed that size.
+    pub fn into_decompress_impl(
+        self,
+        max_decompressed_size_bytes: Option<u64>,
+    ) -> Box<dyn DecompressStream> {
+        match self {
+            Self::Gzip => Box::new(gzip::GzipDecompressStream::new(max_decompressed_size_bytes)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Box::new(zstd::ZstdDecompressStream::new(max_decompressed_size_bytes)),
+            #[cfg(feature = "brotli")]
+            Self::Brotli => Box::new(brotli::BrotliDecompressStream::new(
+                max_decompressed_size_bytes,
+            )),
         }
     }
 
@@ -192,13 +382,17 @@ impl CompressionAlgorithm {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Gzip { .. } => GZIP_NAME,
+            #[cfg(feature = "zstd")]
+            Self::Zstd => ZSTD_NAME,
+            #[cfg(feature = "brotli")]
+            Self::Brotli => BROTLI_NAME,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::CompressionAlgorithm;
+    use crate::{CompressionAlgorithm, CompressionOptions};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -217,4 +411,40 @@ mod tests {
         let algo = "gzip".parse::<CompressionAlgorithm>().unwrap();
         assert_eq!("gzip", algo.as_str());
     }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_compression_algorithm_from_str_zstd() {
+        let algo = "zstd".parse::<CompressionAlgorithm>().unwrap();
+        assert_eq!(CompressionAlgorithm::Zstd, algo);
+        assert_eq!("zstd", algo.as_str());
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_compression_algorithm_from_str_brotli() {
+        let algo = "br".parse::<CompressionAlgorithm>().unwrap();
+        assert_eq!(CompressionAlgorithm::Brotli, algo);
+        assert_eq!("br", algo.as_str());
+    }
+
+    #[test]
+    fn test_compression_levels_are_validated_per_algorithm() {
+        let options = CompressionOptions::default();
+        assert!(options.clone().with_level(10).is_err());
+        assert!(options.clone().with_zstd_level(0).is_err());
+        assert!(options.clone().with_zstd_level(23).is_err());
+        assert!(options.clone().with_brotli_level(12).is_err());
+
+        let options = options
+            .with_level(9)
+            .unwrap()
+            .with_zstd_level(19)
+            .unwrap()
+            .with_brotli_level(11)
+            .unwrap();
+        assert_eq!(9, options.level());
+        assert_eq!(19, options.zstd_level());
+        assert_eq!(11, options.brotli_level());
+    }
 }
@@ -0,0 +1,90 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::MaxDecompressedSizeExceeded;
+use aws_smithy_runtime_api::box_error::BoxError;
+use bytes::Bytes;
+use std::io::{self, Write};
+use std::mem;
+
+/// A buffer that decompressors write their output into.
+///
+/// Writes fail once the total number of bytes written would exceed the maximum size. Because the
+/// check happens as output is produced, a small payload that expands to a huge one is rejected
+/// before the decompressed data is buffered in memory.
+#[derive(Debug, Default)]
+pub(crate) struct LimitedBuffer {
+    buffer: Vec<u8>,
+    total_written: u64,
+    max_size_bytes: Option<u64>,
+}
+
+impl LimitedBuffer {
+    pub(crate) fn new(max_size_bytes: Option<u64>) -> Self {
+        Self {
+            max_size_bytes,
+            ..Default::default()
+        }
+    }
+
+    /// Take all data written since this was last called.
+    pub(crate) fn take(&mut self) -> Bytes {
+        mem::take(&mut self.buffer).into()
+    }
+}
+
+impl Write for LimitedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let total_written = self.total_written + buf.len() as u64;
+        if let Some(max_size_bytes) = self.max_size_bytes {
+            if total_written > max_size_bytes {
+                return Err(io::Error::other(MaxDecompressedSizeExceeded {
+                    max_size_bytes,
+                }));
+            }
+        }
+        self.total_written = total_written;
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Convert an error returned by a decompressor into a `BoxError`.
+///
+/// Decompressors wrap errors returned by their writer in an `io::Error`. This unwraps
+/// [`MaxDecompressedSizeExceeded`] so that callers can downcast to it directly.
+pub(crate) fn into_box_error(err: io::Error) -> BoxError {
+    if err
+        .get_ref()
+        .is_some_and(|inner| inner.is::<MaxDecompressedSizeExceeded>())
+    {
+        err.into_inner().expect("checked above")
+    } else {
+        err.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{into_box_error, LimitedBuffer};
+    use crate::MaxDecompressedSizeExceeded;
+    use std::io::Write;
+
+    #[test]
+    fn test_writes_past_the_limit_fail() {
+        let mut buffer = LimitedBuffer::new(Some(10));
+        buffer.write_all(b"hello").unwrap();
+        buffer.write_all(b"world").unwrap();
+        assert_eq!(&b"helloworld"[..], &buffer.take()[..]);
+
+        let err = into_box_error(buffer.write_all(b"!").unwrap_err());
+        assert!(err.downcast_ref::<MaxDecompressedSizeExceeded>().is_some());
+        assert!(buffer.take().is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::limited::{into_box_error, LimitedBuffer};
+use crate::{Compress, CompressStream, CompressionOptions, DecompressStream};
+use aws_smithy_runtime_api::box_error::BoxError;
+use bytes::Bytes;
+use std::io::prelude::*;
+use std::mem;
+use zstd::stream::raw::Decoder;
+use zstd::stream::write::Encoder;
+use zstd::stream::zio::Writer;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Zstd {
+    level: i32,
+}
+
+impl Zstd {
+    fn compress_bytes(&self, bytes: &[u8], writer: impl Write) -> Result<(), BoxError> {
+        let mut encoder = Encoder::new(writer, self.level)?;
+        encoder.write_all(bytes)?;
+        encoder.finish()?;
+
+        Ok(())
+    }
+}
+
+impl Compress for Zstd {
+    fn compress_bytes(&mut self, bytes: &[u8], writer: &mut dyn Write) -> Result<(), BoxError> {
+        Zstd::compress_bytes(self, bytes, writer)
+    }
+}
+
+/// A zstd compressor that maintains its state across chunks of a stream.
+pub(crate) struct ZstdStream {
+    encoder: Encoder<'static, Vec<u8>>,
+}
+
+impl CompressStream for ZstdStream {
+    fn compress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        self.encoder.write_all(bytes)?;
+        Ok(mem::take(self.encoder.get_mut()).into())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        self.encoder.do_finish()?;
+        Ok(mem::take(self.encoder.get_mut()).into())
+    }
+}
+
+impl From<&CompressionOptions> for ZstdStream {
+    fn from(options: &CompressionOptions) -> Self {
+        ZstdStream {
+            encoder: Encoder::new(Vec::new(), options.zstd_level)
+                .expect("levels are validated by `CompressionOptions`"),
+        }
+    }
+}
+
+/// A zstd decompressor that maintains its state across chunks of a stream.
+pub(crate) struct ZstdDecompressStream {
+    // Unlike `zstd::stream::write::Decoder`, the `Writer` reports a truncated frame when finished.
+    decoder: Writer<LimitedBuffer, Decoder<'static>>,
+}
+
+impl ZstdDecompressStream {
+    pub(crate) fn new(max_decompressed_size_bytes: Option<u64>) -> Self {
+        Self {
+            decoder: Writer::new(
+                LimitedBuffer::new(max_decompressed_size_bytes),
+                Decoder::new().expect("creating a decoder without a dictionary is infallible"),
+            ),
+        }
+    }
+}
+
+impl DecompressStream for ZstdDecompressStream {
+    fn decompress_chunk(&mut self, bytes: &[u8]) -> Result<Bytes, BoxError> {
+        self.decoder.write_all(bytes).map_err(into_box_error)?;
+        Ok(self.decoder.writer_mut().take())
+    }
+
+    fn finish(&mut self) -> Result<Bytes, BoxError> {
+        // Fails if the stream ended in the middle of a frame
+        self.decoder.finish().map_err(into_box_error)?;
+        Ok(self.decoder.writer_mut().take())
+    }
+}
+
+#[cfg(feature = "http-body-0-4-x")]
+mod http_body_0_4_x {
+    use crate::http::http_body_0_4_x::CompressRequest;
+
+    impl CompressRequest for super::Zstd {
+        fn header_value(&self) -> http_0_2::HeaderValue {
+            http_0_2::HeaderValue::from_static(crate::ZSTD_NAME)
+        }
+    }
+}
+
+#[cfg(feature = "http-body-1-x")]
+mod http_body_1_x {
+    use crate::http::http_body_1_x::CompressRequest;
+
+    impl CompressRequest for super::Zstd {
+        fn header_value(&self) -> http_1_0::HeaderValue {
+            http_1_0::HeaderValue::from_static(crate::ZSTD_NAME)
+        }
+    }
+}
+
+impl From<&CompressionOptions> for Zstd {
+    fn from(options: &CompressionOptions) -> Self {
+        Zstd {
+            level: options.zstd_level,
+        }
+    }
+}
+
+impl From<CompressionOptions> for Zstd {
+    fn from(options: CompressionOptions) -> Self {
+        Zstd::from(&options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Zstd, ZstdDecompressStream, ZstdStream};
+    use crate::{CompressStream, CompressionOptions, DecompressStream};
+    use pretty_assertions::assert_eq;
+
+    fn gettysburg_address() -> &'static [u8] {
+        include_bytes!("../test-data/gettysburg_address.txt")
+    }
+
+    #[test]
+    fn test_zstd_compression() {
+        let zstd = Zstd::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        zstd.compress_bytes(gettysburg_address(), &mut compressed_output)
+            .expect("compression succeeds");
+        assert!(compressed_output.len() < gettysburg_address().len());
+
+        let uncompressed_actual = zstd::decode_all(&compressed_output[..]).unwrap();
+        assert_eq!(gettysburg_address(), &uncompressed_actual[..]);
+    }
+
+    #[test]
+    fn test_zstd_compression_respects_level() {
+        for level in [1, 22] {
+            let options = CompressionOptions::default()
+                .with_zstd_level(level)
+                .unwrap();
+            let zstd = Zstd::from(&options);
+            let mut compressed_output = Vec::new();
+            zstd.compress_bytes(gettysburg_address(), &mut compressed_output)
+                .expect("compression succeeds");
+
+            let uncompressed_actual = zstd::decode_all(&compressed_output[..]).unwrap();
+            assert_eq!(gettysburg_address(), &uncompressed_actual[..]);
+        }
+    }
+
+    #[test]
+    fn test_zstd_stream_compression() {
+        let mut zstd = ZstdStream::from(&CompressionOptions::default());
+        let mut compressed_output = Vec::new();
+        for chunk in gettysburg_address().chunks(100) {
+            let compressed = zstd.compress_chunk(chunk).expect("compression succeeds");
+            compressed_output.extend_from_slice(&compressed);
+        }
+        compressed_output.extend_from_slice(&zstd.finish().expect("compression succeeds"));
+
+        let uncompressed_actual = zstd::decode_all(&compressed_output[..]).unwrap();
+        assert_eq!(gettysburg_address(), &uncompressed_actual[..]);
+    }
+
+    #[test]
+    fn test_zstd_stream_decompression() {
+        let mut compressed_input = Vec::new();
+        Zstd::from(&CompressionOptions::default())
+            .compress_bytes(gettysburg_address(), &mut compressed_input)
+            .expect("compression succeeds");
+
+        let mut zstd = ZstdDecompressStream::new(None);
+        let mut decompressed_output = Vec::new();
+        for chunk in compressed_input.chunks(100) {
+            let decompressed = zstd
+                .decompress_chunk(chunk)
+                .expect("decompression succeeds");
+            decompressed_output.extend_from_slice(&decompressed);
+        }
+        decompressed_output.extend_from_slice(&zstd.finish().expect("decompression succeeds"));
+
+        assert_eq!(gettysburg_address(), &decompressed_output[..]);
+    }
+
+    #[test]
+    fn test_zstd_stream_decompression_of_concatenated_frames() {
+        let zstd = Zstd::from(&CompressionOptions::default());
+        let mut compressed_input = Vec::new();
+        zstd.compress_bytes(b"hello ", &mut compressed_input)
+            .expect("compression succeeds");
+        zstd.compress_bytes(b"world", &mut compressed_input)
+            .expect("compression succeeds");
+
+        let mut zstd = ZstdDecompressStream::new(None);
+        let mut decompressed_output = zstd
+            .decompress_chunk(&compressed_input)
+            .expect("decompression succeeds")
+            .to_vec();
+        decompressed_output.extend_from_slice(&zstd.finish().expect("decompression succeeds"));
+        assert_eq!(b"hello world", &decompressed_output[..]);
+    }
+
+    #[test]
+    fn test_zstd_stream_decompression_of_truncated_input_fails() {
+        let mut compressed_input = Vec::new();
+        Zstd::from(&CompressionOptions::default())
+            .compress_bytes(gettysburg_address(), &mut compressed_input)
+            .expect("compression succeeds");
+        compressed_input.truncate(compressed_input.len() - 10);
+
+        let mut zstd = ZstdDecompressStream::new(None);
+        zstd.decompress_chunk(&compressed_input)
+            .expect("the input is valid so far");
+        let err = zstd.finish().expect_err("the input is truncated");
+        assert!(err.to_string().contains("incomplete frame"), "{err}");
+    }
+}
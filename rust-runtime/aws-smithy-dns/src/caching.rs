@@ -0,0 +1,364 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! DNS resolver that caches the results of another [`ResolveDns`] implementation.
+
+use crate::happy_eyeballs::{order_addresses, DEFAULT_FIRST_ADDRESS_FAMILY_COUNT};
+use aws_smithy_async::time::{SharedTimeSource, SystemTimeSource, TimeSource};
+use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns, ResolveDnsError};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_ENTRIES: usize = 256;
+
+/// DNS resolver that caches the results of another [`ResolveDns`] implementation.
+///
+/// Successful lookups are cached for the configured TTL, and failed lookups for the configured
+/// negative TTL. Once a successful lookup has expired, it continues to be returned for the
+/// configured stale-while-revalidate window while it's refreshed in the background, so that
+/// requests don't wait on DNS for hosts that are used regularly. Background refreshes require a
+/// [tokio] runtime; without one, expired entries are refreshed before returning.
+///
+/// Resolved addresses can optionally be ordered for
+/// [Happy Eyeballs](crate::happy_eyeballs) connection racing.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn example() {
+/// use aws_smithy_dns::CachingDnsResolver;
+/// use aws_smithy_runtime_api::client::dns::ResolveDns;
+/// use std::time::Duration;
+/// # #[derive(Debug)]
+/// # struct SomeResolver;
+/// # impl ResolveDns for SomeResolver {
+/// #     fn resolve_dns<'a>(&'a self, _name: &'a str) -> aws_smithy_runtime_api::client::dns::DnsFuture<'a> {
+/// #         unimplemented!()
+/// #     }
+/// # }
+///
+/// let resolver = CachingDnsResolver::builder()
+///     .ttl(Duration::from_secs(60))
+///     .stale_while_revalidate(Duration::from_secs(30))
+///     .happy_eyeballs(true)
+///     .build(SomeResolver);
+/// let addresses = resolver.resolve_dns("example.com").await;
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CachingDnsResolver<R> {
+    inner: Arc<Inner<R>>,
+}
+
+impl<R> Clone for CachingDnsResolver<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl CachingDnsResolver<()> {
+    /// Returns a builder for a `CachingDnsResolver`
+    pub fn builder() -> CachingDnsResolverBuilder {
+        CachingDnsResolverBuilder::new()
+    }
+}
+
+impl<R: ResolveDns + 'static> CachingDnsResolver<R> {
+    /// Creates a new `CachingDnsResolver` with the default configuration that caches the results
+    /// of `resolver`
+    pub fn new(resolver: R) -> Self {
+        CachingDnsResolverBuilder::new().build(resolver)
+    }
+
+    /// Flush the cache
+    pub fn clear_cache(&self) {
+        self.inner.cache.lock().unwrap().clear();
+    }
+
+    /// Refresh a stale entry in the background, or before returning if there is no runtime to
+    /// refresh it on
+    async fn revalidate(&self, name: String) -> Option<Vec<IpAddr>> {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                let inner = self.inner.clone();
+                handle.spawn(async move {
+                    if let Err(err) = inner.resolve(name.clone()).await {
+                        tracing::debug!(name = %name, error = %err, "failed to refresh stale DNS entry");
+                    }
+                });
+                None
+            }
+            Err(_) => self.inner.resolve(name).await.ok(),
+        }
+    }
+}
+
+impl<R: ResolveDns + 'static> ResolveDns for CachingDnsResolver<R> {
+    fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+        DnsFuture::new(async move {
+            let name = name.to_ascii_lowercase();
+            match self.inner.lookup(&name) {
+                Lookup::Fresh(Ok(addresses)) => Ok(addresses),
+                Lookup::Fresh(Err(failure)) => Err(ResolveDnsError::new(failure)),
+                Lookup::Stale(addresses) => Ok(self.revalidate(name).await.unwrap_or(addresses)),
+                Lookup::Miss => self.inner.resolve(name).await,
+            }
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Inner<R> {
+    resolver: R,
+    time_source: SharedTimeSource,
+    ttl: Duration,
+    negative_ttl: Duration,
+    stale_while_revalidate: Duration,
+    max_entries: usize,
+    first_address_family_count: Option<usize>,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: Result<Vec<IpAddr>, CachedDnsFailure>,
+    expires_at: SystemTime,
+    refreshing: bool,
+}
+
+impl CacheEntry {
+    /// The time after which this entry may no longer be returned
+    fn evict_at(&self, stale_while_revalidate: Duration) -> SystemTime {
+        match self.result {
+            Ok(_) => self.expires_at + stale_while_revalidate,
+            Err(_) => self.expires_at,
+        }
+    }
+}
+
+enum Lookup {
+    Fresh(Result<Vec<IpAddr>, CachedDnsFailure>),
+    Stale(Vec<IpAddr>),
+    Miss,
+}
+
+impl<R: ResolveDns> Inner<R> {
+    fn lookup(&self, name: &str) -> Lookup {
+        let now = self.time_source.now();
+        let mut cache = self.cache.lock().unwrap();
+        let Some(entry) = cache.get_mut(name) else {
+            return Lookup::Miss;
+        };
+        if now < entry.expires_at {
+            return Lookup::Fresh(entry.result.clone());
+        }
+        match &entry.result {
+            Ok(addresses) if now < entry.evict_at(self.stale_while_revalidate) => {
+                if entry.refreshing {
+                    // another caller is already refreshing this entry
+                    Lookup::Fresh(Ok(addresses.clone()))
+                } else {
+                    entry.refreshing = true;
+                    Lookup::Stale(addresses.clone())
+                }
+            }
+            _ => Lookup::Miss,
+        }
+    }
+
+    async fn resolve(&self, name: String) -> Result<Vec<IpAddr>, ResolveDnsError> {
+        let result = self.resolver.resolve_dns(&name).await;
+        let now = self.time_source.now();
+        let mut cache = self.cache.lock().unwrap();
+        match result {
+            Ok(addresses) => {
+                let addresses = match self.first_address_family_count {
+                    Some(count) => order_addresses(addresses, count),
+                    None => addresses,
+                };
+                self.insert(
+                    &mut cache,
+                    name,
+                    CacheEntry {
+                        result: Ok(addresses.clone()),
+                        expires_at: now + self.ttl,
+                        refreshing: false,
+                    },
+                );
+                Ok(addresses)
+            }
+            Err(err) => {
+                // keep serving a stale entry for the rest of its window if refreshing it failed
+                if let Some(entry) = cache.get_mut(&name) {
+                    if entry.result.is_ok() && now < entry.evict_at(self.stale_while_revalidate) {
+                        entry.refreshing = false;
+                        return Err(err);
+                    }
+                }
+                if self.negative_ttl.is_zero() {
+                    cache.remove(&name);
+                    return Err(err);
+                }
+                let failure = CachedDnsFailure(Arc::new(err));
+                self.insert(
+                    &mut cache,
+                    name,
+                    CacheEntry {
+                        result: Err(failure.clone()),
+                        expires_at: now + self.negative_ttl,
+                        refreshing: false,
+                    },
+                );
+                Err(ResolveDnsError::new(failure))
+            }
+        }
+    }
+
+    fn insert(&self, cache: &mut HashMap<String, CacheEntry>, name: String, entry: CacheEntry) {
+        if !cache.contains_key(&name) && cache.len() >= self.max_entries {
+            let now = self.time_source.now();
+            cache.retain(|_, entry| now < entry.evict_at(self.stale_while_revalidate));
+            if cache.len() >= self.max_entries {
+                let oldest = cache
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(name, _)| name.clone());
+                if let Some(oldest) = oldest {
+                    cache.remove(&oldest);
+                }
+            }
+        }
+        if self.max_entries > 0 {
+            cache.insert(name, entry);
+        }
+    }
+}
+
+/// A failed DNS lookup, returned from the cache until its negative TTL expires
+#[derive(Clone, Debug)]
+struct CachedDnsFailure(Arc<ResolveDnsError>);
+
+impl fmt::Display for CachedDnsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DNS lookup failed (the failure is cached)")
+    }
+}
+
+impl StdError for CachedDnsFailure {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(self.0.as_ref())
+    }
+}
+
+/// Builder for [`CachingDnsResolver`]
+#[derive(Clone, Debug)]
+pub struct CachingDnsResolverBuilder {
+    time_source: Option<SharedTimeSource>,
+    ttl: Duration,
+    negative_ttl: Duration,
+    stale_while_revalidate: Duration,
+    max_entries: usize,
+    first_address_family_count: Option<usize>,
+}
+
+impl Default for CachingDnsResolverBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CachingDnsResolverBuilder {
+    /// Creates a new builder
+    pub fn new() -> Self {
+        Self {
+            time_source: None,
+            ttl: DEFAULT_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            stale_while_revalidate: Duration::ZERO,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            first_address_family_count: None,
+        }
+    }
+
+    /// How long successful lookups are cached for. Defaults to 30 seconds.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// How long failed lookups are cached for. Defaults to 5 seconds.
+    ///
+    /// Setting this to zero disables caching of failed lookups.
+    pub fn negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    /// How long an expired successful lookup continues to be returned while it's refreshed.
+    /// Defaults to zero, which disables stale-while-revalidate.
+    pub fn stale_while_revalidate(mut self, window: Duration) -> Self {
+        self.stale_while_revalidate = window;
+        self
+    }
+
+    /// Maximum number of host names to cache. Defaults to 256.
+    ///
+    /// When the cache is full, the entry that expires first is evicted.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Order resolved addresses for [Happy Eyeballs](crate::happy_eyeballs) connection racing.
+    /// Disabled by default, which preserves the order returned by the underlying resolver.
+    pub fn happy_eyeballs(mut self, enabled: bool) -> Self {
+        self.first_address_family_count = enabled.then_some(
+            self.first_address_family_count
+                .unwrap_or(DEFAULT_FIRST_ADDRESS_FAMILY_COUNT),
+        );
+        self
+    }
+
+    /// Number of IPv6 addresses to attempt before alternating address families when Happy
+    /// Eyeballs ordering is enabled. Defaults to 1.
+    ///
+    /// Setting this enables Happy Eyeballs ordering.
+    pub fn first_address_family_count(mut self, count: usize) -> Self {
+        self.first_address_family_count = Some(count);
+        self
+    }
+
+    /// Set the time source used to expire cache entries. Defaults to the system clock.
+    pub fn time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = Some(SharedTimeSource::new(time_source));
+        self
+    }
+
+    /// Build a [`CachingDnsResolver`] that caches the results of `resolver`
+    pub fn build<R: ResolveDns + 'static>(self, resolver: R) -> CachingDnsResolver<R> {
+        CachingDnsResolver {
+            inner: Arc::new(Inner {
+                resolver,
+                time_source: self
+                    .time_source
+                    .unwrap_or_else(|| SharedTimeSource::new(SystemTimeSource::new())),
+                ttl: self.ttl,
+                negative_ttl: self.negative_ttl,
+                stale_while_revalidate: self.stale_while_revalidate,
+                max_entries: self.max_entries,
+                first_address_family_count: self.first_address_family_count,
+                cache: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
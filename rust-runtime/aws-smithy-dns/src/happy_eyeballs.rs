@@ -0,0 +1,61 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Address ordering for [Happy Eyeballs](https://www.rfc-editor.org/rfc/rfc8305) connection racing.
+//!
+//! Connectors that race connection attempts (such as the connectors in `aws-smithy-http-client`)
+//! prefer the address family of the first resolved address, and fall back to the other family if
+//! connecting takes too long. Ordering resolved addresses as described in
+//! [RFC 8305 section 4](https://www.rfc-editor.org/rfc/rfc8305#section-4) makes IPv6 the preferred
+//! family while keeping IPv4 addresses near the front of the list, so that a broken IPv6 network
+//! costs at most one connection attempt delay.
+
+use std::net::IpAddr;
+
+/// Default number of addresses of the preferred address family (IPv6) to attempt before
+/// alternating address families.
+pub const DEFAULT_FIRST_ADDRESS_FAMILY_COUNT: usize = 1;
+
+/// Order `addresses` for Happy Eyeballs connection racing.
+///
+/// The first `first_address_family_count` addresses are IPv6 addresses (if there are any), after
+/// which the remaining addresses alternate between IPv4 and IPv6. The relative order of addresses
+/// within each address family is preserved.
+///
+/// # Examples
+///
+/// ```rust
+/// use aws_smithy_dns::happy_eyeballs::order_addresses;
+/// use std::net::IpAddr;
+///
+/// let addresses: Vec<IpAddr> = vec![
+///     "192.0.2.1".parse().unwrap(),
+///     "192.0.2.2".parse().unwrap(),
+///     "2001:db8::1".parse().unwrap(),
+///     "2001:db8::2".parse().unwrap(),
+/// ];
+/// let ordered: Vec<IpAddr> = vec![
+///     "2001:db8::1".parse().unwrap(),
+///     "192.0.2.1".parse().unwrap(),
+///     "2001:db8::2".parse().unwrap(),
+///     "192.0.2.2".parse().unwrap(),
+/// ];
+/// assert_eq!(ordered, order_addresses(addresses, 1));
+/// ```
+pub fn order_addresses(addresses: Vec<IpAddr>, first_address_family_count: usize) -> Vec<IpAddr> {
+    let (v6, v4): (Vec<_>, Vec<_>) = addresses.into_iter().partition(IpAddr::is_ipv6);
+    let mut ordered = Vec::with_capacity(v6.len() + v4.len());
+    let mut v6 = v6.into_iter();
+    let mut v4 = v4.into_iter();
+
+    ordered.extend(v6.by_ref().take(first_address_family_count.max(1)));
+    loop {
+        match (v4.next(), v6.next()) {
+            (None, None) => break,
+            (v4, v6) => ordered.extend(v4.into_iter().chain(v6)),
+        }
+    }
+    ordered
+}
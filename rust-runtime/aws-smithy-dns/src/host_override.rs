@@ -0,0 +1,282 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! DNS resolver that answers from static host rules, for local stand-ins and split-horizon DNS.
+
+use aws_smithy_runtime_api::client::dns::{
+    DnsFuture, ResolveDns, ResolveDnsError, SharedDnsResolver,
+};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// DNS resolver that answers from static host rules, falling back to another resolver.
+///
+/// Three kinds of rules are supported:
+/// - **Exact** rules such as `localstack.local` match a single host name.
+/// - **Wildcard** rules such as `*.s3.us-east-1.amazonaws.com` match any host name ending in
+///   the part after the `*`, with any number of labels in place of the `*`. They don't match
+///   `s3.us-east-1.amazonaws.com` itself. When several wildcard rules match, the longest one wins.
+/// - **CIDR** rules such as `203.0.113.0/24` rewrite the addresses returned by the fallback
+///   resolver: any returned address within the CIDR block is replaced with the rule's addresses.
+///
+/// Exact rules take precedence over wildcard rules. Host names are matched case-insensitively,
+/// ignoring a trailing `.`. Host names that don't match any exact or wildcard rule are resolved
+/// with the fallback resolver, or fail to resolve if there isn't one.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use aws_smithy_dns::{HickoryDnsResolver, HostOverrideResolver};
+/// use aws_smithy_http_client::{tls, Builder};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let localhost = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// let resolver = HostOverrideResolver::builder()
+///     .override_host("*.s3.us-east-1.amazonaws.com", [localhost])
+///     .override_host("sts.amazonaws.com", [localhost])
+///     .fallback(HickoryDnsResolver::default())
+///     .build()
+///     .expect("valid rules");
+/// let http_client = Builder::new()
+///     .tls_provider(tls::Provider::Rustls(tls::rustls_provider::CryptoMode::Ring))
+///     .build_with_resolver(resolver);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HostOverrideResolver {
+    inner: Arc<Rules>,
+}
+
+#[derive(Debug)]
+struct Rules {
+    exact: HashMap<String, Vec<IpAddr>>,
+    /// Wildcard suffixes (including the leading `.`), longest first
+    wildcards: Vec<(String, Vec<IpAddr>)>,
+    cidrs: Vec<(Cidr, Vec<IpAddr>)>,
+    fallback: Option<SharedDnsResolver>,
+}
+
+impl HostOverrideResolver {
+    /// Returns a builder for a `HostOverrideResolver`
+    pub fn builder() -> HostOverrideResolverBuilder {
+        HostOverrideResolverBuilder::default()
+    }
+}
+
+impl Rules {
+    fn lookup(&self, name: &str) -> Option<&[IpAddr]> {
+        if let Some(addresses) = self.exact.get(name) {
+            return Some(addresses);
+        }
+        self.wildcards
+            .iter()
+            .find(|(suffix, _)| name.len() > suffix.len() && name.ends_with(suffix.as_str()))
+            .map(|(_, addresses)| addresses.as_slice())
+    }
+
+    fn rewrite(&self, addresses: Vec<IpAddr>) -> Vec<IpAddr> {
+        if self.cidrs.is_empty() {
+            return addresses;
+        }
+        let mut rewritten = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let replacements = self
+                .cidrs
+                .iter()
+                .find(|(cidr, _)| cidr.contains(address))
+                .map(|(_, replacements)| replacements.as_slice())
+                .unwrap_or(std::slice::from_ref(&address));
+            for replacement in replacements {
+                if !rewritten.contains(replacement) {
+                    rewritten.push(*replacement);
+                }
+            }
+        }
+        rewritten
+    }
+}
+
+impl ResolveDns for HostOverrideResolver {
+    fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+        let normalized = name.trim_end_matches('.').to_ascii_lowercase();
+        if let Some(addresses) = self.inner.lookup(&normalized) {
+            return DnsFuture::ready(Ok(addresses.to_vec()));
+        }
+        match &self.inner.fallback {
+            Some(fallback) => DnsFuture::new(async move {
+                let addresses = fallback.resolve_dns(name).await?;
+                Ok(self.inner.rewrite(addresses))
+            }),
+            None => DnsFuture::ready(Err(ResolveDnsError::new(format!(
+                "no host override matches `{name}` and no fallback resolver is configured"
+            )))),
+        }
+    }
+}
+
+/// Builder for [`HostOverrideResolver`]
+#[derive(Debug, Default)]
+pub struct HostOverrideResolverBuilder {
+    hosts: Vec<(String, Vec<IpAddr>)>,
+    cidrs: Vec<(String, Vec<IpAddr>)>,
+    fallback: Option<SharedDnsResolver>,
+}
+
+impl HostOverrideResolverBuilder {
+    /// Resolve `host` to `addresses`
+    ///
+    /// `host` is either an exact host name, or a wildcard such as `*.amazonaws.com`. Adding a
+    /// rule for a host that already has one replaces it.
+    pub fn override_host(
+        mut self,
+        host: impl Into<String>,
+        addresses: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        self.hosts
+            .push((host.into(), addresses.into_iter().collect()));
+        self
+    }
+
+    /// Replace addresses returned by the fallback resolver that are within `cidr` (e.g.
+    /// `203.0.113.0/24`) with `addresses`
+    ///
+    /// If an address is within more than one CIDR block, the first matching rule is used.
+    pub fn rewrite_cidr(
+        mut self,
+        cidr: impl Into<String>,
+        addresses: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        self.cidrs
+            .push((cidr.into(), addresses.into_iter().collect()));
+        self
+    }
+
+    /// Resolve host names that don't match any rule with `resolver`
+    pub fn fallback(mut self, resolver: impl ResolveDns + 'static) -> Self {
+        self.fallback = Some(SharedDnsResolver::new(resolver));
+        self
+    }
+
+    /// Build a [`HostOverrideResolver`], validating its rules
+    pub fn build(self) -> Result<HostOverrideResolver, InvalidHostOverride> {
+        let mut exact = HashMap::new();
+        let mut wildcards = HashMap::new();
+        for (host, addresses) in self.hosts {
+            if addresses.is_empty() {
+                return Err(InvalidHostOverride::new(format!(
+                    "no addresses were given for `{host}`"
+                )));
+            }
+            let normalized = host.trim_end_matches('.').to_ascii_lowercase();
+            match normalized.strip_prefix('*') {
+                Some(suffix)
+                    if suffix.starts_with('.') && suffix.len() > 1 && !suffix.contains('*') =>
+                {
+                    wildcards.insert(suffix.to_string(), addresses);
+                }
+                _ if !normalized.is_empty() && !normalized.contains('*') => {
+                    exact.insert(normalized, addresses);
+                }
+                _ => {
+                    return Err(InvalidHostOverride::new(format!(
+                        "`{host}` is not a valid host name or wildcard"
+                    )))
+                }
+            }
+        }
+        let mut wildcards = wildcards.into_iter().collect::<Vec<_>>();
+        wildcards.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let cidrs = self
+            .cidrs
+            .into_iter()
+            .map(|(cidr, addresses)| {
+                if addresses.is_empty() {
+                    return Err(InvalidHostOverride::new(format!(
+                        "no addresses were given for `{cidr}`"
+                    )));
+                }
+                Ok((Cidr::parse(&cidr)?, addresses))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !cidrs.is_empty() && self.fallback.is_none() {
+            return Err(InvalidHostOverride::new(
+                "CIDR rules rewrite the addresses returned by the fallback resolver, \
+                 but no fallback resolver is configured",
+            ));
+        }
+
+        Ok(HostOverrideResolver {
+            inner: Arc::new(Rules {
+                exact,
+                wildcards,
+                cidrs,
+                fallback: self.fallback,
+            }),
+        })
+    }
+}
+
+/// A rule given to [`HostOverrideResolverBuilder`] is invalid
+#[derive(Debug)]
+pub struct InvalidHostOverride {
+    message: String,
+}
+
+impl InvalidHostOverride {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for InvalidHostOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid host override: {}", self.message)
+    }
+}
+
+impl StdError for InvalidHostOverride {}
+
+/// An IPv4 or IPv6 CIDR block
+#[derive(Debug)]
+struct Cidr {
+    network: IpAddr,
+    prefix_len: u32,
+}
+
+impl Cidr {
+    fn parse(cidr: &str) -> Result<Self, InvalidHostOverride> {
+        let invalid = || InvalidHostOverride::new(format!("`{cidr}` is not a valid CIDR block"));
+        let (network, prefix_len) = cidr.split_once('/').ok_or_else(invalid)?;
+        let network: IpAddr = network.parse().map_err(|_| invalid())?;
+        let prefix_len: u32 = prefix_len.parse().map_err(|_| invalid())?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max_prefix_len {
+            return Err(invalid());
+        }
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len).unwrap_or(0);
+                u32::from(network) & mask == u32::from(address) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len).unwrap_or(0);
+                u128::from(network) & mask == u128::from(address) & mask
+            }
+            _ => false,
+        }
+    }
+}
@@ -9,8 +9,16 @@
 
 //! Built-in DNS resolver implementations for smithy-rs clients.
 
+#[cfg(feature = "caching")]
+pub mod caching;
+pub mod happy_eyeballs;
 #[cfg(all(feature = "hickory-dns", not(target_family = "wasm")))]
 pub mod hickory;
+pub mod host_override;
+
+#[cfg(feature = "caching")]
+pub use caching::CachingDnsResolver;
+pub use host_override::HostOverrideResolver;
 
 #[cfg(all(feature = "hickory-dns", not(target_family = "wasm")))]
 pub use hickory::HickoryDnsResolver;
@@ -0,0 +1,286 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(feature = "caching")]
+
+use aws_smithy_async::test_util::ManualTimeSource;
+use aws_smithy_dns::CachingDnsResolver;
+use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns, ResolveDnsError};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Resolver that returns the addresses it's been given for a host, and counts lookups
+#[derive(Clone, Debug, Default)]
+struct TestResolver {
+    records: Arc<Mutex<HashMap<String, Vec<IpAddr>>>>,
+    query_count: Arc<AtomicUsize>,
+}
+
+impl TestResolver {
+    fn set(&self, name: &str, addresses: &[&str]) {
+        self.records.lock().unwrap().insert(
+            name.to_string(),
+            addresses.iter().map(|ip| ip.parse().unwrap()).collect(),
+        );
+    }
+
+    fn remove(&self, name: &str) {
+        self.records.lock().unwrap().remove(name);
+    }
+
+    fn query_count(&self) -> usize {
+        self.query_count.load(Ordering::SeqCst)
+    }
+}
+
+impl ResolveDns for TestResolver {
+    fn resolve_dns<'a>(&'a self, name: &'a str) -> DnsFuture<'a> {
+        self.query_count.fetch_add(1, Ordering::SeqCst);
+        let result = self
+            .records
+            .lock()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| ResolveDnsError::new(format!("no records for {name}")));
+        DnsFuture::ready(result)
+    }
+}
+
+fn ips(addresses: &[&str]) -> Vec<IpAddr> {
+    addresses.iter().map(|ip| ip.parse().unwrap()).collect()
+}
+
+fn time_source() -> ManualTimeSource {
+    ManualTimeSource::new(UNIX_EPOCH + Duration::from_secs(1_000_000))
+}
+
+#[tokio::test]
+async fn lookups_are_cached_until_the_ttl_expires() {
+    let inner = TestResolver::default();
+    inner.set("example.com", &["192.0.2.1"]);
+    let time = time_source();
+    let resolver = CachingDnsResolver::builder()
+        .ttl(Duration::from_secs(10))
+        .time_source(time.clone())
+        .build(inner.clone());
+
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("EXAMPLE.com").await.unwrap()
+    );
+    assert_eq!(1, inner.query_count());
+
+    inner.set("example.com", &["192.0.2.2"]);
+    time.advance(Duration::from_secs(9));
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    assert_eq!(1, inner.query_count());
+
+    time.advance(Duration::from_secs(1));
+    assert_eq!(
+        ips(&["192.0.2.2"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    assert_eq!(2, inner.query_count());
+
+    resolver.clear_cache();
+    resolver.resolve_dns("example.com").await.unwrap();
+    assert_eq!(3, inner.query_count());
+}
+
+#[tokio::test]
+async fn failures_are_cached_until_the_negative_ttl_expires() {
+    let inner = TestResolver::default();
+    let time = time_source();
+    let resolver = CachingDnsResolver::builder()
+        .negative_ttl(Duration::from_secs(5))
+        .time_source(time.clone())
+        .build(inner.clone());
+
+    resolver.resolve_dns("example.com").await.unwrap_err();
+    inner.set("example.com", &["192.0.2.1"]);
+    let err = resolver.resolve_dns("example.com").await.unwrap_err();
+    assert_eq!(1, inner.query_count());
+    // the original failure is available as a source
+    let mut source = std::error::Error::source(&err);
+    let mut messages = vec![];
+    while let Some(err) = source {
+        messages.push(err.to_string());
+        source = err.source();
+    }
+    assert!(messages.contains(&"no records for example.com".to_string()));
+
+    time.advance(Duration::from_secs(5));
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    assert_eq!(2, inner.query_count());
+}
+
+#[tokio::test]
+async fn negative_caching_can_be_disabled() {
+    let inner = TestResolver::default();
+    let resolver = CachingDnsResolver::builder()
+        .negative_ttl(Duration::ZERO)
+        .build(inner.clone());
+
+    resolver.resolve_dns("example.com").await.unwrap_err();
+    resolver.resolve_dns("example.com").await.unwrap_err();
+    assert_eq!(2, inner.query_count());
+}
+
+#[tokio::test]
+async fn stale_entries_are_returned_while_revalidating() {
+    let inner = TestResolver::default();
+    inner.set("example.com", &["192.0.2.1"]);
+    let time = time_source();
+    let resolver = CachingDnsResolver::builder()
+        .ttl(Duration::from_secs(10))
+        .stale_while_revalidate(Duration::from_secs(10))
+        .time_source(time.clone())
+        .build(inner.clone());
+
+    resolver.resolve_dns("example.com").await.unwrap();
+    inner.set("example.com", &["192.0.2.2"]);
+    time.advance(Duration::from_secs(15));
+
+    // the stale entry is returned immediately, and refreshed in the background
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    for _ in 0..100 {
+        if inner.query_count() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(2, inner.query_count());
+    assert_eq!(
+        ips(&["192.0.2.2"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    assert_eq!(2, inner.query_count());
+}
+
+#[tokio::test]
+async fn stale_entries_outlive_failed_revalidation_until_the_window_ends() {
+    let inner = TestResolver::default();
+    inner.set("example.com", &["192.0.2.1"]);
+    let time = time_source();
+    let resolver = CachingDnsResolver::builder()
+        .ttl(Duration::from_secs(10))
+        .stale_while_revalidate(Duration::from_secs(10))
+        .time_source(time.clone())
+        .build(inner.clone());
+
+    resolver.resolve_dns("example.com").await.unwrap();
+    inner.remove("example.com");
+    time.advance(Duration::from_secs(15));
+
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+
+    time.advance(Duration::from_secs(5));
+    resolver.resolve_dns("example.com").await.unwrap_err();
+}
+
+#[tokio::test]
+async fn oldest_entry_is_evicted_when_full() {
+    let inner = TestResolver::default();
+    inner.set("a.com", &["192.0.2.1"]);
+    inner.set("b.com", &["192.0.2.2"]);
+    inner.set("c.com", &["192.0.2.3"]);
+    let time = time_source();
+    let resolver = CachingDnsResolver::builder()
+        .max_entries(2)
+        .time_source(time.clone())
+        .build(inner.clone());
+
+    resolver.resolve_dns("a.com").await.unwrap();
+    time.advance(Duration::from_secs(1));
+    resolver.resolve_dns("b.com").await.unwrap();
+    resolver.resolve_dns("c.com").await.unwrap();
+    assert_eq!(3, inner.query_count());
+
+    resolver.resolve_dns("b.com").await.unwrap();
+    resolver.resolve_dns("c.com").await.unwrap();
+    assert_eq!(3, inner.query_count());
+    resolver.resolve_dns("a.com").await.unwrap();
+    assert_eq!(4, inner.query_count());
+}
+
+#[tokio::test]
+async fn addresses_are_ordered_for_happy_eyeballs() {
+    let inner = TestResolver::default();
+    inner.set(
+        "example.com",
+        &[
+            "192.0.2.1",
+            "192.0.2.2",
+            "192.0.2.3",
+            "2001:db8::1",
+            "2001:db8::2",
+        ],
+    );
+
+    let resolver = CachingDnsResolver::new(inner.clone());
+    assert_eq!(
+        ips(&[
+            "192.0.2.1",
+            "192.0.2.2",
+            "192.0.2.3",
+            "2001:db8::1",
+            "2001:db8::2"
+        ]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+
+    let resolver = CachingDnsResolver::builder()
+        .happy_eyeballs(true)
+        .build(inner.clone());
+    assert_eq!(
+        ips(&[
+            "2001:db8::1",
+            "192.0.2.1",
+            "2001:db8::2",
+            "192.0.2.2",
+            "192.0.2.3"
+        ]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+
+    let resolver = CachingDnsResolver::builder()
+        .first_address_family_count(2)
+        .build(inner);
+    assert_eq!(
+        ips(&[
+            "2001:db8::1",
+            "2001:db8::2",
+            "192.0.2.1",
+            "192.0.2.2",
+            "192.0.2.3"
+        ]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+}
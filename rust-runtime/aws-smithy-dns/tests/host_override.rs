@@ -0,0 +1,151 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_dns::HostOverrideResolver;
+use aws_smithy_runtime_api::client::dns::{DnsFuture, ResolveDns};
+use std::net::IpAddr;
+
+/// Resolver that resolves every host name to the same addresses
+#[derive(Debug)]
+struct FixedResolver(Vec<IpAddr>);
+
+impl ResolveDns for FixedResolver {
+    fn resolve_dns<'a>(&'a self, _name: &'a str) -> DnsFuture<'a> {
+        DnsFuture::ready(Ok(self.0.clone()))
+    }
+}
+
+fn ips(addresses: &[&str]) -> Vec<IpAddr> {
+    addresses.iter().map(|ip| ip.parse().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn exact_and_wildcard_rules() {
+    let resolver = HostOverrideResolver::builder()
+        .override_host("*.s3.us-east-1.amazonaws.com", ips(&["127.0.0.1"]))
+        .override_host("*.us-east-1.amazonaws.com", ips(&["127.0.0.2"]))
+        .override_host("special.s3.us-east-1.amazonaws.com.", ips(&["::1"]))
+        .fallback(FixedResolver(ips(&["192.0.2.1"])))
+        .build()
+        .unwrap();
+
+    let resolve = |name: &'static str| {
+        let resolver = resolver.clone();
+        async move { resolver.resolve_dns(name).await.unwrap() }
+    };
+    assert_eq!(
+        ips(&["127.0.0.1"]),
+        resolve("bucket.s3.us-east-1.amazonaws.com").await
+    );
+    assert_eq!(
+        ips(&["127.0.0.1"]),
+        resolve("a.b.S3.us-east-1.amazonaws.com.").await
+    );
+    assert_eq!(
+        ips(&["::1"]),
+        resolve("special.s3.us-east-1.amazonaws.com").await
+    );
+    assert_eq!(
+        ips(&["127.0.0.2"]),
+        resolve("s3.us-east-1.amazonaws.com").await
+    );
+    assert_eq!(
+        ips(&["127.0.0.2"]),
+        resolve("sts.us-east-1.amazonaws.com").await
+    );
+    assert_eq!(
+        ips(&["192.0.2.1"]),
+        resolve("us-east-1.amazonaws.com").await
+    );
+    assert_eq!(ips(&["192.0.2.1"]), resolve("example.com").await);
+}
+
+#[tokio::test]
+async fn unmatched_hosts_fail_without_a_fallback() {
+    let resolver = HostOverrideResolver::builder()
+        .override_host("localstack.local", ips(&["127.0.0.1"]))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        ips(&["127.0.0.1"]),
+        resolver.resolve_dns("localstack.local").await.unwrap()
+    );
+    resolver.resolve_dns("example.com").await.unwrap_err();
+}
+
+#[tokio::test]
+async fn cidr_rules_rewrite_fallback_results() {
+    let resolver = HostOverrideResolver::builder()
+        .override_host("pinned.example.com", ips(&["203.0.113.7"]))
+        .rewrite_cidr("203.0.113.0/24", ips(&["10.0.0.1", "10.0.0.2"]))
+        .rewrite_cidr("2001:db8::/32", ips(&["fd00::1"]))
+        .fallback(FixedResolver(ips(&[
+            "203.0.113.1",
+            "198.51.100.1",
+            "203.0.113.2",
+            "2001:db8::1",
+            "2001:db9::1",
+        ])))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        ips(&[
+            "10.0.0.1",
+            "10.0.0.2",
+            "198.51.100.1",
+            "fd00::1",
+            "2001:db9::1"
+        ]),
+        resolver.resolve_dns("example.com").await.unwrap()
+    );
+    // addresses from host rules are not rewritten
+    assert_eq!(
+        ips(&["203.0.113.7"]),
+        resolver.resolve_dns("pinned.example.com").await.unwrap()
+    );
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    let localhost = ips(&["127.0.0.1"]);
+    let invalid_hosts = ["", "*", "*.", "a.*.com", "**.example.com", "*example.com"];
+    for host in invalid_hosts {
+        HostOverrideResolver::builder()
+            .override_host(host, localhost.clone())
+            .build()
+            .expect_err(host);
+    }
+    HostOverrideResolver::builder()
+        .override_host("example.com", [])
+        .build()
+        .expect_err("no addresses");
+
+    let fallback = || FixedResolver(localhost.clone());
+    for cidr in [
+        "10.0.0.0",
+        "10.0.0.0/33",
+        "::/129",
+        "example.com/8",
+        "10.0.0.0/x",
+    ] {
+        HostOverrideResolver::builder()
+            .rewrite_cidr(cidr, localhost.clone())
+            .fallback(fallback())
+            .build()
+            .expect_err(cidr);
+    }
+    HostOverrideResolver::builder()
+        .rewrite_cidr("10.0.0.0/8", localhost.clone())
+        .build()
+        .expect_err("no fallback resolver");
+    HostOverrideResolver::builder()
+        .rewrite_cidr("0.0.0.0/0", localhost.clone())
+        .rewrite_cidr("::/0", localhost.clone())
+        .fallback(fallback())
+        .build()
+        .expect("valid rules");
+}
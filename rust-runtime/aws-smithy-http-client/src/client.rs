@@ -4,6 +4,7 @@
  */
 
 mod dns;
+mod metrics;
 /// Proxy configuration
 pub mod proxy;
 mod timeout;
@@ -13,7 +14,7 @@ pub mod tls;
 pub(crate) mod connect;
 
 use crate::cfg::cfg_tls;
-use crate::tls::TlsContext;
+use crate::tls::{CertificateVerificationError, TlsContext};
 use aws_smithy_async::future::timeout::TimedOutError;
 use aws_smithy_async::rt::sleep::{default_async_sleep, AsyncSleep, SharedAsyncSleep};
 use aws_smithy_runtime_api::box_error::BoxError;
@@ -115,6 +116,7 @@ pub struct ConnectorBuilder<Tls = TlsUnset> {
     client_builder: Option<hyper_util::client::legacy::Builder>,
     enable_tcp_nodelay: bool,
     interface: Option<String>,
+    happy_eyeballs_timeout: Option<Option<Duration>>,
     proxy_config: Option<proxy::ProxyConfig>,
     #[allow(unused)]
     tls: Tls,
@@ -143,6 +145,7 @@ impl ConnectorBuilder<TlsUnset> {
             client_builder: self.client_builder,
             enable_tcp_nodelay: self.enable_tcp_nodelay,
             interface: self.interface,
+            happy_eyeballs_timeout: self.happy_eyeballs_timeout,
             proxy_config: self.proxy_config,
             tls: TlsProviderSelected {
                 provider,
@@ -206,6 +209,8 @@ impl<Any> ConnectorBuilder<Any> {
             .map(|c| (c.connect_timeout(), c.read_timeout()))
             .unwrap_or((None, None));
 
+        let metrics = metrics::ConnectionMetrics::new();
+        let tcp_connector = metrics::MeteredConnector::new(tcp_connector, metrics.clone());
         let connector = match connect_timeout {
             Some(duration) => timeout::ConnectTimeout::new(
                 tcp_connector,
@@ -235,6 +240,7 @@ impl<Any> ConnectorBuilder<Any> {
             adapter: Box::new(Adapter {
                 client: read_timeout,
                 proxy_matcher,
+                metrics,
             }),
         }
     }
@@ -254,6 +260,9 @@ impl<Any> ConnectorBuilder<Any> {
         if let Some(interface) = &self.interface {
             conn.set_interface(interface);
         }
+        if let Some(happy_eyeballs_timeout) = self.happy_eyeballs_timeout {
+            conn.set_happy_eyeballs_timeout(happy_eyeballs_timeout);
+        }
         conn
     }
 
@@ -320,6 +329,29 @@ impl<Any> ConnectorBuilder<Any> {
         self
     }
 
+    /// Configure the delay before connecting to the fallback address family starts
+    ///
+    /// When a host resolves to both IPv6 and IPv4 addresses, the connector first attempts the
+    /// address family of the first resolved address. If that hasn't succeeded after this delay,
+    /// connection attempts to the other address family are started in parallel, as described in
+    /// [RFC 8305](https://www.rfc-editor.org/rfc/rfc8305). Resolvers that order addresses for
+    /// Happy Eyeballs (such as `aws_smithy_dns::CachingDnsResolver`) prefer IPv6.
+    ///
+    /// Passing `None` disables racing, so that addresses are attempted one at a time in the order
+    /// they were resolved. Defaults to 300 milliseconds.
+    pub fn happy_eyeballs_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.happy_eyeballs_timeout = Some(timeout);
+        self
+    }
+
+    /// Configure the delay before connecting to the fallback address family starts
+    ///
+    /// This is the mutable version of [`happy_eyeballs_timeout`](Self::happy_eyeballs_timeout).
+    pub fn set_happy_eyeballs_timeout(&mut self, timeout: Option<Duration>) -> &mut Self {
+        self.happy_eyeballs_timeout = Some(timeout);
+        self
+    }
+
     /// Configure proxy settings for this connector
     ///
     /// This method allows you to set explicit proxy configuration for the HTTP client.
@@ -385,6 +417,7 @@ struct Adapter<C> {
         hyper_util::client::legacy::Client<timeout::ConnectTimeout<C>, SdkBody>,
     >,
     proxy_matcher: Option<Matcher>,
+    metrics: Option<metrics::ConnectionMetrics>,
 }
 
 impl<C> fmt::Debug for Adapter<C> {
@@ -480,8 +513,10 @@ where
         }
         let mut client = self.client.clone();
         use tower::Service;
+        let in_flight = self.metrics.as_ref().map(|m| m.start_request());
         let fut = client.call(request);
         HttpConnectorFuture::new(async move {
+            let _in_flight = in_flight;
             let response = fut
                 .await
                 .map_err(downcast_error)?
@@ -505,6 +540,9 @@ fn downcast_error(err: BoxError) -> ConnectorError {
         Ok(connector_error) => return *connector_error,
         Err(box_error) => box_error,
     };
+    if find_source::<CertificateVerificationError>(err.as_ref()).is_some() {
+        return ConnectorError::certificate_verification(err);
+    }
     // generally, the top of chain will probably be a hyper error. Go through a set of hyper specific
     // error classifications
     let err = match find_source::<hyper::Error>(err.as_ref()) {
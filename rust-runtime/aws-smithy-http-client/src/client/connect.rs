@@ -5,10 +5,14 @@
 use crate::client::connect;
 use crate::proxy;
 use aws_smithy_runtime_api::box_error::BoxError;
+use http_1x::uri::Scheme;
 use http_1x::Uri;
 use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper_util::client::legacy::connect::proxy::SocksV5;
 use hyper_util::client::legacy::connect::{Connected, Connection};
+use hyper_util::client::proxy::matcher::Intercept;
 use pin_project_lite::pin_project;
+use std::fmt;
 use std::future::Future;
 use std::io;
 use std::io::IoSlice;
@@ -86,10 +90,89 @@ impl Write for Conn {
     }
 }
 
+/// The port used for SOCKS proxies when the proxy URL doesn't include one
+const DEFAULT_SOCKS_PORT: u16 = 1080;
+
+/// Returns `true` if `intercept` points to a SOCKS proxy rather than an HTTP proxy
+pub(crate) fn is_socks_proxy(intercept: &Intercept) -> bool {
+    intercept
+        .uri()
+        .scheme_str()
+        .is_some_and(|scheme| scheme.starts_with("socks"))
+}
+
+/// Tunnel a connection to `dst` through the SOCKS5 proxy that `intercept` points to
+///
+/// The connection to the proxy itself is made with `inner`. Hostnames are resolved by the proxy
+/// for `socks5h://` proxies, and locally for `socks5://` proxies.
+///
+/// The tunneled connection is transparent, so it must not be marked as a proxied connection.
+/// Otherwise, hyper would send requests in absolute-form as it does for HTTP proxies.
+pub(crate) fn socks5_tunnel<C>(
+    inner: C,
+    intercept: &Intercept,
+    dst: &Uri,
+) -> Pin<Box<dyn Future<Output = Result<C::Response, BoxError>> + Send>>
+where
+    C: tower::Service<Uri> + Send + 'static,
+    C::Future: Send + 'static,
+    C::Response: Read + Write + Unpin + Send + 'static,
+    C::Error: fmt::Debug + fmt::Display + Send + Sync + 'static,
+{
+    let (proxy_uri, dst) = match (socks5_proxy_uri(intercept), with_default_port(dst)) {
+        (Ok(proxy_uri), Ok(dst)) => (proxy_uri, dst),
+        (Err(err), _) | (_, Err(err)) => return Box::pin(async move { Err(err) }),
+    };
+    let local_dns = intercept.uri().scheme_str() == Some("socks5");
+    let socks = SocksV5::new(proxy_uri, inner).local_dns(local_dns);
+    let mut socks = match intercept.raw_auth() {
+        Some((username, password)) => socks.with_auth(username.to_owned(), password.to_owned()),
+        None => socks,
+    };
+
+    tracing::trace!(local_dns, "tunneling over SOCKS5 proxy");
+    let fut = tower::Service::call(&mut socks, dst);
+    Box::pin(async move {
+        fut.await
+            .map_err(|e| BoxError::from(format!("SOCKS5 tunnel failed: {}", e)))
+    })
+}
+
+/// Returns the URI used to connect to a SOCKS5 proxy with an HTTP connector
+fn socks5_proxy_uri(intercept: &Intercept) -> Result<Uri, BoxError> {
+    let uri = intercept.uri();
+    match uri.scheme_str() {
+        Some("socks5") | Some("socks5h") => {}
+        scheme => {
+            return Err(format!(
+                "unsupported SOCKS proxy scheme: {} (only SOCKS5 is supported)",
+                scheme.unwrap_or_default()
+            )
+            .into())
+        }
+    }
+    let host = uri.host().ok_or("missing host in SOCKS proxy URI")?;
+    let port = uri.port_u16().unwrap_or(DEFAULT_SOCKS_PORT);
+    // The connection to the proxy is plain TCP, which HTTP connectors provide for `http` URIs
+    Ok(format!("http://{}:{}", host, port).parse()?)
+}
+
+/// Returns `dst` with an explicit port, since the port must be sent in the SOCKS handshake
+fn with_default_port(dst: &Uri) -> Result<Uri, BoxError> {
+    if dst.port().is_some() {
+        return Ok(dst.clone());
+    }
+    let scheme = dst.scheme().unwrap_or(&Scheme::HTTP);
+    let port = if scheme == &Scheme::HTTPS { 443 } else { 80 };
+    let host = dst.host().ok_or("missing host in URI")?;
+    Ok(format!("{}://{}:{}", scheme, host, port).parse()?)
+}
+
 /// HTTP-only proxy connector for handling HTTP requests through HTTP proxies
 ///
 /// This connector handles the HTTP proxy logic when no TLS provider is selected,
-/// including request URL modification and proxy authentication.
+/// including request URL modification and proxy authentication. Connections to SOCKS5
+/// proxies are tunneled to the target server.
 #[derive(Debug, Clone)]
 pub(crate) struct HttpProxyConnector<C> {
     inner: C,
@@ -116,7 +199,7 @@ where
         + Unpin
         + 'static,
     C::Future: Send + 'static,
-    C::Error: Into<BoxError>,
+    C::Error: Into<BoxError> + fmt::Debug + fmt::Display + Send + Sync + 'static,
 {
     type Response = connect::Conn;
     type Error = BoxError;
@@ -138,7 +221,16 @@ where
             None
         };
 
-        if let Some(intercept) = proxy_intercept {
+        if let Some(intercept) = proxy_intercept.as_ref().filter(|i| is_socks_proxy(i)) {
+            // HTTP through SOCKS proxy: Tunnel to the target server
+            let fut = socks5_tunnel(self.inner.clone(), intercept, &dst);
+            Box::pin(async move {
+                Ok(connect::Conn {
+                    inner: Box::new(fut.await?),
+                    is_proxy: false,
+                })
+            })
+        } else if let Some(intercept) = proxy_intercept {
             // HTTP through proxy: Connect to proxy server
             let proxy_uri = intercept.uri().clone();
             let fut = self.inner.call(proxy_uri);
@@ -12,6 +12,9 @@ use std::task::{Context, Poll};
 use std::vec;
 
 /// A bridge that allows our `ResolveDns` trait to work with Hyper's `Resolver` interface (based on tower)
+///
+/// The resolved addresses are passed on in order, since the connector prefers the address family
+/// of the first address when racing connection attempts (Happy Eyeballs).
 #[derive(Clone)]
 pub(crate) struct HyperUtilResolver<R> {
     pub(crate) resolver: R,
@@ -0,0 +1,188 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use aws_smithy_observability::global::get_telemetry_provider;
+use aws_smithy_observability::instruments::UpDownCounter;
+use http_1x::Uri;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use pin_project_lite::pin_project;
+
+const SCOPE: &str = "aws-smithy-http-client";
+
+/// Gauges for the connections and requests of a connector
+#[derive(Clone, Debug)]
+pub(crate) struct ConnectionMetrics {
+    open_connections: Arc<dyn UpDownCounter>,
+    in_flight_requests: Arc<dyn UpDownCounter>,
+}
+
+impl ConnectionMetrics {
+    /// Creates the instruments with the global telemetry provider, if one is available
+    pub(crate) fn new() -> Option<Self> {
+        let meter = get_telemetry_provider()
+            .ok()?
+            .meter_provider()
+            .get_meter(SCOPE, None);
+        Some(Self {
+            open_connections: meter
+                .create_up_down_counter("smithy.client.http.connections.open")
+                .set_units("{connection}")
+                .set_description("The number of open connections, both in use and idle in the pool")
+                .build(),
+            in_flight_requests: meter
+                .create_up_down_counter("smithy.client.http.requests.in_flight")
+                .set_units("{request}")
+                .set_description("The number of requests waiting for a connection or a response")
+                .build(),
+        })
+    }
+
+    /// Counts a request as in flight until the returned guard is dropped
+    pub(crate) fn start_request(&self) -> Counted {
+        Counted::new(self.in_flight_requests.clone())
+    }
+}
+
+/// Increments a gauge until dropped
+#[derive(Debug)]
+pub(crate) struct Counted(Arc<dyn UpDownCounter>);
+
+impl Counted {
+    fn new(counter: Arc<dyn UpDownCounter>) -> Self {
+        counter.add(1, None, None);
+        Self(counter)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.0.add(-1, None, None);
+    }
+}
+
+/// Connector that counts the connections it opens until they're closed
+#[derive(Clone, Debug)]
+pub(crate) struct MeteredConnector<C> {
+    inner: C,
+    metrics: Option<ConnectionMetrics>,
+}
+
+impl<C> MeteredConnector<C> {
+    pub(crate) fn new(inner: C, metrics: Option<ConnectionMetrics>) -> Self {
+        Self { inner, metrics }
+    }
+}
+
+impl<C> tower::Service<Uri> for MeteredConnector<C>
+where
+    C: tower::Service<Uri>,
+{
+    type Response = MeteredConnection<C::Response>;
+    type Error = C::Error;
+    type Future = MeteredConnectorFuture<C::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        MeteredConnectorFuture {
+            inner: self.inner.call(uri),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+pin_project! {
+    pub(crate) struct MeteredConnectorFuture<F> {
+        #[pin]
+        inner: F,
+        metrics: Option<ConnectionMetrics>,
+    }
+}
+
+impl<F, T, E> Future for MeteredConnectorFuture<F>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Result<MeteredConnection<T>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let io = match this.inner.poll(cx) {
+            Poll::Ready(Ok(io)) => io,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let open = this
+            .metrics
+            .as_ref()
+            .map(|metrics| Counted::new(metrics.open_connections.clone()));
+        Poll::Ready(Ok(MeteredConnection {
+            inner: io,
+            _open: open,
+        }))
+    }
+}
+
+/// A connection that's counted as open until it's dropped
+#[derive(Debug)]
+pub(crate) struct MeteredConnection<T> {
+    inner: T,
+    _open: Option<Counted>,
+}
+
+impl<T: Connection> Connection for MeteredConnection<T> {
+    fn connected(&self) -> Connected {
+        self.inner.connected()
+    }
+}
+
+impl<T: Read + Unpin> Read for MeteredConnection<T> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<T: Write + Unpin> Write for MeteredConnection<T> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+}
@@ -5,7 +5,7 @@
 
 //! Proxy configuration for HTTP clients
 //!
-//! This module provides types and utilities for configuring HTTP and HTTPS proxies,
+//! This module provides types and utilities for configuring HTTP, HTTPS, and SOCKS5 proxies,
 //! including support for environment variable detection, authentication, and bypass rules.
 
 use http_1x::Uri;
@@ -14,7 +14,7 @@ use std::fmt;
 
 /// Proxy configuration for HTTP clients
 ///
-/// Supports HTTP and HTTPS proxy configuration with authentication and bypass rules.
+/// Supports HTTP, HTTPS, and SOCKS5 proxy configuration with authentication and bypass rules.
 /// Can be configured programmatically or automatically detected from environment variables.
 ///
 /// # Examples
@@ -30,6 +30,9 @@ use std::fmt;
 ///     .with_basic_auth("username", "password")
 ///     .no_proxy("localhost,*.internal");
 ///
+/// // All traffic through a SOCKS5 proxy that resolves hostnames
+/// let config = ProxyConfig::socks5("socks5h://socks.example.com:1080")?;
+///
 /// // Detect from environment variables
 /// let config = ProxyConfig::from_env();
 /// # Ok::<(), Box<dyn std::error::Error>>(())
@@ -62,6 +65,12 @@ enum ProxyConfigInner {
         auth: Option<ProxyAuth>,
         no_proxy: Option<String>,
     },
+    /// SOCKS5 proxy for all traffic
+    Socks5 {
+        uri: Uri,
+        auth: Option<ProxyAuth>,
+        no_proxy: Option<String>,
+    },
     /// Explicitly disabled
     Disabled,
 }
@@ -221,6 +230,47 @@ impl ProxyConfig {
         })
     }
 
+    /// Create a new proxy configuration that tunnels all traffic through a SOCKS5 proxy
+    ///
+    /// The scheme of the proxy URL determines where hostnames are resolved:
+    /// - `socks5://proxy.example.com:1080` - Resolve hostnames locally and send the proxy IP addresses
+    /// - `socks5h://proxy.example.com:1080` - Send hostnames to the proxy and let it resolve them
+    ///
+    /// If the URL doesn't include a port, the default SOCKS port (1080) is used. Both HTTP and
+    /// HTTPS requests are tunneled; TLS is negotiated with the target server over the tunnel.
+    /// Username/password authentication can be added with [`with_basic_auth`](Self::with_basic_auth).
+    ///
+    /// # Arguments
+    /// * `proxy_url` - The SOCKS5 proxy URL
+    ///
+    /// # Examples
+    /// ```rust
+    /// use aws_smithy_http_client::proxy::ProxyConfig;
+    ///
+    /// let config = ProxyConfig::socks5("socks5h://proxy.example.com:1080")?
+    ///     .with_basic_auth("username", "password");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn socks5<U>(proxy_url: U) -> Result<Self, ProxyError>
+    where
+        U: TryInto<Uri>,
+        U::Error: fmt::Display,
+    {
+        let uri = proxy_url
+            .try_into()
+            .map_err(|e| ErrorKind::InvalidUrl(e.to_string()))?;
+
+        Self::validate_socks5_proxy_uri(&uri)?;
+
+        Ok(ProxyConfig {
+            inner: ProxyConfigInner::Socks5 {
+                uri,
+                auth: None,
+                no_proxy: None,
+            },
+        })
+    }
+
     /// Create a proxy configuration that disables all proxy usage
     ///
     /// This is useful for explicitly disabling proxy support even when
@@ -240,6 +290,9 @@ impl ProxyConfig {
 
     /// Add basic authentication to this proxy configuration
     ///
+    /// For SOCKS5 proxies, the credentials are sent with the username/password
+    /// authentication method (RFC 1929).
+    ///
     /// # Arguments
     /// * `username` - Username for proxy authentication
     /// * `password` - Password for proxy authentication
@@ -272,6 +325,9 @@ impl ProxyConfig {
             ProxyConfigInner::All {
                 auth: ref mut a, ..
             } => *a = Some(auth),
+            ProxyConfigInner::Socks5 {
+                auth: ref mut a, ..
+            } => *a = Some(auth),
             ProxyConfigInner::FromEnvironment | ProxyConfigInner::Disabled => {
                 // Cannot add auth to environment or disabled configs
             }
@@ -311,6 +367,10 @@ impl ProxyConfig {
                 no_proxy: ref mut n,
                 ..
             } => *n = Some(rules_str),
+            ProxyConfigInner::Socks5 {
+                no_proxy: ref mut n,
+                ..
+            } => *n = Some(rules_str),
             ProxyConfigInner::FromEnvironment | ProxyConfigInner::Disabled => {
                 // Cannot add no_proxy to environment or disabled configs
                 // Environment configs will use NO_PROXY env var
@@ -326,7 +386,8 @@ impl ProxyConfig {
     /// Reads standard proxy environment variables:
     /// - `HTTP_PROXY` / `http_proxy`: HTTP proxy URL
     /// - `HTTPS_PROXY` / `https_proxy`: HTTPS proxy URL
-    /// - `ALL_PROXY` / `all_proxy`: Proxy for all protocols (fallback)
+    /// - `ALL_PROXY` / `all_proxy`: Proxy for all protocols (fallback). This may also be a
+    ///   `socks5://` or `socks5h://` URL to use a SOCKS5 proxy.
     /// - `NO_PROXY` / `no_proxy`: Comma-separated bypass rules
     ///
     /// If no proxy environment variables are set, this returns a configuration
@@ -418,6 +479,24 @@ impl ProxyConfig {
 
                 builder.build()
             }
+            ProxyConfigInner::Socks5 {
+                uri,
+                auth,
+                no_proxy,
+            } => {
+                let mut builder = Matcher::builder();
+
+                // The matcher keeps the credentials of SOCKS proxy URLs as a raw username and password
+                let proxy_url = Self::build_proxy_url(uri, auth);
+                builder = builder.all(proxy_url);
+
+                // Add NO_PROXY rules if present
+                if let Some(no_proxy_rules) = no_proxy {
+                    builder = builder.no(no_proxy_rules);
+                }
+
+                builder.build()
+            }
             ProxyConfigInner::Disabled => {
                 // Create an empty matcher that won't intercept anything
                 Matcher::builder().build()
@@ -434,6 +513,8 @@ impl ProxyConfig {
             ProxyConfigInner::Http { uri, .. } => uri.scheme_str() == Some("https"),
             ProxyConfigInner::Https { uri, .. } => uri.scheme_str() == Some("https"),
             ProxyConfigInner::All { uri, .. } => uri.scheme_str() == Some("https"),
+            // The connection to a SOCKS5 proxy is never encrypted
+            ProxyConfigInner::Socks5 { .. } => false,
             ProxyConfigInner::FromEnvironment => {
                 // Check environment variables for HTTPS proxy URLs
                 Self::env_vars_require_tls()
@@ -491,6 +572,33 @@ impl ProxyConfig {
         Ok(())
     }
 
+    fn validate_socks5_proxy_uri(uri: &Uri) -> Result<(), ProxyError> {
+        // Validate scheme
+        match uri.scheme_str() {
+            Some("socks5") | Some("socks5h") => {}
+            Some(scheme) => {
+                return Err(ErrorKind::InvalidUrl(format!(
+                    "unsupported SOCKS proxy scheme: {}",
+                    scheme
+                ))
+                .into());
+            }
+            None => {
+                return Err(ErrorKind::InvalidUrl(
+                    "proxy URL must include scheme (socks5:// or socks5h://)".to_string(),
+                )
+                .into());
+            }
+        }
+
+        // Validate host
+        if uri.host().is_none() {
+            return Err(ErrorKind::InvalidUrl("proxy URL must include host".to_string()).into());
+        }
+
+        Ok(())
+    }
+
     fn build_proxy_url(uri: Uri, auth: Option<ProxyAuth>) -> String {
         let uri_str = uri.to_string();
 
@@ -545,6 +653,24 @@ mod tests {
         assert!(!config.is_from_env());
     }
 
+    #[test]
+    fn test_proxy_config_socks5() {
+        let config = ProxyConfig::socks5("socks5://proxy.example.com:1080").unwrap();
+        assert!(!config.is_disabled());
+        assert!(!config.is_from_env());
+        assert!(!config.requires_tls());
+
+        let config = ProxyConfig::socks5("socks5h://proxy.example.com").unwrap();
+        assert!(!config.is_disabled());
+    }
+
+    #[test]
+    fn test_proxy_config_socks5_invalid_scheme() {
+        assert!(ProxyConfig::socks5("http://proxy.example.com:8080").is_err());
+        assert!(ProxyConfig::socks5("socks4://proxy.example.com:1080").is_err());
+        assert!(ProxyConfig::http("socks5://proxy.example.com:1080").is_err());
+    }
+
     #[test]
     fn test_proxy_config_disabled() {
         let config = ProxyConfig::disabled();
@@ -771,6 +897,30 @@ mod tests {
         assert!(intercept.basic_auth().is_some());
     }
 
+    #[test]
+    fn test_into_hyper_util_matcher_socks5() {
+        let config = ProxyConfig::socks5("socks5h://proxy.example.com:1080")
+            .unwrap()
+            .with_basic_auth("user", "pass")
+            .no_proxy("localhost");
+        let matcher = config.into_hyper_util_matcher();
+
+        // Both HTTP and HTTPS requests are intercepted
+        for test_uri in ["http://example.com", "https://example.com"] {
+            let intercept = matcher.intercept(&test_uri.parse().unwrap()).unwrap();
+            assert_eq!(
+                intercept.uri().to_string(),
+                "socks5h://proxy.example.com:1080/"
+            );
+            // Credentials are sent during the SOCKS handshake, not in a header
+            assert!(intercept.basic_auth().is_none());
+            assert_eq!(intercept.raw_auth(), Some(("user", "pass")));
+        }
+
+        let bypassed_uri = "http://localhost".parse().unwrap();
+        assert!(matcher.intercept(&bypassed_uri).is_none());
+    }
+
     #[test]
     fn test_into_hyper_util_matcher_disabled() {
         let config = ProxyConfig::disabled();
@@ -4,6 +4,17 @@
  */
 use crate::cfg::{cfg_rustls, cfg_s2n_tls};
 use crate::HttpClientError;
+use aws_smithy_runtime_api::box_error::BoxError;
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+mod verify;
+pub(crate) use verify::ServerCertificateVerifiers;
+pub use verify::{CertificatePins, CertificateVerificationError, VerifyServerCertificate};
 
 /// Choice of underlying cryptography library
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -26,6 +37,10 @@ pub enum Provider {
 pub struct TlsContext {
     #[allow(unused)]
     trust_store: TrustStore,
+    #[allow(unused)]
+    client_identity: Option<ClientIdentity>,
+    #[allow(unused)]
+    server_certificate_verifiers: ServerCertificateVerifiers,
 }
 
 impl TlsContext {
@@ -45,12 +60,16 @@ impl Default for TlsContext {
 #[derive(Debug)]
 pub struct TlsContextBuilder {
     trust_store: TrustStore,
+    client_identity: Option<ClientIdentity>,
+    server_certificate_verifiers: ServerCertificateVerifiers,
 }
 
 impl TlsContextBuilder {
     fn new() -> Self {
         TlsContextBuilder {
             trust_store: TrustStore::default(),
+            client_identity: None,
+            server_certificate_verifiers: ServerCertificateVerifiers::default(),
         }
     }
 
@@ -60,10 +79,65 @@ impl TlsContextBuilder {
         self
     }
 
+    /// Configure the identity presented to servers that request client authentication (mutual TLS)
+    ///
+    /// By default, no client certificate is presented.
+    pub fn with_client_identity(mut self, client_identity: ClientIdentity) -> Self {
+        self.client_identity = Some(client_identity);
+        self
+    }
+
+    /// Configure the identity presented to servers that request client authentication (mutual TLS)
+    ///
+    /// By default, no client certificate is presented.
+    pub fn set_client_identity(&mut self, client_identity: Option<ClientIdentity>) -> &mut Self {
+        self.client_identity = client_identity;
+        self
+    }
+
+    /// Pin the public keys of servers' certificates
+    ///
+    /// See [CertificatePins] for more information.
+    pub fn with_certificate_pins(self, pins: CertificatePins) -> Self {
+        self.with_server_certificate_verifier(pins)
+    }
+
+    /// Add a verifier that further restricts which server certificates are accepted
+    ///
+    /// This may be called more than once to add multiple verifiers, all of which must accept a
+    /// server's certificate chain for the connection to succeed.
+    pub fn with_server_certificate_verifier(
+        mut self,
+        verifier: impl VerifyServerCertificate + 'static,
+    ) -> Self {
+        self.server_certificate_verifiers.push(Arc::new(verifier));
+        self
+    }
+
     /// Build a new [TlsContext]
+    ///
+    /// This fails if a client identity was configured that can't be loaded or whose private key
+    /// doesn't match its certificate, or if server certificate verifiers were configured with a
+    /// trust store that can't trust any server.
     pub fn build(self) -> Result<TlsContext, HttpClientError> {
+        if let Some(client_identity) = &self.client_identity {
+            client_identity
+                .load()
+                .and_then(|pem| pem.validate())
+                .map_err(HttpClientError::new)?;
+        }
+        if !self.server_certificate_verifiers.is_empty()
+            && !self.trust_store.enable_native_roots
+            && self.trust_store.custom_certs.is_empty()
+        {
+            return Err(HttpClientError::new(
+                "server certificate verifiers require a trust store with at least one certificate",
+            ));
+        }
         Ok(TlsContext {
             trust_store: self.trust_store,
+            client_identity: self.client_identity,
+            server_certificate_verifiers: self.server_certificate_verifiers,
         })
     }
 }
@@ -138,6 +212,223 @@ impl Default for TrustStore {
     }
 }
 
+/// Certificate chain and private key used to authenticate the client to servers that
+/// request client authentication (mutual TLS)
+///
+/// An identity created with [ClientIdentity::from_pem_files] is reloaded from disk whenever
+/// one of the files changes, so that rotated certificates are used by new connections without
+/// rebuilding the HTTP client. If the updated files can't be loaded (e.g. because only one of
+/// them has been replaced so far), the previously loaded identity continues to be used.
+///
+/// NOTE: only PEM encoded identities are currently supported. PKCS#12 archives can be converted
+/// to PEM with `openssl pkcs12 -in identity.p12 -nodes`.
+#[derive(Debug, Clone)]
+pub struct ClientIdentity {
+    source: ClientIdentitySource,
+}
+
+#[derive(Debug, Clone)]
+enum ClientIdentitySource {
+    Pem(Arc<IdentityPem>),
+    PemFiles(Arc<PemFiles>),
+}
+
+impl ClientIdentity {
+    /// Create a client identity from a PEM encoded certificate chain and private key
+    ///
+    /// The certificate chain must start with the client's certificate, optionally followed by
+    /// the intermediate certificates needed to verify it.
+    pub fn from_pem(cert_chain: impl Into<Vec<u8>>, private_key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            source: ClientIdentitySource::Pem(Arc::new(IdentityPem {
+                cert_chain: cert_chain.into(),
+                private_key: private_key.into(),
+            })),
+        }
+    }
+
+    /// Create a client identity that is loaded from a PEM encoded certificate chain file and
+    /// private key file
+    ///
+    /// The files are checked for changes whenever a new TLS connection is established, and the
+    /// identity is reloaded if either of them has changed. With rustls, TLS session resumption
+    /// is disabled for these identities since a resumed session would continue to use the
+    /// previous certificate.
+    pub fn from_pem_files(
+        cert_chain_path: impl Into<PathBuf>,
+        private_key_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            source: ClientIdentitySource::PemFiles(Arc::new(PemFiles {
+                cert_chain_path: cert_chain_path.into(),
+                private_key_path: private_key_path.into(),
+                loaded: Mutex::new(None),
+            })),
+        }
+    }
+
+    /// Load the identity, failing if it can't be loaded
+    fn load(&self) -> Result<Arc<IdentityPem>, BoxError> {
+        match &self.source {
+            ClientIdentitySource::Pem(pem) => {
+                pem.parse()?;
+                Ok(pem.clone())
+            }
+            ClientIdentitySource::PemFiles(files) => files.reload(),
+        }
+    }
+
+    /// Whether the identity may change after it was first loaded
+    #[allow(unused)]
+    pub(crate) fn is_reloadable(&self) -> bool {
+        matches!(self.source, ClientIdentitySource::PemFiles(_))
+    }
+
+    /// The current identity
+    ///
+    /// This returns the same `Arc` for as long as the identity hasn't changed, so that TLS
+    /// providers can cache anything they derive from it.
+    #[allow(unused)]
+    pub(crate) fn current(&self) -> Arc<IdentityPem> {
+        match &self.source {
+            ClientIdentitySource::Pem(pem) => pem.clone(),
+            ClientIdentitySource::PemFiles(files) => files.current(),
+        }
+    }
+}
+
+/// PEM encoded certificate chain and private key
+pub(crate) struct IdentityPem {
+    #[allow(unused)]
+    pub(crate) cert_chain: Vec<u8>,
+    #[allow(unused)]
+    pub(crate) private_key: Vec<u8>,
+}
+
+impl IdentityPem {
+    /// Parse the certificate chain and private key into their DER encoding
+    pub(crate) fn parse(
+        &self,
+    ) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), BoxError> {
+        let cert_chain = CertificateDer::pem_slice_iter(&self.cert_chain)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("invalid client certificate chain: {err}"))?;
+        if cert_chain.is_empty() {
+            return Err("client certificate chain does not contain any certificates".into());
+        }
+        let private_key = PrivateKeyDer::from_pem_slice(&self.private_key)
+            .map_err(|err| format!("invalid client private key: {err}"))?;
+        Ok((cert_chain, private_key))
+    }
+
+    /// Check that the identity can be used by the enabled TLS providers, including that its
+    /// private key matches its certificate
+    fn validate(&self) -> Result<(), BoxError> {
+        #[cfg(any(
+            feature = "rustls-aws-lc",
+            feature = "rustls-aws-lc-fips",
+            feature = "rustls-ring"
+        ))]
+        rustls_provider::build_connector::validate_client_identity(self)?;
+        #[cfg(feature = "s2n-tls")]
+        s2n_tls_provider::build_connector::validate_client_identity(self)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for IdentityPem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdentityPem")
+            .field("cert_chain", &String::from_utf8_lossy(&self.cert_chain))
+            .field("private_key", &"** redacted **")
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct PemFiles {
+    cert_chain_path: PathBuf,
+    private_key_path: PathBuf,
+    loaded: Mutex<Option<LoadedPemFiles>>,
+}
+
+#[derive(Debug)]
+struct LoadedPemFiles {
+    versions: [FileVersion; 2],
+    pem: Arc<IdentityPem>,
+}
+
+/// Used to detect when a file has been modified
+#[derive(Debug, PartialEq, Eq)]
+struct FileVersion {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileVersion {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+fn read_versioned(path: &Path) -> Result<(FileVersion, Vec<u8>), BoxError> {
+    let read = || -> std::io::Result<_> { Ok((FileVersion::of(path)?, std::fs::read(path)?)) };
+    read().map_err(|err| format!("failed to read {}: {err}", path.display()).into())
+}
+
+impl PemFiles {
+    fn read(&self) -> Result<([FileVersion; 2], IdentityPem), BoxError> {
+        let (cert_chain_version, cert_chain) = read_versioned(&self.cert_chain_path)?;
+        let (private_key_version, private_key) = read_versioned(&self.private_key_path)?;
+        let pem = IdentityPem {
+            cert_chain,
+            private_key,
+        };
+        pem.parse()?;
+        Ok(([cert_chain_version, private_key_version], pem))
+    }
+
+    fn reload(&self) -> Result<Arc<IdentityPem>, BoxError> {
+        let (versions, pem) = self.read()?;
+        let pem = Arc::new(pem);
+        *self.loaded.lock().unwrap() = Some(LoadedPemFiles {
+            versions,
+            pem: pem.clone(),
+        });
+        Ok(pem)
+    }
+
+    fn current(&self) -> Arc<IdentityPem> {
+        let mut loaded = self.loaded.lock().unwrap();
+        let loaded = loaded
+            .as_mut()
+            .expect("client identity is loaded when the TlsContext is built");
+        let unchanged = FileVersion::of(&self.cert_chain_path)
+            .and_then(|cert| Ok([cert, FileVersion::of(&self.private_key_path)?]))
+            .is_ok_and(|versions| versions == loaded.versions);
+        if !unchanged {
+            match self.read() {
+                Ok((versions, pem)) => {
+                    tracing::debug!(
+                        cert_chain_path = %self.cert_chain_path.display(),
+                        "reloaded client identity"
+                    );
+                    loaded.versions = versions;
+                    loaded.pem = Arc::new(pem);
+                }
+                Err(err) => tracing::warn!(
+                    "failed to reload client identity, continuing to use the previous one: {err}"
+                ),
+            }
+        }
+        loaded.pem.clone()
+    }
+}
+
 cfg_rustls! {
     /// rustls based support and adapters
     pub mod rustls_provider;
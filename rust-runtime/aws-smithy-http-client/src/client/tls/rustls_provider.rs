@@ -52,15 +52,28 @@ impl Provider {
 
 pub(crate) mod build_connector {
     use crate::client::tls::rustls_provider::CryptoMode;
-    use crate::tls::TlsContext;
+    use crate::client::tls::{IdentityPem, ServerCertificateVerifiers};
+    use crate::tls::{ClientIdentity, TlsContext};
+    use aws_smithy_runtime_api::box_error::BoxError;
     use client::connect::HttpConnector;
     use hyper_util::client::legacy as client;
-    use rustls::crypto::CryptoProvider;
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::client::{
+        verify_server_cert_signed_by_trust_anchor, verify_server_name, ResolvesClientCert,
+    };
+    use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+    use rustls::server::ParsedCertificate;
+    use rustls::sign::CertifiedKey;
+    use rustls::{
+        CertificateError, DigitallySignedStruct, OtherError, RootCertStore, SignatureScheme,
+    };
+    use rustls_pki_types::{ServerName, SignatureVerificationAlgorithm, UnixTime};
     use rustls_native_certs::CertificateResult;
     use rustls_pki_types::pem::PemObject;
     use rustls_pki_types::CertificateDer;
-    use std::sync::Arc;
+    use std::cell::RefCell;
     use std::sync::LazyLock;
+    use std::sync::{Arc, Mutex};
 
     /// Cached native certificates
     ///
@@ -129,6 +142,249 @@ pub(crate) mod build_connector {
         }
     }
 
+    fn certified_key(
+        pem: &IdentityPem,
+        crypto_provider: &CryptoProvider,
+    ) -> Result<Arc<CertifiedKey>, BoxError> {
+        let (cert_chain, private_key) = pem.parse()?;
+        Ok(Arc::new(CertifiedKey::from_der(
+            cert_chain,
+            private_key,
+            crypto_provider,
+        )?))
+    }
+
+    /// Check that `pem` can be loaded by every enabled crypto provider
+    pub(crate) fn validate_client_identity(pem: &IdentityPem) -> Result<(), BoxError> {
+        let crypto_modes = [
+            #[cfg(feature = "rustls-aws-lc")]
+            CryptoMode::AwsLc,
+            #[cfg(feature = "rustls-ring")]
+            CryptoMode::Ring,
+            #[cfg(feature = "rustls-aws-lc-fips")]
+            CryptoMode::AwsLcFips,
+        ];
+        for crypto_mode in crypto_modes {
+            certified_key(pem, &crypto_mode.provider())
+                .map_err(|err| format!("invalid client identity: {err}"))?;
+        }
+        Ok(())
+    }
+
+    /// Resolves the client certificate from a [`ClientIdentity`], picking up any changes to it
+    #[derive(Debug)]
+    struct ClientIdentityResolver {
+        identity: ClientIdentity,
+        crypto_provider: Arc<CryptoProvider>,
+        // empty until the identity was loaded successfully
+        current: Mutex<Option<(Arc<IdentityPem>, Arc<CertifiedKey>)>>,
+    }
+
+    impl ClientIdentityResolver {
+        fn new(identity: ClientIdentity, crypto_provider: Arc<CryptoProvider>) -> Self {
+            Self {
+                identity,
+                crypto_provider,
+                current: Mutex::new(None),
+            }
+        }
+    }
+
+    impl ResolvesClientCert for ClientIdentityResolver {
+        fn resolve(
+            &self,
+            _root_hint_subjects: &[&[u8]],
+            _sigschemes: &[SignatureScheme],
+        ) -> Option<Arc<CertifiedKey>> {
+            let pem = self.identity.current();
+            let mut current = self.current.lock().unwrap();
+            if !current
+                .as_ref()
+                .is_some_and(|(loaded, _)| Arc::ptr_eq(loaded, &pem))
+            {
+                match certified_key(&pem, &self.crypto_provider) {
+                    Ok(key) => *current = Some((pem, key)),
+                    Err(err) if current.is_some() => tracing::warn!(
+                        "failed to load client identity, continuing to use the previous one: {err}"
+                    ),
+                    Err(err) => tracing::warn!(
+                        "failed to load client identity, no client certificate will be presented: {err}"
+                    ),
+                }
+            }
+            current.as_ref().map(|(_, key)| key.clone())
+        }
+
+        fn has_certs(&self) -> bool {
+            true
+        }
+    }
+
+    /// Verifies server certificates against the trust store, followed by the
+    /// [`VerifyServerCertificate`](crate::tls::VerifyServerCertificate)s of the `TlsContext`
+    #[derive(Debug)]
+    struct ServerCertificateVerifier {
+        roots: RootCertStore,
+        // the certificates of `roots`, in the same order
+        root_certs: Vec<CertificateDer<'static>>,
+        crypto_provider: Arc<CryptoProvider>,
+        verifiers: ServerCertificateVerifiers,
+    }
+
+    impl ServerCertificateVerifier {
+        fn new(tls_context: &TlsContext, crypto_provider: Arc<CryptoProvider>) -> Self {
+            let mut certs = Vec::new();
+            if tls_context.trust_store.enable_native_roots {
+                certs.extend(NATIVE_ROOTS.iter().cloned());
+            }
+            for pem_cert in &tls_context.trust_store.custom_certs {
+                certs.extend(CertificateDer::pem_slice_iter(&pem_cert.0).flatten());
+            }
+            // Keep the certificates of the trust anchors, so that verifiers can be given the
+            // root certificate of a verified path
+            let (mut roots, mut root_certs) = (RootCertStore::empty(), Vec::new());
+            for cert in certs {
+                if let Ok(anchor) = webpki::anchor_from_trusted_cert(&cert) {
+                    roots.roots.push(anchor.to_owned());
+                    root_certs.push(cert);
+                }
+            }
+            Self {
+                roots,
+                root_certs,
+                crypto_provider,
+                verifiers: tls_context.server_certificate_verifiers.clone(),
+            }
+        }
+    }
+
+    impl ServerCertVerifier for ServerCertificateVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            intermediates: &[CertificateDer<'_>],
+            server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let signature_algorithms = self.crypto_provider.signature_verification_algorithms;
+            let cert = ParsedCertificate::try_from(end_entity)?;
+            verify_server_cert_signed_by_trust_anchor(
+                &cert,
+                &self.roots,
+                intermediates,
+                now,
+                signature_algorithms.all,
+            )?;
+            verify_server_name(&cert, server_name)?;
+            verify_certificate_path(
+                &self.verifiers,
+                &server_name.to_str(),
+                &self.roots,
+                &self.root_certs,
+                end_entity,
+                intermediates,
+                now,
+                signature_algorithms.all,
+            )?;
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(
+                message,
+                cert,
+                dss,
+                &self.crypto_provider.signature_verification_algorithms,
+            )
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(
+                message,
+                cert,
+                dss,
+                &self.crypto_provider.signature_verification_algorithms,
+            )
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.crypto_provider
+                .signature_verification_algorithms
+                .supported_schemes()
+        }
+    }
+
+    /// Runs `verifiers` against the paths from `end_entity` to a trusted root, succeeding as soon
+    /// as they accept one of them
+    ///
+    /// The verifiers only see the certificates of a path that was built and verified, so
+    /// certificates that the server appends to its chain without being part of that path
+    /// (e.g. a copy of a pinned certificate) can't satisfy them.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_certificate_path(
+        verifiers: &ServerCertificateVerifiers,
+        server_name: &str,
+        roots: &RootCertStore,
+        root_certs: &[CertificateDer<'_>],
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+        signature_algorithms: &[&dyn SignatureVerificationAlgorithm],
+    ) -> Result<(), rustls::Error> {
+        let rejection = RefCell::new(None);
+        let verify_path = |path: &webpki::VerifiedPath<'_>| {
+            let root_cert = roots
+                .roots
+                .iter()
+                .position(|anchor| anchor == path.anchor())
+                .map(|index| root_certs[index].as_ref());
+            let cert_chain = std::iter::once(path.end_entity().der())
+                .chain(path.intermediate_certificates().map(|cert| cert.der()))
+                .collect::<Vec<_>>();
+            let cert_chain = cert_chain
+                .iter()
+                .map(|cert| cert.as_ref())
+                .chain(root_cert)
+                .collect::<Vec<_>>();
+            verifiers.verify(server_name, &cert_chain).map_err(|err| {
+                *rejection.borrow_mut() = Some(err);
+                // not a fatal error, so that other paths are tried
+                webpki::Error::UnknownIssuer
+            })
+        };
+        let other_error = |err: Arc<dyn std::error::Error + Send + Sync>| {
+            rustls::Error::InvalidCertificate(CertificateError::Other(OtherError(err)))
+        };
+        let cert = webpki::EndEntityCert::try_from(end_entity)
+            .map_err(|_| rustls::Error::InvalidCertificate(CertificateError::BadEncoding))?;
+        match cert.verify_for_usage(
+            signature_algorithms,
+            &roots.roots,
+            intermediates,
+            now,
+            webpki::KeyUsage::server_auth(),
+            None,
+            Some(&verify_path),
+        ) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(match rejection.into_inner() {
+                Some(rejection) => other_error(Arc::new(rejection)),
+                None => other_error(Arc::new(err)),
+            }),
+        }
+    }
+
     /// Create a rustls ClientConfig with smithy-rs defaults
     ///
     /// This centralizes the rustls ClientConfig creation logic to ensure
@@ -137,12 +393,34 @@ pub(crate) mod build_connector {
         crypto_mode: CryptoMode,
         tls_context: &TlsContext,
     ) -> rustls::ClientConfig {
-        let root_certs = tls_context.rustls_root_certs();
-        rustls::ClientConfig::builder_with_provider(Arc::new(restrict_ciphers(crypto_mode.provider())))
+        let crypto_provider = Arc::new(restrict_ciphers(crypto_mode.provider()));
+        let builder = rustls::ClientConfig::builder_with_provider(crypto_provider.clone())
             .with_safe_default_protocol_versions()
-            .expect("Error with the TLS configuration. Please file a bug report under https://github.com/smithy-lang/smithy-rs/issues.")
-            .with_root_certificates(root_certs)
-            .with_no_client_auth()
+            .expect("Error with the TLS configuration. Please file a bug report under https://github.com/smithy-lang/smithy-rs/issues.");
+        let builder = if tls_context.server_certificate_verifiers.is_empty() {
+            builder.with_root_certificates(tls_context.rustls_root_certs())
+        } else {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(ServerCertificateVerifier::new(
+                    tls_context,
+                    crypto_provider.clone(),
+                )))
+        };
+        match &tls_context.client_identity {
+            Some(identity) => {
+                let mut config = builder.with_client_cert_resolver(Arc::new(
+                    ClientIdentityResolver::new(identity.clone(), crypto_provider),
+                ));
+                // A resumed session is authenticated with the certificate of the original
+                // session, which would keep the previous identity in use after a rotation
+                if identity.is_reloadable() {
+                    config.resumption = rustls::client::Resumption::disabled();
+                }
+                config
+            }
+            None => builder.with_no_client_auth(),
+        }
     }
 
     pub(crate) fn wrap_connector<R>(
@@ -165,8 +443,9 @@ pub(crate) mod build_connector {
 }
 
 pub(crate) mod connect {
-    use crate::client::connect::{Conn, Connecting};
+    use crate::client::connect::{is_socks_proxy, socks5_tunnel, Conn, Connecting};
     use crate::client::proxy::ProxyConfig;
+    use crate::tls::CertificateVerificationError;
     use aws_smithy_runtime_api::box_error::BoxError;
     use http_1x::uri::Scheme;
     use http_1x::Uri;
@@ -241,7 +520,10 @@ pub(crate) mod connect {
             };
 
             if let Some(intercept) = proxy_intercept {
-                if dst.scheme() == Some(&Scheme::HTTPS) {
+                if is_socks_proxy(&intercept) {
+                    // HTTP(S) through SOCKS proxy: Tunnel to the target server
+                    self.handle_socks_proxy(dst, intercept)
+                } else if dst.scheme() == Some(&Scheme::HTTPS) {
                     // HTTPS through HTTP proxy: Use CONNECT tunneling + manual TLS
                     self.handle_https_through_proxy(dst, intercept)
                 } else {
@@ -266,7 +548,7 @@ pub(crate) mod connect {
         fn handle_direct_connection(&mut self, dst: Uri) -> Connecting {
             let fut = self.https.call(dst);
             Box::pin(async move {
-                let conn = fut.await?;
+                let conn = fut.await.map_err(classify_handshake_error)?;
                 Ok(Conn {
                     inner: Box::new(conn),
                     is_proxy: false,
@@ -296,7 +578,6 @@ pub(crate) mod connect {
             dst: Uri,
             intercept: hyper_util::client::proxy::matcher::Intercept,
         ) -> Connecting {
-            use rustls_pki_types::ServerName;
             // For HTTPS through HTTP proxy, we need to:
             // 1. Establish CONNECT tunnel using the HTTPS connector
             // 2. Perform manual TLS handshake over the tunneled stream
@@ -325,28 +606,86 @@ pub(crate) mod connect {
                     .map_err(|e| BoxError::from(format!("CONNECT tunnel failed: {}", e)))?;
 
                 // Stage 2: Manual TLS handshake over tunneled stream
-                let host = dst_clone
-                    .host()
-                    .ok_or("missing host in URI for TLS handshake")?;
+                let tls_conn = tls_handshake(tls_config, &dst_clone, tunneled).await?;
 
-                let server_name = ServerName::try_from(host.to_owned()).map_err(|e| {
-                    BoxError::from(format!("invalid server name for TLS handshake: {}", e))
-                })?;
+                Ok(Conn {
+                    inner: Box::new(tls_conn),
+                    is_proxy: true,
+                })
+            })
+        }
 
-                let tls_connector = tokio_rustls::TlsConnector::from(tls_config)
-                    .connect(server_name, TokioIo::new(tunneled))
-                    .await?;
+        fn handle_socks_proxy(
+            &mut self,
+            dst: Uri,
+            intercept: hyper_util::client::proxy::matcher::Intercept,
+        ) -> Connecting {
+            // The tunnel is established with the HTTPS connector, which connects to the proxy
+            // over plain TCP
+            let tunnel = socks5_tunnel(self.https.clone(), &intercept, &dst);
+
+            if dst.scheme() != Some(&Scheme::HTTPS) {
+                return Box::pin(async move {
+                    Ok(Conn {
+                        inner: Box::new(tunnel.await?),
+                        is_proxy: false,
+                    })
+                });
+            }
+
+            let tls_config = self.tls_config.clone();
+            Box::pin(async move {
+                let tunneled = tunnel.await?;
+                let tls_conn = tls_handshake(tls_config, &dst, tunneled).await?;
 
                 Ok(Conn {
-                    inner: Box::new(RustTlsConn {
-                        inner: TokioIo::new(tls_connector),
-                    }),
-                    is_proxy: true,
+                    inner: Box::new(tls_conn),
+                    is_proxy: false,
                 })
             })
         }
     }
 
+    /// Perform a TLS handshake with `dst` over a stream tunneled through a proxy
+    async fn tls_handshake(
+        tls_config: Arc<rustls::ClientConfig>,
+        dst: &Uri,
+        tunneled: MaybeHttpsStream<TokioIo<TcpStream>>,
+    ) -> Result<RustTlsConn<TokioIo<MaybeHttpsStream<TokioIo<TcpStream>>>>, BoxError> {
+        use rustls_pki_types::ServerName;
+
+        let host = dst.host().ok_or("missing host in URI for TLS handshake")?;
+
+        let server_name = ServerName::try_from(host.to_owned()).map_err(|e| {
+            BoxError::from(format!("invalid server name for TLS handshake: {}", e))
+        })?;
+
+        let tls_connector = tokio_rustls::TlsConnector::from(tls_config)
+            .connect(server_name, TokioIo::new(tunneled))
+            .await
+            .map_err(|e| classify_handshake_error(e.into()))?;
+
+        Ok(RustTlsConn {
+            inner: TokioIo::new(tls_connector),
+        })
+    }
+
+    /// Surface rejected server certificates as a [`CertificateVerificationError`]
+    fn classify_handshake_error(err: BoxError) -> BoxError {
+        let mut next: Option<&(dyn Error + 'static)> = Some(err.as_ref());
+        while let Some(source) = next {
+            if let Some(rustls::Error::InvalidCertificate(_)) = source.downcast_ref() {
+                return CertificateVerificationError::new(err).into();
+            }
+            // `io::Error::source` skips the wrapped error, so it has to be unwrapped explicitly
+            next = match source.downcast_ref::<io::Error>().and_then(io::Error::get_ref) {
+                Some(inner) => Some(inner),
+                None => source.source(),
+            };
+        }
+        err
+    }
+
     pin_project! {
         pub(crate) struct RustTlsConn<T> {
             #[pin] pub(super) inner: TokioIo<TlsStream<T>>
@@ -4,11 +4,15 @@
  */
 
 pub(crate) mod build_connector {
+    use crate::client::tls::{IdentityPem, ServerCertificateVerifiers};
     use crate::tls::TlsContext;
+    use aws_smithy_runtime_api::box_error::BoxError;
     use client::connect::HttpConnector;
     use hyper_util::client::legacy as client;
+    use s2n_tls::connection::{Builder, Connection};
+    use s2n_tls::enums::{ClientAuthType, Mode};
     use s2n_tls::security::Policy;
-    use std::sync::LazyLock;
+    use std::sync::{Arc, LazyLock, Mutex};
 
     // Default S2N security policy which sets protocol versions and cipher suites
     //  See https://aws.github.io/s2n-tls/usage-guide/ch06-security-policies.html
@@ -25,40 +29,126 @@ pub(crate) mod build_connector {
         builder
     }
 
-    static CACHED_CONFIG: LazyLock<s2n_tls::config::Config> = LazyLock::new(|| {
+    static CACHED_CONFIG: LazyLock<Result<s2n_tls::config::Config, String>> =
+        LazyLock::new(|| {
+            let mut config = base_config();
+            config
+                .with_system_certs(true)
+                .map_err(|err| err.to_string())?;
+            // actually loads the system certs
+            config.build().map_err(|err| err.to_string())
+        });
+
+    /// Check that `pem` can be loaded by s2n-tls
+    pub(crate) fn validate_client_identity(pem: &IdentityPem) -> Result<(), BoxError> {
         let mut config = base_config();
-        config.with_system_certs(true).unwrap();
-        // actually loads the system certs
-        config.build().expect("valid s2n config")
-    });
+        config
+            .load_pem(&pem.cert_chain, &pem.private_key)
+            .map_err(|err| format!("invalid client identity: {err}"))?;
+        Ok(())
+    }
 
     impl TlsContext {
-        fn s2n_config(&self) -> s2n_tls::config::Config {
+        fn s2n_config(
+            &self,
+            identity: Option<&IdentityPem>,
+        ) -> Result<s2n_tls::config::Config, s2n_tls::error::Error> {
             // TODO(s2n-tls): s2n does not support turning a config back into a builder or a way to load a trust store and re-use it
             // instead if we are only using the defaults then use a cached config, otherwise pay the cost to build a new one
-            if self.trust_store.enable_native_roots && self.trust_store.custom_certs.is_empty() {
-                CACHED_CONFIG.clone()
+            if self.trust_store.enable_native_roots
+                && self.trust_store.custom_certs.is_empty()
+                && identity.is_none()
+            {
+                CACHED_CONFIG
+                    .clone()
+                    .map_err(|err| s2n_tls::error::Error::application(err.into()))
             } else {
                 let mut config = base_config();
-                config
-                    .with_system_certs(self.trust_store.enable_native_roots)
-                    .unwrap();
+                config.with_system_certs(self.trust_store.enable_native_roots)?;
                 for pem_cert in &self.trust_store.custom_certs {
-                    config
-                        .trust_pem(pem_cert.0.as_slice())
-                        .expect("valid certificate");
+                    config.trust_pem(pem_cert.0.as_slice())?;
+                }
+                if let Some(identity) = identity {
+                    config.load_pem(&identity.cert_chain, &identity.private_key)?;
+                    config.set_client_auth_type(ClientAuthType::Optional)?;
+                }
+                config.build()
+            }
+        }
+    }
+
+    /// The client identity a config was built with, and the config
+    type LoadedConfig = (Option<Arc<IdentityPem>>, s2n_tls::config::Config);
+
+    /// Builds s2n connections, rebuilding the config whenever the client identity changes
+    #[derive(Clone)]
+    pub(crate) struct TlsConfig {
+        tls_context: TlsContext,
+        // empty until a config was built successfully
+        current: Arc<Mutex<Option<LoadedConfig>>>,
+    }
+
+    impl TlsConfig {
+        fn new(tls_context: &TlsContext) -> Self {
+            Self {
+                tls_context: tls_context.clone(),
+                current: Arc::new(Mutex::new(None)),
+            }
+        }
+
+        pub(crate) fn server_certificate_verifiers(&self) -> &ServerCertificateVerifiers {
+            &self.tls_context.server_certificate_verifiers
+        }
+
+        fn config(&self) -> Result<s2n_tls::config::Config, s2n_tls::error::Error> {
+            let mut current = self.current.lock().unwrap();
+            let pem = self
+                .tls_context
+                .client_identity
+                .as_ref()
+                .map(|identity| identity.current());
+            if let Some((loaded, config)) = &*current {
+                let unchanged = match (loaded, &pem) {
+                    (Some(loaded), Some(pem)) => Arc::ptr_eq(loaded, pem),
+                    (None, None) => true,
+                    _ => false,
+                };
+                if unchanged {
+                    return Ok(config.clone());
+                }
+            }
+            match self.tls_context.s2n_config(pem.as_deref()) {
+                Ok(config) => {
+                    *current = Some((pem, config.clone()));
+                    Ok(config)
                 }
-                config.build().expect("valid s2n config")
+                Err(err) => match &*current {
+                    Some((_, config)) => {
+                        tracing::warn!(
+                            "failed to load client identity, continuing to use the previous one: {err}"
+                        );
+                        Ok(config.clone())
+                    }
+                    None => Err(err),
+                },
             }
         }
     }
 
+    impl Builder for TlsConfig {
+        type Output = Connection;
+
+        fn build_connection(&self, mode: Mode) -> Result<Self::Output, s2n_tls::error::Error> {
+            self.config()?.build_connection(mode)
+        }
+    }
+
     pub(crate) fn wrap_connector<R>(
         mut http_connector: HttpConnector<R>,
         tls_context: &TlsContext,
         proxy_config: crate::client::proxy::ProxyConfig,
     ) -> super::connect::S2nTlsConnector<R> {
-        let config = tls_context.s2n_config();
+        let config = TlsConfig::new(tls_context);
         http_connector.enforce_http(false);
         let mut builder = s2n_tls_hyper::connector::HttpsConnector::builder_with_http(
             http_connector,
@@ -72,8 +162,10 @@ pub(crate) mod build_connector {
 }
 
 pub(crate) mod connect {
-    use crate::client::connect::{Conn, Connecting};
+    use super::build_connector::TlsConfig;
+    use crate::client::connect::{is_socks_proxy, socks5_tunnel, Conn, Connecting};
     use crate::client::proxy::ProxyConfig;
+    use crate::tls::CertificateVerificationError;
     use aws_smithy_runtime_api::box_error::BoxError;
     use http_1x::uri::Scheme;
     use http_1x::Uri;
@@ -91,15 +183,15 @@ pub(crate) mod connect {
 
     #[derive(Clone)]
     pub(crate) struct S2nTlsConnector<R> {
-        https: s2n_tls_hyper::connector::HttpsConnector<HttpConnector<R>>,
-        tls_config: s2n_tls::config::Config,
+        https: s2n_tls_hyper::connector::HttpsConnector<HttpConnector<R>, TlsConfig>,
+        tls_config: TlsConfig,
         proxy_matcher: Option<Arc<Matcher>>, // Pre-computed for performance
     }
 
     impl<R> S2nTlsConnector<R> {
         pub(super) fn new(
-            https: s2n_tls_hyper::connector::HttpsConnector<HttpConnector<R>>,
-            tls_config: s2n_tls::config::Config,
+            https: s2n_tls_hyper::connector::HttpsConnector<HttpConnector<R>, TlsConfig>,
+            tls_config: TlsConfig,
             proxy_config: ProxyConfig,
         ) -> Self {
             // Pre-compute the proxy matcher once during construction
@@ -142,7 +234,10 @@ pub(crate) mod connect {
             };
 
             if let Some(intercept) = proxy_intercept {
-                if dst.scheme() == Some(&Scheme::HTTPS) {
+                if is_socks_proxy(&intercept) {
+                    // HTTP(S) through SOCKS proxy: Tunnel to the target server
+                    self.handle_socks_proxy(dst, intercept)
+                } else if dst.scheme() == Some(&Scheme::HTTPS) {
                     // HTTPS through HTTP proxy: Use CONNECT tunneling + manual TLS
                     self.handle_https_through_proxy(dst, intercept)
                 } else {
@@ -165,9 +260,17 @@ pub(crate) mod connect {
         R::Error: Into<Box<dyn Error + Send + Sync>>,
     {
         fn handle_direct_connection(&mut self, dst: Uri) -> Connecting {
+            if dst.scheme() == Some(&Scheme::HTTPS)
+                && !self.tls_config.server_certificate_verifiers().is_empty()
+            {
+                return self.handle_verified_direct_connection(dst);
+            }
             let fut = self.https.call(dst);
             Box::pin(async move {
-                let conn = fut.await?;
+                let conn = fut.await.map_err(|err| match err {
+                    s2n_tls_hyper::error::Error::TlsError(err) => classify_handshake_error(err),
+                    err => err.into(),
+                })?;
                 Ok(Conn {
                     inner: Box::new(conn),
                     is_proxy: false,
@@ -175,6 +278,28 @@ pub(crate) mod connect {
             })
         }
 
+        /// Connect over plain TCP and perform the TLS handshake manually, since the connections
+        /// established by `s2n_tls_hyper` don't expose the server's certificate chain
+        fn handle_verified_direct_connection(&mut self, dst: Uri) -> Connecting {
+            let tcp_dst = dst.host().map(|host| {
+                let port = dst.port_u16().unwrap_or(443);
+                format!("http://{host}:{port}")
+            });
+            let tcp_dst = match tcp_dst.map(|uri| uri.parse::<Uri>()) {
+                Some(Ok(uri)) => uri,
+                _ => return Box::pin(async { Err("missing host in URI for TLS handshake".into()) }),
+            };
+            let tcp = self.https.call(tcp_dst);
+            let tls_config = self.tls_config.clone();
+            Box::pin(async move {
+                let tls_conn = tls_handshake(tls_config, &dst, tcp.await?).await?;
+                Ok(Conn {
+                    inner: Box::new(tls_conn),
+                    is_proxy: false,
+                })
+            })
+        }
+
         fn handle_http_through_proxy(
             &mut self,
             _dst: Uri,
@@ -225,25 +350,87 @@ pub(crate) mod connect {
                     .map_err(|e| BoxError::from(format!("CONNECT tunnel failed: {}", e)))?;
 
                 // Stage 2: Manual TLS handshake over tunneled stream
-                let host = dst_clone
-                    .host()
-                    .ok_or("missing host in URI for TLS handshake")?;
-
-                // s2n-tls uses string server names (simpler than rustls ServerName)
-                let tls_connector = s2n_tls_tokio::TlsConnector::new(tls_config);
-                let tls_stream = tls_connector
-                    .connect(host, TokioIo::new(tunneled))
-                    .await
-                    .map_err(|e| BoxError::from(format!("s2n-tls handshake failed: {}", e)))?;
+                let tls_conn = tls_handshake(tls_config, &dst_clone, tunneled).await?;
 
                 Ok(Conn {
-                    inner: Box::new(S2nTlsConn {
-                        inner: TokioIo::new(tls_stream),
-                    }),
+                    inner: Box::new(tls_conn),
                     is_proxy: true,
                 })
             })
         }
+
+        fn handle_socks_proxy(
+            &mut self,
+            dst: Uri,
+            intercept: hyper_util::client::proxy::matcher::Intercept,
+        ) -> Connecting {
+            // The tunnel is established with the HTTPS connector, which connects to the proxy
+            // over plain TCP
+            let tunnel = socks5_tunnel(self.https.clone(), &intercept, &dst);
+
+            if dst.scheme() != Some(&Scheme::HTTPS) {
+                return Box::pin(async move {
+                    Ok(Conn {
+                        inner: Box::new(tunnel.await?),
+                        is_proxy: false,
+                    })
+                });
+            }
+
+            let tls_config = self.tls_config.clone();
+            Box::pin(async move {
+                let tunneled = tunnel.await?;
+                let tls_conn = tls_handshake(tls_config, &dst, tunneled).await?;
+
+                Ok(Conn {
+                    inner: Box::new(tls_conn),
+                    is_proxy: false,
+                })
+            })
+        }
+    }
+
+    /// Perform a TLS handshake with `dst` over a stream tunneled through a proxy
+    async fn tls_handshake<T>(
+        tls_config: TlsConfig,
+        dst: &Uri,
+        tunneled: T,
+    ) -> Result<S2nTlsConn<TokioIo<T>>, BoxError>
+    where
+        T: hyper::rt::Read + hyper::rt::Write + Unpin,
+    {
+        let host = dst.host().ok_or("missing host in URI for TLS handshake")?;
+
+        // s2n-tls uses string server names (simpler than rustls ServerName)
+        let verifiers = tls_config.server_certificate_verifiers().clone();
+        let tls_connector = s2n_tls_tokio::TlsConnector::new(tls_config);
+        let tls_stream = tls_connector
+            .connect(host, TokioIo::new(tunneled))
+            .await
+            .map_err(classify_handshake_error)?;
+
+        if !verifiers.is_empty() {
+            let cert_chain = tls_stream.as_ref().peer_cert_chain()?;
+            let cert_chain = cert_chain
+                .iter()
+                .map(|cert| Ok(cert?.der()?.to_vec()))
+                .collect::<Result<Vec<_>, s2n_tls::error::Error>>()?;
+            let cert_chain = cert_chain.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            verifiers.verify(host, &cert_chain)?;
+        }
+
+        Ok(S2nTlsConn {
+            inner: TokioIo::new(tls_stream),
+        })
+    }
+
+    /// Surface rejected server certificates as a [`CertificateVerificationError`]
+    fn classify_handshake_error(err: s2n_tls::error::Error) -> BoxError {
+        if err.name().starts_with("S2N_ERR_CERT") {
+            CertificateVerificationError::new(err).into()
+        } else {
+            BoxError::from(format!("s2n-tls handshake failed: {}", err))
+        }
     }
 
     // Simple wrapper that implements Connection for s2n-tls streams
@@ -0,0 +1,222 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use rustls_pki_types::{CertificateDer, SubjectPublicKeyInfoDer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Verifies the certificate chain presented by a server
+///
+/// Verifiers are run after the certificate chain has been successfully verified against the
+/// [`TrustStore`](super::TrustStore), so they can only further restrict which certificates are
+/// accepted. Implementations must not block, since they're called during the TLS handshake.
+pub trait VerifyServerCertificate: fmt::Debug + Send + Sync {
+    /// Verify the certificate chain presented by the server
+    ///
+    /// `server_name` is the name of the server that was connected to (the SNI name), and
+    /// `cert_chain` contains the DER encoded certificates of the chain that was verified, starting
+    /// with the server's end-entity certificate. Certificates presented by the server that aren't
+    /// part of that chain are not included. Depending on the TLS provider, the root certificate
+    /// may be omitted.
+    fn verify(
+        &self,
+        server_name: &str,
+        cert_chain: &[&[u8]],
+    ) -> Result<(), CertificateVerificationError>;
+}
+
+/// A server certificate was rejected during the TLS handshake
+///
+/// Connections that fail because of this error result in a
+/// [`ConnectorError`](aws_smithy_runtime_api::client::result::ConnectorError) for which
+/// `is_certificate_verification()` returns true.
+#[derive(Debug)]
+pub struct CertificateVerificationError {
+    reason: BoxError,
+}
+
+impl CertificateVerificationError {
+    /// Create a new `CertificateVerificationError` with the reason the certificate was rejected
+    pub fn new(reason: impl Into<BoxError>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for CertificateVerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "server certificate verification failed")
+    }
+}
+
+impl std::error::Error for CertificateVerificationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.reason.as_ref())
+    }
+}
+
+/// Public key pins for servers, by host name
+///
+/// A connection to a pinned host is only accepted if the SHA-256 hash of the DER encoded
+/// SubjectPublicKeyInfo of at least one certificate in the server's verified chain matches one of
+/// the host's pins. Connections to hosts without any pins are not affected.
+///
+/// The pin for a certificate can be computed with:
+/// ```text
+/// openssl x509 -in cert.pem -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CertificatePins {
+    pins: HashMap<String, Vec<[u8; 32]>>,
+}
+
+impl CertificatePins {
+    /// Create a new empty set of pins
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pin `host` to the given SHA-256 hash of a SubjectPublicKeyInfo
+    ///
+    /// This may be called more than once for the same host to allow any of several public keys,
+    /// e.g. to include a backup key. Host names are case-insensitive.
+    pub fn with_pin(mut self, host: impl Into<String>, spki_sha256: [u8; 32]) -> Self {
+        self.add_pin(host, spki_sha256);
+        self
+    }
+
+    /// Pin `host` to the given SHA-256 hash of a SubjectPublicKeyInfo
+    ///
+    /// This may be called more than once for the same host to allow any of several public keys,
+    /// e.g. to include a backup key. Host names are case-insensitive.
+    pub fn add_pin(&mut self, host: impl Into<String>, spki_sha256: [u8; 32]) -> &mut Self {
+        self.pins
+            .entry(host.into().to_ascii_lowercase())
+            .or_default()
+            .push(spki_sha256);
+        self
+    }
+}
+
+impl VerifyServerCertificate for CertificatePins {
+    fn verify(
+        &self,
+        server_name: &str,
+        cert_chain: &[&[u8]],
+    ) -> Result<(), CertificateVerificationError> {
+        let Some(pins) = self.pins.get(&server_name.to_ascii_lowercase()) else {
+            return Ok(());
+        };
+        let is_pinned = cert_chain
+            .iter()
+            .filter_map(|cert| subject_public_key_info(cert))
+            .any(|spki| pins.contains(&Sha256::digest(spki).into()));
+        if is_pinned {
+            Ok(())
+        } else {
+            Err(CertificateVerificationError::new(format!(
+                "no certificate presented by `{server_name}` matches its pinned public keys"
+            )))
+        }
+    }
+}
+
+/// Verifiers configured on a [`TlsContext`](super::TlsContext)
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ServerCertificateVerifiers(Vec<Arc<dyn VerifyServerCertificate>>);
+
+impl ServerCertificateVerifiers {
+    pub(crate) fn push(&mut self, verifier: Arc<dyn VerifyServerCertificate>) {
+        self.0.push(verifier);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Run all verifiers, failing on the first one that rejects the certificate chain
+    #[allow(unused)]
+    pub(crate) fn verify(
+        &self,
+        server_name: &str,
+        cert_chain: &[&[u8]],
+    ) -> Result<(), CertificateVerificationError> {
+        self.0
+            .iter()
+            .try_for_each(|verifier| verifier.verify(server_name, cert_chain))
+    }
+}
+
+/// Returns the DER encoded SubjectPublicKeyInfo of a DER encoded X.509 certificate
+fn subject_public_key_info(cert: &[u8]) -> Option<SubjectPublicKeyInfoDer<'static>> {
+    let cert = CertificateDer::from(cert);
+    let cert = webpki::EndEntityCert::try_from(&cert).ok()?;
+    Some(cert.subject_public_key_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{subject_public_key_info, CertificatePins, VerifyServerCertificate};
+    use rustls_pki_types::pem::PemObject;
+    use rustls_pki_types::CertificateDer;
+    use sha2::{Digest, Sha256};
+
+    const SERVER_PEM: &[u8] = include_bytes!("../../../tests/server.pem");
+    const CLIENT_PEM: &[u8] = include_bytes!("../../../tests/client.pem");
+
+    fn certs(pem: &[u8]) -> Vec<CertificateDer<'static>> {
+        CertificateDer::pem_slice_iter(pem)
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn spki_sha256(cert: &[u8]) -> [u8; 32] {
+        Sha256::digest(subject_public_key_info(cert).unwrap()).into()
+    }
+
+    #[test]
+    fn subject_public_key_info_is_parsed() {
+        for cert in certs(SERVER_PEM) {
+            let spki = subject_public_key_info(&cert).expect("valid certificate");
+            // the SPKI is a SEQUENCE of the algorithm and the public key
+            assert_eq!(0x30, spki[0]);
+            assert!(spki.len() > 256);
+        }
+        let server = &certs(SERVER_PEM)[0];
+        let client = &certs(CLIENT_PEM)[0];
+        assert_ne!(spki_sha256(server), spki_sha256(client));
+    }
+
+    #[test]
+    fn invalid_certificates_are_not_parsed() {
+        let cert = certs(SERVER_PEM).remove(0);
+        assert!(subject_public_key_info(&cert[..cert.len() / 2]).is_none());
+        assert!(subject_public_key_info(&[]).is_none());
+        assert!(subject_public_key_info(b"not a certificate").is_none());
+    }
+
+    #[test]
+    fn pins_are_matched_against_the_whole_chain() {
+        let chain = certs(SERVER_PEM);
+        let chain = chain.iter().map(|cert| cert.as_ref()).collect::<Vec<_>>();
+        let intermediate_pin = spki_sha256(chain[1]);
+        let client_pin = spki_sha256(&certs(CLIENT_PEM)[0]);
+
+        let pins = CertificatePins::new()
+            .with_pin("LocalHost", client_pin)
+            .with_pin("localhost", intermediate_pin)
+            .with_pin("sdktest.com", client_pin);
+        pins.verify("localhost", &chain)
+            .expect("intermediate is pinned");
+        pins.verify("unpinned.com", &chain)
+            .expect("host is not pinned");
+        pins.verify("sdktest.com", &chain)
+            .expect_err("no certificate is pinned");
+    }
+}
@@ -1103,3 +1103,352 @@ async fn test_connect_uri_form_rustls() {
 async fn test_connect_uri_form_s2n_tls() {
     run_connect_uri_form_test(tls::Provider::S2nTls, "s2n-tls").await;
 }
+
+// ================================================================================================
+// SOCKS5 Proxy Tests
+// ================================================================================================
+//
+// These tests tunnel requests through an in-process SOCKS5 server.
+
+/// Mock SOCKS5 server that forwards every tunnel to a fixed upstream address
+///
+/// The destination requested by the client is recorded rather than connected to, so tests can
+/// verify the SOCKS handshake while using hostnames that don't resolve.
+#[derive(Debug)]
+struct MockSocks5Server {
+    addr: SocketAddr,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    destinations: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockSocks5Server {
+    /// Create a new SOCKS5 server, optionally requiring username/password authentication
+    async fn new(upstream: SocketAddr, credentials: Option<(&str, &str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let destinations = Arc::new(Mutex::new(Vec::new()));
+        let credentials =
+            credentials.map(|(user, pass)| (user.as_bytes().to_vec(), pass.as_bytes().to_vec()));
+
+        let destinations_clone = destinations.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    result = listener.accept() => {
+                        let Ok((stream, _)) = result else { break };
+                        let destinations = destinations_clone.clone();
+                        let credentials = credentials.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) =
+                                Self::serve(stream, upstream, credentials, destinations).await
+                            {
+                                eprintln!("Mock SOCKS5 server connection error: {}", err);
+                            }
+                        });
+                    }
+                    _ = &mut shutdown_rx => break,
+                }
+            }
+        });
+
+        Self {
+            addr,
+            shutdown_tx: Some(shutdown_tx),
+            destinations,
+        }
+    }
+
+    async fn serve(
+        mut stream: tokio::net::TcpStream,
+        upstream: SocketAddr,
+        credentials: Option<(Vec<u8>, Vec<u8>)>,
+        destinations: Arc<Mutex<Vec<String>>>,
+    ) -> std::io::Result<()> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Method negotiation: VER, NMETHODS, METHODS
+        let mut header = [0u8; 2];
+        stream.read_exact(&mut header).await?;
+        let mut methods = vec![0u8; header[1] as usize];
+        stream.read_exact(&mut methods).await?;
+        let method = if credentials.is_some() { 0x02 } else { 0x00 };
+        if !methods.contains(&method) {
+            stream.write_all(&[0x05, 0xFF]).await?;
+            return Ok(());
+        }
+        stream.write_all(&[0x05, method]).await?;
+
+        // Username/password authentication (RFC 1929): VER, ULEN, UNAME, PLEN, PASSWD
+        if let Some((expected_user, expected_pass)) = credentials {
+            let mut len = [0u8; 2];
+            stream.read_exact(&mut len).await?;
+            let mut user = vec![0u8; len[1] as usize];
+            stream.read_exact(&mut user).await?;
+            stream.read_exact(&mut len[..1]).await?;
+            let mut pass = vec![0u8; len[0] as usize];
+            stream.read_exact(&mut pass).await?;
+            if user != expected_user || pass != expected_pass {
+                stream.write_all(&[0x01, 0x01]).await?;
+                return Ok(());
+            }
+            stream.write_all(&[0x01, 0x00]).await?;
+        }
+
+        // Connect request: VER, CMD, RSV, ATYP, DST.ADDR, DST.PORT
+        let mut request = [0u8; 4];
+        stream.read_exact(&mut request).await?;
+        let host = match request[3] {
+            0x01 => {
+                let mut ip = [0u8; 4];
+                stream.read_exact(&mut ip).await?;
+                std::net::Ipv4Addr::from(ip).to_string()
+            }
+            0x03 => {
+                let mut len = [0u8; 1];
+                stream.read_exact(&mut len).await?;
+                let mut domain = vec![0u8; len[0] as usize];
+                stream.read_exact(&mut domain).await?;
+                String::from_utf8(domain).unwrap()
+            }
+            0x04 => {
+                let mut ip = [0u8; 16];
+                stream.read_exact(&mut ip).await?;
+                format!("[{}]", std::net::Ipv6Addr::from(ip))
+            }
+            atyp => panic!("unexpected address type {}", atyp),
+        };
+        let port = stream.read_u16().await?;
+        destinations
+            .lock()
+            .unwrap()
+            .push(format!("{}:{}", host, port));
+
+        let mut upstream = tokio::net::TcpStream::connect(upstream).await?;
+        stream
+            .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+            .await?;
+        tokio::io::copy_bidirectional(&mut stream, &mut upstream).await?;
+        Ok(())
+    }
+
+    /// Get the address this server is listening on
+    fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Get the destinations that clients requested tunnels to
+    fn destinations(&self) -> Vec<String> {
+        self.destinations.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockSocks5Server {
+    fn drop(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// Creates a target server that expects to receive requests in origin form
+async fn socks_target_server() -> MockProxyServer {
+    MockProxyServer::new(|req| {
+        // The SOCKS tunnel is transparent, so requests are sent as if connecting directly
+        assert_eq!(req.uri, "/api/data");
+        Response::builder()
+            .status(StatusCode::OK)
+            .body("tunneled response".to_string())
+            .unwrap()
+    })
+    .await
+}
+
+/// Tests that HTTP requests are tunneled through a SOCKS5 proxy that resolves hostnames
+#[tokio::test]
+async fn test_socks5h_proxy_http_request() {
+    let target = socks_target_server().await;
+    let socks = MockSocks5Server::new(target.addr(), None).await;
+
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr())).unwrap();
+    let result =
+        make_http_request_through_proxy(proxy_config, "http://aws.amazon.com/api/data").await;
+
+    let (status, body) = result.expect("HTTP request through SOCKS5 proxy should succeed");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "tunneled response");
+
+    // The hostname is sent to the proxy and the default HTTP port is used
+    assert_eq!(socks.destinations(), vec!["aws.amazon.com:80"]);
+    let requests = target.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].headers.get("host").map(String::as_str),
+        Some("aws.amazon.com")
+    );
+    assert!(!requests[0].headers.contains_key("proxy-authorization"));
+}
+
+/// Tests that hostnames are resolved locally for `socks5://` proxies
+#[tokio::test]
+async fn test_socks5_proxy_resolves_locally() {
+    let target = socks_target_server().await;
+    let socks = MockSocks5Server::new(target.addr(), None).await;
+
+    let proxy_config = ProxyConfig::socks5(format!("socks5://{}", socks.addr())).unwrap();
+    let result =
+        make_http_request_through_proxy(proxy_config, "http://localhost:8080/api/data").await;
+
+    result.expect("HTTP request through SOCKS5 proxy should succeed");
+    let destinations = socks.destinations();
+    assert!(
+        destinations == vec!["127.0.0.1:8080"] || destinations == vec!["[::1]:8080"],
+        "expected a resolved IP address, got {:?}",
+        destinations
+    );
+}
+
+/// Tests SOCKS5 username/password authentication
+#[tokio::test]
+async fn test_socks5_proxy_authentication() {
+    let target = socks_target_server().await;
+    let socks = MockSocks5Server::new(target.addr(), Some(("socksuser", "sockspass"))).await;
+
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr()))
+        .unwrap()
+        .with_basic_auth("socksuser", "sockspass");
+    let result =
+        make_http_request_through_proxy(proxy_config, "http://aws.amazon.com/api/data").await;
+
+    let (status, _) = result.expect("authenticated SOCKS5 request should succeed");
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(socks.destinations(), vec!["aws.amazon.com:80"]);
+
+    // Wrong credentials are rejected before a tunnel is requested
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr()))
+        .unwrap()
+        .with_basic_auth("socksuser", "wrongpass");
+    let result =
+        make_http_request_through_proxy(proxy_config, "http://aws.amazon.com/api/data").await;
+    assert!(result.is_err(), "invalid SOCKS5 credentials should fail");
+
+    // Missing credentials are rejected during method negotiation
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr())).unwrap();
+    let result =
+        make_http_request_through_proxy(proxy_config, "http://aws.amazon.com/api/data").await;
+    assert!(result.is_err(), "missing SOCKS5 credentials should fail");
+
+    assert_eq!(socks.destinations().len(), 1);
+    assert_eq!(target.requests().len(), 1);
+}
+
+/// Tests that a `socks5h://` URL in `ALL_PROXY` is used, including embedded credentials
+#[tokio::test]
+async fn test_socks5_proxy_from_environment_variables() {
+    let target = socks_target_server().await;
+    let socks = MockSocks5Server::new(target.addr(), Some(("envuser", "envpass"))).await;
+
+    with_env_vars(
+        &[
+            (
+                "ALL_PROXY",
+                &format!("socks5h://envuser:envpass@{}", socks.addr()),
+            ),
+            ("HTTP_PROXY", ""),
+            ("http_proxy", ""),
+            ("NO_PROXY", "localhost,127.0.0.1"),
+        ],
+        || async {
+            let proxy_config = ProxyConfig::from_env();
+            let result =
+                make_http_request_through_proxy(proxy_config, "http://aws.amazon.com/api/data")
+                    .await;
+
+            let (status, body) = result.expect("environment SOCKS5 request should succeed");
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body, "tunneled response");
+            assert_eq!(socks.destinations(), vec!["aws.amazon.com:80"]);
+        },
+    )
+    .await;
+}
+
+/// Tests that NO_PROXY rules bypass a SOCKS5 proxy
+#[tokio::test]
+async fn test_socks5_proxy_no_proxy_bypass() {
+    let target = MockProxyServer::with_response(StatusCode::OK, "direct response").await;
+    let socks = MockSocks5Server::new(target.addr(), None).await;
+
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr()))
+        .unwrap()
+        .no_proxy("127.0.0.1");
+    let target_url = format!("http://{}/api/data", target.addr());
+    let (_, body) = make_http_request_through_proxy(proxy_config, &target_url)
+        .await
+        .expect("direct request should succeed");
+
+    assert_eq!(body, "direct response");
+    assert!(socks.destinations().is_empty());
+}
+
+/// Generic test function for requests through a SOCKS5 proxy with a TLS provider
+///
+/// HTTP requests are tunneled as-is. For HTTPS requests, TLS is negotiated with the target over
+/// the tunnel; the target here only speaks plain HTTP, so the handshake fails after the tunnel
+/// is established.
+async fn run_socks5_tls_provider_test(tls_provider: tls::Provider, provider_name: &str) {
+    let target = socks_target_server().await;
+    let socks = MockSocks5Server::new(target.addr(), Some(("tlsuser", "tlspass"))).await;
+    let proxy_config = ProxyConfig::socks5(format!("socks5h://{}", socks.addr()))
+        .unwrap()
+        .with_basic_auth("tlsuser", "tlspass");
+
+    let (status, body) = make_https_request_through_proxy(
+        proxy_config.clone(),
+        "http://aws.amazon.com/api/data",
+        tls_provider.clone(),
+    )
+    .await
+    .unwrap_or_else(|e| {
+        panic!("HTTP request through SOCKS5 should succeed for {provider_name}: {e}")
+    });
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "tunneled response");
+
+    let result = make_https_request_through_proxy(
+        proxy_config,
+        "https://secure.aws.amazon.com/api/secure",
+        tls_provider,
+    )
+    .await;
+    assert!(
+        result.is_err(),
+        "TLS handshake with a plain HTTP server should fail for {}",
+        provider_name
+    );
+
+    assert_eq!(
+        socks.destinations(),
+        vec!["aws.amazon.com:80", "secure.aws.amazon.com:443"],
+        "unexpected SOCKS5 destinations for {}",
+        provider_name
+    );
+}
+
+/// Tests requests through a SOCKS5 proxy - rustls provider
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_socks5_proxy_rustls() {
+    run_socks5_tls_provider_test(
+        tls::Provider::rustls(tls::rustls_provider::CryptoMode::Ring),
+        "rustls",
+    )
+    .await;
+}
+
+/// Tests requests through a SOCKS5 proxy - s2n-tls provider
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_socks5_proxy_s2n_tls() {
+    run_socks5_tls_provider_test(tls::Provider::S2nTls, "s2n-tls").await;
+}
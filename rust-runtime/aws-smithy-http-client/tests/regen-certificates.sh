@@ -12,6 +12,10 @@ set -xe
 # Files generated by this script:
 # server.rsa - private RSA server key
 # server.pem - certificate chain containing certificates [server, intermediate, CA]
+# client.rsa - private RSA client key
+# client.pem - certificate chain containing certificates [client, intermediate]
+# client-rotated.rsa - private RSA client key, used to test reloading rotated client identities
+# client-rotated.pem - certificate chain containing certificates [client-rotated, intermediate]
 
 # generate the root CA certificate and key
 openssl req -nodes \
@@ -46,6 +50,21 @@ openssl rsa \
           -in server.key \
           -out server.rsa
 
+# generate keys and signing requests for clients
+for client in client client-rotated; do
+  openssl req -nodes \
+            -newkey rsa:2048 \
+            -keyout $client.key \
+            -out $client.req \
+            -sha256 \
+            -batch \
+            -subj "/CN=sdk test $client"
+
+  openssl rsa \
+            -in $client.key \
+            -out $client.rsa
+done
+
 # sign intermediate cert with CA root
 openssl x509 -req \
             -in inter.req \
@@ -68,5 +87,22 @@ openssl x509 -req \
             -set_serial 456 \
             -extensions v3_end -extfile openssl.cnf
 
+# sign client certs with intermediate
+serial=789
+for client in client client-rotated; do
+  openssl x509 -req \
+              -in $client.req \
+              -out $client.cert \
+              -CA inter.cert \
+              -CAkey inter.key \
+              -sha256 \
+              -days 2000 \
+              -set_serial $serial \
+              -extensions v3_client -extfile openssl.cnf
+  serial=$((serial + 1))
+done
+
 cat server.cert inter.cert ca.cert > server.pem
+cat client.cert inter.cert > client.pem
+cat client-rotated.cert inter.cert > client-rotated.pem
 rm *.key *.cert *.req
@@ -12,10 +12,14 @@
 
 use aws_smithy_async::time::SystemTimeSource;
 use aws_smithy_http_client::tls;
-use aws_smithy_http_client::tls::{TlsContext, TrustStore};
+use aws_smithy_http_client::tls::{
+    CertificatePins, CertificateVerificationError, ClientIdentity, TlsContext, TrustStore,
+    VerifyServerCertificate,
+};
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnector, HttpConnectorSettings};
 use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::result::ConnectorError;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
 use aws_smithy_types::byte_stream::ByteStream;
 use http_1x::{Method, Request, Response, StatusCode};
@@ -24,10 +28,12 @@ use hyper::body::{Bytes, Incoming};
 use hyper::service::service_fn;
 use hyper_util::rt::{TokioExecutor, TokioIo};
 use hyper_util::server::conn::auto::Builder;
-use rustls::ServerConfig;
+use rustls::server::{ParsedCertificate, WebPkiClientVerifier};
+use rustls::{RootCertStore, ServerConfig};
 use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use sha2::{Digest, Sha256};
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{fs, io};
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
@@ -37,39 +43,77 @@ use tracing::{debug, error};
 struct TestServer {
     _handle: JoinHandle<()>,
     listen_addr: SocketAddr,
+    // The end-entity certificates presented by clients, one per connection
+    client_certs: Arc<Mutex<Vec<CertificateDer<'static>>>>,
 }
 
 async fn server() -> Result<TestServer, BoxError> {
+    serve(false, load_certs("tests/server.pem")?).await
+}
+
+/// A server that requires clients to present a certificate issued by the test CA
+async fn mtls_server() -> Result<TestServer, BoxError> {
+    serve(true, load_certs("tests/server.pem")?).await
+}
+
+/// A server that presents an additional certificate after its own chain, which isn't part of the
+/// path from its end-entity certificate to the root
+async fn server_with_appended_certificate(
+    cert: CertificateDer<'static>,
+) -> Result<TestServer, BoxError> {
+    let mut certs = load_certs("tests/server.pem")?;
+    certs.push(cert);
+    serve(false, certs).await
+}
+
+async fn serve(
+    require_client_auth: bool,
+    certs: Vec<CertificateDer<'static>>,
+) -> Result<TestServer, BoxError> {
     // Set process wide crypto provider
     let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
-    // load public certificate.
-    let certs = load_certs("tests/server.pem")?;
-
     // load private key.
     let key = load_private_key("tests/server.rsa")?;
 
     debug!("Starting to serve on https://{}", addr);
 
     // TLS config
-    let mut server_config = ServerConfig::builder()
-        .with_no_client_auth()
+    let server_config = ServerConfig::builder();
+    let server_config = if require_client_auth {
+        // the root CA is the last certificate in the server's chain
+        let mut roots = RootCertStore::empty();
+        roots.add(certs[2].clone())?;
+        server_config
+            .with_client_cert_verifier(WebPkiClientVerifier::builder(Arc::new(roots)).build()?)
+    } else {
+        server_config.with_no_client_auth()
+    };
+    let mut server_config = server_config
         .with_single_cert(certs, key)
         .map_err(|e| error(e.to_string()))?;
 
     server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec(), b"http/1.0".to_vec()];
+    if require_client_auth {
+        // HTTP/1 only, so that clients can use `connection: close` to force new connections
+        server_config.alpn_protocols.remove(0);
+    }
     let tls_acceptor = TlsAcceptor::from(Arc::new(server_config));
     let service = service_fn(echo);
+    let client_certs = Arc::new(Mutex::new(Vec::new()));
 
+    let server_client_certs = client_certs.clone();
     let server = async move {
+        let client_certs = server_client_certs;
         loop {
             let (tcp_stream, remote_addr) = listener.accept().await.unwrap();
             debug!("accepted connection from: {}", remote_addr);
 
             let tls_acceptor = tls_acceptor.clone();
+            let client_certs = client_certs.clone();
             tokio::spawn(async move {
                 let tls_stream = match tls_acceptor.accept(tcp_stream).await {
                     Ok(tls_stream) => tls_stream,
@@ -78,6 +122,9 @@ async fn server() -> Result<TestServer, BoxError> {
                         return;
                     }
                 };
+                if let Some(certs) = tls_stream.get_ref().1.peer_certificates() {
+                    client_certs.lock().unwrap().push(certs[0].clone());
+                }
                 if let Err(err) = Builder::new(TokioExecutor::new())
                     .serve_connection(TokioIo::new(tls_stream), service)
                     .await
@@ -93,6 +140,7 @@ async fn server() -> Result<TestServer, BoxError> {
     Ok(TestServer {
         _handle: server_task,
         listen_addr: addr,
+        client_certs,
     })
 }
 
@@ -149,6 +197,56 @@ fn tls_context_from_pem(filename: &str) -> TlsContext {
         .unwrap()
 }
 
+fn mtls_context(client_identity: Option<ClientIdentity>) -> TlsContext {
+    let pem_contents = fs::read("tests/server.pem").unwrap();
+    let trust_store = TrustStore::empty().with_pem_certificate(pem_contents);
+    let mut builder = TlsContext::builder().with_trust_store(trust_store);
+    builder.set_client_identity(client_identity);
+    builder.build().unwrap()
+}
+
+fn client_identity_from_pem(name: &str) -> ClientIdentity {
+    ClientIdentity::from_pem(
+        fs::read(format!("tests/{name}.pem")).unwrap(),
+        fs::read(format!("tests/{name}.rsa")).unwrap(),
+    )
+}
+
+#[test]
+fn client_identity_is_validated() {
+    let err = TlsContext::builder()
+        .with_client_identity(ClientIdentity::from_pem(
+            fs::read("tests/client.pem").unwrap(),
+            "not a private key",
+        ))
+        .build()
+        .expect_err("invalid private key");
+    assert!(
+        format!("{:?}", err).contains("invalid client private key"),
+        "{err:?}"
+    );
+
+    TlsContext::builder()
+        .with_client_identity(ClientIdentity::from_pem_files(
+            "tests/does-not-exist.pem",
+            "tests/client.rsa",
+        ))
+        .build()
+        .expect_err("missing certificate chain file");
+
+    let err = TlsContext::builder()
+        .with_client_identity(ClientIdentity::from_pem(
+            fs::read("tests/client.pem").unwrap(),
+            fs::read("tests/client-rotated.rsa").unwrap(),
+        ))
+        .build()
+        .expect_err("the private key doesn't match the certificate");
+    assert!(
+        format!("{:?}", err).contains("invalid client identity"),
+        "{err:?}"
+    );
+}
+
 #[cfg(feature = "rustls-aws-lc")]
 #[should_panic(expected = "InvalidCertificate(UnknownIssuer)")]
 #[tokio::test]
@@ -248,8 +346,294 @@ async fn test_s2n_tls_custom_ca() {
     run_tls_test(&client).await.unwrap()
 }
 
-async fn run_tls_test(client: &dyn HttpClient) -> Result<(), BoxError> {
-    let server = server().await?;
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_rustls_ring_client_identity() {
+    let client = aws_smithy_http_client::Builder::new()
+        .tls_provider(tls::Provider::Rustls(
+            tls::rustls_provider::CryptoMode::Ring,
+        ))
+        .tls_context(mtls_context(Some(client_identity_from_pem("client"))))
+        .build_https();
+
+    run_mtls_test(&client).await
+}
+
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_rustls_ring_client_identity_reload() {
+    run_client_identity_reload_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::Rustls(
+                tls::rustls_provider::CryptoMode::Ring,
+            ))
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_rustls_ring_missing_client_identity() {
+    let client = aws_smithy_http_client::Builder::new()
+        .tls_provider(tls::Provider::Rustls(
+            tls::rustls_provider::CryptoMode::Ring,
+        ))
+        .tls_context(mtls_context(None))
+        .build_https();
+
+    let server = mtls_server().await.unwrap();
+    get(&client, &server, false)
+        .await
+        .expect_err("server requires a client certificate");
+}
+
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_s2n_tls_client_identity() {
+    let client = aws_smithy_http_client::Builder::new()
+        .tls_provider(tls::Provider::S2nTls)
+        .tls_context(mtls_context(Some(client_identity_from_pem("client"))))
+        .build_https();
+
+    run_mtls_test(&client).await
+}
+
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_s2n_tls_client_identity_reload() {
+    run_client_identity_reload_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::S2nTls)
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_s2n_tls_missing_client_identity() {
+    let client = aws_smithy_http_client::Builder::new()
+        .tls_provider(tls::Provider::S2nTls)
+        .tls_context(mtls_context(None))
+        .build_https();
+
+    let server = mtls_server().await.unwrap();
+    get(&client, &server, false)
+        .await
+        .expect_err("server requires a client certificate");
+}
+
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_rustls_ring_server_certificate_verification() {
+    run_server_certificate_verification_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::Rustls(
+                tls::rustls_provider::CryptoMode::Ring,
+            ))
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_s2n_tls_server_certificate_verification() {
+    run_server_certificate_verification_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::S2nTls)
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+#[cfg(feature = "rustls-ring")]
+#[tokio::test]
+async fn test_rustls_ring_appended_pinned_certificate() {
+    run_appended_pinned_certificate_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::Rustls(
+                tls::rustls_provider::CryptoMode::Ring,
+            ))
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+#[cfg(feature = "s2n-tls")]
+#[tokio::test]
+async fn test_s2n_tls_appended_pinned_certificate() {
+    run_appended_pinned_certificate_test(|tls_context| {
+        aws_smithy_http_client::Builder::new()
+            .tls_provider(tls::Provider::S2nTls)
+            .tls_context(tls_context)
+            .build_https()
+    })
+    .await
+}
+
+/// Server name and certificate chain that a verifier was called with
+type VerifierCall = (String, Vec<Vec<u8>>);
+
+/// Records the server certificates it's called with
+#[derive(Debug, Default, Clone)]
+struct RecordingVerifier {
+    calls: Arc<Mutex<Vec<VerifierCall>>>,
+}
+
+impl VerifyServerCertificate for RecordingVerifier {
+    fn verify(
+        &self,
+        server_name: &str,
+        cert_chain: &[&[u8]],
+    ) -> Result<(), CertificateVerificationError> {
+        let cert_chain = cert_chain.iter().map(|cert| cert.to_vec()).collect();
+        self.calls
+            .lock()
+            .unwrap()
+            .push((server_name.to_owned(), cert_chain));
+        Ok(())
+    }
+}
+
+fn spki_sha256(cert: &CertificateDer<'_>) -> [u8; 32] {
+    let cert = ParsedCertificate::try_from(cert).unwrap();
+    Sha256::digest(cert.subject_public_key_info()).into()
+}
+
+async fn run_server_certificate_verification_test<C: HttpClient>(
+    build_client: impl Fn(TlsContext) -> C,
+) {
+    let server = server().await.unwrap();
+    let server_certs = load_certs("tests/server.pem").unwrap();
+    let tls_context = |verifier: RecordingVerifier, pins: CertificatePins| {
+        let pem_contents = fs::read("tests/server.pem").unwrap();
+        TlsContext::builder()
+            .with_trust_store(TrustStore::empty().with_pem_certificate(pem_contents))
+            .with_server_certificate_verifier(verifier)
+            .with_certificate_pins(pins)
+            .build()
+            .unwrap()
+    };
+
+    // pinned to the intermediate's public key
+    let verifier = RecordingVerifier::default();
+    let pins = CertificatePins::new().with_pin("localhost", spki_sha256(&server_certs[1]));
+    let client = build_client(tls_context(verifier.clone(), pins));
+    get(&client, &server, false).await.unwrap();
+    let calls = verifier.calls.lock().unwrap().clone();
+    assert!(!calls.is_empty());
+    for (server_name, cert_chain) in &calls {
+        assert_eq!("localhost", server_name);
+        // depending on the TLS provider, the chain may or may not include the root certificate
+        assert!(cert_chain.len() >= 2);
+        assert!(cert_chain[0] == server_certs[0].to_vec());
+        assert!(cert_chain[1] == server_certs[1].to_vec());
+    }
+
+    // pinned to a public key that the server doesn't present
+    let client_certs = load_certs("tests/client.pem").unwrap();
+    let pins = CertificatePins::new().with_pin("localhost", spki_sha256(&client_certs[0]));
+    let client = build_client(tls_context(RecordingVerifier::default(), pins));
+    let err = get(&client, &server, false)
+        .await
+        .expect_err("server certificate isn't pinned");
+    let err = err.downcast_ref::<ConnectorError>().unwrap();
+    assert!(err.is_certificate_verification(), "{err:?}");
+}
+
+/// A server that appends a copy of the pinned certificate to its chain mustn't pass pin
+/// verification, since the pinned certificate isn't part of the verified chain
+async fn run_appended_pinned_certificate_test<C: HttpClient>(
+    build_client: impl Fn(TlsContext) -> C,
+) {
+    let server_certs = load_certs("tests/server.pem").unwrap();
+    let pinned_cert = load_certs("tests/client.pem").unwrap().remove(0);
+    let server = server_with_appended_certificate(pinned_cert.clone())
+        .await
+        .unwrap();
+    let tls_context = |pins: CertificatePins| {
+        let pem_contents = fs::read("tests/server.pem").unwrap();
+        TlsContext::builder()
+            .with_trust_store(TrustStore::empty().with_pem_certificate(pem_contents))
+            .with_certificate_pins(pins)
+            .build()
+            .unwrap()
+    };
+
+    // the appended certificate doesn't prevent the chain from being verified
+    let pins = CertificatePins::new().with_pin("localhost", spki_sha256(&server_certs[1]));
+    get(&build_client(tls_context(pins)), &server, false)
+        .await
+        .unwrap();
+
+    let pins = CertificatePins::new().with_pin("localhost", spki_sha256(&pinned_cert));
+    let err = get(&build_client(tls_context(pins)), &server, false)
+        .await
+        .expect_err("the pinned certificate isn't part of the verified chain");
+    let err = err.downcast_ref::<ConnectorError>().unwrap();
+    assert!(err.is_certificate_verification(), "{err:?}");
+}
+
+async fn run_mtls_test(client: &dyn HttpClient) {
+    let server = mtls_server().await.unwrap();
+    get(client, &server, false).await.unwrap();
+    assert_eq!(
+        load_certs("tests/client.pem").unwrap()[0],
+        server.client_certs.lock().unwrap()[0]
+    );
+}
+
+/// Rotates the client identity on disk between requests, and verifies that the new identity
+/// is presented on the next connection
+async fn run_client_identity_reload_test<C: HttpClient>(
+    build_client: impl FnOnce(TlsContext) -> C,
+) {
+    let dir = std::env::temp_dir().join(format!(
+        "smithy-client-identity-{}-{}",
+        std::process::id(),
+        server_id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let (cert_path, key_path) = (dir.join("identity.pem"), dir.join("identity.rsa"));
+    fs::copy("tests/client.pem", &cert_path).unwrap();
+    fs::copy("tests/client.rsa", &key_path).unwrap();
+
+    let client = build_client(mtls_context(Some(ClientIdentity::from_pem_files(
+        &cert_path, &key_path,
+    ))));
+    let server = mtls_server().await.unwrap();
+    get(&client, &server, true).await.unwrap();
+
+    fs::copy("tests/client-rotated.pem", &cert_path).unwrap();
+    fs::copy("tests/client-rotated.rsa", &key_path).unwrap();
+    get(&client, &server, true).await.unwrap();
+
+    let expected = vec![
+        load_certs("tests/client.pem").unwrap()[0].clone(),
+        load_certs("tests/client-rotated.pem").unwrap()[0].clone(),
+    ];
+    assert_eq!(expected, *server.client_certs.lock().unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Distinguishes the temporary directories of tests running concurrently
+fn server_id() -> usize {
+    static NEXT_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+async fn get(
+    client: &dyn HttpClient,
+    server: &TestServer,
+    close_connection: bool,
+) -> Result<(), BoxError> {
     let endpoint = format!("https://localhost:{}/", server.listen_addr.port());
 
     let connector_settings = HttpConnectorSettings::builder().build();
@@ -258,7 +642,12 @@ async fn run_tls_test(client: &dyn HttpClient) -> Result<(), BoxError> {
         .build()
         .unwrap();
     let connector = client.http_connector(&connector_settings, &runtime_components);
-    let mut response = connector.call(HttpRequest::get(endpoint).unwrap()).await?;
+    let mut request = HttpRequest::get(endpoint).unwrap();
+    if close_connection {
+        // make sure that the next request establishes a new connection
+        request.headers_mut().insert("connection", "close");
+    }
+    let mut response = connector.call(request).await?;
 
     let sdk_body = response.take_body();
     let body_stream = ByteStream::new(sdk_body);
@@ -266,3 +655,8 @@ async fn run_tls_test(client: &dyn HttpClient) -> Result<(), BoxError> {
     assert_eq!(b"Hello TLS!", &resp_bytes[..]);
     Ok(())
 }
+
+async fn run_tls_test(client: &dyn HttpClient) -> Result<(), BoxError> {
+    let server = server().await?;
+    get(client, &server, false).await
+}
@@ -0,0 +1,232 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Reading the credentials of each [`AuthScheme`] from a request.
+
+use aws_smithy_types::base64;
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, Uri};
+
+use super::{ApiKeyLocation, AuthError, AuthScheme, Credentials};
+
+const SIGV4_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+/// Reads the credentials of `scheme` from a request.
+///
+/// Returns `None` if the request doesn't have credentials for the scheme, and an error if it has
+/// credentials that are malformed.
+pub(crate) fn read_credentials(
+    scheme: &AuthScheme,
+    uri: &Uri,
+    headers: &HeaderMap,
+) -> Option<Result<Credentials, AuthError>> {
+    match scheme {
+        AuthScheme::HttpBearer => {
+            let token = authorization(headers, "Bearer")?;
+            Some(Ok(Credentials::Bearer { token: token.into() }))
+        }
+        AuthScheme::HttpBasic => {
+            let encoded = authorization(headers, "Basic")?;
+            Some(basic(encoded))
+        }
+        AuthScheme::HttpApiKey {
+            name,
+            location: ApiKeyLocation::Header,
+            scheme,
+        } => {
+            let value = headers.get(*name)?.to_str().ok()?;
+            let key = match scheme {
+                Some(scheme) => strip_scheme(value, scheme)?,
+                None => value,
+            };
+            Some(Ok(Credentials::ApiKey { key: key.into() }))
+        }
+        AuthScheme::HttpApiKey {
+            name,
+            location: ApiKeyLocation::Query,
+            ..
+        } => {
+            let key = query_param(uri, name)?;
+            Some(Ok(Credentials::ApiKey { key }))
+        }
+        AuthScheme::SigV4 { name } => {
+            let credential = match authorization(headers, SIGV4_ALGORITHM) {
+                Some(authorization) => authorization
+                    .split(',')
+                    .find_map(|component| component.trim().strip_prefix("Credential="))
+                    .map(String::from),
+                None => match query_param(uri, "X-Amz-Algorithm") {
+                    Some(algorithm) if algorithm == SIGV4_ALGORITHM => query_param(uri, "X-Amz-Credential"),
+                    _ => return None,
+                },
+            };
+            Some(sigv4(credential, name))
+        }
+    }
+}
+
+/// Returns the value of the `Authorization` header without its `scheme` prefix, if it has it.
+fn authorization<'a>(headers: &'a HeaderMap, scheme: &str) -> Option<&'a str> {
+    headers
+        .get_all(AUTHORIZATION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(|value| strip_scheme(value, scheme))
+}
+
+/// Strips a case-insensitive auth `scheme` and the whitespace following it from `value`.
+fn strip_scheme<'a>(value: &'a str, scheme: &str) -> Option<&'a str> {
+    let (prefix, rest) = value.split_once(' ')?;
+    prefix.eq_ignore_ascii_case(scheme).then(|| rest.trim_start())
+}
+
+fn query_param(uri: &Uri, name: &str) -> Option<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(uri.query()?)
+        .ok()?
+        .into_iter()
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+fn basic(encoded: &str) -> Result<Credentials, AuthError> {
+    let decoded = base64::decode(encoded)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or_else(|| AuthError::unauthenticated("the basic auth credentials are not valid base64"))?;
+    let (username, password) = decoded
+        .split_once(':')
+        .ok_or_else(|| AuthError::unauthenticated("the basic auth credentials are missing a password"))?;
+    Ok(Credentials::Basic {
+        username: username.into(),
+        password: password.into(),
+    })
+}
+
+/// Parses a credential like `AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request`.
+fn sigv4(credential: Option<String>, signing_name: &str) -> Result<Credentials, AuthError> {
+    let credential = credential.ok_or_else(|| AuthError::unauthenticated("the signature is missing a credential"))?;
+    let mut scope = credential.rsplitn(5, '/');
+    let (Some("aws4_request"), Some(service), Some(region), Some(date), Some(access_key_id)) =
+        (scope.next(), scope.next(), scope.next(), scope.next(), scope.next())
+    else {
+        return Err(AuthError::unauthenticated(
+            "the credential of the signature is malformed",
+        ));
+    };
+    if service != signing_name {
+        return Err(AuthError::unauthenticated(format!(
+            "the credential is scoped to `{service}` rather than `{signing_name}`"
+        )));
+    }
+    Ok(Credentials::SigV4 {
+        access_key_id: access_key_id.into(),
+        date: date.into(),
+        region: region.into(),
+        service: service.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue, Uri};
+
+    use super::read_credentials;
+    use crate::auth::{ApiKeyLocation, AuthScheme, Credentials};
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn bearer() {
+        let uri = Uri::from_static("/");
+        let credentials = read_credentials(&AuthScheme::HttpBearer, &uri, &headers("authorization", "bearer abc"));
+        assert_eq!(
+            Credentials::Bearer { token: "abc".into() },
+            credentials.unwrap().unwrap()
+        );
+
+        let basic = headers("authorization", "Basic dXNlcjpwYXNz");
+        assert!(read_credentials(&AuthScheme::HttpBearer, &uri, &basic).is_none());
+    }
+
+    #[test]
+    fn basic() {
+        let uri = Uri::from_static("/");
+        let credentials = read_credentials(
+            &AuthScheme::HttpBasic,
+            &uri,
+            &headers("authorization", "Basic dXNlcjpwYXNz"),
+        );
+        assert_eq!(
+            Credentials::Basic {
+                username: "user".into(),
+                password: "pass".into()
+            },
+            credentials.unwrap().unwrap()
+        );
+
+        let malformed = headers("authorization", "Basic not base64");
+        let error = read_credentials(&AuthScheme::HttpBasic, &uri, &malformed)
+            .unwrap()
+            .unwrap_err();
+        assert!(error.is_unauthenticated());
+    }
+
+    #[test]
+    fn api_key() {
+        let in_header = AuthScheme::HttpApiKey {
+            name: "x-api-key",
+            location: ApiKeyLocation::Header,
+            scheme: Some("ApiKey"),
+        };
+        let uri = Uri::from_static("/");
+        let credentials = read_credentials(&in_header, &uri, &headers("x-api-key", "ApiKey abc"));
+        assert_eq!(Credentials::ApiKey { key: "abc".into() }, credentials.unwrap().unwrap());
+        assert!(read_credentials(&in_header, &uri, &headers("x-api-key", "abc")).is_none());
+
+        let in_query = AuthScheme::HttpApiKey {
+            name: "key",
+            location: ApiKeyLocation::Query,
+            scheme: None,
+        };
+        let uri = Uri::from_static("/?a=b&key=a%20b");
+        let credentials = read_credentials(&in_query, &uri, &HeaderMap::new());
+        assert_eq!(Credentials::ApiKey { key: "a b".into() }, credentials.unwrap().unwrap());
+    }
+
+    #[test]
+    fn sigv4() {
+        let scheme = AuthScheme::SigV4 { name: "iam" };
+        let uri = Uri::from_static("/");
+        let signed = headers(
+            "authorization",
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/iam/aws4_request, \
+             SignedHeaders=host;x-amz-date, Signature=5d672d79c15b13162d9279b0855cfba6789a8edb4c82c400e06b5924a6f2b5d7",
+        );
+        let expected = Credentials::SigV4 {
+            access_key_id: "AKIDEXAMPLE".into(),
+            date: "20150830".into(),
+            region: "us-east-1".into(),
+            service: "iam".into(),
+        };
+        assert_eq!(expected, read_credentials(&scheme, &uri, &signed).unwrap().unwrap());
+
+        let presigned = Uri::from_static(
+            "/?X-Amz-Algorithm=AWS4-HMAC-SHA256&X-Amz-Credential=AKIDEXAMPLE%2F20150830%2Fus-east-1%2Fiam%2Faws4_request",
+        );
+        assert_eq!(
+            expected,
+            read_credentials(&scheme, &presigned, &HeaderMap::new())
+                .unwrap()
+                .unwrap()
+        );
+
+        let other_service = AuthScheme::SigV4 { name: "s3" };
+        assert!(read_credentials(&other_service, &uri, &signed).unwrap().is_err());
+        assert!(read_credentials(&scheme, &uri, &HeaderMap::new()).is_none());
+    }
+}
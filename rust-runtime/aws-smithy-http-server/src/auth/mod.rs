@@ -0,0 +1,405 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![deny(missing_docs, missing_debug_implementations)]
+
+//! Provides [`AuthPlugin`], which authenticates requests according to the [auth traits] applied to
+//! each operation.
+//!
+//! The plugin reads the credentials of the modeled auth schemes from the HTTP request, in the
+//! order the schemes are listed in the model, and hands them to an [`Authenticate`]
+//! implementation you provide. The principal it resolves is inserted into the request extensions,
+//! where handlers can extract it with [`Extension`](crate::Extension).
+//!
+//! A request without credentials for any of the operation's schemes is rejected with a `401`,
+//! unless the operation has the [`@optionalAuth`] trait or no auth schemes. When the authenticator
+//! rejects the credentials, the request is rejected with the `401` or `403` of the returned
+//! [`AuthError`]. Rejections are rendered as the protocol's `UnauthenticatedException` and
+//! `AccessDeniedException` runtime errors.
+//!
+//! For [`AuthScheme::SigV4`], the authenticator is given the hash of the payload the request was
+//! signed with, in [`AuthRequest::payload_hash`]. Unless the request's `x-amz-content-sha256`
+//! header is `UNSIGNED-PAYLOAD` or one of the `STREAMING-*` values, this requires reading the whole
+//! payload into memory before the request is authenticated. Payloads larger than
+//! [`AuthPlugin::max_payload_size_bytes`] are rejected with [`PayloadTooLarge`], which is rendered
+//! as the protocol's `SerializationException`. The signatures of the chunks of `STREAMING-*`
+//! payloads aren't verified.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_smithy_http_server::auth::*;
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! #[derive(Clone, Debug)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! let authenticator = authenticator_fn(|request: AuthRequest| async move {
+//!     match request.credentials() {
+//!         Credentials::Bearer { token } if token == "secret" => Ok(User { name: "admin".into() }),
+//!         Credentials::Bearer { .. } => Err(AuthError::access_denied("unknown token")),
+//!         _ => Err(AuthError::unauthenticated("unsupported credentials")),
+//!     }
+//! });
+//! let http_plugins = HttpPlugins::new().authenticate(authenticator);
+//! ```
+//!
+//! Handlers then take the principal as an argument:
+//!
+//! ```
+//! # use aws_smithy_http_server::Extension;
+//! # #[derive(Clone, Debug)]
+//! # struct User { name: String }
+//! # struct GetProfileInput;
+//! # struct GetProfileOutput;
+//! async fn get_profile(input: GetProfileInput, user: Extension<User>) -> GetProfileOutput {
+//!     println!("hello {}", user.name);
+//!     GetProfileOutput
+//! }
+//! ```
+//!
+//! [auth traits]: https://smithy.io/2.0/spec/authentication-traits.html
+//! [`@optionalAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#optionalauth-trait
+
+mod credentials;
+mod payload;
+mod plugin;
+mod service;
+
+use std::fmt;
+use std::future::Future;
+
+use http::{HeaderMap, Method, StatusCode, Uri};
+
+use crate::shape_id::ShapeId;
+
+pub use plugin::*;
+pub use service::*;
+
+/// The default maximum size of a payload read into memory to authenticate a request: 10 MiB.
+pub const DEFAULT_MAX_PAYLOAD_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The auth schemes of an operation, as modeled with [auth traits].
+///
+/// This trait is implemented on the generated operation types. The plugin uses it to find which
+/// credentials to look for in a request.
+///
+/// [auth traits]: https://smithy.io/2.0/spec/authentication-traits.html
+pub trait OperationAuth {
+    /// The auth schemes the operation supports, in priority order. No auth schemes means the
+    /// operation doesn't require authentication.
+    const AUTH_SCHEMES: &'static [AuthScheme];
+
+    /// Whether the operation can be called without credentials, as when it has the
+    /// [`@optionalAuth`] trait.
+    ///
+    /// [`@optionalAuth`]: https://smithy.io/2.0/spec/authentication-traits.html#optionalauth-trait
+    const OPTIONAL_AUTH: bool = false;
+}
+
+/// A modeled auth scheme.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// [`@httpBearerAuth`](https://smithy.io/2.0/spec/authentication-traits.html#httpbearerauth-trait):
+    /// a token in the `Authorization` header.
+    HttpBearer,
+    /// [`@httpBasicAuth`](https://smithy.io/2.0/spec/authentication-traits.html#httpbasicauth-trait):
+    /// a username and password in the `Authorization` header.
+    HttpBasic,
+    /// [`@httpApiKeyAuth`](https://smithy.io/2.0/spec/authentication-traits.html#httpapikeyauth-trait):
+    /// an API key in a header or query string parameter.
+    HttpApiKey {
+        /// The name of the header or query string parameter.
+        name: &'static str,
+        /// Where the API key is sent.
+        location: ApiKeyLocation,
+        /// The scheme that prefixes the API key in the header, such as `ApiKey`.
+        scheme: Option<&'static str>,
+    },
+    /// [`@aws.auth#sigv4`](https://smithy.io/2.0/aws/aws-auth.html#aws-auth-sigv4-trait): an
+    /// AWS Signature Version 4 in the `Authorization` header or the query string.
+    SigV4 {
+        /// The signing name of the service.
+        name: &'static str,
+    },
+}
+
+impl AuthScheme {
+    /// The value of the `WWW-Authenticate` header to challenge clients with, for the schemes that
+    /// have one.
+    fn challenge(&self) -> Option<&'static str> {
+        match self {
+            Self::HttpBearer => Some("Bearer"),
+            Self::HttpBasic => Some("Basic"),
+            Self::HttpApiKey { .. } | Self::SigV4 { .. } => None,
+        }
+    }
+}
+
+/// Where the API key of an [`AuthScheme::HttpApiKey`] is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyLocation {
+    /// In a header.
+    Header,
+    /// In a query string parameter.
+    Query,
+}
+
+/// Credentials read from a request.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// A bearer token.
+    Bearer {
+        /// The token.
+        token: String,
+    },
+    /// A username and password.
+    Basic {
+        /// The username.
+        username: String,
+        /// The password.
+        password: String,
+    },
+    /// An API key.
+    ApiKey {
+        /// The key.
+        key: String,
+    },
+    /// An AWS Signature Version 4.
+    ///
+    /// Only the signature's credential scope has been parsed: the authenticator must verify the
+    /// signature itself, for example with `aws_sigv4::http_request::verify`, using the secret of
+    /// the access key and the [`payload_hash`](AuthRequest::payload_hash) of the request.
+    SigV4 {
+        /// The ID of the access key the request was signed with.
+        access_key_id: String,
+        /// The date in the credential scope, formatted like `20150830`.
+        date: String,
+        /// The region in the credential scope.
+        region: String,
+        /// The signing name in the credential scope.
+        service: String,
+    },
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bearer { .. } => f.debug_struct("Bearer").field("token", &"** redacted **").finish(),
+            Self::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"** redacted **")
+                .finish(),
+            Self::ApiKey { .. } => f.debug_struct("ApiKey").field("key", &"** redacted **").finish(),
+            Self::SigV4 {
+                access_key_id,
+                date,
+                region,
+                service,
+            } => f
+                .debug_struct("SigV4")
+                .field("access_key_id", access_key_id)
+                .field("date", date)
+                .field("region", region)
+                .field("service", service)
+                .finish(),
+        }
+    }
+}
+
+/// A request to authenticate, given to an [`Authenticate`] implementation.
+#[derive(Debug, Clone)]
+pub struct AuthRequest {
+    operation: ShapeId,
+    scheme: AuthScheme,
+    credentials: Credentials,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    payload_hash: Option<String>,
+}
+
+impl AuthRequest {
+    /// The operation that was called.
+    pub fn operation(&self) -> &ShapeId {
+        &self.operation
+    }
+
+    /// The auth scheme the credentials were read for.
+    pub fn scheme(&self) -> &AuthScheme {
+        &self.scheme
+    }
+
+    /// The credentials read from the request.
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
+    /// The method of the request.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// The URI of the request.
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// The headers of the request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The hex encoded SHA-256 hash of the payload, or the special value of the
+    /// `x-amz-content-sha256` header (such as `UNSIGNED-PAYLOAD`) that the request was signed
+    /// with. Only set for [`AuthScheme::SigV4`].
+    ///
+    /// When the request has an `x-amz-content-sha256` header with a hash, it's been checked to
+    /// match the payload. Signatures should be verified with this as a precomputed payload hash.
+    pub fn payload_hash(&self) -> Option<&str> {
+        self.payload_hash.as_deref()
+    }
+}
+
+/// The reason an [`Authenticate`] implementation rejected a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthError {
+    kind: AuthErrorKind,
+    message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthErrorKind {
+    Unauthenticated,
+    AccessDenied,
+}
+
+impl AuthError {
+    /// The request's credentials are missing or invalid. Rendered as a `401 Unauthorized`.
+    pub fn unauthenticated(message: impl Into<String>) -> Self {
+        Self {
+            kind: AuthErrorKind::Unauthenticated,
+            message: message.into(),
+        }
+    }
+
+    /// The request's principal isn't allowed to call the operation. Rendered as a
+    /// `403 Forbidden`.
+    pub fn access_denied(message: impl Into<String>) -> Self {
+        Self {
+            kind: AuthErrorKind::AccessDenied,
+            message: message.into(),
+        }
+    }
+
+    /// The status code of the response the error is rendered as.
+    pub fn status_code(&self) -> StatusCode {
+        match self.kind {
+            AuthErrorKind::Unauthenticated => StatusCode::UNAUTHORIZED,
+            AuthErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+        }
+    }
+
+    /// The message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether the request's credentials are missing or invalid, as opposed to not allowing the
+    /// operation to be called.
+    pub fn is_unauthenticated(&self) -> bool {
+        self.kind == AuthErrorKind::Unauthenticated
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            AuthErrorKind::Unauthenticated => write!(f, "unauthenticated: {}", self.message),
+            AuthErrorKind::AccessDenied => write!(f, "access denied: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+/// A payload that had to be read into memory to authenticate a request was larger than the
+/// configured maximum.
+///
+/// This is converted into the protocol's `Serialization` runtime error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadTooLarge {
+    max_payload_size_bytes: u64,
+}
+
+impl PayloadTooLarge {
+    /// The maximum size of the payload that was exceeded.
+    pub fn max_payload_size_bytes(&self) -> u64 {
+        self.max_payload_size_bytes
+    }
+}
+
+impl fmt::Display for PayloadTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the request payload is larger than the maximum of {} bytes",
+            self.max_payload_size_bytes
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+/// Resolves the principal that made a request from its credentials.
+///
+/// Implemented for closures by [`authenticator_fn`].
+pub trait Authenticate {
+    /// The authenticated principal, inserted into the request extensions.
+    type Principal;
+    /// The future returned by [`authenticate`](Authenticate::authenticate).
+    type Future: Future<Output = Result<Self::Principal, AuthError>>;
+
+    /// Authenticates a request, returning its principal.
+    fn authenticate(&self, request: AuthRequest) -> Self::Future;
+}
+
+/// An [`Authenticate`] implementation backed by a closure. See [`authenticator_fn`].
+#[derive(Clone)]
+pub struct AuthenticatorFn<F> {
+    f: F,
+}
+
+impl<F> fmt::Debug for AuthenticatorFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthenticatorFn").finish_non_exhaustive()
+    }
+}
+
+/// Creates an [`Authenticate`] implementation from an async closure.
+pub fn authenticator_fn<F, Fut, P>(f: F) -> AuthenticatorFn<F>
+where
+    F: Fn(AuthRequest) -> Fut,
+    Fut: Future<Output = Result<P, AuthError>>,
+{
+    AuthenticatorFn { f }
+}
+
+impl<F, Fut, P> Authenticate for AuthenticatorFn<F>
+where
+    F: Fn(AuthRequest) -> Fut,
+    Fut: Future<Output = Result<P, AuthError>>,
+{
+    type Principal = P;
+    type Future = Fut;
+
+    fn authenticate(&self, request: AuthRequest) -> Self::Future {
+        (self.f)(request)
+    }
+}
@@ -0,0 +1,167 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Determining the hash of the payload a SigV4 signature was computed over.
+
+use std::fmt::Write;
+
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Body, LengthLimitError, Limited};
+use sha2::{Digest, Sha256};
+
+use crate::error::BoxError;
+
+use super::{AuthError, PayloadTooLarge};
+
+const CONTENT_SHA256: &str = "x-amz-content-sha256";
+
+/// Why the hash of a payload couldn't be determined.
+#[derive(Debug)]
+pub(crate) enum PayloadError {
+    /// The payload doesn't match the `x-amz-content-sha256` header, or couldn't be read.
+    Unauthenticated(AuthError),
+    /// The payload is larger than the maximum that's read into memory.
+    TooLarge(PayloadTooLarge),
+}
+
+impl From<AuthError> for PayloadError {
+    fn from(error: AuthError) -> Self {
+        Self::Unauthenticated(error)
+    }
+}
+
+/// Returns the hash of the payload a SigV4 signed request was signed with, along with the body
+/// to continue the request with.
+///
+/// This is the value of the `x-amz-content-sha256` header if the request has one. The body is read
+/// into memory to check that it matches a hash in the header, or to compute its hash if there's no
+/// header, failing once more than `max_payload_size_bytes` have been read. Other values of the
+/// header, like `UNSIGNED-PAYLOAD` and `STREAMING-*`, are returned as is without reading the body.
+pub(crate) async fn payload_hash<B>(
+    headers: &HeaderMap,
+    body: B,
+    max_payload_size_bytes: u64,
+) -> Result<(String, B), PayloadError>
+where
+    B: Body + From<Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let declared = match headers.get(CONTENT_SHA256) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .map_err(|_| AuthError::unauthenticated("the `x-amz-content-sha256` header isn't valid"))?,
+        ),
+        None => None,
+    };
+    if let Some(declared) = declared.filter(|declared| !is_sha256(declared)) {
+        return Ok((declared.to_owned(), body));
+    }
+
+    let limit = usize::try_from(max_payload_size_bytes).unwrap_or(usize::MAX);
+    let payload = hyper::body::to_bytes(Limited::new(body, limit))
+        .await
+        .map_err(|error| {
+            if error.is::<LengthLimitError>() {
+                PayloadError::TooLarge(PayloadTooLarge { max_payload_size_bytes })
+            } else {
+                AuthError::unauthenticated(format!("failed to read the request payload: {error}")).into()
+            }
+        })?;
+    let hash = Sha256::digest(&payload).iter().fold(String::new(), |mut hash, byte| {
+        let _ = write!(hash, "{byte:02x}");
+        hash
+    });
+    match declared {
+        Some(declared) if !declared.eq_ignore_ascii_case(&hash) => {
+            Err(AuthError::unauthenticated("the `x-amz-content-sha256` header doesn't match the payload").into())
+        }
+        _ => Ok((hash, B::from(payload))),
+    }
+}
+
+/// Whether `value` is a hex encoded SHA-256 hash.
+fn is_sha256(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+    use hyper::Body;
+
+    use super::{payload_hash, PayloadError};
+    use crate::auth::DEFAULT_MAX_PAYLOAD_SIZE_BYTES;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const PAYLOAD_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    fn headers(content_sha256: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-content-sha256", HeaderValue::from_static(content_sha256));
+        headers
+    }
+
+    async fn bytes(body: Body) -> Vec<u8> {
+        hyper::body::to_bytes(body).await.unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn computes_missing_hash() {
+        let (hash, body) = payload_hash(&HeaderMap::new(), Body::from("hello"), DEFAULT_MAX_PAYLOAD_SIZE_BYTES)
+            .await
+            .unwrap();
+        assert_eq!(PAYLOAD_SHA256, hash);
+        assert_eq!(b"hello".to_vec(), bytes(body).await);
+
+        let (hash, _) = payload_hash(&HeaderMap::new(), Body::empty(), DEFAULT_MAX_PAYLOAD_SIZE_BYTES)
+            .await
+            .unwrap();
+        assert_eq!(EMPTY_SHA256, hash);
+    }
+
+    #[tokio::test]
+    async fn verifies_declared_hash() {
+        let (hash, body) = payload_hash(
+            &headers(PAYLOAD_SHA256),
+            Body::from("hello"),
+            DEFAULT_MAX_PAYLOAD_SIZE_BYTES,
+        )
+        .await
+        .unwrap();
+        assert_eq!(PAYLOAD_SHA256, hash);
+        assert_eq!(b"hello".to_vec(), bytes(body).await);
+
+        let error = payload_hash(
+            &headers(PAYLOAD_SHA256),
+            Body::from("tampered"),
+            DEFAULT_MAX_PAYLOAD_SIZE_BYTES,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, PayloadError::Unauthenticated(error) if error.is_unauthenticated()));
+    }
+
+    #[tokio::test]
+    async fn special_values_are_not_verified() {
+        for value in ["UNSIGNED-PAYLOAD", "STREAMING-AWS4-HMAC-SHA256-PAYLOAD"] {
+            let (hash, body) = payload_hash(&headers(value), Body::from("hello"), 1).await.unwrap();
+            assert_eq!(value, hash);
+            assert_eq!(b"hello".to_vec(), bytes(body).await);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_large_payloads() {
+        let (hash, _) = payload_hash(&HeaderMap::new(), Body::from("hello"), 5).await.unwrap();
+        assert_eq!(PAYLOAD_SHA256, hash);
+
+        let error = payload_hash(&headers(PAYLOAD_SHA256), Body::from("hello"), 4)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, PayloadError::TooLarge(error) if error.max_payload_size_bytes() == 4));
+    }
+}
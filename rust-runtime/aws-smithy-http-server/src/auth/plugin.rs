@@ -0,0 +1,68 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::plugin::{HttpMarker, HttpPlugins, PluginStack};
+use crate::service::ServiceShape;
+use crate::{operation::OperationShape, plugin::Plugin};
+
+use super::{AuthService, OperationAuth, DEFAULT_MAX_PAYLOAD_SIZE_BYTES};
+
+/// A [`Plugin`] which applies [`AuthService`] to every operation, authenticating requests with
+/// the given [`Authenticate`](super::Authenticate) implementation.
+#[derive(Debug, Clone)]
+pub struct AuthPlugin<A> {
+    authenticator: A,
+    max_payload_size_bytes: u64,
+}
+
+impl<A> AuthPlugin<A> {
+    /// Creates a new [`AuthPlugin`] that authenticates requests with `authenticator`.
+    pub fn new(authenticator: A) -> Self {
+        Self {
+            authenticator,
+            max_payload_size_bytes: DEFAULT_MAX_PAYLOAD_SIZE_BYTES,
+        }
+    }
+
+    /// Sets the maximum size of a payload that's read into memory to authenticate a request, see
+    /// [`AuthService::max_payload_size_bytes`].
+    pub fn max_payload_size_bytes(mut self, max_payload_size_bytes: u64) -> Self {
+        self.max_payload_size_bytes = max_payload_size_bytes;
+        self
+    }
+}
+
+impl<Ser, Op, T, A> Plugin<Ser, Op, T> for AuthPlugin<A>
+where
+    Ser: ServiceShape,
+    Op: OperationShape,
+    Op: OperationAuth,
+    A: Clone,
+{
+    type Output = AuthService<T, A, Ser::Protocol>;
+
+    fn apply(&self, input: T) -> Self::Output {
+        AuthService::new(input, self.authenticator.clone(), Op::ID, Op::AUTH_SCHEMES)
+            .optional_auth(Op::OPTIONAL_AUTH)
+            .max_payload_size_bytes(self.max_payload_size_bytes)
+    }
+}
+
+impl<A> HttpMarker for AuthPlugin<A> {}
+
+/// An extension trait for applying [`AuthPlugin`].
+pub trait AuthExt<CurrentPlugin> {
+    /// Applies an [`AuthService`] to every operation, authenticating requests according to the
+    /// [auth traits] of the operation. See [`AuthService`] for more information.
+    ///
+    /// [auth traits]: https://smithy.io/2.0/spec/authentication-traits.html
+    fn authenticate<A>(self, authenticator: A) -> HttpPlugins<PluginStack<AuthPlugin<A>, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> AuthExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn authenticate<A>(self, authenticator: A) -> HttpPlugins<PluginStack<AuthPlugin<A>, CurrentPlugin>> {
+        self.push(AuthPlugin::new(authenticator))
+    }
+}
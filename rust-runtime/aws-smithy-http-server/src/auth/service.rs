@@ -0,0 +1,352 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Service`] authenticating requests before they reach an operation.
+
+use std::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{header::WWW_AUTHENTICATE, HeaderValue, Request, Response, StatusCode};
+use http_body::Body;
+use tower::Service;
+
+use crate::{body::BoxBody, error::BoxError, response::IntoResponse, shape_id::ShapeId};
+
+use super::payload::{payload_hash, PayloadError};
+use super::{
+    credentials::read_credentials, AuthError, AuthRequest, AuthScheme, Authenticate, PayloadTooLarge,
+    DEFAULT_MAX_PAYLOAD_SIZE_BYTES,
+};
+
+/// A middleware [`Service`] that authenticates requests with one of an operation's
+/// [`AuthScheme`]s, and inserts the resolved principal into the request extensions.
+///
+/// Rejected requests are answered with the protocol `P`'s rendering of the [`AuthError`]. The
+/// payload of a request authenticated with [`AuthScheme::SigV4`] may be read into memory before
+/// it's authenticated, see [`AuthRequest::payload_hash`]. Payloads larger than
+/// [`AuthService::max_payload_size_bytes`] are answered with the protocol's rendering of
+/// [`PayloadTooLarge`] instead.
+pub struct AuthService<S, A, P> {
+    inner: S,
+    authenticator: A,
+    operation: ShapeId,
+    schemes: &'static [AuthScheme],
+    optional_auth: bool,
+    max_payload_size_bytes: u64,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<S, A, P> AuthService<S, A, P> {
+    /// Constructs a new [`AuthService`] for the `operation` with the given auth `schemes`.
+    pub fn new(inner: S, authenticator: A, operation: ShapeId, schemes: &'static [AuthScheme]) -> Self {
+        Self {
+            inner,
+            authenticator,
+            operation,
+            schemes,
+            optional_auth: false,
+            max_payload_size_bytes: DEFAULT_MAX_PAYLOAD_SIZE_BYTES,
+            _protocol: PhantomData,
+        }
+    }
+
+    /// Allows requests without credentials through, unauthenticated.
+    pub fn optional_auth(mut self, optional_auth: bool) -> Self {
+        self.optional_auth = optional_auth;
+        self
+    }
+
+    /// Sets the maximum size of a payload that's read into memory to authenticate a request.
+    ///
+    /// The default is [`DEFAULT_MAX_PAYLOAD_SIZE_BYTES`].
+    pub fn max_payload_size_bytes(mut self, max_payload_size_bytes: u64) -> Self {
+        self.max_payload_size_bytes = max_payload_size_bytes;
+        self
+    }
+}
+
+/// Renders an [`AuthError`] as a response of the protocol `P`, challenging the client to
+/// authenticate with one of the `schemes` when it's unauthenticated.
+fn reject<P>(operation: &ShapeId, schemes: &[AuthScheme], error: AuthError) -> Response<BoxBody>
+where
+    AuthError: IntoResponse<P>,
+{
+    tracing::debug!(operation = %operation.absolute(), %error, "rejecting request");
+    let mut response = error.into_response();
+    if response.status() == StatusCode::UNAUTHORIZED {
+        for challenge in schemes.iter().filter_map(AuthScheme::challenge) {
+            response
+                .headers_mut()
+                .append(WWW_AUTHENTICATE, HeaderValue::from_static(challenge));
+        }
+    }
+    response
+}
+
+impl<S, A, P> Clone for AuthService<S, A, P>
+where
+    S: Clone,
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            authenticator: self.authenticator.clone(),
+            operation: self.operation.clone(),
+            schemes: self.schemes,
+            optional_auth: self.optional_auth,
+            max_payload_size_bytes: self.max_payload_size_bytes,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, A, P> fmt::Debug for AuthService<S, A, P>
+where
+    S: fmt::Debug,
+    A: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthService")
+            .field("inner", &self.inner)
+            .field("authenticator", &self.authenticator)
+            .field("operation", &self.operation)
+            .field("schemes", &self.schemes)
+            .field("optional_auth", &self.optional_auth)
+            .field("max_payload_size_bytes", &self.max_payload_size_bytes)
+            .finish()
+    }
+}
+
+impl<S, A, P, B> Service<Request<B>> for AuthService<S, A, P>
+where
+    S: Service<Request<B>, Response = Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    A: Authenticate + Clone + Send + 'static,
+    A::Future: Send + 'static,
+    A::Principal: Send + Sync + 'static,
+    AuthError: IntoResponse<P>,
+    PayloadTooLarge: IntoResponse<P>,
+    B: Body + From<Bytes> + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        // The inner service was made ready by `poll_ready`, so it's the one that must be called.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let credentials = self.schemes.iter().find_map(|scheme| {
+            read_credentials(scheme, req.uri(), req.headers()).map(|credentials| (scheme, credentials))
+        });
+        let (scheme, credentials) = match credentials {
+            Some((scheme, Ok(credentials))) => (scheme, credentials),
+            Some((_, Err(error))) => {
+                let response = reject::<P>(&self.operation, self.schemes, error);
+                return Box::pin(async move { Ok(response) });
+            }
+            None if self.optional_auth || self.schemes.is_empty() => return Box::pin(inner.call(req)),
+            None => {
+                let error = AuthError::unauthenticated("the request is missing credentials");
+                let response = reject::<P>(&self.operation, self.schemes, error);
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+
+        let scheme = *scheme;
+        let authenticator = self.authenticator.clone();
+        let (operation, schemes) = (self.operation.clone(), self.schemes);
+        let max_payload_size_bytes = self.max_payload_size_bytes;
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let (payload_hash, body) = match scheme {
+                AuthScheme::SigV4 { .. } => match payload_hash(&parts.headers, body, max_payload_size_bytes).await {
+                    Ok((payload_hash, body)) => (Some(payload_hash), body),
+                    Err(PayloadError::Unauthenticated(error)) => return Ok(reject::<P>(&operation, schemes, error)),
+                    Err(PayloadError::TooLarge(error)) => {
+                        tracing::debug!(operation = %operation.absolute(), %error, "rejecting request");
+                        return Ok(error.into_response());
+                    }
+                },
+                _ => (None, body),
+            };
+            let mut req = Request::from_parts(parts, body);
+
+            let authenticate = authenticator.authenticate(AuthRequest {
+                operation: operation.clone(),
+                scheme,
+                credentials,
+                method: req.method().clone(),
+                uri: req.uri().clone(),
+                headers: req.headers().clone(),
+                payload_hash,
+            });
+            match authenticate.await {
+                Ok(principal) => {
+                    req.extensions_mut().insert(principal);
+                    inner.call(req).await
+                }
+                Err(error) => Ok(reject::<P>(&operation, schemes, error)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{header::WWW_AUTHENTICATE, Request, Response, StatusCode};
+    use tower::{service_fn, Service, ServiceExt};
+
+    use super::AuthService;
+    use crate::auth::{authenticator_fn, AuthError, AuthRequest, AuthScheme, Credentials};
+    use crate::body::{to_boxed, Body, BoxBody};
+    use crate::protocol::rest_json_1::RestJson1;
+    use crate::shape_id::ShapeId;
+
+    const ID: ShapeId = ShapeId::new("namespace#Operation", "namespace", "Operation");
+    const SCHEMES: &[AuthScheme] = &[AuthScheme::HttpBearer, AuthScheme::HttpBasic];
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Principal(String);
+
+    fn auth_service() -> impl Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible> {
+        let inner = service_fn(|request: Request<Body>| async move {
+            let principal = request
+                .extensions()
+                .get::<Principal>()
+                .map(|principal| principal.0.clone())
+                .unwrap_or_default();
+            Ok::<_, Infallible>(Response::new(to_boxed(principal)))
+        });
+        let authenticator = authenticator_fn(|request: AuthRequest| async move {
+            match request.credentials() {
+                Credentials::Bearer { token } if token == "admin" => Ok(Principal("admin".into())),
+                Credentials::Basic { username, .. } => {
+                    Err(AuthError::access_denied(format!("{username} can't call this")))
+                }
+                _ => Err(AuthError::unauthenticated("unknown token")),
+            }
+        });
+        AuthService::<_, _, RestJson1>::new(inner, authenticator, ID, SCHEMES)
+    }
+
+    fn request(authorization: Option<&'static str>) -> Request<Body> {
+        let mut request = Request::builder();
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    async fn body(response: Response<BoxBody>) -> String {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn inserts_principal() {
+        let response = auth_service().oneshot(request(Some("Bearer admin"))).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("admin", body(response).await);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_credentials() {
+        let response = auth_service().oneshot(request(None)).await.unwrap();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        assert_eq!("UnauthenticatedException", response.headers()["x-amzn-errortype"]);
+        let challenges: Vec<_> = response.headers().get_all(WWW_AUTHENTICATE).iter().collect();
+        assert_eq!(vec!["Bearer", "Basic"], challenges);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_credentials() {
+        let response = auth_service().oneshot(request(Some("Bearer nobody"))).await.unwrap();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+        let response = auth_service()
+            .oneshot(request(Some("Basic dXNlcjpwYXNz")))
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+        assert_eq!("AccessDeniedException", response.headers()["x-amzn-errortype"]);
+        assert!(response.headers().get(WWW_AUTHENTICATE).is_none());
+    }
+
+    #[tokio::test]
+    async fn optional_auth() {
+        let inner = service_fn(|_request: Request<Body>| async { Ok::<_, Infallible>(Response::new(to_boxed(""))) });
+        let authenticator = authenticator_fn(|_request: AuthRequest| async { Ok(Principal("admin".into())) });
+        let service = AuthService::<_, _, RestJson1>::new(inner, authenticator, ID, SCHEMES).optional_auth(true);
+        let response = service.oneshot(request(None)).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+    }
+
+    #[tokio::test]
+    async fn sigv4_payload_hash() {
+        const SCHEMES: &[AuthScheme] = &[AuthScheme::SigV4 { name: "service" }];
+        const AUTHORIZATION: &str = "AWS4-HMAC-SHA256 Credential=AKID/20150830/us-east-1/service/aws4_request, \
+            SignedHeaders=host, Signature=0123";
+        // echoes the body, to check that it's still available after it was hashed
+        let inner = service_fn(|request: Request<Body>| async move {
+            let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+            Ok::<_, Infallible>(Response::new(to_boxed(body)))
+        });
+        let authenticator = authenticator_fn(|request: AuthRequest| async move {
+            match request.payload_hash() {
+                Some("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824") => {
+                    Ok(Principal("signer".into()))
+                }
+                _ => Err(AuthError::unauthenticated("signature doesn't match")),
+            }
+        });
+        let service = AuthService::<_, _, RestJson1>::new(inner, authenticator, ID, SCHEMES);
+
+        let request = Request::builder()
+            .header("authorization", AUTHORIZATION)
+            .body(Body::from("hello"))
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert_eq!("hello", body(response).await);
+
+        // the header doesn't match the payload
+        let request = Request::builder()
+            .header("authorization", AUTHORIZATION)
+            .header(
+                "x-amz-content-sha256",
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            )
+            .body(Body::from("tampered"))
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+        // the payload is larger than the maximum read into memory
+        let request = Request::builder()
+            .header("authorization", AUTHORIZATION)
+            .body(Body::from("hello"))
+            .unwrap();
+        let response = service.max_payload_size_bytes(4).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!("SerializationException", response.headers()["x-amzn-errortype"]);
+    }
+}
@@ -0,0 +1,87 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::{
+    fmt,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use aws_smithy_compression::CompressStream;
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Body, SizeHint};
+
+use crate::{body::BoxBody, Error};
+
+/// A response body compressed as a single continuous stream.
+///
+/// `aws_smithy_compression::body::compress::StreamingCompressedBody` can't be used here, since it
+/// wraps an `SdkBody`, which has to be `Sync`.
+pub(super) struct CompressedBody {
+    body: BoxBody,
+    compressor: Box<dyn CompressStream>,
+    is_compression_finished: bool,
+}
+
+impl CompressedBody {
+    pub(super) fn new(body: BoxBody, compressor: Box<dyn CompressStream>) -> Self {
+        Self {
+            body,
+            compressor,
+            is_compression_finished: false,
+        }
+    }
+}
+
+impl fmt::Debug for CompressedBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressedBody")
+            .field("is_compression_finished", &self.is_compression_finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Body for CompressedBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_data(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Self::Data, Self::Error>>> {
+        let this = self.get_mut();
+        if this.is_compression_finished {
+            return Poll::Ready(None);
+        }
+        loop {
+            match ready!(Pin::new(&mut this.body).poll_data(cx)) {
+                Some(Ok(data)) => {
+                    let compressed = this.compressor.compress_chunk(&data).map_err(Error::new)?;
+                    // The compressor may buffer its input; keep polling until it produces output.
+                    if !compressed.is_empty() {
+                        return Poll::Ready(Some(Ok(compressed)));
+                    }
+                }
+                Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+                None => {
+                    this.is_compression_finished = true;
+                    let compressed = this.compressor.finish().map_err(Error::new)?;
+                    return Poll::Ready((!compressed.is_empty()).then_some(Ok(compressed)));
+                }
+            }
+        }
+    }
+
+    fn poll_trailers(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Pin::new(&mut self.get_mut().body).poll_trailers(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.is_compression_finished && self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // The size of the compressed data isn't known until it's been compressed.
+        SizeHint::default()
+    }
+}
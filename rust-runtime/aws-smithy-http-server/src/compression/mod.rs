@@ -0,0 +1,267 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![deny(missing_docs, missing_debug_implementations)]
+
+//! Provides [`CompressionPlugin`], which decompresses request bodies according to their
+//! `Content-Encoding` header and compresses response bodies according to the request's
+//! `Accept-Encoding` header.
+//!
+//! Clients generated from models with the [`@requestCompression`] trait compress request bodies.
+//! The plugin decompresses them before they're deserialized, failing the request once the
+//! decompressed body grows larger than
+//! [`max_decompressed_size_bytes`](CompressionConfig::max_decompressed_size_bytes), to protect
+//! against decompression bombs. Requests with an encoding the server doesn't support are rejected
+//! with the protocol's `UnsupportedMediaTypeException`.
+//!
+//! `gzip` is always supported. `zstd` and `br` are supported when the `zstd` and `brotli` features
+//! are enabled.
+//!
+//! # Example
+//!
+//! ```
+//! # use aws_smithy_http_server::compression::*;
+//! # use aws_smithy_http_server::plugin::HttpPlugins;
+//! let response_compression = CompressionOptions::default()
+//!     .with_level(9)
+//!     .unwrap()
+//!     .with_min_compression_size_bytes(1024)
+//!     .unwrap();
+//! let config = CompressionConfig::new()
+//!     .with_max_decompressed_size_bytes(1024 * 1024)
+//!     .with_response_compression(response_compression);
+//! let http_plugins = HttpPlugins::new().compress(config);
+//! ```
+//!
+//! [`@requestCompression`]: https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait
+
+mod body;
+mod plugin;
+mod service;
+
+use std::{fmt, str::FromStr};
+
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_ENCODING},
+    HeaderMap,
+};
+
+pub use aws_smithy_compression::{CompressionAlgorithm, CompressionOptions};
+pub use plugin::*;
+pub use service::*;
+
+const IDENTITY: &str = "identity";
+
+/// The default maximum size of a decompressed request body: 10 MiB.
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The supported algorithms, in the order they're preferred in when a client accepts several of
+/// them equally.
+const SUPPORTED_ALGORITHMS: &[CompressionAlgorithm] = &[
+    #[cfg(feature = "zstd")]
+    CompressionAlgorithm::Zstd,
+    #[cfg(feature = "brotli")]
+    CompressionAlgorithm::Brotli,
+    CompressionAlgorithm::Gzip,
+];
+
+/// Configures how [`CompressionService`] decompresses requests and compresses responses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionConfig {
+    max_decompressed_size_bytes: u64,
+    response_compression: CompressionOptions,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            max_decompressed_size_bytes: DEFAULT_MAX_DECOMPRESSED_SIZE_BYTES,
+            response_compression: CompressionOptions::default(),
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Creates a new [`CompressionConfig`] with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The maximum size a request body is allowed to decompress to.
+    pub fn max_decompressed_size_bytes(&self) -> u64 {
+        self.max_decompressed_size_bytes
+    }
+
+    /// The options used to compress responses.
+    pub fn response_compression(&self) -> &CompressionOptions {
+        &self.response_compression
+    }
+
+    /// Set the maximum size a request body is allowed to decompress to.
+    ///
+    /// The default is [`DEFAULT_MAX_DECOMPRESSED_SIZE_BYTES`].
+    pub fn with_max_decompressed_size_bytes(self, max_decompressed_size_bytes: u64) -> Self {
+        Self {
+            max_decompressed_size_bytes,
+            ..self
+        }
+    }
+
+    /// Set the options used to compress responses.
+    ///
+    /// Responses smaller than [`CompressionOptions::min_compression_size_bytes`] aren't
+    /// compressed, and disabling the options turns off response compression entirely.
+    pub fn with_response_compression(self, response_compression: CompressionOptions) -> Self {
+        Self {
+            response_compression,
+            ..self
+        }
+    }
+}
+
+/// A request's `Content-Encoding` isn't supported by the server.
+///
+/// This is converted into the protocol's `UnsupportedMediaType` runtime error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedContentEncoding {
+    encoding: String,
+}
+
+impl UnsupportedContentEncoding {
+    /// The unsupported encoding.
+    pub fn encoding(&self) -> &str {
+        &self.encoding
+    }
+}
+
+impl fmt::Display for UnsupportedContentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported content encoding `{}`", self.encoding)
+    }
+}
+
+impl std::error::Error for UnsupportedContentEncoding {}
+
+/// Returns the algorithms a request body was compressed with, in the order they were applied.
+fn content_encodings(headers: &HeaderMap) -> Result<Vec<CompressionAlgorithm>, UnsupportedContentEncoding> {
+    let mut algorithms = Vec::new();
+    for value in headers.get_all(CONTENT_ENCODING) {
+        let value = value.to_str().map_err(|_| UnsupportedContentEncoding {
+            encoding: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+        })?;
+        for encoding in value.split(',').map(str::trim) {
+            if encoding.is_empty() || encoding.eq_ignore_ascii_case(IDENTITY) {
+                continue;
+            }
+            let algorithm = CompressionAlgorithm::from_str(encoding).map_err(|_| UnsupportedContentEncoding {
+                encoding: encoding.to_owned(),
+            })?;
+            algorithms.push(algorithm);
+        }
+    }
+    Ok(algorithms)
+}
+
+/// Picks the supported algorithm with the highest quality value in a request's `Accept-Encoding`
+/// header, if it accepts any.
+fn negotiate(headers: &HeaderMap) -> Option<CompressionAlgorithm> {
+    let mut qualities = [None; SUPPORTED_ALGORITHMS.len()];
+    let mut wildcard = None;
+    let codings = headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','));
+    for coding in codings {
+        let mut params = coding.split(';').map(str::trim);
+        let name = params.next().unwrap_or_default();
+        let quality = params
+            .find_map(|param| param.strip_prefix("q=").or_else(|| param.strip_prefix("Q=")))
+            .map_or(Some(1.0), |quality| quality.parse::<f32>().ok());
+        let Some(quality) = quality else { continue };
+        if name == "*" {
+            wildcard = Some(quality);
+        } else if let Some(index) = SUPPORTED_ALGORITHMS
+            .iter()
+            .position(|algorithm| name.eq_ignore_ascii_case(algorithm.as_str()))
+        {
+            qualities[index] = Some(quality);
+        }
+    }
+
+    let mut negotiated = None;
+    for (algorithm, quality) in SUPPORTED_ALGORITHMS.iter().zip(qualities) {
+        let quality = quality.or(wildcard).unwrap_or_default();
+        if quality > 0.0 && negotiated.is_none_or(|(_, best)| quality > best) {
+            negotiated = Some((*algorithm, quality));
+        }
+    }
+    negotiated.map(|(algorithm, _)| algorithm)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderValue};
+
+    use super::{content_encodings, negotiate, CompressionAlgorithm};
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn content_encodings_in_order() {
+        assert!(content_encodings(&HeaderMap::new()).unwrap().is_empty());
+        assert!(content_encodings(&headers("content-encoding", "identity"))
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            vec![CompressionAlgorithm::Gzip, CompressionAlgorithm::Gzip],
+            content_encodings(&headers("content-encoding", "GZIP, identity, gzip")).unwrap()
+        );
+
+        let error = content_encodings(&headers("content-encoding", "gzip, compress")).unwrap_err();
+        assert_eq!("compress", error.encoding());
+    }
+
+    #[test]
+    fn negotiate_by_quality() {
+        assert_eq!(None, negotiate(&HeaderMap::new()));
+        assert_eq!(None, negotiate(&headers("accept-encoding", "identity, deflate")));
+        assert_eq!(None, negotiate(&headers("accept-encoding", "gzip;q=0")));
+        assert_eq!(
+            Some(CompressionAlgorithm::Gzip),
+            negotiate(&headers("accept-encoding", "deflate, gzip;q=0.5"))
+        );
+        assert_eq!(None, negotiate(&headers("accept-encoding", "*;q=0")));
+    }
+
+    #[test]
+    fn negotiate_wildcard() {
+        assert_eq!(
+            Some(CompressionAlgorithm::Gzip),
+            negotiate(&headers("accept-encoding", "*, zstd;q=0, br;q=0"))
+        );
+        assert_eq!(
+            None,
+            negotiate(&headers("accept-encoding", "*, gzip;q=0, zstd;q=0, br;q=0"))
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn negotiate_prefers_highest_quality() {
+        assert_eq!(
+            Some(CompressionAlgorithm::Zstd),
+            negotiate(&headers("accept-encoding", "gzip, zstd"))
+        );
+        assert_eq!(
+            Some(CompressionAlgorithm::Gzip),
+            negotiate(&headers("accept-encoding", "gzip, zstd;q=0.9"))
+        );
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack};
+use crate::service::ServiceShape;
+
+use super::{CompressionConfig, CompressionService};
+
+/// A [`Plugin`] which applies [`CompressionService`] to every operation.
+#[derive(Debug, Clone, Default)]
+pub struct CompressionPlugin {
+    config: CompressionConfig,
+}
+
+impl CompressionPlugin {
+    /// Creates a new [`CompressionPlugin`] with the given configuration.
+    pub fn new(config: CompressionConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CompressionPlugin
+where
+    Ser: ServiceShape,
+{
+    type Output = CompressionService<T, Ser::Protocol>;
+
+    fn apply(&self, input: T) -> Self::Output {
+        CompressionService::new(input, self.config.clone())
+    }
+}
+
+impl HttpMarker for CompressionPlugin {}
+
+/// An extension trait for applying [`CompressionPlugin`].
+pub trait CompressionExt<CurrentPlugin> {
+    /// Applies a [`CompressionService`] to every operation, decompressing requests and
+    /// compressing responses according to `config`. See [`CompressionService`] for more
+    /// information.
+    fn compress(self, config: CompressionConfig) -> HttpPlugins<PluginStack<CompressionPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> CompressionExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn compress(self, config: CompressionConfig) -> HttpPlugins<PluginStack<CompressionPlugin, CurrentPlugin>> {
+        self.push(CompressionPlugin::new(config))
+    }
+}
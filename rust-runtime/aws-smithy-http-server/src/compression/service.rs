@@ -0,0 +1,315 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Service`] decompressing requests and compressing responses.
+
+use std::{
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use aws_smithy_compression::body::decompress::DecompressedBody;
+use aws_smithy_types::body::SdkBody;
+use bytes::Bytes;
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY},
+    HeaderValue, Method, Request, Response, StatusCode,
+};
+use http_body::Body;
+use tower::Service;
+
+use crate::{
+    body::{boxed, BoxBody},
+    error::BoxError,
+    response::IntoResponse,
+};
+
+use super::{body::CompressedBody, content_encodings, negotiate, CompressionAlgorithm, CompressionConfig};
+use super::{CompressionOptions, UnsupportedContentEncoding};
+
+const EVENT_STREAM_CONTENT_TYPE: &str = "application/vnd.amazon.eventstream";
+
+/// A middleware [`Service`] that decompresses request bodies according to their
+/// `Content-Encoding`, and compresses response bodies with the best algorithm accepted by the
+/// request's `Accept-Encoding`.
+///
+/// The inner service is called with an [`SdkBody`]. A request body that decompresses to more than
+/// [`CompressionConfig::max_decompressed_size_bytes`] fails to be read, which the operation
+/// rejects as a malformed request. Requests with an unsupported `Content-Encoding` are answered
+/// with the protocol `P`'s rendering of [`UnsupportedContentEncoding`].
+pub struct CompressionService<S, P> {
+    inner: S,
+    config: CompressionConfig,
+    _protocol: PhantomData<fn() -> P>,
+}
+
+impl<S, P> CompressionService<S, P> {
+    /// Constructs a new [`CompressionService`] with the given configuration.
+    pub fn new(inner: S, config: CompressionConfig) -> Self {
+        Self {
+            inner,
+            config,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, P> Clone for CompressionService<S, P>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<S, P> fmt::Debug for CompressionService<S, P>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressionService")
+            .field("inner", &self.inner)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+/// Compresses a response with `algorithm`, unless it's already encoded, too small to be worth
+/// compressing, or an event stream, whose messages must be delivered as soon as they're sent.
+fn compress(
+    response: Response<BoxBody>,
+    algorithm: CompressionAlgorithm,
+    options: &CompressionOptions,
+) -> Response<BoxBody> {
+    let (mut parts, body) = response.into_parts();
+    if parts.headers.contains_key(CONTENT_ENCODING) {
+        return Response::from_parts(parts, body);
+    }
+    // The response depends on the `Accept-Encoding` of the request even when it isn't compressed.
+    parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+
+    let is_event_stream = parts.headers.get(CONTENT_TYPE).is_some_and(|content_type| {
+        content_type
+            .as_bytes()
+            .starts_with(EVENT_STREAM_CONTENT_TYPE.as_bytes())
+    });
+    let is_small = body
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size < u64::from(options.min_compression_size_bytes()));
+    if matches!(parts.status, StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED) || is_event_stream || is_small {
+        return Response::from_parts(parts, body);
+    }
+
+    parts
+        .headers
+        .insert(CONTENT_ENCODING, HeaderValue::from_static(algorithm.as_str()));
+    parts.headers.remove(CONTENT_LENGTH);
+    let body = CompressedBody::new(body, algorithm.into_streaming_impl(options));
+    Response::from_parts(parts, boxed(body))
+}
+
+impl<S, P, B> Service<Request<B>> for CompressionService<S, P>
+where
+    S: Service<Request<SdkBody>, Response = Response<BoxBody>>,
+    S::Future: Send + 'static,
+    UnsupportedContentEncoding: IntoResponse<P>,
+    B: Body<Data = Bytes> + Send + Sync + 'static,
+    B::Error: Into<BoxError> + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let (mut parts, body) = req.into_parts();
+        let algorithms = match content_encodings(&parts.headers) {
+            Ok(algorithms) => algorithms,
+            Err(error) => {
+                tracing::debug!(%error, "rejecting request");
+                let response = error.into_response();
+                return Box::pin(async move { Ok(response) });
+            }
+        };
+
+        let mut body = SdkBody::from_body_0_4(body);
+        if !algorithms.is_empty() {
+            // The operation sees the decompressed body, whose length isn't known up front.
+            parts.headers.remove(CONTENT_ENCODING);
+            parts.headers.remove(CONTENT_LENGTH);
+            // Encodings are listed in the order they were applied, so they're undone in reverse.
+            for algorithm in algorithms.into_iter().rev() {
+                let decompressor = algorithm.into_decompress_impl(Some(self.config.max_decompressed_size_bytes()));
+                body = SdkBody::from_body_0_4(DecompressedBody::new(body, decompressor));
+            }
+        }
+
+        let options = self.config.response_compression().clone();
+        let algorithm = if options.is_enabled() && parts.method != Method::HEAD {
+            negotiate(&parts.headers)
+        } else {
+            None
+        };
+        let future = self.inner.call(Request::from_parts(parts, body));
+        Box::pin(async move {
+            let response = future.await?;
+            Ok(match algorithm {
+                Some(algorithm) => compress(response, algorithm, &options),
+                None => response,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, io::Write};
+
+    use aws_smithy_types::body::SdkBody;
+    use flate2::{write::GzEncoder, Compression};
+    use http::{Request, Response, StatusCode};
+    use tower::{service_fn, Service, ServiceExt};
+
+    use super::CompressionService;
+    use crate::body::{to_boxed, Body, BoxBody};
+    use crate::compression::{CompressionConfig, CompressionOptions};
+    use crate::protocol::rest_json_1::RestJson1;
+
+    const PAYLOAD: &str = "a payload that's repeated until it's large enough to be compressed. ";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn gunzip(data: &[u8]) -> Vec<u8> {
+        let mut decoder = flate2::write::GzDecoder::new(Vec::new());
+        decoder.write_all(data).unwrap();
+        decoder.finish().unwrap()
+    }
+
+    /// A service echoing the request body back, with `content-length` set if the request had it.
+    fn compression_service(
+        config: CompressionConfig,
+    ) -> impl Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible> {
+        let inner = service_fn(|request: Request<SdkBody>| async move {
+            let had_content_length = request.headers().contains_key("content-length");
+            let body = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => body,
+                Err(error) => {
+                    let mut response = Response::new(to_boxed(error.to_string()));
+                    *response.status_mut() = StatusCode::BAD_REQUEST;
+                    return Ok(response);
+                }
+            };
+            let mut response = Response::builder();
+            if had_content_length {
+                response = response.header("content-length", body.len());
+            }
+            Ok::<_, Infallible>(response.body(to_boxed(body)).unwrap())
+        });
+        CompressionService::<_, RestJson1>::new(inner, config)
+    }
+
+    async fn body(response: Response<BoxBody>) -> Vec<u8> {
+        hyper::body::to_bytes(response.into_body()).await.unwrap().to_vec()
+    }
+
+    #[tokio::test]
+    async fn decompresses_request() {
+        let request = Request::builder()
+            .header("content-encoding", "gzip")
+            .header("content-length", "1")
+            .body(Body::from(gzip(PAYLOAD.as_bytes())))
+            .unwrap();
+        let response = compression_service(CompressionConfig::new())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(response.headers().get("content-length").is_none());
+        assert_eq!(PAYLOAD.as_bytes(), body(response).await);
+    }
+
+    #[tokio::test]
+    async fn limits_decompressed_size() {
+        let payload = PAYLOAD.repeat(100);
+        let request = Request::builder()
+            .header("content-encoding", "gzip")
+            .body(Body::from(gzip(payload.as_bytes())))
+            .unwrap();
+        let config = CompressionConfig::new().with_max_decompressed_size_bytes(100);
+        let response = compression_service(config).oneshot(request).await.unwrap();
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_encoding() {
+        let request = Request::builder()
+            .header("content-encoding", "compress")
+            .body(Body::from(PAYLOAD))
+            .unwrap();
+        let response = compression_service(CompressionConfig::new())
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(StatusCode::UNSUPPORTED_MEDIA_TYPE, response.status());
+        assert_eq!("UnsupportedMediaTypeException", response.headers()["x-amzn-errortype"]);
+    }
+
+    #[tokio::test]
+    async fn compresses_response() {
+        let options = CompressionOptions::default()
+            .with_min_compression_size_bytes(PAYLOAD.len() as u32)
+            .unwrap();
+        let config = CompressionConfig::new().with_response_compression(options);
+
+        let request = Request::builder()
+            .header("accept-encoding", "deflate, gzip;q=0.8")
+            .header("content-length", "1")
+            .body(Body::from(PAYLOAD))
+            .unwrap();
+        let response = compression_service(config.clone()).oneshot(request).await.unwrap();
+        assert_eq!("gzip", response.headers()["content-encoding"]);
+        assert_eq!("accept-encoding", response.headers()["vary"]);
+        assert!(response.headers().get("content-length").is_none());
+        assert_eq!(PAYLOAD.as_bytes(), gunzip(&body(response).await));
+
+        let request = Request::builder()
+            .header("accept-encoding", "gzip")
+            .body(Body::from(&PAYLOAD[1..]))
+            .unwrap();
+        let response = compression_service(config).oneshot(request).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        assert_eq!("accept-encoding", response.headers()["vary"]);
+        assert_eq!(&PAYLOAD.as_bytes()[1..], body(response).await);
+    }
+
+    #[tokio::test]
+    async fn response_compression_disabled() {
+        let options = CompressionOptions::default().with_enabled(false);
+        let config = CompressionConfig::new().with_response_compression(options);
+        let request = Request::builder()
+            .header("accept-encoding", "gzip")
+            .body(Body::from(PAYLOAD.repeat(1000)))
+            .unwrap();
+        let response = compression_service(config).oneshot(request).await.unwrap();
+        assert!(response.headers().get("content-encoding").is_none());
+        assert!(response.headers().get("vary").is_none());
+    }
+}
@@ -15,7 +15,9 @@
 #[macro_use]
 pub(crate) mod macros;
 
+pub mod auth;
 pub mod body;
+pub mod compression;
 pub(crate) mod error;
 pub mod extension;
 pub mod instrumentation;
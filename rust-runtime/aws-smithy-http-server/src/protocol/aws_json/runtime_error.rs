@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::auth::{AuthError, PayloadTooLarge};
+use crate::compression::UnsupportedContentEncoding;
 use crate::protocol::aws_json_11::AwsJson1_1;
 use crate::response::IntoResponse;
 use crate::runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
@@ -28,6 +30,12 @@ pub enum RuntimeError {
     /// See: [`crate::protocol::rest_json_1::runtime_error::RuntimeError::Validation`]
     #[error("validation failure: operation input contains data that does not adhere to the modeled constraints: {0}")]
     Validation(String),
+    /// See: [`crate::protocol::rest_json_1::runtime_error::RuntimeError::Unauthenticated`]
+    #[error("unauthenticated: {0}")]
+    Unauthenticated(String),
+    /// See: [`crate::protocol::rest_json_1::runtime_error::RuntimeError::AccessDenied`]
+    #[error("access denied: {0}")]
+    AccessDenied(String),
 }
 
 impl RuntimeError {
@@ -38,6 +46,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Unauthenticated(_) => "UnauthenticatedException",
+            Self::AccessDenied(_) => "AccessDeniedException",
         }
     }
 
@@ -48,6 +58,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthenticated(_) => StatusCode::UNAUTHORIZED,
+            Self::AccessDenied(_) => StatusCode::FORBIDDEN,
         }
     }
 }
@@ -113,3 +125,49 @@ impl From<RequestRejection> for RuntimeError {
         }
     }
 }
+
+impl IntoResponse<AwsJson1_0> for AuthError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::from(self))
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for AuthError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::from(self))
+    }
+}
+
+impl From<AuthError> for RuntimeError {
+    fn from(err: AuthError) -> Self {
+        if err.is_unauthenticated() {
+            Self::Unauthenticated(err.message().to_string())
+        } else {
+            Self::AccessDenied(err.message().to_string())
+        }
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for UnsupportedContentEncoding {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::UnsupportedMediaType)
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for UnsupportedContentEncoding {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::UnsupportedMediaType)
+    }
+}
+
+impl IntoResponse<AwsJson1_0> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::Serialization(crate::Error::new(self)))
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::Serialization(crate::Error::new(self)))
+    }
+}
@@ -32,6 +32,8 @@
 use super::rejection::RequestRejection;
 use super::rejection::ResponseRejection;
 use super::RestJson1;
+use crate::auth::{AuthError, PayloadTooLarge};
+use crate::compression::UnsupportedContentEncoding;
 use crate::extension::RuntimeErrorExtension;
 use crate::response::IntoResponse;
 use crate::runtime_error::InternalFailureException;
@@ -61,6 +63,14 @@ pub enum RuntimeError {
     /// [constraint traits]: <https://awslabs.github.io/smithy/2.0/spec/constraint-traits.html>
     #[error("validation failure: operation input contains data that does not adhere to the modeled constraints: {0}")]
     Validation(String),
+    /// The request's credentials are missing or invalid.
+    // This is returned by the [`crate::auth::AuthPlugin`] when it rejects a request, before the
+    // request reaches the operation.
+    #[error("unauthenticated: {0}")]
+    Unauthenticated(String),
+    /// The request's principal isn't allowed to call the operation.
+    #[error("access denied: {0}")]
+    AccessDenied(String),
 }
 
 impl RuntimeError {
@@ -74,6 +84,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Unauthenticated(_) => "UnauthenticatedException",
+            Self::AccessDenied(_) => "AccessDeniedException",
         }
     }
 
@@ -84,6 +96,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthenticated(_) => StatusCode::UNAUTHORIZED,
+            Self::AccessDenied(_) => StatusCode::FORBIDDEN,
         }
     }
 }
@@ -128,3 +142,31 @@ impl From<RequestRejection> for RuntimeError {
         }
     }
 }
+
+impl IntoResponse<RestJson1> for AuthError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::from(self))
+    }
+}
+
+impl From<AuthError> for RuntimeError {
+    fn from(err: AuthError) -> Self {
+        if err.is_unauthenticated() {
+            Self::Unauthenticated(err.message().to_string())
+        } else {
+            Self::AccessDenied(err.message().to_string())
+        }
+    }
+}
+
+impl IntoResponse<RestJson1> for UnsupportedContentEncoding {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::UnsupportedMediaType)
+    }
+}
+
+impl IntoResponse<RestJson1> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::Serialization(crate::Error::new(self)))
+    }
+}
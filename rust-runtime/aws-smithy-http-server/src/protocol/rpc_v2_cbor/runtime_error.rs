@@ -3,6 +3,8 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::auth::{AuthError, PayloadTooLarge};
+use crate::compression::UnsupportedContentEncoding;
 use crate::response::IntoResponse;
 use crate::runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
 use crate::{extension::RuntimeErrorExtension, protocol::rpc_v2_cbor::RpcV2Cbor};
@@ -30,6 +32,12 @@ pub enum RuntimeError {
         "validation failure: operation input contains data that does not adhere to the modeled constraints: {0:?}"
     )]
     Validation(Vec<u8>),
+    /// See: [`crate::protocol::rest_json_1::runtime_error::RuntimeError::Unauthenticated`]
+    #[error("unauthenticated: {0}")]
+    Unauthenticated(String),
+    /// See: [`crate::protocol::rest_json_1::runtime_error::RuntimeError::AccessDenied`]
+    #[error("access denied: {0}")]
+    AccessDenied(String),
 }
 
 impl RuntimeError {
@@ -40,6 +48,8 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::Unauthenticated(_) => "UnauthenticatedException",
+            Self::AccessDenied(_) => "AccessDeniedException",
         }
     }
 
@@ -50,6 +60,8 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthenticated(_) => StatusCode::UNAUTHORIZED,
+            Self::AccessDenied(_) => StatusCode::FORBIDDEN,
         }
     }
 }
@@ -96,3 +108,31 @@ impl From<RequestRejection> for RuntimeError {
         }
     }
 }
+
+impl IntoResponse<RpcV2Cbor> for AuthError {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::from(self))
+    }
+}
+
+impl From<AuthError> for RuntimeError {
+    fn from(err: AuthError) -> Self {
+        if err.is_unauthenticated() {
+            Self::Unauthenticated(err.message().to_string())
+        } else {
+            Self::AccessDenied(err.message().to_string())
+        }
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for UnsupportedContentEncoding {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::UnsupportedMediaType)
+    }
+}
+
+impl IntoResponse<RpcV2Cbor> for PayloadTooLarge {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RpcV2Cbor>::into_response(RuntimeError::Serialization(crate::Error::new(self)))
+    }
+}
@@ -0,0 +1,257 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! OpenTelemetry based implementations of the Smithy Observability Tracer traits.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+
+use crate::attributes::{kv_from_option_attr, value_from_attr};
+use aws_smithy_observability::tracer::{
+    ProvideSpan, Span, SpanBuilder, SpanContext, SpanKind, SpanStatus,
+};
+pub use aws_smithy_observability::tracer::{ProvideTracer, Tracer};
+
+use aws_smithy_observability::{AttributeValue, Attributes, ErrorKind, ObservabilityError};
+use opentelemetry::trace::{
+    Span as OtelSpanTrait, SpanContext as OtelSpanContext, SpanId, SpanKind as OtelSpanKind,
+    Status as OtelStatus, TraceContextExt, TraceFlags, TraceId, TraceState,
+    Tracer as OtelTracerTrait, TracerProvider as OtelTracerProviderTrait,
+};
+use opentelemetry::{Context as OtelContext, KeyValue};
+use opentelemetry_sdk::trace::{
+    Span as OtelSdkSpan, Tracer as OtelSdkTracer, TracerProvider as OtelSdkTracerProvider,
+};
+
+#[derive(Debug)]
+struct SpanWrap(Mutex<OtelSdkSpan>);
+impl Span for SpanWrap {
+    fn set_attribute(&self, key: Cow<'static, str>, value: AttributeValue) {
+        self.0
+            .lock()
+            .unwrap()
+            .set_attribute(KeyValue::new(key, value_from_attr(value)));
+    }
+
+    fn set_status(&self, status: SpanStatus) {
+        let status = match status {
+            SpanStatus::Ok => OtelStatus::Ok,
+            SpanStatus::Error { description } => OtelStatus::error(description),
+            _ => OtelStatus::Unset,
+        };
+        self.0.lock().unwrap().set_status(status);
+    }
+
+    // Recorded as an `exception` event, following the OTel semantic conventions
+    fn record_error(&self, error: &(dyn Error + 'static)) {
+        self.0.lock().unwrap().record_error(error);
+    }
+
+    fn add_event(&self, name: Cow<'static, str>, attributes: Option<&Attributes>) {
+        self.0
+            .lock()
+            .unwrap()
+            .add_event(name, kv_from_option_attr(attributes));
+    }
+
+    fn span_context(&self) -> SpanContext {
+        let span = self.0.lock().unwrap();
+        let context = span.span_context();
+        SpanContext::new(
+            context.trace_id().to_bytes(),
+            context.span_id().to_bytes(),
+            context.is_sampled(),
+        )
+    }
+
+    fn end(&self) {
+        self.0.lock().unwrap().end();
+    }
+}
+
+#[derive(Debug)]
+struct TracerWrap(OtelSdkTracer);
+impl ProvideSpan for TracerWrap {
+    fn start_span(&self, builder: SpanBuilder<'_>) -> Arc<dyn Span> {
+        // Spans without a Smithy parent are parented to the caller's current OTel context
+        let parent_cx = match builder.get_parent() {
+            Some(parent) => OtelContext::current().with_remote_span_context(OtelSpanContext::new(
+                TraceId::from_bytes(parent.trace_id()),
+                SpanId::from_bytes(parent.span_id()),
+                if parent.is_sampled() {
+                    TraceFlags::SAMPLED
+                } else {
+                    TraceFlags::default()
+                },
+                false,
+                TraceState::default(),
+            )),
+            None => OtelContext::current(),
+        };
+
+        let mut otel_builder = self
+            .0
+            .span_builder(builder.get_name().clone())
+            .with_kind(match builder.get_kind() {
+                SpanKind::Client => OtelSpanKind::Client,
+                SpanKind::Server => OtelSpanKind::Server,
+                _ => OtelSpanKind::Internal,
+            })
+            .with_attributes(kv_from_option_attr(builder.get_attributes()));
+        if let Some(start_time) = builder.get_start_time() {
+            otel_builder = otel_builder.with_start_time(start_time);
+        }
+
+        Arc::new(SpanWrap(Mutex::new(
+            self.0.build_with_context(otel_builder, &parent_cx),
+        )))
+    }
+}
+
+/// An OpenTelemetry based implementation of the AWS SDK's [ProvideTracer] trait
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct OtelTracerProvider {
+    tracer_provider: OtelSdkTracerProvider,
+}
+
+impl OtelTracerProvider {
+    /// Create a new [OtelTracerProvider] from an [OtelSdkTracerProvider].
+    pub fn new(otel_tracer_provider: OtelSdkTracerProvider) -> Self {
+        Self {
+            tracer_provider: otel_tracer_provider,
+        }
+    }
+
+    /// Flush the span pipeline.
+    pub fn flush(&self) -> Result<(), ObservabilityError> {
+        self.tracer_provider
+            .force_flush()
+            .into_iter()
+            .try_for_each(|result| {
+                result.map_err(|err| ObservabilityError::new(ErrorKind::Other, err))
+            })
+    }
+}
+
+impl ProvideTracer for OtelTracerProvider {
+    fn get_tracer(&self, scope: &'static str, _attributes: Option<&Attributes>) -> Tracer {
+        Tracer::new(Arc::new(TracerWrap(self.tracer_provider.tracer(scope))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use aws_smithy_observability::tracer::{SpanKind, SpanStatus};
+    use aws_smithy_observability::{AttributeValue, Attributes, TelemetryProvider};
+    use opentelemetry::trace::{
+        FutureExt, SpanKind as OtelSpanKind, Status as OtelStatus, TraceContextExt,
+        Tracer as OtelTracerTrait, TracerProvider as OtelTracerProviderTrait,
+    };
+    use opentelemetry::{Context as OtelContext, Value};
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider as OtelSdkTracerProvider;
+
+    use super::OtelTracerProvider;
+
+    #[tokio::test]
+    async fn spans_are_exported() {
+        // Create the OTel tracing objects
+        let exporter = InMemorySpanExporter::default();
+        let otel_tp = OtelSdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        // Create the SDK tracing types from the OTel objects
+        let sdk_tp = Arc::new(OtelTracerProvider::new(otel_tp.clone()));
+        let sdk_ref = sdk_tp.clone();
+        let sdk_tp = TelemetryProvider::builder().tracer_provider(sdk_tp).build();
+        let tracer = sdk_tp.tracer_provider().get_tracer("TestTracer", None);
+
+        // Start a span in the caller's OTel context, and a child of it from the SDK
+        let caller_span = otel_tp.tracer("Caller").start("caller");
+        let caller_cx = OtelContext::current_with_span(caller_span);
+        let (call, attempt) = async {
+            let mut attrs = Attributes::new();
+            attrs.set("rpc.service", AttributeValue::String("TestService".into()));
+            let call = tracer
+                .create_span("TestService.TestOperation")
+                .set_kind(SpanKind::Client)
+                .set_attributes(attrs)
+                .start();
+            let attempt = tracer
+                .create_span("attempt")
+                .set_parent(Some(call.span_context()))
+                .start();
+            (call, attempt)
+        }
+        .with_context(caller_cx.clone())
+        .await;
+
+        let mut event_attrs = Attributes::new();
+        event_attrs.set("attempt", AttributeValue::I64(2));
+        call.add_event("retry".into(), Some(&event_attrs));
+        attempt.record_error(&std::io::Error::other("connection reset"));
+        attempt.set_status(SpanStatus::Error {
+            description: "connection reset".into(),
+        });
+        attempt.end();
+        call.set_attribute("http.response.status_code".into(), AttributeValue::I64(200));
+        call.set_status(SpanStatus::Ok);
+        call.end();
+        caller_cx.span().end();
+
+        sdk_ref.flush().unwrap();
+        let finished_spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(3, finished_spans.len());
+        let [attempt_data, call_data, caller_data] = &finished_spans[..] else {
+            unreachable!()
+        };
+
+        // The SDK spans are a part of the caller's trace
+        assert_eq!(
+            caller_data.span_context.trace_id(),
+            call_data.span_context.trace_id()
+        );
+        assert_eq!(caller_data.span_context.span_id(), call_data.parent_span_id);
+        assert_eq!(
+            call_data.span_context.span_id(),
+            attempt_data.parent_span_id
+        );
+        assert_eq!(
+            call.span_context().span_id(),
+            call_data.span_context.span_id().to_bytes()
+        );
+
+        assert_eq!("TestService.TestOperation", call_data.name);
+        assert_eq!(OtelSpanKind::Client, call_data.span_kind);
+        assert_eq!(OtelStatus::Ok, call_data.status);
+        let attr = |key: &str| {
+            call_data
+                .attributes
+                .iter()
+                .find(|kv| kv.key.as_str() == key)
+                .map(|kv| kv.value.clone())
+        };
+        assert_eq!(Some(Value::from("TestService")), attr("rpc.service"));
+        assert_eq!(Some(Value::I64(200)), attr("http.response.status_code"));
+        let retry = &call_data.events.events[0];
+        assert_eq!("retry", retry.name);
+        assert_eq!(Value::I64(2), retry.attributes[0].value);
+
+        // Errors are recorded as exception events
+        assert_eq!(OtelStatus::error("connection reset"), attempt_data.status);
+        let exception = &attempt_data.events.events[0];
+        assert_eq!("exception", exception.name);
+        assert_eq!("exception.message", exception.attributes[0].key.as_str());
+        assert_eq!(
+            Value::from("connection reset"),
+            exception.attributes[0].value
+        );
+    }
+}
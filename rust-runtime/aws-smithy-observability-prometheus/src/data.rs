@@ -0,0 +1,200 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Point-in-time views of the metrics recorded by an
+//! [`InMemoryMeterProvider`](crate::meter::InMemoryMeterProvider).
+
+use aws_smithy_observability::{AttributeValue, Attributes};
+use std::sync::Arc;
+
+/// The kind of instrument a [Metric] was recorded with.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MetricKind {
+    /// A value that only increases, from a monotonic counter.
+    Counter,
+    /// A value that increases and decreases, from an up-down counter.
+    UpDownCounter,
+    /// The last observed value, from a gauge.
+    Gauge,
+    /// A distribution of recorded values, from a histogram.
+    Histogram,
+}
+
+/// All metrics recorded by an [`InMemoryMeterProvider`](crate::meter::InMemoryMeterProvider)
+/// at the time of [collection](crate::meter::InMemoryMeterProvider::collect), ordered by name.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSnapshot {
+    pub(crate) metrics: Vec<Metric>,
+}
+
+impl MetricsSnapshot {
+    /// Get the metric with the given instrument name.
+    pub fn get(&self, name: &str) -> Option<&Metric> {
+        self.metrics.iter().find(|metric| metric.name == name)
+    }
+
+    /// Iterate over all metrics.
+    pub fn iter(&self) -> impl Iterator<Item = &Metric> {
+        self.metrics.iter()
+    }
+}
+
+/// The data recorded by a single instrument.
+#[derive(Clone, Debug)]
+pub struct Metric {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) units: Option<String>,
+    pub(crate) kind: MetricKind,
+    pub(crate) data_points: Vec<DataPoint>,
+}
+
+impl Metric {
+    /// Get the instrument name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the instrument description.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Get the instrument units.
+    pub fn units(&self) -> Option<&str> {
+        self.units.as_deref()
+    }
+
+    /// Get the instrument kind.
+    pub fn kind(&self) -> MetricKind {
+        self.kind
+    }
+
+    /// Get the data points, one per distinct set of attributes.
+    pub fn data_points(&self) -> &[DataPoint] {
+        &self.data_points
+    }
+
+    /// The sum of the values of all data points. For histograms, this is the sum of every
+    /// recorded value.
+    pub fn sum(&self) -> f64 {
+        self.data_points
+            .iter()
+            .map(|point| match &point.value {
+                PointValue::Value(value) => *value,
+                PointValue::Histogram(histogram) => histogram.sum,
+            })
+            .sum()
+    }
+
+    /// The number of values recorded across all data points of a histogram, or 0 for other
+    /// kinds of metric.
+    pub fn count(&self) -> u64 {
+        self.data_points
+            .iter()
+            .filter_map(DataPoint::histogram)
+            .map(HistogramData::count)
+            .sum()
+    }
+}
+
+/// The value of a [Metric] for one set of attributes.
+#[derive(Clone, Debug)]
+pub struct DataPoint {
+    pub(crate) attributes: Attributes,
+    /// The attributes as sorted `(key, value)` strings, which identify this data point
+    pub(crate) labels: Vec<(String, String)>,
+    pub(crate) value: PointValue,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum PointValue {
+    Value(f64),
+    Histogram(HistogramData),
+}
+
+impl DataPoint {
+    /// Get the attributes.
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
+    /// Get the value of the attribute with the given key.
+    pub fn attribute(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes.get(key)
+    }
+
+    /// Get the value of a counter, up-down counter, or gauge data point.
+    pub fn value(&self) -> Option<f64> {
+        match &self.value {
+            PointValue::Value(value) => Some(*value),
+            PointValue::Histogram(_) => None,
+        }
+    }
+
+    /// Get the distribution of a histogram data point.
+    pub fn histogram(&self) -> Option<&HistogramData> {
+        match &self.value {
+            PointValue::Histogram(histogram) => Some(histogram),
+            PointValue::Value(_) => None,
+        }
+    }
+}
+
+/// The distribution of the values recorded by a histogram.
+#[derive(Clone, Debug)]
+pub struct HistogramData {
+    pub(crate) bounds: Arc<[f64]>,
+    /// The number of values in each bucket, with a final bucket for values above every bound
+    pub(crate) bucket_counts: Vec<u64>,
+    pub(crate) count: u64,
+    pub(crate) sum: f64,
+}
+
+impl HistogramData {
+    pub(crate) fn new(bounds: Arc<[f64]>) -> Self {
+        Self {
+            bucket_counts: vec![0; bounds.len() + 1],
+            bounds,
+            count: 0,
+            sum: 0.0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.bucket_counts[bucket] += 1;
+        self.count += 1;
+        self.sum += value;
+    }
+
+    /// The number of recorded values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The sum of the recorded values.
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// Iterate over `(upper bound, cumulative count)` pairs, ending with an infinite upper
+    /// bound that counts every recorded value.
+    pub fn buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.bounds
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY))
+            .zip(self.bucket_counts.iter().scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            }))
+    }
+}
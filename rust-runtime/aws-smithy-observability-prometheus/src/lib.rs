@@ -0,0 +1,50 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+/* Automatically managed default lints */
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+/* End of automatically managed default lints */
+#![warn(
+    missing_docs,
+    rustdoc::missing_crate_level_docs,
+    unreachable_pub,
+    rust_2018_idioms
+)]
+
+//! Smithy Observability in-memory metrics with Prometheus exposition
+//!
+//! [`InMemoryMeterProvider`](meter::InMemoryMeterProvider) keeps every measurement made with its
+//! instruments in memory. A [`MetricsSnapshot`](data::MetricsSnapshot) of them can be queried
+//! directly, which is useful for asserting on the metrics an SDK client emitted in tests, or
+//! rendered in the Prometheus text exposition format with [`text::encode`] to be served from a
+//! `/metrics` endpoint.
+//!
+//! # Examples
+//! ```
+//! use std::sync::Arc;
+//! use aws_smithy_observability::TelemetryProvider;
+//! use aws_smithy_observability_prometheus::meter::InMemoryMeterProvider;
+//! use aws_smithy_observability_prometheus::text;
+//!
+//! let provider = InMemoryMeterProvider::new();
+//! let telemetry_provider = TelemetryProvider::builder()
+//!     .meter_provider(Arc::new(provider.clone()))
+//!     .build();
+//!
+//! let meter = telemetry_provider.meter_provider().get_meter("example", None);
+//! let attempts = meter.create_monotonic_counter("smithy.client.call.attempts").build();
+//! attempts.add(3, None, None);
+//!
+//! let snapshot = provider.collect();
+//! assert_eq!(3.0, snapshot.get("smithy.client.call.attempts").unwrap().sum());
+//! assert_eq!(
+//!     "# TYPE smithy_client_call_attempts_total counter\nsmithy_client_call_attempts_total 3\n",
+//!     text::encode(&snapshot)
+//! );
+//! ```
+
+pub mod data;
+pub mod meter;
+pub mod text;
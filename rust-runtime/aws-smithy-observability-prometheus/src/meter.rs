@@ -0,0 +1,516 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! In-memory implementations of the Smithy Observability Meter traits.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::data::{DataPoint, HistogramData, Metric, MetricKind, MetricsSnapshot, PointValue};
+use aws_smithy_observability::instruments::{
+    AsyncInstrumentBuilder, AsyncMeasure, Histogram, InstrumentBuilder, MonotonicCounter,
+    ProvideInstrument, UpDownCounter,
+};
+pub use aws_smithy_observability::meter::{Meter, ProvideMeter};
+use aws_smithy_observability::{AttributeValue, Attributes, Context};
+
+/// Histogram bucket bounds suited to durations measured in seconds, matching the Prometheus
+/// client defaults.
+const DEFAULT_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A [ProvideMeter] that keeps the measurements of its instruments in memory.
+///
+/// Instruments from every meter share a single namespace, as in the Prometheus exposition
+/// format: creating an instrument with the name and kind of an existing one records into the
+/// existing one, while creating one with the name of an instrument of a different kind replaces
+/// it. Clones share the same measurements.
+#[derive(Clone, Debug)]
+pub struct InMemoryMeterProvider {
+    registry: Arc<Registry>,
+}
+
+impl Default for InMemoryMeterProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryMeterProvider {
+    /// Create a new [InMemoryMeterProvider] whose histograms use the default Prometheus bucket
+    /// bounds.
+    pub fn new() -> Self {
+        Self::with_histogram_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+
+    /// Create a new [InMemoryMeterProvider] whose histograms use the given bucket upper bounds.
+    pub fn with_histogram_buckets(bounds: impl Into<Vec<f64>>) -> Self {
+        let mut bounds = bounds.into();
+        bounds.retain(|bound| bound.is_finite());
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+        Self {
+            registry: Arc::new(Registry {
+                bounds: bounds.into(),
+                instruments: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Observe all asynchronous instruments and return a snapshot of every recorded metric.
+    pub fn collect(&self) -> MetricsSnapshot {
+        let instruments = self.registry.instruments.lock().unwrap().clone();
+        let mut metrics = instruments
+            .iter()
+            .map(|instrument| instrument.collect())
+            .collect::<Vec<_>>();
+        metrics.sort_by(|a, b| a.name.cmp(&b.name));
+        MetricsSnapshot { metrics }
+    }
+}
+
+impl ProvideMeter for InMemoryMeterProvider {
+    fn get_meter(&self, _scope: &'static str, _attributes: Option<&Attributes>) -> Meter {
+        Meter::new(Arc::new(InMemoryMeter(self.registry.clone())))
+    }
+}
+
+#[derive(Debug)]
+struct Registry {
+    bounds: Arc<[f64]>,
+    instruments: Mutex<Vec<Arc<Instrument>>>,
+}
+
+impl Registry {
+    fn register(
+        &self,
+        name: &str,
+        description: &Option<impl AsRef<str>>,
+        units: &Option<impl AsRef<str>>,
+        kind: MetricKind,
+    ) -> Arc<Instrument> {
+        let mut instruments = self.instruments.lock().unwrap();
+        if let Some(index) = instruments.iter().position(|i| i.name == name) {
+            if instruments[index].kind == kind {
+                return instruments[index].clone();
+            }
+            instruments.remove(index);
+        }
+        let instrument = Arc::new(Instrument {
+            name: name.to_string(),
+            description: description.as_ref().map(|d| d.as_ref().to_string()),
+            units: units.as_ref().map(|u| u.as_ref().to_string()),
+            kind,
+            bounds: self.bounds.clone(),
+            data_points: Mutex::new(BTreeMap::new()),
+            callbacks: Mutex::new(Vec::new()),
+            next_callback_id: AtomicU64::new(0),
+        });
+        instruments.push(instrument.clone());
+        instrument
+    }
+}
+
+type Callback = Arc<dyn Fn(&Instrument) + Send + Sync>;
+
+struct Instrument {
+    name: String,
+    description: Option<String>,
+    units: Option<String>,
+    kind: MetricKind,
+    bounds: Arc<[f64]>,
+    data_points: Mutex<BTreeMap<Vec<(String, String)>, DataPoint>>,
+    callbacks: Mutex<Vec<(u64, Callback)>>,
+    next_callback_id: AtomicU64,
+}
+
+// Callbacks aren't Debug, so we impl a minimal version
+impl Debug for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instrument")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+impl Instrument {
+    fn update(&self, attributes: Option<&Attributes>, update: impl FnOnce(&mut PointValue)) {
+        let labels = labels(attributes);
+        let mut data_points = self.data_points.lock().unwrap();
+        let data_point = data_points
+            .entry(labels.clone())
+            .or_insert_with(|| DataPoint {
+                attributes: attributes.cloned().unwrap_or_default(),
+                labels,
+                value: match self.kind {
+                    MetricKind::Histogram => {
+                        PointValue::Histogram(HistogramData::new(self.bounds.clone()))
+                    }
+                    _ => PointValue::Value(0.0),
+                },
+            });
+        update(&mut data_point.value);
+    }
+
+    fn add(&self, value: f64, attributes: Option<&Attributes>) {
+        self.update(attributes, |point| {
+            if let PointValue::Value(total) = point {
+                *total += value;
+            }
+        });
+    }
+
+    fn set(&self, value: f64, attributes: Option<&Attributes>) {
+        self.update(attributes, |point| {
+            if let PointValue::Value(current) = point {
+                *current = value;
+            }
+        });
+    }
+
+    fn record(&self, value: f64, attributes: Option<&Attributes>) {
+        self.update(attributes, |point| {
+            if let PointValue::Histogram(histogram) = point {
+                histogram.record(value);
+            }
+        });
+    }
+
+    fn register_callback(&self, callback: Callback) -> u64 {
+        let id = self.next_callback_id.fetch_add(1, Ordering::Relaxed);
+        self.callbacks.lock().unwrap().push((id, callback));
+        id
+    }
+
+    fn unregister_callback(&self, id: u64) {
+        self.callbacks.lock().unwrap().retain(|(i, _)| *i != id);
+    }
+
+    fn collect(&self) -> Metric {
+        // Callbacks record into this instrument, so they're run without holding the lock
+        let callbacks = self.callbacks.lock().unwrap().clone();
+        for (_, callback) in callbacks {
+            callback(self);
+        }
+        Metric {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            units: self.units.clone(),
+            kind: self.kind,
+            data_points: self.data_points.lock().unwrap().values().cloned().collect(),
+        }
+    }
+}
+
+/// Returns the attributes as `(key, value)` strings, sorted by key
+fn labels(attributes: Option<&Attributes>) -> Vec<(String, String)> {
+    let mut labels = attributes
+        .map(|attributes| {
+            attributes
+                .attributes()
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        AttributeValue::I64(v) => v.to_string(),
+                        AttributeValue::F64(v) => v.to_string(),
+                        AttributeValue::String(v) => v.clone(),
+                        AttributeValue::Bool(v) => v.to_string(),
+                        other => format!("{other:?}"),
+                    };
+                    (key.clone(), value)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    labels.sort();
+    labels
+}
+
+#[derive(Debug)]
+struct SyncInstrument(Arc<Instrument>);
+
+impl Histogram for SyncInstrument {
+    fn record(&self, value: f64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.0.record(value, attributes);
+    }
+}
+
+impl MonotonicCounter for SyncInstrument {
+    fn add(&self, value: u64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.0.add(value as f64, attributes);
+    }
+}
+
+impl UpDownCounter for SyncInstrument {
+    fn add(&self, value: i64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.0.add(value as f64, attributes);
+    }
+}
+
+/// Measurement types of asynchronous instruments
+trait Measurement: Copy + Debug + Send + Sync + 'static {
+    fn as_f64(self) -> f64;
+}
+
+impl Measurement for f64 {
+    fn as_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Measurement for i64 {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Measurement for u64 {
+    fn as_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Passed to the callback of an asynchronous instrument when it's observed
+#[derive(Debug)]
+struct Observer<'a, T>(&'a Instrument, PhantomData<T>);
+
+impl<T: Measurement> AsyncMeasure for Observer<'_, T> {
+    type Value = T;
+
+    fn record(&self, value: T, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.0.set(value.as_f64(), attributes);
+    }
+
+    fn stop(&self) {}
+}
+
+#[derive(Debug)]
+struct AsyncInstrument<T> {
+    instrument: Arc<Instrument>,
+    callback_id: u64,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Measurement> AsyncInstrument<T> {
+    #[allow(clippy::type_complexity)]
+    fn new(
+        instrument: Arc<Instrument>,
+        callback: Arc<dyn Fn(&dyn AsyncMeasure<Value = T>) + Send + Sync>,
+    ) -> Self {
+        let callback_id = instrument.register_callback(Arc::new(move |instrument| {
+            callback(&Observer(instrument, PhantomData))
+        }));
+        Self {
+            instrument,
+            callback_id,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Measurement> AsyncMeasure for AsyncInstrument<T> {
+    type Value = T;
+
+    fn record(&self, value: T, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.instrument.set(value.as_f64(), attributes);
+    }
+
+    fn stop(&self) {
+        self.instrument.unregister_callback(self.callback_id);
+    }
+}
+
+#[derive(Debug)]
+struct InMemoryMeter(Arc<Registry>);
+
+impl InMemoryMeter {
+    fn register<T>(&self, builder: &InstrumentBuilder<'_, T>, kind: MetricKind) -> Arc<Instrument> {
+        self.0.register(
+            builder.get_name(),
+            builder.get_description(),
+            builder.get_units(),
+            kind,
+        )
+    }
+
+    fn register_async<T, M: Measurement>(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, T, M>,
+        kind: MetricKind,
+    ) -> AsyncInstrument<M> {
+        let instrument = self.0.register(
+            builder.get_name(),
+            builder.get_description(),
+            builder.get_units(),
+            kind,
+        );
+        AsyncInstrument::new(instrument, builder.get_callback())
+    }
+}
+
+impl ProvideInstrument for InMemoryMeter {
+    fn create_gauge(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = f64>>, f64>,
+    ) -> Arc<dyn AsyncMeasure<Value = f64>> {
+        Arc::new(self.register_async(builder, MetricKind::Gauge))
+    }
+
+    fn create_up_down_counter(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn UpDownCounter>>,
+    ) -> Arc<dyn UpDownCounter> {
+        Arc::new(SyncInstrument(
+            self.register(&builder, MetricKind::UpDownCounter),
+        ))
+    }
+
+    fn create_async_up_down_counter(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = i64>>, i64>,
+    ) -> Arc<dyn AsyncMeasure<Value = i64>> {
+        Arc::new(self.register_async(builder, MetricKind::UpDownCounter))
+    }
+
+    fn create_monotonic_counter(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn MonotonicCounter>>,
+    ) -> Arc<dyn MonotonicCounter> {
+        Arc::new(SyncInstrument(self.register(&builder, MetricKind::Counter)))
+    }
+
+    fn create_async_monotonic_counter(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = u64>>, u64>,
+    ) -> Arc<dyn AsyncMeasure<Value = u64>> {
+        Arc::new(self.register_async(builder, MetricKind::Counter))
+    }
+
+    fn create_histogram(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn Histogram>>,
+    ) -> Arc<dyn Histogram> {
+        Arc::new(SyncInstrument(
+            self.register(&builder, MetricKind::Histogram),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryMeterProvider;
+    use crate::data::MetricKind;
+    use aws_smithy_observability::meter::ProvideMeter;
+    use aws_smithy_observability::{AttributeValue, Attributes};
+
+    fn attributes(method: &str) -> Attributes {
+        let mut attributes = Attributes::new();
+        attributes.set("rpc.method", AttributeValue::String(method.into()));
+        attributes
+    }
+
+    #[test]
+    fn sync_instruments_are_recorded() {
+        let provider = InMemoryMeterProvider::with_histogram_buckets([1.0, 0.1]);
+        let meter = provider.get_meter("test", None);
+
+        let attempts = meter
+            .create_monotonic_counter("attempts")
+            .set_description("The number of attempts")
+            .build();
+        attempts.add(2, Some(&attributes("GetObject")), None);
+        attempts.add(1, Some(&attributes("PutObject")), None);
+        // recreating an instrument records into the existing one
+        let attempts = meter.create_monotonic_counter("attempts").build();
+        attempts.add(1, Some(&attributes("GetObject")), None);
+
+        let in_flight = meter.create_up_down_counter("in_flight").build();
+        in_flight.add(2, None, None);
+        in_flight.add(-1, None, None);
+
+        let duration = meter.create_histogram("duration").set_units("s").build();
+        for value in [0.0625, 0.5, 0.125, 3.0] {
+            duration.record(value, None, None);
+        }
+
+        let snapshot = provider.collect();
+        let names = snapshot.iter().map(|m| m.name()).collect::<Vec<_>>();
+        assert_eq!(vec!["attempts", "duration", "in_flight"], names);
+
+        let attempts = snapshot.get("attempts").unwrap();
+        assert_eq!(MetricKind::Counter, attempts.kind());
+        assert_eq!(Some("The number of attempts"), attempts.description());
+        assert_eq!(4.0, attempts.sum());
+        let get_object = attempts
+            .data_points()
+            .iter()
+            .find(|p| {
+                p.attribute("rpc.method") == Some(&AttributeValue::String("GetObject".into()))
+            })
+            .unwrap();
+        assert_eq!(Some(3.0), get_object.value());
+
+        assert_eq!(1.0, snapshot.get("in_flight").unwrap().sum());
+
+        let duration = snapshot.get("duration").unwrap();
+        assert_eq!(Some("s"), duration.units());
+        assert_eq!(4, duration.count());
+        assert_eq!(3.6875, duration.sum());
+        let buckets = duration.data_points()[0]
+            .histogram()
+            .unwrap()
+            .buckets()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![(0.1, 1), (1.0, 3), (f64::INFINITY, 4)], buckets);
+    }
+
+    #[test]
+    fn async_instruments_are_observed_on_collect() {
+        let provider = InMemoryMeterProvider::new();
+        let meter = provider.get_meter("test", None);
+
+        let gauge = meter
+            .create_gauge("permits", |measure| {
+                measure.record(42.0, Some(&attributes("GetObject")), None)
+            })
+            .build();
+        let counter = meter
+            .create_async_monotonic_counter("bytes", |measure| measure.record(7, None, None))
+            .build();
+        assert_eq!(42.0, provider.collect().get("permits").unwrap().sum());
+        assert_eq!(7.0, provider.collect().get("bytes").unwrap().sum());
+
+        // measurements made outside of a callback are kept until they're observed again
+        gauge.stop();
+        counter.stop();
+        gauge.record(3.0, Some(&attributes("GetObject")), None);
+        assert_eq!(3.0, provider.collect().get("permits").unwrap().sum());
+    }
+
+    #[test]
+    fn instruments_of_a_different_kind_replace_existing_ones() {
+        let provider = InMemoryMeterProvider::new();
+        let meter = provider.get_meter("test", None);
+
+        meter
+            .create_monotonic_counter("value")
+            .build()
+            .add(1, None, None);
+        meter
+            .create_histogram("value")
+            .build()
+            .record(5.0, None, None);
+
+        let snapshot = provider.collect();
+        let value = snapshot.get("value").unwrap();
+        assert_eq!(MetricKind::Histogram, value.kind());
+        assert_eq!(1, value.count());
+    }
+}
@@ -0,0 +1,215 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Encoding of metrics in the [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format).
+//!
+//! Names are converted following the OpenTelemetry conventions for Prometheus: invalid
+//! characters are replaced with `_`, the units are appended as a suffix (e.g. `_seconds` for
+//! `s`), and counters end in `_total`.
+
+use crate::data::{DataPoint, Metric, MetricKind, MetricsSnapshot};
+use std::fmt::Write;
+
+/// The `Content-Type` of the text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Encode every metric with at least one data point in the Prometheus text exposition format.
+pub fn encode(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    for metric in snapshot.iter() {
+        if !metric.data_points().is_empty() {
+            encode_metric(&mut out, metric);
+        }
+    }
+    out
+}
+
+fn encode_metric(out: &mut String, metric: &Metric) {
+    let name = metric_name(metric);
+    if let Some(description) = metric.description() {
+        let _ = writeln!(out, "# HELP {name} {}", escape(description, false));
+    }
+    let metric_type = match metric.kind() {
+        MetricKind::Counter => "counter",
+        MetricKind::Histogram => "histogram",
+        _ => "gauge",
+    };
+    let _ = writeln!(out, "# TYPE {name} {metric_type}");
+
+    for data_point in metric.data_points() {
+        match data_point.histogram() {
+            Some(histogram) => {
+                for (bound, count) in histogram.buckets() {
+                    let le = format_f64(bound);
+                    let _ = writeln!(
+                        out,
+                        "{name}_bucket{} {count}",
+                        labels(data_point, Some(&le))
+                    );
+                }
+                let labels = labels(data_point, None);
+                let _ = writeln!(out, "{name}_sum{labels} {}", format_f64(histogram.sum()));
+                let _ = writeln!(out, "{name}_count{labels} {}", histogram.count());
+            }
+            None => {
+                let value = format_f64(data_point.value().unwrap_or_default());
+                let _ = writeln!(out, "{name}{} {value}", labels(data_point, None));
+            }
+        }
+    }
+}
+
+fn metric_name(metric: &Metric) -> String {
+    let mut name = sanitize(metric.name(), true);
+    if let Some(suffix) = metric.units().and_then(unit_suffix) {
+        if !name.ends_with(&format!("_{suffix}")) {
+            name = format!("{name}_{suffix}");
+        }
+    }
+    if metric.kind() == MetricKind::Counter && !name.ends_with("_total") {
+        name.push_str("_total");
+    }
+    name
+}
+
+/// Returns the name suffix for UCUM `units`, or `None` for dimensionless units
+fn unit_suffix(units: &str) -> Option<String> {
+    // Annotations in curly braces, like `{request}`, don't name a unit
+    let units = match units.find('{') {
+        Some(start) => &units[..start],
+        None => units,
+    };
+    let suffix = match units {
+        "" | "1" => return None,
+        "d" => "days",
+        "h" => "hours",
+        "min" => "minutes",
+        "s" => "seconds",
+        "ms" => "milliseconds",
+        "us" => "microseconds",
+        "ns" => "nanoseconds",
+        "By" => "bytes",
+        "KiBy" => "kibibytes",
+        "MiBy" => "mebibytes",
+        "%" => "percent",
+        other => return Some(sanitize(other, false)),
+    };
+    Some(suffix.to_string())
+}
+
+/// Replaces characters that aren't valid in a metric name, or a label name unless `colons` are
+/// allowed, with `_`
+fn sanitize(name: &str, colons: bool) -> String {
+    let mut sanitized = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+            ':' if colons => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    if sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+fn labels(data_point: &DataPoint, le: Option<&str>) -> String {
+    let labels = data_point
+        .labels
+        .iter()
+        .map(|(key, value)| (sanitize(key, false), value.as_str()))
+        .chain(le.map(|le| ("le".to_string(), le)))
+        .map(|(key, value)| format!("{key}=\"{}\"", escape(value, true)))
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels.join(","))
+    }
+}
+
+/// Escapes backslashes and line feeds, and double quotes in label values
+fn escape(value: &str, quotes: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quotes => escaped.push_str("\\\""),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn format_f64(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+    use crate::meter::InMemoryMeterProvider;
+    use aws_smithy_observability::meter::ProvideMeter;
+    use aws_smithy_observability::{AttributeValue, Attributes};
+
+    #[test]
+    fn encodes_text_exposition_format() {
+        let provider = InMemoryMeterProvider::with_histogram_buckets([0.5, 1.0]);
+        let meter = provider.get_meter("test", None);
+
+        let mut attributes = Attributes::new();
+        attributes.set("rpc.service", AttributeValue::String("S3".into()));
+        attributes.set(
+            "rpc.method",
+            AttributeValue::String("Get\"Object\"\n".into()),
+        );
+
+        let attempts = meter
+            .create_monotonic_counter("smithy.client.call.attempts")
+            .set_units("{attempt}")
+            .set_description("The number of attempts\nfor an operation")
+            .build();
+        attempts.add(3, Some(&attributes), None);
+
+        let duration = meter
+            .create_histogram("smithy.client.call.duration")
+            .set_units("s")
+            .build();
+        duration.record(0.25, Some(&attributes), None);
+        duration.record(2.0, Some(&attributes), None);
+
+        meter
+            .create_gauge("1.permits", |measure| measure.record(0.5, None, None))
+            .build();
+        // instruments without data points aren't encoded
+        meter.create_up_down_counter("unused").build();
+
+        assert_eq!(
+            r#"# TYPE _1_permits gauge
+_1_permits 0.5
+# HELP smithy_client_call_attempts_total The number of attempts\nfor an operation
+# TYPE smithy_client_call_attempts_total counter
+smithy_client_call_attempts_total{rpc_method="Get\"Object\"\n",rpc_service="S3"} 3
+# TYPE smithy_client_call_duration_seconds histogram
+smithy_client_call_duration_seconds_bucket{rpc_method="Get\"Object\"\n",rpc_service="S3",le="0.5"} 1
+smithy_client_call_duration_seconds_bucket{rpc_method="Get\"Object\"\n",rpc_service="S3",le="1"} 1
+smithy_client_call_duration_seconds_bucket{rpc_method="Get\"Object\"\n",rpc_service="S3",le="+Inf"} 2
+smithy_client_call_duration_seconds_sum{rpc_method="Get\"Object\"\n",rpc_service="S3"} 2.25
+smithy_client_call_duration_seconds_count{rpc_method="Get\"Object\"\n",rpc_service="S3"} 2
+"#,
+            encode(&provider.collect())
+        );
+    }
+}
@@ -81,5 +81,13 @@ mod tests {
             .create_monotonic_counter("TestMonoCounter")
             .build();
         instrument.add(4, None, None);
+
+        // Use the global provider to create a span
+        let curr_tracer = curr_provider
+            .tracer_provider()
+            .get_tracer("TestTracer", None);
+        let span = curr_tracer.create_span("TestSpan").start();
+        assert!(!span.span_context().is_valid());
+        span.end();
     }
 }
@@ -3,8 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-//! An noop implementation of the Meter traits
+//! An noop implementation of the Meter and Tracer traits
 
+use std::borrow::Cow;
+use std::error::Error;
 use std::marker::PhantomData;
 use std::{fmt::Debug, sync::Arc};
 
@@ -12,8 +14,11 @@ use crate::instruments::{
     AsyncInstrumentBuilder, AsyncMeasure, Histogram, InstrumentBuilder, MonotonicCounter,
     ProvideInstrument, UpDownCounter,
 };
+use crate::tracer::{
+    ProvideSpan, ProvideTracer, Span, SpanBuilder, SpanContext, SpanStatus, Tracer,
+};
 use crate::{
-    attributes::Attributes,
+    attributes::{AttributeValue, Attributes},
     context::Context,
     meter::{Meter, ProvideMeter},
 };
@@ -105,3 +110,37 @@ impl Histogram for NoopHistogram {
     ) {
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct NoopTracerProvider;
+impl ProvideTracer for NoopTracerProvider {
+    fn get_tracer(&self, _scope: &'static str, _attributes: Option<&Attributes>) -> Tracer {
+        Tracer::new(Arc::new(NoopTracer))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct NoopTracer;
+impl ProvideSpan for NoopTracer {
+    fn start_span(&self, _builder: SpanBuilder<'_>) -> Arc<dyn Span> {
+        Arc::new(NoopSpan)
+    }
+}
+
+#[derive(Debug)]
+struct NoopSpan;
+impl Span for NoopSpan {
+    fn set_attribute(&self, _key: Cow<'static, str>, _value: AttributeValue) {}
+
+    fn set_status(&self, _status: SpanStatus) {}
+
+    fn record_error(&self, _error: &(dyn Error + 'static)) {}
+
+    fn add_event(&self, _name: Cow<'static, str>, _attributes: Option<&Attributes>) {}
+
+    fn span_context(&self) -> SpanContext {
+        SpanContext::invalid()
+    }
+
+    fn end(&self) {}
+}
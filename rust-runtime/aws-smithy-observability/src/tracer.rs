@@ -0,0 +1,262 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Traces record the path of a request through a system as a tree of timed [Span]s.
+
+use crate::attributes::{AttributeValue, Attributes};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Debug};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Provides named instances of [Tracer].
+pub trait ProvideTracer: Send + Sync + Debug {
+    /// Get or create a named [Tracer].
+    fn get_tracer(&self, scope: &'static str, attributes: Option<&Attributes>) -> Tracer;
+}
+
+/// The entry point to creating spans.
+#[derive(Clone, Debug)]
+pub struct Tracer {
+    pub(crate) span_provider: Arc<dyn ProvideSpan + Send + Sync>,
+}
+
+impl Tracer {
+    /// Create a new [Tracer] from a [ProvideSpan]
+    pub fn new(span_provider: Arc<dyn ProvideSpan + Send + Sync>) -> Self {
+        Tracer { span_provider }
+    }
+
+    /// Create a new [Span].
+    pub fn create_span(&self, name: impl Into<Cow<'static, str>>) -> SpanBuilder<'_> {
+        SpanBuilder::new(self, name.into())
+    }
+}
+
+/// The trait that telemetry providers implement to start spans.
+pub trait ProvideSpan: Send + Sync + Debug {
+    /// Start a new span.
+    fn start_span(&self, builder: SpanBuilder<'_>) -> Arc<dyn Span>;
+}
+
+/// Configuration for starting a span.
+#[non_exhaustive]
+pub struct SpanBuilder<'a> {
+    span_provider: &'a dyn ProvideSpan,
+    name: Cow<'static, str>,
+    kind: SpanKind,
+    parent: Option<SpanContext>,
+    attributes: Option<Attributes>,
+    start_time: Option<SystemTime>,
+}
+
+impl<'a> SpanBuilder<'a> {
+    fn new(tracer: &'a Tracer, name: Cow<'static, str>) -> Self {
+        SpanBuilder {
+            span_provider: tracer.span_provider.as_ref(),
+            name,
+            kind: SpanKind::Internal,
+            parent: None,
+            attributes: None,
+            start_time: None,
+        }
+    }
+
+    /// Get the name.
+    pub fn get_name(&self) -> &Cow<'static, str> {
+        &self.name
+    }
+
+    /// Set the kind. Defaults to [SpanKind::Internal].
+    pub fn set_kind(mut self, kind: SpanKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Get the kind.
+    pub fn get_kind(&self) -> SpanKind {
+        self.kind
+    }
+
+    /// Set the parent. Spans without a parent are the root of a new trace.
+    pub fn set_parent(mut self, parent: Option<SpanContext>) -> Self {
+        self.parent = parent.filter(SpanContext::is_valid);
+        self
+    }
+
+    /// Get the parent.
+    pub fn get_parent(&self) -> Option<&SpanContext> {
+        self.parent.as_ref()
+    }
+
+    /// Set the initial attributes.
+    pub fn set_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    /// Get the initial attributes.
+    pub fn get_attributes(&self) -> Option<&Attributes> {
+        self.attributes.as_ref()
+    }
+
+    /// Set the start time. Defaults to the time the span is started.
+    pub fn set_start_time(mut self, start_time: SystemTime) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Get the start time.
+    pub fn get_start_time(&self) -> Option<SystemTime> {
+        self.start_time
+    }
+
+    /// Start the span.
+    pub fn start(self) -> Arc<dyn Span> {
+        self.span_provider.start_span(self)
+    }
+}
+
+/// A single timed operation within a trace.
+///
+/// Spans are ended explicitly with [Span::end]; implementations should ignore calls made after
+/// that.
+pub trait Span: Send + Sync + Debug {
+    /// Set an attribute on this span.
+    fn set_attribute(&self, key: Cow<'static, str>, value: AttributeValue);
+
+    /// Set the status of this span.
+    fn set_status(&self, status: SpanStatus);
+
+    /// Record an error that occurred during this span.
+    fn record_error(&self, error: &(dyn Error + 'static));
+
+    /// Record a named event, such as a retry, that occurred during this span.
+    fn add_event(&self, name: Cow<'static, str>, attributes: Option<&Attributes>);
+
+    /// Get the [SpanContext] identifying this span, for parenting other spans to it and for
+    /// propagating it to other services.
+    fn span_context(&self) -> SpanContext;
+
+    /// End this span.
+    fn end(&self);
+}
+
+/// The role of a [Span] in a trace.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SpanKind {
+    /// An operation internal to an application.
+    Internal,
+    /// A request to a remote service.
+    Client,
+    /// The handling of a request from a remote client.
+    Server,
+}
+
+/// The status of a [Span].
+#[non_exhaustive]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SpanStatus {
+    /// The default status.
+    Unset,
+    /// The operation completed successfully.
+    Ok,
+    /// The operation failed.
+    Error {
+        /// A description of the failure.
+        description: Cow<'static, str>,
+    },
+}
+
+/// The identity of a [Span] within a trace.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpanContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    sampled: bool,
+}
+
+impl SpanContext {
+    /// Create a new [SpanContext].
+    pub fn new(trace_id: [u8; 16], span_id: [u8; 8], sampled: bool) -> Self {
+        Self {
+            trace_id,
+            span_id,
+            sampled,
+        }
+    }
+
+    /// A [SpanContext] that doesn't identify any span, for spans that aren't recorded.
+    pub fn invalid() -> Self {
+        Self::new([0; 16], [0; 8], false)
+    }
+
+    /// Returns true if both the trace ID and span ID are non-zero.
+    pub fn is_valid(&self) -> bool {
+        self.trace_id != [0; 16] && self.span_id != [0; 8]
+    }
+
+    /// Get the trace ID.
+    pub fn trace_id(&self) -> [u8; 16] {
+        self.trace_id
+    }
+
+    /// Get the span ID.
+    pub fn span_id(&self) -> [u8; 8] {
+        self.span_id
+    }
+
+    /// Returns true if the trace is sampled.
+    pub fn is_sampled(&self) -> bool {
+        self.sampled
+    }
+
+    /// Format this context as a [W3C `traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header)
+    /// header value, or `None` if it isn't valid.
+    pub fn to_traceparent(&self) -> Option<String> {
+        self.is_valid().then(|| {
+            format!(
+                "00-{}-{}-{:02x}",
+                Hex(&self.trace_id),
+                Hex(&self.span_id),
+                self.sampled as u8
+            )
+        })
+    }
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpanContext;
+
+    #[test]
+    fn traceparent_format() {
+        let trace_id = 0x4bf92f3577b34da6a3ce929d0e0e4736u128.to_be_bytes();
+        let span_id = 0x00f067aa0ba902b7u64.to_be_bytes();
+        assert_eq!(
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string()),
+            SpanContext::new(trace_id, span_id, true).to_traceparent()
+        );
+        assert_eq!(
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00".to_string()),
+            SpanContext::new(trace_id, span_id, false).to_traceparent()
+        );
+        assert_eq!(None, SpanContext::invalid().to_traceparent());
+        assert_eq!(
+            None,
+            SpanContext::new(trace_id, [0; 8], true).to_traceparent()
+        );
+    }
+}
@@ -77,6 +77,8 @@ enum ErrorKind<E> {
     Connector { source: ConnectorError },
     /// An error that occurs when a response can't be deserialized.
     Response { source: BoxError },
+    /// An error that occurs when a request isn't sent because a circuit breaker is open.
+    CircuitOpen { source: BoxError },
     /// A general orchestrator error.
     Other { source: BoxError },
 }
@@ -141,6 +143,18 @@ impl<E> OrchestratorError<E> {
         matches!(self.kind, ErrorKind::Timeout { .. })
     }
 
+    /// Create a circuit open error with the given source.
+    pub fn circuit_open(source: BoxError) -> Self {
+        Self {
+            kind: ErrorKind::CircuitOpen { source },
+        }
+    }
+
+    /// True if the underlying error is a circuit open error.
+    pub fn is_circuit_open_error(&self) -> bool {
+        matches!(self.kind, ErrorKind::CircuitOpen { .. })
+    }
+
     /// Create a response error with the given source.
     pub fn response(source: BoxError) -> Self {
         Self {
@@ -201,6 +215,7 @@ impl<E> OrchestratorError<E> {
             }
             ErrorKind::Connector { source } => SdkError::dispatch_failure(source),
             ErrorKind::Timeout { source } => SdkError::timeout_error(source),
+            ErrorKind::CircuitOpen { source } => SdkError::circuit_open(source),
             ErrorKind::Response { source } => SdkError::response_error(source, response.unwrap()),
             ErrorKind::Other { source } => {
                 use Phase::*;
@@ -223,6 +238,7 @@ impl<E> OrchestratorError<E> {
             ErrorKind::Interceptor { source } => ErrorKind::Interceptor { source },
             ErrorKind::Response { source } => ErrorKind::Response { source },
             ErrorKind::Timeout { source } => ErrorKind::Timeout { source },
+            ErrorKind::CircuitOpen { source } => ErrorKind::CircuitOpen { source },
             ErrorKind::Other { source } => ErrorKind::Other { source },
         };
         OrchestratorError { kind }
@@ -240,6 +256,7 @@ where
             ErrorKind::Interceptor { source } => source as _,
             ErrorKind::Response { source } => source.as_ref(),
             ErrorKind::Timeout { source } => source.as_ref(),
+            ErrorKind::CircuitOpen { source } => source.as_ref(),
             ErrorKind::Other { source } => source.as_ref(),
         })
     }
@@ -253,6 +270,7 @@ impl<E> fmt::Display for OrchestratorError<E> {
             ErrorKind::Interceptor { .. } => "interceptor error",
             ErrorKind::Response { .. } => "response error",
             ErrorKind::Timeout { .. } => "timeout",
+            ErrorKind::CircuitOpen { .. } => "circuit breaker is open",
             ErrorKind::Other { .. } => "an unknown error occurred",
         })
     }
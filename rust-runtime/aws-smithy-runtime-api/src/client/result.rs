@@ -55,6 +55,7 @@ pub mod builders {
     source_only_error_builder!(ConstructionFailure, ConstructionFailureBuilder, BoxError);
     source_only_error_builder!(TimeoutError, TimeoutErrorBuilder, BoxError);
     source_only_error_builder!(DispatchFailure, DispatchFailureBuilder, ConnectorError);
+    source_only_error_builder!(CircuitOpen, CircuitOpenBuilder, BoxError);
 
     /// Builder for [`ResponseError`].
     #[derive(Debug)]
@@ -220,6 +221,11 @@ impl DispatchFailure {
         self.source.is_user()
     }
 
+    /// Returns true if the error is a rejected server certificate
+    pub fn is_certificate_verification(&self) -> bool {
+        self.source.is_certificate_verification()
+    }
+
     /// Returns true if the error is an unclassified error.
     pub fn is_other(&self) -> bool {
         self.source.is_other()
@@ -236,6 +242,19 @@ impl DispatchFailure {
     }
 }
 
+/// Error context for [`SdkError::CircuitOpen`]
+#[derive(Debug)]
+pub struct CircuitOpen {
+    source: BoxError,
+}
+
+impl CircuitOpen {
+    /// Creates a builder for this error context type.
+    pub fn builder() -> builders::CircuitOpenBuilder {
+        builders::CircuitOpenBuilder::new()
+    }
+}
+
 /// Error context for [`SdkError::ResponseError`]
 #[derive(Debug)]
 pub struct ResponseError<R> {
@@ -334,6 +353,9 @@ pub enum SdkError<E, R> {
 
     /// An error response was received from the service
     ServiceError(ServiceError<E, R>),
+
+    /// The request was not sent because the circuit breaker for its endpoint is open.
+    CircuitOpen(CircuitOpen),
 }
 
 impl<E, R> SdkError<E, R> {
@@ -369,6 +391,13 @@ impl<E, R> SdkError<E, R> {
         Self::ServiceError(ServiceError { source, raw })
     }
 
+    /// Construct a `SdkError` for a request that wasn't sent because a circuit breaker is open
+    pub fn circuit_open(source: impl Into<BoxError>) -> Self {
+        Self::CircuitOpen(CircuitOpen {
+            source: source.into(),
+        })
+    }
+
     /// Returns the underlying service error `E` if there is one
     ///
     /// If the `SdkError` is not a `ServiceError` (for example, the error is a network timeout),
@@ -455,6 +484,7 @@ impl<E, R> SdkError<E, R> {
             SdkError::ResponseError(context) => Ok(context.source),
             SdkError::DispatchFailure(context) => Ok(context.source.into()),
             SdkError::ServiceError(context) => Ok(context.source.into()),
+            SdkError::CircuitOpen(context) => Ok(context.source),
         }
     }
 
@@ -480,6 +510,7 @@ impl<E, R> SdkError<E, R> {
             SdkError::DispatchFailure(context) => SdkError::<E2, R>::DispatchFailure(context),
             SdkError::ResponseError(context) => SdkError::<E2, R>::ResponseError(context),
             SdkError::TimeoutError(context) => SdkError::<E2, R>::TimeoutError(context),
+            SdkError::CircuitOpen(context) => SdkError::<E2, R>::CircuitOpen(context),
         }
     }
 }
@@ -492,6 +523,7 @@ impl<E, R> Display for SdkError<E, R> {
             SdkError::DispatchFailure(_) => write!(f, "dispatch failure"),
             SdkError::ResponseError(_) => write!(f, "response error"),
             SdkError::ServiceError(_) => write!(f, "service error"),
+            SdkError::CircuitOpen(_) => write!(f, "circuit breaker is open"),
         }
     }
 }
@@ -508,6 +540,7 @@ where
             SdkError::ResponseError(context) => Some(context.source.as_ref()),
             SdkError::DispatchFailure(context) => Some(&context.source),
             SdkError::ServiceError(context) => Some(&context.source),
+            SdkError::CircuitOpen(context) => Some(context.source.as_ref()),
         }
     }
 }
@@ -529,6 +562,7 @@ where
             SdkError::DispatchFailure(_) => &EMPTY_ERROR_METADATA,
             SdkError::ResponseError(_) => &EMPTY_ERROR_METADATA,
             SdkError::ServiceError(err) => err.source.meta(),
+            SdkError::CircuitOpen(_) => &EMPTY_ERROR_METADATA,
         }
     }
 }
@@ -544,6 +578,9 @@ enum ConnectorErrorKind {
     /// Socket/IO error
     Io,
 
+    /// The server's certificate was rejected during the TLS handshake
+    CertificateVerification,
+
     /// An unclassified Error with an explicit error kind
     Other(Option<ErrorKind>),
 }
@@ -582,6 +619,9 @@ impl Display for ConnectorError {
             ConnectorErrorKind::Timeout => write!(f, "timeout"),
             ConnectorErrorKind::User => write!(f, "user error"),
             ConnectorErrorKind::Io => write!(f, "io error"),
+            ConnectorErrorKind::CertificateVerification => {
+                write!(f, "certificate verification error")
+            }
             ConnectorErrorKind::Other(_) => write!(f, "other"),
         }
     }
@@ -635,6 +675,18 @@ impl ConnectorError {
         }
     }
 
+    /// Construct a [`ConnectorError`] from a rejected server certificate (e.g. an untrusted
+    /// certificate, or one that doesn't match a pinned public key)
+    ///
+    /// Certificate verification errors are not retried.
+    pub fn certificate_verification(source: BoxError) -> Self {
+        Self {
+            kind: ConnectorErrorKind::CertificateVerification,
+            source,
+            connection: ConnectionStatus::Unknown,
+        }
+    }
+
     /// Construct a [`ConnectorError`] from an different unclassified error.
     ///
     /// Optionally, an explicit `Kind` may be passed.
@@ -661,6 +713,11 @@ impl ConnectorError {
         matches!(self.kind, ConnectorErrorKind::User)
     }
 
+    /// Returns true if the error is a rejected server certificate
+    pub fn is_certificate_verification(&self) -> bool {
+        matches!(self.kind, ConnectorErrorKind::CertificateVerification)
+    }
+
     /// Returns true if the error is an unclassified error.
     pub fn is_other(&self) -> bool {
         matches!(self.kind, ConnectorErrorKind::Other(..))
@@ -250,6 +250,21 @@ pub trait ClassifyRetry: Send + Sync + fmt::Debug {
     /// should be retried. Returns a [`RetryAction`].
     fn classify_retry(&self, ctx: &InterceptorContext) -> RetryAction;
 
+    /// Run this classifier on the [`InterceptorContext`], given the result of the classifiers that
+    /// ran before it.
+    ///
+    /// Classifiers that refine an earlier classification, such as by adding an explicit delay to
+    /// it, should override this. The default implementation ignores `previous` and calls
+    /// [`classify_retry`](ClassifyRetry::classify_retry).
+    fn classify_retry_with_previous(
+        &self,
+        ctx: &InterceptorContext,
+        previous: &RetryAction,
+    ) -> RetryAction {
+        let _ = previous;
+        self.classify_retry(ctx)
+    }
+
     /// The name of this retry classifier.
     ///
     /// Used for debugging purposes.
@@ -289,6 +304,14 @@ impl ClassifyRetry for SharedRetryClassifier {
         self.0.classify_retry(ctx)
     }
 
+    fn classify_retry_with_previous(
+        &self,
+        ctx: &InterceptorContext,
+        previous: &RetryAction,
+    ) -> RetryAction {
+        self.0.classify_retry_with_previous(ctx, previous)
+    }
+
     fn name(&self) -> &'static str {
         self.0.name()
     }
@@ -24,6 +24,8 @@ pub mod config_override;
 /// The client orchestrator implementation
 pub mod orchestrator;
 
+pub mod circuit_breaker;
+
 /// Smithy code related to retry handling and token buckets.
 ///
 /// This code defines when and how failed requests should be retried. It also defines the behavior
@@ -0,0 +1,801 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A circuit breaker that stops sending requests to an endpoint that keeps failing.
+//!
+//! The retry [`TokenBucket`](crate::client::retries::TokenBucket) limits how many failed requests
+//! are retried, but new requests are still sent to a service that is down. A [`CircuitBreaker`]
+//! tracks the outcome of recent attempts for each partition, either the
+//! [`RetryPartition`](crate::client::retries::RetryPartition) or the resolved endpoint, and
+//! trips when too many of them fail:
+//!
+//! - While the circuit is **closed**, requests are sent as usual. The circuit opens when either
+//!   the failure rate of recent attempts or the number of consecutive failures reaches its
+//!   threshold.
+//! - While the circuit is **open**, requests fail immediately with
+//!   [`SdkError::CircuitOpen`](aws_smithy_runtime_api::client::result::SdkError::CircuitOpen)
+//!   without being sent. After the open duration, the circuit becomes half-open.
+//! - While the circuit is **half-open**, a limited number of probe requests are sent. The circuit
+//!   closes if they all succeed, and opens again if any of them fails.
+//!
+//! Server errors and transient errors, like timeouts and IO errors, count as failures. Throttling
+//! errors and client errors are responses from a healthy service, so they count as successes.
+//!
+//! At most [`max_circuits`](CircuitBreakerConfigBuilder::max_circuits) circuits are tracked. When a
+//! request goes to a new partition and that limit has been reached, the least recently used circuit
+//! is dropped, preferring closed circuits so that partitions that are down keep failing fast.
+//!
+//! The state of each circuit is recorded on the `smithy.client.circuit_breaker.state` gauge when
+//! metrics are enabled.
+
+use crate::client::metrics::OperationTelemetry;
+use crate::client::retries::classifiers::run_classifiers_on_ctx;
+use crate::client::retries::RetryPartition;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    FinalizerInterceptorContextRef, InterceptorContext,
+};
+use aws_smithy_runtime_api::client::interceptors::{Intercept, SharedInterceptor};
+use aws_smithy_runtime_api::client::retries::classifiers::{RetryAction, RetryReason};
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreReplace};
+use aws_smithy_types::retry::ErrorKind;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_FAILURE_RATE_THRESHOLD: f64 = 0.5;
+const DEFAULT_WINDOW_SIZE: usize = 20;
+const DEFAULT_MINIMUM_REQUESTS: usize = 10;
+const DEFAULT_CONSECUTIVE_FAILURES: u32 = 5;
+const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+const DEFAULT_HALF_OPEN_REQUESTS: u32 = 1;
+const DEFAULT_MAX_CIRCUITS: usize = 1000;
+
+/// What the circuits of a [`CircuitBreaker`] are partitioned by.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CircuitBreakerPartition {
+    /// One circuit per [`RetryPartition`], which is usually one per service and region.
+    #[default]
+    RetryPartition,
+    /// One circuit per host and port of the resolved endpoint.
+    Endpoint,
+}
+
+/// The state of a circuit.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircuitState {
+    /// Requests are sent.
+    Closed,
+    /// Requests fail without being sent.
+    Open,
+    /// A limited number of probe requests are sent to decide whether the circuit closes.
+    HalfOpen,
+}
+
+impl CircuitState {
+    /// The value recorded on the state gauge
+    fn gauge_value(self) -> f64 {
+        match self {
+            CircuitState::Closed => 0.0,
+            CircuitState::Open => 1.0,
+            CircuitState::HalfOpen => 2.0,
+        }
+    }
+}
+
+/// Configuration for a [`CircuitBreaker`].
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerConfig {
+    partition: CircuitBreakerPartition,
+    failure_rate_threshold: f64,
+    window_size: usize,
+    minimum_requests: usize,
+    consecutive_failures: u32,
+    open_duration: Duration,
+    half_open_requests: u32,
+    max_circuits: usize,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Creates a builder for constructing a `CircuitBreakerConfig`.
+    pub fn builder() -> CircuitBreakerConfigBuilder {
+        CircuitBreakerConfigBuilder::default()
+    }
+
+    /// What the circuits are partitioned by.
+    pub fn partition(&self) -> CircuitBreakerPartition {
+        self.partition
+    }
+
+    /// The failure rate of recent attempts, between 0 and 1, at which a circuit opens.
+    pub fn failure_rate_threshold(&self) -> f64 {
+        self.failure_rate_threshold
+    }
+
+    /// The number of most recent attempts the failure rate is computed from.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// The number of attempts that must be observed before the failure rate can open a circuit.
+    pub fn minimum_requests(&self) -> usize {
+        self.minimum_requests
+    }
+
+    /// The number of consecutive failures at which a circuit opens.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// How long a circuit stays open before it becomes half-open.
+    pub fn open_duration(&self) -> Duration {
+        self.open_duration
+    }
+
+    /// The number of probe requests that must succeed for a half-open circuit to close.
+    pub fn half_open_requests(&self) -> u32 {
+        self.half_open_requests
+    }
+
+    /// The maximum number of circuits that are tracked.
+    pub fn max_circuits(&self) -> usize {
+        self.max_circuits
+    }
+}
+
+/// Builder for constructing a `CircuitBreakerConfig`.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreakerConfigBuilder {
+    partition: Option<CircuitBreakerPartition>,
+    failure_rate_threshold: Option<f64>,
+    window_size: Option<usize>,
+    minimum_requests: Option<usize>,
+    consecutive_failures: Option<u32>,
+    open_duration: Option<Duration>,
+    half_open_requests: Option<u32>,
+    max_circuits: Option<usize>,
+}
+
+impl CircuitBreakerConfigBuilder {
+    /// Creates a new `CircuitBreakerConfigBuilder` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets what the circuits are partitioned by. Defaults to the retry partition.
+    pub fn partition(mut self, partition: CircuitBreakerPartition) -> Self {
+        self.partition = Some(partition);
+        self
+    }
+
+    /// Sets the failure rate of recent attempts, between 0 and 1, at which a circuit opens.
+    /// Defaults to 0.5.
+    ///
+    /// # Panics
+    /// If `failure_rate_threshold` isn't between 0 and 1.
+    pub fn failure_rate_threshold(mut self, failure_rate_threshold: f64) -> Self {
+        assert!(
+            failure_rate_threshold > 0.0 && failure_rate_threshold <= 1.0,
+            "failure rate threshold must be greater than 0 and at most 1"
+        );
+        self.failure_rate_threshold = Some(failure_rate_threshold);
+        self
+    }
+
+    /// Sets the number of most recent attempts the failure rate is computed from. Defaults to 20.
+    ///
+    /// # Panics
+    /// If `window_size` is 0.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        assert!(window_size > 0, "window size must be greater than 0");
+        self.window_size = Some(window_size);
+        self
+    }
+
+    /// Sets the number of attempts that must be observed before the failure rate can open a
+    /// circuit. Defaults to 10, and is capped at the window size.
+    pub fn minimum_requests(mut self, minimum_requests: usize) -> Self {
+        self.minimum_requests = Some(minimum_requests);
+        self
+    }
+
+    /// Sets the number of consecutive failures at which a circuit opens. Defaults to 5.
+    ///
+    /// # Panics
+    /// If `consecutive_failures` is 0.
+    pub fn consecutive_failures(mut self, consecutive_failures: u32) -> Self {
+        assert!(
+            consecutive_failures > 0,
+            "consecutive failures must be greater than 0"
+        );
+        self.consecutive_failures = Some(consecutive_failures);
+        self
+    }
+
+    /// Sets how long a circuit stays open before it becomes half-open. Defaults to 30 seconds.
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = Some(open_duration);
+        self
+    }
+
+    /// Sets the number of probe requests that must succeed for a half-open circuit to close.
+    /// Defaults to 1.
+    ///
+    /// # Panics
+    /// If `half_open_requests` is 0.
+    pub fn half_open_requests(mut self, half_open_requests: u32) -> Self {
+        assert!(
+            half_open_requests > 0,
+            "half-open requests must be greater than 0"
+        );
+        self.half_open_requests = Some(half_open_requests);
+        self
+    }
+
+    /// Sets the maximum number of circuits that are tracked. Defaults to 1000.
+    ///
+    /// Once this many partitions have circuits, a request to a new partition drops the least
+    /// recently used circuit, preferring closed ones.
+    ///
+    /// # Panics
+    /// If `max_circuits` is 0.
+    pub fn max_circuits(mut self, max_circuits: usize) -> Self {
+        assert!(max_circuits > 0, "max circuits must be greater than 0");
+        self.max_circuits = Some(max_circuits);
+        self
+    }
+
+    /// Builds a `CircuitBreakerConfig`.
+    pub fn build(self) -> CircuitBreakerConfig {
+        let window_size = self.window_size.unwrap_or(DEFAULT_WINDOW_SIZE);
+        CircuitBreakerConfig {
+            partition: self.partition.unwrap_or_default(),
+            failure_rate_threshold: self
+                .failure_rate_threshold
+                .unwrap_or(DEFAULT_FAILURE_RATE_THRESHOLD),
+            window_size,
+            minimum_requests: self
+                .minimum_requests
+                .unwrap_or(DEFAULT_MINIMUM_REQUESTS)
+                .min(window_size),
+            consecutive_failures: self
+                .consecutive_failures
+                .unwrap_or(DEFAULT_CONSECUTIVE_FAILURES),
+            open_duration: self.open_duration.unwrap_or(DEFAULT_OPEN_DURATION),
+            half_open_requests: self
+                .half_open_requests
+                .unwrap_or(DEFAULT_HALF_OPEN_REQUESTS),
+            max_circuits: self.max_circuits.unwrap_or(DEFAULT_MAX_CIRCUITS),
+        }
+    }
+}
+
+/// Tracks the circuits of the partitions requests are sent to.
+///
+/// The circuits are shared with every clone of a circuit breaker, so a circuit breaker should be
+/// set on a client rather than on each operation.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+}
+
+impl Storable for CircuitBreaker {
+    type Storer = StoreReplace<Self>;
+}
+
+impl CircuitBreaker {
+    /// Creates a circuit breaker with the given config.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            circuits: Default::default(),
+        }
+    }
+
+    /// The config of this circuit breaker.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Returns the state of the circuit for `partition`, which is the name of a retry partition or
+    /// the host and port of an endpoint, depending on the [`CircuitBreakerPartition`].
+    pub fn state(&self, partition: &str) -> CircuitState {
+        self.circuits
+            .lock()
+            .unwrap()
+            .get(partition)
+            .map(Circuit::state)
+            .unwrap_or(CircuitState::Closed)
+    }
+
+    /// Decides whether a request to `partition` may be sent
+    fn admit(&self, partition: &str, now: SystemTime) -> Result<Admission, CircuitState> {
+        let mut circuits = self.circuits.lock().unwrap();
+        if !circuits.contains_key(partition) && circuits.len() >= self.config.max_circuits {
+            evict_least_recently_used(&mut circuits);
+        }
+        let circuit = circuits
+            .entry(partition.to_string())
+            .or_insert_with(|| Circuit::new(now));
+        circuit.last_used = now;
+        match &mut circuit.state {
+            State::Closed { .. } => {}
+            State::Open { until } => {
+                if now < *until {
+                    return Err(CircuitState::Open);
+                }
+                circuit.transition(State::HalfOpen {
+                    in_flight: 1,
+                    successes: 0,
+                    since: now,
+                });
+            }
+            State::HalfOpen {
+                in_flight, since, ..
+            } => {
+                if *in_flight < self.config.half_open_requests {
+                    *in_flight += 1;
+                } else if now >= *since + self.config.open_duration {
+                    // The probes never completed, like when the operation was canceled, so
+                    // start over with new probes
+                    *in_flight = 1;
+                    *since = now;
+                } else {
+                    return Err(CircuitState::HalfOpen);
+                }
+            }
+        }
+        Ok(Admission {
+            partition: partition.to_string(),
+            generation: circuit.generation,
+        })
+    }
+
+    /// Records the outcome of an admitted attempt, and returns the state of its circuit
+    fn record(&self, admission: &Admission, failed: bool, now: SystemTime) -> CircuitState {
+        let config = &self.config;
+        let mut circuits = self.circuits.lock().unwrap();
+        // The circuit was dropped to make room for others, so start over when it's next used
+        let Some(circuit) = circuits.get_mut(&admission.partition) else {
+            return CircuitState::Closed;
+        };
+        // Outcomes of attempts admitted before the last transition don't describe the current state
+        if admission.generation != circuit.generation {
+            return circuit.state();
+        }
+        let open = State::Open {
+            until: now + config.open_duration,
+        };
+        match &mut circuit.state {
+            State::Closed {
+                outcomes,
+                consecutive_failures,
+            } => {
+                if outcomes.len() == config.window_size {
+                    outcomes.pop_front();
+                }
+                outcomes.push_back(failed);
+                *consecutive_failures = if failed { *consecutive_failures + 1 } else { 0 };
+
+                let failures = outcomes.iter().filter(|failed| **failed).count();
+                let failure_rate = failures as f64 / outcomes.len() as f64;
+                if *consecutive_failures >= config.consecutive_failures
+                    || (outcomes.len() >= config.minimum_requests
+                        && failure_rate >= config.failure_rate_threshold)
+                {
+                    debug!(
+                        partition = %admission.partition,
+                        failure_rate, "opening the circuit"
+                    );
+                    circuit.transition(open);
+                }
+            }
+            State::HalfOpen {
+                in_flight,
+                successes,
+                ..
+            } => {
+                *in_flight = in_flight.saturating_sub(1);
+                if failed {
+                    debug!(
+                        partition = %admission.partition,
+                        "a probe request failed; reopening the circuit"
+                    );
+                    circuit.transition(open);
+                } else {
+                    *successes += 1;
+                    if *successes >= config.half_open_requests {
+                        debug!(
+                            partition = %admission.partition,
+                            "the probe requests succeeded; closing the circuit"
+                        );
+                        circuit.transition(State::default());
+                    }
+                }
+            }
+            State::Open { .. } => {}
+        }
+        circuit.state()
+    }
+}
+
+/// Drops the least recently used circuit, preferring closed circuits
+fn evict_least_recently_used(circuits: &mut HashMap<String, Circuit>) {
+    let least_recently_used = circuits
+        .iter()
+        .min_by_key(|(_, circuit)| (circuit.state() != CircuitState::Closed, circuit.last_used))
+        .map(|(partition, _)| partition.clone());
+    if let Some(partition) = least_recently_used {
+        debug!(partition = %partition, "dropping the least recently used circuit");
+        circuits.remove(&partition);
+    }
+}
+
+#[derive(Debug)]
+struct Circuit {
+    state: State,
+    /// Incremented on every transition
+    generation: u64,
+    /// When a request to the circuit's partition was last admitted or rejected
+    last_used: SystemTime,
+}
+
+impl Circuit {
+    fn new(now: SystemTime) -> Self {
+        Self {
+            state: State::default(),
+            generation: 0,
+            last_used: now,
+        }
+    }
+
+    fn state(&self) -> CircuitState {
+        match self.state {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { .. } => CircuitState::Open,
+            State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    fn transition(&mut self, state: State) {
+        self.state = state;
+        self.generation += 1;
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        /// Whether each recent attempt failed, oldest first
+        outcomes: VecDeque<bool>,
+        consecutive_failures: u32,
+    },
+    Open {
+        until: SystemTime,
+    },
+    HalfOpen {
+        in_flight: u32,
+        successes: u32,
+        since: SystemTime,
+    },
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Closed {
+            outcomes: VecDeque::new(),
+            consecutive_failures: 0,
+        }
+    }
+}
+
+/// An attempt that the circuit breaker allowed to be sent
+#[derive(Clone, Debug)]
+struct Admission {
+    partition: String,
+    /// The generation of the circuit when the attempt was admitted
+    generation: u64,
+}
+
+impl Storable for Admission {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The error for a request that wasn't sent because its circuit is open.
+#[derive(Debug)]
+pub struct CircuitOpenError {
+    partition: String,
+    state: CircuitState,
+}
+
+impl CircuitOpenError {
+    /// The retry partition name or endpoint host and port of the circuit.
+    pub fn partition(&self) -> &str {
+        &self.partition
+    }
+
+    /// The state of the circuit, which is half-open when its probe requests are still in flight.
+    pub fn state(&self) -> CircuitState {
+        self.state
+    }
+}
+
+impl fmt::Display for CircuitOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.state {
+            CircuitState::HalfOpen => write!(
+                f,
+                "the circuit for `{}` is half-open and its probe requests haven't completed",
+                self.partition
+            ),
+            _ => write!(f, "the circuit for `{}` is open", self.partition),
+        }
+    }
+}
+
+impl std::error::Error for CircuitOpenError {}
+
+/// Returns the partition of the request's circuit, if a circuit breaker is configured
+fn partition(ctx: &InterceptorContext, cfg: &ConfigBag) -> Option<(CircuitBreaker, String)> {
+    let circuit_breaker = cfg.load::<CircuitBreaker>()?.clone();
+    let partition = match circuit_breaker.config.partition {
+        CircuitBreakerPartition::RetryPartition => cfg.load::<RetryPartition>()?.to_string(),
+        CircuitBreakerPartition::Endpoint => {
+            let uri = ctx.request()?.uri().parse::<http_1x::Uri>().ok()?;
+            uri.authority()?.to_string()
+        }
+    };
+    Some((circuit_breaker, partition))
+}
+
+/// Checks that the circuit of the request in `ctx` allows it to be sent, if a circuit breaker is
+/// configured. The outcome of admitted requests is recorded by the [`CircuitBreakerInterceptor`].
+pub(crate) fn admit(
+    ctx: &InterceptorContext,
+    runtime_components: &RuntimeComponents,
+    cfg: &mut ConfigBag,
+) -> Result<(), CircuitOpenError> {
+    let Some((circuit_breaker, partition)) = partition(ctx, cfg) else {
+        return Ok(());
+    };
+    let Some(time_source) = runtime_components.time_source() else {
+        debug!("not checking the circuit breaker because no time source was set");
+        return Ok(());
+    };
+    let result = circuit_breaker.admit(&partition, time_source.now());
+    record_state(&partition, circuit_breaker.state(&partition), cfg);
+    match result {
+        Ok(admission) => {
+            cfg.interceptor_state().store_put(admission);
+            Ok(())
+        }
+        Err(state) => Err(CircuitOpenError { partition, state }),
+    }
+}
+
+/// Records the state of a circuit, if metrics are enabled
+fn record_state(partition: &str, state: CircuitState, cfg: &ConfigBag) {
+    if let Some(telemetry) = cfg.load::<OperationTelemetry>() {
+        telemetry.record_circuit_breaker_state(partition, state.gauge_value(), cfg);
+    }
+}
+
+/// Interceptor that records the outcome of attempts the circuit breaker admitted
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct CircuitBreakerInterceptor;
+
+impl CircuitBreakerInterceptor {
+    /// Creates a new `CircuitBreakerInterceptor`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Intercept for CircuitBreakerInterceptor {
+    fn name(&self) -> &'static str {
+        "CircuitBreakerInterceptor"
+    }
+
+    fn read_after_attempt(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        // Attempts that weren't admitted, like ones that failed before transmit, aren't recorded
+        let Some(admission) = cfg.load::<Admission>().cloned() else {
+            return Ok(());
+        };
+        cfg.interceptor_state().unset::<Admission>();
+        let (Some(circuit_breaker), Some(time_source)) = (
+            cfg.load::<CircuitBreaker>(),
+            runtime_components.time_source(),
+        ) else {
+            return Ok(());
+        };
+
+        let failed = matches!(
+            run_classifiers_on_ctx(runtime_components.retry_classifiers(), context.inner()),
+            RetryAction::RetryIndicated(RetryReason::RetryableError {
+                kind: ErrorKind::ServerError | ErrorKind::TransientError,
+                ..
+            })
+        );
+        let state = circuit_breaker.record(&admission, failed, time_source.now());
+        record_state(&admission.partition, state, cfg);
+        Ok(())
+    }
+}
+
+/// Runtime plugin that enables a [`CircuitBreaker`].
+#[derive(Debug)]
+pub struct CircuitBreakerRuntimePlugin {
+    config: FrozenLayer,
+    runtime_components: RuntimeComponentsBuilder,
+}
+
+impl CircuitBreakerRuntimePlugin {
+    /// Creates a runtime plugin that enables the given circuit breaker.
+    pub fn new(circuit_breaker: CircuitBreaker) -> Self {
+        let mut config = Layer::new("CircuitBreaker");
+        config.store_put(circuit_breaker);
+        Self {
+            config: config.freeze(),
+            runtime_components: RuntimeComponentsBuilder::new("CircuitBreakerRuntimePlugin")
+                .with_interceptor(SharedInterceptor::new(CircuitBreakerInterceptor::new())),
+        }
+    }
+}
+
+impl RuntimePlugin for CircuitBreakerRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        Some(self.config.clone())
+    }
+
+    fn runtime_components(
+        &self,
+        _current_components: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Borrowed(&self.runtime_components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+    use std::time::{Duration, SystemTime};
+
+    fn record(circuit_breaker: &CircuitBreaker, failed: bool, now: SystemTime) -> CircuitState {
+        let admission = circuit_breaker.admit("test", now).expect("admitted");
+        circuit_breaker.record(&admission, failed, now)
+    }
+
+    #[test]
+    fn opens_on_consecutive_failures() {
+        let circuit_breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .consecutive_failures(3)
+                .build(),
+        );
+        let now = SystemTime::UNIX_EPOCH;
+        record(&circuit_breaker, true, now);
+        record(&circuit_breaker, true, now);
+        record(&circuit_breaker, false, now);
+        record(&circuit_breaker, true, now);
+        assert_eq!(CircuitState::Closed, record(&circuit_breaker, true, now));
+        assert_eq!(CircuitState::Open, record(&circuit_breaker, true, now));
+        assert_eq!(
+            Err(CircuitState::Open),
+            circuit_breaker.admit("test", now).map(|_| ())
+        );
+        // other partitions are unaffected
+        assert!(circuit_breaker.admit("other", now).is_ok());
+    }
+
+    #[test]
+    fn opens_on_failure_rate() {
+        let circuit_breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .failure_rate_threshold(0.5)
+                .window_size(4)
+                .minimum_requests(4)
+                .build(),
+        );
+        let now = SystemTime::UNIX_EPOCH;
+        // alternating failures never reach the consecutive failure threshold
+        record(&circuit_breaker, true, now);
+        record(&circuit_breaker, false, now);
+        // not enough requests yet
+        assert_eq!(CircuitState::Closed, record(&circuit_breaker, true, now));
+        assert_eq!(CircuitState::Open, record(&circuit_breaker, false, now));
+    }
+
+    #[test]
+    fn half_open_probes_close_or_reopen_the_circuit() {
+        let circuit_breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .consecutive_failures(1)
+                .open_duration(Duration::from_secs(10))
+                .build(),
+        );
+        let start = SystemTime::UNIX_EPOCH;
+        let stale = circuit_breaker.admit("test", start).unwrap();
+        assert_eq!(CircuitState::Open, record(&circuit_breaker, true, start));
+        let after_open = start + Duration::from_secs(10);
+
+        // a single probe is allowed
+        let probe = circuit_breaker.admit("test", after_open).unwrap();
+        assert_eq!(CircuitState::HalfOpen, circuit_breaker.state("test"));
+        assert_eq!(
+            Err(CircuitState::HalfOpen),
+            circuit_breaker.admit("test", after_open).map(|_| ())
+        );
+        // outcomes of attempts admitted before the circuit opened are ignored
+        assert_eq!(
+            CircuitState::HalfOpen,
+            circuit_breaker.record(&stale, false, after_open)
+        );
+        assert_eq!(
+            CircuitState::Open,
+            circuit_breaker.record(&probe, true, after_open)
+        );
+
+        let after_reopen = after_open + Duration::from_secs(10);
+        let probe = circuit_breaker.admit("test", after_reopen).unwrap();
+        assert_eq!(
+            CircuitState::Closed,
+            circuit_breaker.record(&probe, false, after_reopen)
+        );
+    }
+
+    #[test]
+    fn least_recently_used_closed_circuits_are_dropped() {
+        let circuit_breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .consecutive_failures(1)
+                .max_circuits(2)
+                .build(),
+        );
+        let start = SystemTime::UNIX_EPOCH;
+        let admission = circuit_breaker.admit("down", start).unwrap();
+        assert_eq!(
+            CircuitState::Open,
+            circuit_breaker.record(&admission, true, start)
+        );
+        let later = start + Duration::from_secs(1);
+        let dropped = circuit_breaker.admit("closed", later).unwrap();
+
+        // the open circuit is older, but the closed one is dropped
+        assert!(circuit_breaker.admit("new", later).is_ok());
+        assert_eq!(2, circuit_breaker.circuits.lock().unwrap().len());
+        assert_eq!(CircuitState::Open, circuit_breaker.state("down"));
+        assert_eq!(
+            CircuitState::Closed,
+            circuit_breaker.record(&dropped, true, later)
+        );
+        assert!(!circuit_breaker
+            .circuits
+            .lock()
+            .unwrap()
+            .contains_key("closed"));
+    }
+}
@@ -5,3 +5,6 @@
 
 pub mod content_length_enforcement;
 pub mod minimum_throughput;
+pub mod request_compression;
+#[cfg(feature = "response-decompression")]
+pub mod response_decompression;
@@ -0,0 +1,20 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Types shared between request compression and the interceptors that run after it.
+
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+/// Marks a request whose streaming body is compressed as it's sent.
+///
+/// The compressed length of such a body isn't known until all of it has been sent. Interceptors
+/// that need a sized body, such as the request checksum interceptor, use this marker to tell a
+/// compressed stream apart from a body that's unsized for any other reason.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CompressedStreamingBody;
+
+impl Storable for CompressedStreamingBody {
+    type Storer = StoreReplace<Self>;
+}
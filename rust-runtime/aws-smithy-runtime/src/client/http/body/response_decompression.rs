@@ -0,0 +1,274 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! RuntimePlugin to transparently decompress response bodies
+//!
+//! When [`ResponseDecompressionRuntimePlugin`] is added to a client, requests advertise the
+//! supported encodings with an `Accept-Encoding` header, and responses with a supported
+//! `Content-Encoding` (`gzip`, `zstd`, or `br`) are decompressed before they're deserialized.
+//! This works for streaming (e.g. `ByteStream`) outputs as well, since the body is decompressed
+//! as it's read.
+//!
+//! The orchestrator applies decompression _after_ the `modify_before_deserialization` interceptor
+//! hook has run. This means that any body wrappers installed by interceptors, such as response
+//! checksum validation or content-length enforcement, observe the body exactly as it was sent
+//! over the wire. Checksums and `Content-Length` describe the encoded body, so this is the order
+//! in which they need to be validated.
+
+use aws_smithy_compression::body::decompress::DecompressedBody;
+use aws_smithy_compression::CompressionAlgorithm;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::BeforeTransmitInterceptorContextMut;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::client::runtime_plugin::RuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreReplace};
+use std::borrow::Cow;
+
+/// The encodings that responses may be decompressed from, in order of preference.
+const ACCEPT_ENCODING: &str = "gzip, zstd, br";
+
+/// Configuration for response decompression.
+///
+/// This is stored in the config bag by [`ResponseDecompressionRuntimePlugin`].
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ResponseDecompressionConfig {
+    max_decompressed_size_bytes: Option<u64>,
+}
+
+impl ResponseDecompressionConfig {
+    /// Create a new `ResponseDecompressionConfig` without a maximum decompressed size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size that a response body may be decompressed to.
+    ///
+    /// Reading a response body that decompresses to more than this many bytes will fail with an
+    /// [`aws_smithy_compression::MaxDecompressedSizeExceeded`] error. This protects against
+    /// "decompression bombs". By default, there is no limit.
+    pub fn with_max_decompressed_size_bytes(mut self, max_decompressed_size_bytes: u64) -> Self {
+        self.max_decompressed_size_bytes = Some(max_decompressed_size_bytes);
+        self
+    }
+
+    /// The maximum size that a response body may be decompressed to, if any.
+    pub fn max_decompressed_size_bytes(&self) -> Option<u64> {
+        self.max_decompressed_size_bytes
+    }
+}
+
+impl Storable for ResponseDecompressionConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+#[derive(Debug, Default)]
+struct ResponseDecompressionInterceptor {}
+
+impl Intercept for ResponseDecompressionInterceptor {
+    fn name(&self) -> &'static str {
+        "ResponseDecompression"
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if cfg.load::<ResponseDecompressionConfig>().is_none() {
+            return Ok(());
+        }
+        let headers = context.request_mut().headers_mut();
+        // Respect an encoding that was explicitly requested
+        if !headers.contains_key("accept-encoding") {
+            headers.insert("accept-encoding", ACCEPT_ENCODING);
+        }
+        Ok(())
+    }
+}
+
+/// Runtime plugin that transparently decompresses response bodies
+///
+/// See the [module docs](self) for more information.
+#[derive(Debug)]
+pub struct ResponseDecompressionRuntimePlugin {
+    config: FrozenLayer,
+}
+
+impl ResponseDecompressionRuntimePlugin {
+    /// Creates a runtime plugin which decompresses response bodies according to the given config
+    pub fn new(config: ResponseDecompressionConfig) -> Self {
+        let mut layer = Layer::new("ResponseDecompression");
+        layer.store_put(config);
+        Self {
+            config: layer.freeze(),
+        }
+    }
+}
+
+impl Default for ResponseDecompressionRuntimePlugin {
+    fn default() -> Self {
+        Self::new(ResponseDecompressionConfig::default())
+    }
+}
+
+impl RuntimePlugin for ResponseDecompressionRuntimePlugin {
+    fn config(&self) -> Option<FrozenLayer> {
+        Some(self.config.clone())
+    }
+
+    fn runtime_components(
+        &self,
+        _current_components: &RuntimeComponentsBuilder,
+    ) -> Cow<'_, RuntimeComponentsBuilder> {
+        Cow::Owned(
+            RuntimeComponentsBuilder::new("ResponseDecompression")
+                .with_interceptor(ResponseDecompressionInterceptor::default()),
+        )
+    }
+}
+
+/// Wrap the response body in a decompressing body if response decompression is enabled and the
+/// response has a supported `Content-Encoding`.
+///
+/// Since the headers no longer describe the decompressed body, `Content-Encoding` and
+/// `Content-Length` are removed from the response.
+pub(crate) fn decompress_response_body(response: &mut HttpResponse, cfg: &ConfigBag) {
+    let Some(config) = cfg.load::<ResponseDecompressionConfig>() else {
+        return;
+    };
+    let Some(content_encoding) = response.headers().get("content-encoding") else {
+        return;
+    };
+    let algorithm = match content_encoding.trim().parse::<CompressionAlgorithm>() {
+        Ok(algorithm) => algorithm,
+        Err(_) => {
+            tracing::debug!(
+                content_encoding,
+                "response has an unsupported content encoding; it will not be decompressed"
+            );
+            return;
+        }
+    };
+
+    tracing::trace!(
+        content_encoding = algorithm.as_str(),
+        "wrapping response body in decompression"
+    );
+    let max_decompressed_size_bytes = config.max_decompressed_size_bytes();
+    let body = response.take_body();
+    *response.body_mut() = body.map(move |body| {
+        SdkBody::from_body_0_4(DecompressedBody::new(
+            body,
+            algorithm.into_decompress_impl(max_decompressed_size_bytes),
+        ))
+    });
+    response.headers_mut().remove("content-encoding");
+    response.headers_mut().remove("content-length");
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decompress_response_body, ResponseDecompressionConfig};
+    use aws_smithy_compression::MaxDecompressedSizeExceeded;
+    use aws_smithy_runtime_api::client::orchestrator::HttpResponse;
+    use aws_smithy_runtime_api::http::StatusCode;
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::byte_stream::ByteStream;
+    use aws_smithy_types::config_bag::{ConfigBag, Layer};
+    use std::error::Error;
+
+    // "hello world" compressed with gzip
+    const GZIP_HELLO_WORLD: &[u8] = &[
+        31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0,
+        133, 17, 74, 13, 11, 0, 0, 0,
+    ];
+
+    fn cfg(config: Option<ResponseDecompressionConfig>) -> ConfigBag {
+        let mut layer = Layer::new("test");
+        if let Some(config) = config {
+            layer.store_put(config);
+        }
+        ConfigBag::of_layers(vec![layer])
+    }
+
+    fn response(content_encoding: &str) -> HttpResponse {
+        let mut response =
+            HttpResponse::new(StatusCode::try_from(200).unwrap(), GZIP_HELLO_WORLD.into());
+        response
+            .headers_mut()
+            .insert("content-encoding", content_encoding.to_owned());
+        response
+            .headers_mut()
+            .insert("content-length", GZIP_HELLO_WORLD.len().to_string());
+        response
+    }
+
+    async fn read_body(response: HttpResponse) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let body = ByteStream::new(response.into_body()).collect().await?;
+        Ok(body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn response_is_decompressed() {
+        let mut response = response("gzip");
+        decompress_response_body(&mut response, &cfg(Some(Default::default())));
+
+        assert!(response.headers().get("content-encoding").is_none());
+        assert!(response.headers().get("content-length").is_none());
+        assert_eq!(b"hello world", &read_body(response).await.unwrap()[..]);
+    }
+
+    #[tokio::test]
+    async fn response_is_not_decompressed_when_disabled() {
+        let mut response = response("gzip");
+        decompress_response_body(&mut response, &cfg(None));
+
+        assert_eq!(Some("gzip"), response.headers().get("content-encoding"));
+        assert_eq!(GZIP_HELLO_WORLD, &read_body(response).await.unwrap()[..]);
+    }
+
+    #[tokio::test]
+    async fn unsupported_encoding_is_not_decompressed() {
+        let mut response = response("compress");
+        decompress_response_body(&mut response, &cfg(Some(Default::default())));
+
+        assert_eq!(Some("compress"), response.headers().get("content-encoding"));
+        assert_eq!(GZIP_HELLO_WORLD, &read_body(response).await.unwrap()[..]);
+    }
+
+    #[tokio::test]
+    async fn max_decompressed_size_is_enforced() {
+        let mut response = response("gzip");
+        let config = ResponseDecompressionConfig::new().with_max_decompressed_size_bytes(5);
+        decompress_response_body(&mut response, &cfg(Some(config)));
+
+        let err = read_body(response).await.expect_err("body is too large");
+        let mut source = err.source();
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<MaxDecompressedSizeExceeded>() {
+                assert_eq!(5, err.max_size_bytes());
+                return;
+            }
+            source = err.source();
+        }
+        panic!("expected a `MaxDecompressedSizeExceeded` error, got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn empty_response_is_not_decompressed() {
+        let mut response = HttpResponse::new(StatusCode::try_from(200).unwrap(), SdkBody::empty());
+        response.headers_mut().insert("content-encoding", "gzip");
+        decompress_response_body(&mut response, &cfg(Some(Default::default())));
+
+        assert!(read_body(response).await.unwrap().is_empty());
+    }
+}
@@ -4,7 +4,11 @@
  */
 
 mod cache;
-pub use cache::{IdentityCache, LazyCacheBuilder};
+#[cfg(feature = "http-auth")]
+pub use cache::TokenSerializer;
+pub use cache::{
+    CacheEncryption, IdentityCache, IdentitySerializer, LazyCacheBuilder, PersistentCacheBuilder,
+};
 
 /// Identity resolver implementation for "no auth".
 pub mod no_auth;
@@ -11,8 +11,12 @@ use aws_smithy_runtime_api::shared::IntoShared;
 use aws_smithy_types::config_bag::ConfigBag;
 
 mod lazy;
+mod persistent;
 use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 pub use lazy::LazyCacheBuilder;
+#[cfg(feature = "http-auth")]
+pub use persistent::TokenSerializer;
+pub use persistent::{CacheEncryption, IdentitySerializer, PersistentCacheBuilder};
 
 /// Identity cache configuration.
 ///
@@ -74,6 +78,14 @@ impl IdentityCache {
     pub fn lazy() -> LazyCacheBuilder {
         LazyCacheBuilder::new()
     }
+
+    /// Configure a persistent identity cache.
+    ///
+    /// Identities are lazy loaded like with the [lazy cache](Self::lazy), and are also stored
+    /// in files so that they can be reused by other processes and after a restart.
+    pub fn persistent() -> PersistentCacheBuilder {
+        PersistentCacheBuilder::new()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -184,20 +184,23 @@ impl LazyCacheBuilder {
 }
 
 #[derive(Debug)]
-struct CachePartitions {
+pub(super) struct CachePartitions {
     partitions: RwLock<HashMap<IdentityCachePartition, ExpiringCache<Identity, BoxError>>>,
     buffer_time: Duration,
 }
 
 impl CachePartitions {
-    fn new(buffer_time: Duration) -> Self {
+    pub(super) fn new(buffer_time: Duration) -> Self {
         Self {
             partitions: RwLock::new(HashMap::new()),
             buffer_time,
         }
     }
 
-    fn partition(&self, key: IdentityCachePartition) -> ExpiringCache<Identity, BoxError> {
+    pub(super) fn partition(
+        &self,
+        key: IdentityCachePartition,
+    ) -> ExpiringCache<Identity, BoxError> {
         let mut partition = self.partitions.read().unwrap().get(&key).cloned();
         // Add the partition to the cache if it doesn't already exist.
         // Partitions will never be removed.
@@ -377,7 +380,7 @@ impl ResolveCachedIdentity for LazyCache {
 }
 
 #[derive(Debug)]
-struct TimedOutError(Duration);
+pub(super) struct TimedOutError(pub(super) Duration);
 
 impl std::error::Error for TimedOutError {}
 
@@ -0,0 +1,875 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Identity cache that persists identities to files, so that they survive process restarts.
+//!
+//! Short-lived processes, such as command line tools, would otherwise resolve a new identity on
+//! every invocation. This cache keeps the identities it resolves in memory like the
+//! [lazy cache](super::LazyCacheBuilder), and also writes them to a file in a cache directory that
+//! the next process reads them from.
+//!
+//! Only the identities of one identity resolver are written to the file, since the resolvers of
+//! other processes can't be told apart. The identities of any other resolver the cache is used
+//! with are only cached in memory.
+
+use super::lazy::{CachePartitions, TimedOutError};
+use aws_smithy_async::future::timeout::Timeout;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::identity::{
+    Identity, IdentityCachePartition, IdentityFuture, ResolveCachedIdentity, ResolveIdentity,
+    SharedIdentityCache, SharedIdentityResolver,
+};
+use aws_smithy_runtime_api::client::runtime_components::{
+    RuntimeComponents, RuntimeComponentsBuilder,
+};
+use aws_smithy_runtime_api::shared::IntoShared;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::DateTime;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime};
+use tracing::Instrument;
+
+const DEFAULT_LOAD_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_EXPIRATION: Duration = Duration::from_secs(15 * 60);
+const DEFAULT_BUFFER_TIME: Duration = Duration::from_secs(10);
+/// How often a process waiting on another process's lock checks whether it was released
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+/// Lock files older than this, or twice the load timeout if that's longer, are assumed to have
+/// been left behind by a process that crashed
+const MIN_STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+const FILE_HEADER: &[u8] = b"smithy-identity-cache-v1\n";
+const PLAINTEXT_MARKER: &[u8] = b"plaintext\n";
+const ENCRYPTED_MARKER: &[u8] = b"encrypted\n";
+
+/// Converts identities to and from the bytes stored in a persistent identity cache.
+///
+/// Identities are type erased, so the cache relies on this to know how to store the identities
+/// of the resolver it caches. [`TokenSerializer`] supports bearer tokens, and the AWS SDK provides
+/// a serializer for AWS credentials in `aws_runtime::identity::CredentialsSerializer`.
+pub trait IdentitySerializer: fmt::Debug + Send + Sync {
+    /// Serializes the data of `identity`, or returns an error if the identity isn't supported.
+    fn serialize(&self, identity: &Identity) -> Result<Vec<u8>, BoxError>;
+
+    /// Deserializes an identity from data returned by [`serialize`](Self::serialize), along with
+    /// the expiration it was cached with.
+    fn deserialize(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError>;
+}
+
+/// Serializer for bearer [`Token`](aws_smithy_runtime_api::client::identity::http::Token) identities.
+#[cfg(feature = "http-auth")]
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct TokenSerializer;
+
+#[cfg(feature = "http-auth")]
+impl TokenSerializer {
+    /// Creates a new `TokenSerializer`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(feature = "http-auth")]
+impl IdentitySerializer for TokenSerializer {
+    fn serialize(&self, identity: &Identity) -> Result<Vec<u8>, BoxError> {
+        use aws_smithy_runtime_api::client::identity::http::Token;
+        let token = identity
+            .data::<Token>()
+            .ok_or("the identity isn't a bearer token")?;
+        Ok(token.token().as_bytes().to_vec())
+    }
+
+    fn deserialize(&self, data: &[u8], expiration: SystemTime) -> Result<Identity, BoxError> {
+        use aws_smithy_runtime_api::client::identity::http::Token;
+        let token = std::str::from_utf8(data)?;
+        Ok(Token::new(token, Some(expiration)).into())
+    }
+}
+
+/// Encrypts the identities stored in a persistent identity cache.
+///
+/// The implementation is responsible for providing the key, for example from the operating
+/// system's keychain, and for authenticating the data so that tampered files fail to decrypt.
+pub trait CacheEncryption: fmt::Debug + Send + Sync {
+    /// Encrypts `plaintext`.
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, BoxError>;
+
+    /// Decrypts `ciphertext` returned by [`encrypt`](Self::encrypt).
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, BoxError>;
+}
+
+/// Builder for persistent identity caching.
+#[derive(Debug, Default)]
+pub struct PersistentCacheBuilder {
+    directory: Option<PathBuf>,
+    key: Option<String>,
+    serializer: Option<Arc<dyn IdentitySerializer>>,
+    encryption: Option<Arc<dyn CacheEncryption>>,
+    load_timeout: Option<Duration>,
+    buffer_time: Option<Duration>,
+    default_expiration: Option<Duration>,
+}
+
+impl PersistentCacheBuilder {
+    /// Create a new builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Directory the cache files are stored in.
+    ///
+    /// The directory is created if it doesn't exist. On Unix, it's created so that only the
+    /// current user can access it, and cache files are only readable by the current user.
+    ///
+    /// This is required.
+    pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Stable key the identity is cached under, such as the ARN of an assumed role.
+    ///
+    /// The key must be the same across processes for them to share the cached identity, and must
+    /// only be used for a single identity resolver, since every identity cached with the same key
+    /// is stored in the same file. Only the identities of the first identity resolver the cache is
+    /// used with are stored in the file; those of any other resolver are only cached in memory.
+    ///
+    /// This is required.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Serializer for the identities of the cached identity resolver.
+    ///
+    /// This is required.
+    pub fn serializer(mut self, serializer: impl IdentitySerializer + 'static) -> Self {
+        self.serializer = Some(Arc::new(serializer));
+        self
+    }
+
+    /// Encrypts the cached identities at rest.
+    ///
+    /// Cache files written without encryption are ignored when encryption is set, and vice versa.
+    pub fn encryption(mut self, encryption: impl CacheEncryption + 'static) -> Self {
+        self.encryption = Some(Arc::new(encryption));
+        self
+    }
+
+    /// Timeout for identity resolution, including the time spent waiting for another process
+    /// resolving the same identity.
+    ///
+    /// Defaults to 5 seconds.
+    pub fn load_timeout(mut self, timeout: Duration) -> Self {
+        self.load_timeout = Some(timeout);
+        self
+    }
+
+    /// Amount of time before the actual identity expiration time where the identity is considered
+    /// expired, both in memory and when loaded from a file.
+    ///
+    /// Defaults to 10 seconds.
+    pub fn buffer_time(mut self, buffer_time: Duration) -> Self {
+        self.buffer_time = Some(buffer_time);
+        self
+    }
+
+    /// Default expiration time to set on an identity if it doesn't have an expiration time.
+    ///
+    /// This must be at least 15 minutes.
+    ///
+    /// Defaults to 15 minutes.
+    pub fn default_expiration(mut self, duration: Duration) -> Self {
+        self.default_expiration = Some(duration);
+        self
+    }
+
+    /// Builds a [`SharedIdentityCache`] from this builder.
+    ///
+    /// # Panics
+    ///
+    /// This builder will panic if required fields are not given, or if given values are not valid.
+    pub fn build(self) -> SharedIdentityCache {
+        let default_expiration = self.default_expiration.unwrap_or(DEFAULT_EXPIRATION);
+        assert!(
+            default_expiration >= DEFAULT_EXPIRATION,
+            "default_expiration must be at least 15 minutes"
+        );
+        let key = self.key.expect("a cache key is required");
+        assert!(!key.is_empty(), "the cache key must not be empty");
+        let buffer_time = self.buffer_time.unwrap_or(DEFAULT_BUFFER_TIME);
+        PersistentCache {
+            path: self
+                .directory
+                .expect("a cache directory is required")
+                .join(file_name(&key)),
+            serializer: self.serializer.expect("an identity serializer is required"),
+            encryption: self.encryption,
+            partitions: CachePartitions::new(buffer_time),
+            persisted_partition: OnceLock::new(),
+            load_timeout: self.load_timeout.unwrap_or(DEFAULT_LOAD_TIMEOUT),
+            buffer_time,
+            default_expiration,
+        }
+        .into_shared()
+    }
+}
+
+/// Escapes the characters of `key` that aren't safe in file names
+fn file_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len() + ".identity".len());
+    for byte in key.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' => name.push(byte as char),
+            _ => name.push_str(&format!("_{byte:02x}")),
+        }
+    }
+    name.push_str(".identity");
+    name
+}
+
+#[derive(Debug)]
+struct PersistentCache {
+    path: PathBuf,
+    serializer: Arc<dyn IdentitySerializer>,
+    encryption: Option<Arc<dyn CacheEncryption>>,
+    partitions: CachePartitions,
+    /// The partition of the resolver whose identities are stored in the file
+    persisted_partition: OnceLock<IdentityCachePartition>,
+    load_timeout: Duration,
+    buffer_time: Duration,
+    default_expiration: Duration,
+}
+
+impl PersistentCache {
+    /// Returns true if the identities of the resolver with the given partition are stored in the
+    /// file, which is the case for the first resolver the cache is used with
+    fn is_persisted(&self, partition: IdentityCachePartition) -> bool {
+        let persisted = *self.persisted_partition.get_or_init(|| partition) == partition;
+        if !persisted {
+            tracing::debug!(
+                path = %self.path.display(),
+                "the cache file is used by another identity resolver, only caching the identity in memory"
+            );
+        }
+        persisted
+    }
+
+    /// Resolves a new identity, failing once the load timeout elapses
+    async fn resolve(
+        &self,
+        resolver: &SharedIdentityResolver,
+        runtime_components: &RuntimeComponents,
+        config_bag: &ConfigBag,
+        sleep_impl: &SharedAsyncSleep,
+        timeout: Duration,
+    ) -> Result<Identity, BoxError> {
+        match Timeout::new(
+            resolver.resolve_identity(runtime_components, config_bag),
+            sleep_impl.sleep(timeout),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_err) => match resolver.fallback_on_interrupt() {
+                Some(identity) => Ok(identity),
+                None => Err(BoxError::from(TimedOutError(self.load_timeout))),
+            },
+        }
+    }
+
+    /// Loads the identity from the cache file if it exists and isn't expired
+    fn load(&self, now: SystemTime) -> Result<Option<(Identity, SystemTime)>, BoxError> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let contents = contents
+            .strip_prefix(FILE_HEADER)
+            .ok_or("unrecognized cache file format")?;
+        let record = match (&self.encryption, contents.strip_prefix(ENCRYPTED_MARKER)) {
+            (Some(encryption), Some(ciphertext)) => encryption.decrypt(ciphertext)?,
+            (None, None) => contents
+                .strip_prefix(PLAINTEXT_MARKER)
+                .ok_or("unrecognized cache file format")?
+                .to_vec(),
+            (Some(_), None) => return Err("the cache file isn't encrypted".into()),
+            (None, Some(_)) => return Err("the cache file is encrypted".into()),
+        };
+
+        let newline = record
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or("unrecognized cache file format")?;
+        let expiration = std::str::from_utf8(&record[..newline])?;
+        let expiration =
+            SystemTime::try_from(DateTime::from_str(expiration, Format::EpochSeconds)?)?;
+        if expiration <= now + self.buffer_time {
+            tracing::debug!(path = %self.path.display(), "the cached identity has expired");
+            return Ok(None);
+        }
+        let identity = self
+            .serializer
+            .deserialize(&record[newline + 1..], expiration)?;
+        Ok(Some((identity, expiration)))
+    }
+
+    /// Writes the identity to the cache file
+    fn store(&self, identity: &Identity, expiration: SystemTime) -> Result<(), BoxError> {
+        let mut record = DateTime::from(expiration)
+            .fmt(Format::EpochSeconds)?
+            .into_bytes();
+        record.push(b'\n');
+        record.extend(self.serializer.serialize(identity)?);
+
+        let mut contents = FILE_HEADER.to_vec();
+        match &self.encryption {
+            Some(encryption) => {
+                contents.extend(ENCRYPTED_MARKER);
+                contents.extend(encryption.encrypt(&record)?);
+            }
+            None => {
+                contents.extend(PLAINTEXT_MARKER);
+                contents.extend(record);
+            }
+        }
+
+        // Write to a temporary file first so that other processes never read a partial file.
+        // Its name is unique so that processes storing an identity at the same time (e.g. once
+        // the lock timed out) don't write to the same temporary file.
+        static NEXT_TEMP_FILE: AtomicUsize = AtomicUsize::new(0);
+        let temp_path = self.path.with_extension(format!(
+            "identity.{}-{}.tmp",
+            std::process::id(),
+            NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = open_private(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(&contents)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&temp_path, &self.path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        Ok(result?)
+    }
+}
+
+/// Creates the file at `path` so that only the current user can read it, creating its parent
+/// directory if needed
+fn open_private(path: &Path) -> io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    match options.open(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            create_private_dir(path.parent().expect("cache files are in a directory"))?;
+            options.open(path)
+        }
+        result => result,
+    }
+}
+
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(path)
+}
+
+/// Lock on a cache file shared with other processes, released when dropped
+#[derive(Debug)]
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Waits up to `timeout` to acquire the lock for the cache file at `path`
+    async fn acquire(
+        path: &Path,
+        time_source: &SharedTimeSource,
+        sleep_impl: &SharedAsyncSleep,
+        timeout: Duration,
+    ) -> io::Result<Option<Self>> {
+        let path = path.with_extension("identity.lock");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        let start = time_source.now();
+        loop {
+            let result = match options.open(&path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    create_private_dir(path.parent().expect("cache files are in a directory"))?;
+                    options.open(&path)
+                }
+                result => result,
+            };
+            match result {
+                Ok(_) => return Ok(Some(Self { path })),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+
+            let now = time_source.now();
+            let lock_age = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok());
+            if lock_age.is_some_and(|age| age > stale_lock_age(timeout)) {
+                tracing::debug!(path = %path.display(), "removing a stale cache lock");
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            if now.duration_since(start).unwrap_or_default() >= timeout {
+                return Ok(None);
+            }
+            sleep_impl.sleep(LOCK_RETRY_INTERVAL).await;
+        }
+    }
+}
+
+/// The age after which a lock is assumed to have been left behind by a crashed process
+///
+/// A process holds the lock while resolving an identity, which takes up to `load_timeout`, so
+/// locks younger than that are never considered stale.
+fn stale_lock_age(load_timeout: Duration) -> Duration {
+    MIN_STALE_LOCK_AGE.max(load_timeout.saturating_mul(2))
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn required_err(thing: &str, how: &str) -> BoxError {
+    format!(
+        "Persistent identity caching requires {thing} to be configured. {how} If this isn't \
+        possible, then disable identity caching by calling the `identity_cache` method on config \
+        with `IdentityCache::no_cache()`"
+    )
+    .into()
+}
+
+impl ResolveCachedIdentity for PersistentCache {
+    fn validate_base_client_config(
+        &self,
+        runtime_components: &RuntimeComponentsBuilder,
+        _cfg: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        if runtime_components.time_source().is_none() {
+            return Err(required_err(
+                "a time source",
+                "Set a time source using the `time_source` method on config.",
+            ));
+        }
+        if runtime_components.sleep_impl().is_none() {
+            return Err(required_err(
+                "an async sleep implementation",
+                "Set a sleep impl using the `sleep_impl` method on config.",
+            ));
+        }
+        Ok(())
+    }
+
+    fn resolve_cached_identity<'a>(
+        &'a self,
+        resolver: SharedIdentityResolver,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        let (time_source, sleep_impl) = match (
+            runtime_components.time_source(),
+            runtime_components.sleep_impl(),
+        ) {
+            (Some(time_source), Some(sleep_impl)) => (time_source, sleep_impl),
+            (None, _) => {
+                return IdentityFuture::ready(Err(required_err(
+                    "a time source",
+                    "Set a time source using the `time_source` method on config.",
+                )))
+            }
+            (_, None) => {
+                return IdentityFuture::ready(Err(required_err(
+                    "an async sleep implementation",
+                    "Set a sleep impl using the `sleep_impl` method on config.",
+                )))
+            }
+        };
+        let now = time_source.now();
+        let partition = resolver.cache_partition();
+        let cache = self.partitions.partition(partition);
+
+        IdentityFuture::new(async move {
+            if let Some(identity) = cache.yield_or_clear_if_expired(now).await {
+                tracing::debug!("loaded identity from the in-memory cache");
+                return Ok(identity);
+            }
+            cache
+                .get_or_load(|| {
+                    let span = tracing::debug_span!("persistent_load_identity");
+                    async move {
+                        if !self.is_persisted(partition) {
+                            let identity = self
+                                .resolve(
+                                    &resolver,
+                                    runtime_components,
+                                    config_bag,
+                                    &sleep_impl,
+                                    self.load_timeout,
+                                )
+                                .await?;
+                            let expiration = identity
+                                .expiration()
+                                .unwrap_or(now + self.default_expiration);
+                            return Ok((identity, expiration));
+                        }
+
+                        // Hold the lock while resolving so that concurrent processes wait for
+                        // this one instead of resolving the identity too
+                        let start = time_source.now();
+                        let lock = FileLock::acquire(
+                            &self.path,
+                            &time_source,
+                            &sleep_impl,
+                            self.load_timeout,
+                        )
+                        .await
+                        .unwrap_or_else(|err| {
+                            tracing::warn!(err = %err, "failed to lock the identity cache file");
+                            None
+                        });
+                        if lock.is_none() {
+                            tracing::debug!("resolving the identity without locking the cache file");
+                        }
+
+                        match self.load(time_source.now()) {
+                            Ok(Some((identity, expiration))) => {
+                                tracing::debug!(path = %self.path.display(), "loaded identity from the cache file");
+                                return Ok((identity, expiration));
+                            }
+                            Ok(None) => {}
+                            Err(err) => {
+                                tracing::debug!(path = %self.path.display(), err = %err, "ignoring unreadable identity cache file")
+                            }
+                        }
+
+                        // The load timeout includes the time spent waiting for the lock
+                        let waited = time_source
+                            .now()
+                            .duration_since(start)
+                            .unwrap_or_default();
+                        let identity = self
+                            .resolve(
+                                &resolver,
+                                runtime_components,
+                                config_bag,
+                                &sleep_impl,
+                                self.load_timeout.saturating_sub(waited),
+                            )
+                            .await?;
+                        let expiration = identity
+                            .expiration()
+                            .unwrap_or(now + self.default_expiration);
+                        match self.store(&identity, expiration) {
+                            Ok(()) => {
+                                tracing::debug!(path = %self.path.display(), "stored identity in the cache file")
+                            }
+                            Err(err) => {
+                                tracing::warn!(path = %self.path.display(), err = %err, "failed to store identity in the cache file")
+                            }
+                        }
+                        drop(lock);
+                        Ok((identity, expiration))
+                    }
+                    .instrument(span)
+                })
+                .await
+        })
+    }
+}
+
+#[cfg(all(test, feature = "client", feature = "http-auth"))]
+mod tests {
+    use super::*;
+    use aws_smithy_async::rt::sleep::TokioSleep;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_runtime_api::client::identity::http::Token;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use std::time::UNIX_EPOCH;
+
+    #[derive(Debug)]
+    struct CountingResolver {
+        token: &'static str,
+        expiration: SystemTime,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl ResolveIdentity for CountingResolver {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            IdentityFuture::ready(Ok(Token::new(self.token, Some(self.expiration)).into()))
+        }
+    }
+
+    /// XORs the data with a key, which is enough to tell that the cache encrypts its files
+    #[derive(Debug)]
+    struct XorEncryption(u8);
+
+    impl CacheEncryption for XorEncryption {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, BoxError> {
+            Ok(plaintext.iter().map(|byte| byte ^ self.0).collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, BoxError> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    fn epoch_secs(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn components(time_source: &ManualTimeSource) -> RuntimeComponents {
+        RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(time_source.clone()))
+            .with_sleep_impl(Some(TokioSleep::new()))
+            .build()
+            .unwrap()
+    }
+
+    /// Resolves a token through a new cache, as a new process would
+    async fn resolve(
+        builder: PersistentCacheBuilder,
+        time_source: &ManualTimeSource,
+        token: &'static str,
+        calls: &Arc<AtomicUsize>,
+    ) -> String {
+        let resolver = SharedIdentityResolver::new(CountingResolver {
+            token,
+            expiration: epoch_secs(1000),
+            calls: calls.clone(),
+        });
+        let identity = builder
+            .serializer(TokenSerializer::new())
+            .build()
+            .resolve_cached_identity(resolver, &components(time_source), &ConfigBag::base())
+            .await
+            .expect("success");
+        assert_eq!(Some(epoch_secs(1000)), identity.expiration());
+        identity.data::<Token>().unwrap().token().to_string()
+    }
+
+    #[tokio::test]
+    async fn identities_are_shared_across_caches() {
+        let directory = tempfile::tempdir().unwrap();
+        let builder = || {
+            PersistentCacheBuilder::new()
+                .directory(directory.path().join("cache"))
+                .key("arn:aws:iam::123456789012:role/test")
+        };
+        let time_source = ManualTimeSource::new(epoch_secs(100));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        assert_eq!(
+            "first",
+            resolve(builder(), &time_source, "first", &calls).await
+        );
+        assert_eq!(
+            "first",
+            resolve(builder(), &time_source, "second", &calls).await
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+
+        let path = directory
+            .path()
+            .join("cache/arn_3aaws_3aiam_3a_3a123456789012_3arole_2ftest.identity");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+        assert!(fs::read(&path).unwrap().ends_with(b"first"));
+        let files = fs::read_dir(directory.path().join("cache"))
+            .unwrap()
+            .count();
+        assert_eq!(1, files, "temporary and lock files are removed");
+
+        // within the buffer time of the expiration
+        time_source.set_time(epoch_secs(995));
+        assert_eq!(
+            "third",
+            resolve(builder(), &time_source, "third", &calls).await
+        );
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn encrypted_identities() {
+        let directory = tempfile::tempdir().unwrap();
+        let builder = || {
+            PersistentCacheBuilder::new()
+                .directory(directory.path())
+                .key("test")
+        };
+        let time_source = ManualTimeSource::new(epoch_secs(100));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let encrypted = || builder().encryption(XorEncryption(0x5a));
+        assert_eq!(
+            "secret",
+            resolve(encrypted(), &time_source, "secret", &calls).await
+        );
+        let contents = fs::read(directory.path().join("test.identity")).unwrap();
+        assert!(!contents.windows(6).any(|window| window == b"secret"));
+        assert_eq!(
+            "secret",
+            resolve(encrypted(), &time_source, "other", &calls).await
+        );
+        assert_eq!(1, calls.load(Ordering::SeqCst));
+
+        // an unencrypted cache can't read the encrypted file
+        assert_eq!(
+            "plain",
+            resolve(builder(), &time_source, "plain", &calls).await
+        );
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn stale_locks_are_removed() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock_path = directory.path().join("test.identity.lock");
+        fs::write(&lock_path, b"").unwrap();
+        let locked_at = fs::metadata(&lock_path).unwrap().modified().unwrap();
+        let time_source = ManualTimeSource::new(locked_at);
+        let builder = || {
+            PersistentCacheBuilder::new()
+                .directory(directory.path())
+                .key("test")
+                .load_timeout(Duration::from_millis(200))
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        // the lock is held, so the identity is resolved without it once the timeout elapses
+        let time_source_clone = time_source.clone();
+        let advance = async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            time_source_clone.advance(Duration::from_secs(1));
+        };
+        let (token, _) = tokio::join!(resolve(builder(), &time_source, "token", &calls), advance);
+        assert_eq!("token", token);
+        assert!(lock_path.exists(), "another process's lock is left alone");
+
+        time_source.advance(MIN_STALE_LOCK_AGE * 2);
+        fs::remove_file(directory.path().join("test.identity")).unwrap();
+        assert_eq!("new", resolve(builder(), &time_source, "new", &calls).await);
+        assert!(!lock_path.exists(), "the stale lock is removed");
+    }
+
+    #[derive(Debug)]
+    struct NeverResolves;
+
+    impl ResolveIdentity for NeverResolves {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            IdentityFuture::new(std::future::pending())
+        }
+    }
+
+    #[tokio::test]
+    async fn waiting_for_the_lock_counts_towards_the_load_timeout() {
+        let directory = tempfile::tempdir().unwrap();
+        let lock_path = directory.path().join("test.identity.lock");
+        fs::write(&lock_path, b"").unwrap();
+        let locked_at = fs::metadata(&lock_path).unwrap().modified().unwrap();
+        let time_source = ManualTimeSource::new(locked_at);
+        let cache = PersistentCacheBuilder::new()
+            .directory(directory.path())
+            .key("test")
+            .serializer(TokenSerializer::new())
+            .load_timeout(Duration::from_secs(60))
+            .build();
+
+        // the whole load timeout is spent waiting for the lock, so the resolver gets no more time
+        let time_source_clone = time_source.clone();
+        let advance = async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            time_source_clone.advance(Duration::from_secs(61));
+        };
+        let (components, config_bag) = (components(&time_source), ConfigBag::base());
+        let resolve = cache.resolve_cached_identity(
+            SharedIdentityResolver::new(NeverResolves),
+            &components,
+            &config_bag,
+        );
+        let (result, _) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(resolve, advance)
+        })
+        .await
+        .expect("identity resolution doesn't get another load timeout after the lock wait");
+        let err = result.expect_err("the identity isn't resolved in time");
+        assert!(err.is::<TimedOutError>(), "{err}");
+    }
+
+    #[tokio::test]
+    async fn only_the_first_resolver_is_persisted() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = PersistentCacheBuilder::new()
+            .directory(directory.path())
+            .key("test")
+            .serializer(TokenSerializer::new())
+            .build();
+        let time_source = ManualTimeSource::new(epoch_secs(100));
+        let components = components(&time_source);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let resolver = |token| {
+            SharedIdentityResolver::new(CountingResolver {
+                token,
+                expiration: epoch_secs(1000),
+                calls: calls.clone(),
+            })
+        };
+        let (first, second) = (resolver("first"), resolver("second"));
+        for (resolver, expected) in [(&first, "first"), (&second, "second"), (&first, "first")] {
+            let identity = cache
+                .resolve_cached_identity(resolver.clone(), &components, &ConfigBag::base())
+                .await
+                .expect("success");
+            assert_eq!(expected, identity.data::<Token>().unwrap().token());
+        }
+        assert_eq!(2, calls.load(Ordering::SeqCst));
+        let contents = fs::read(directory.path().join("test.identity")).unwrap();
+        assert!(contents.ends_with(b"first"));
+    }
+
+    #[test]
+    fn stale_lock_age_covers_the_load_timeout() {
+        assert_eq!(MIN_STALE_LOCK_AGE, stale_lock_age(Duration::from_secs(5)));
+        assert_eq!(
+            Duration::from_secs(120),
+            stale_lock_age(Duration::from_secs(60))
+        );
+    }
+}
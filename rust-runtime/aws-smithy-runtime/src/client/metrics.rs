@@ -5,22 +5,41 @@
 
 use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_observability::{
-    global::get_telemetry_provider, instruments::Histogram, AttributeValue, Attributes,
-    ObservabilityError,
+    global::get_telemetry_provider,
+    instruments::{AsyncMeasure, Histogram, MonotonicCounter},
+    tracer::{Span, SpanKind, SpanStatus, Tracer},
+    AttributeValue, Attributes, ObservabilityError,
 };
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::auth::AuthSchemeId;
+use aws_smithy_runtime_api::client::interceptors::context::{
+    AfterDeserializationInterceptorContextRef, BeforeDeserializationInterceptorContextRef,
+    BeforeSerializationInterceptorContextRef, BeforeTransmitInterceptorContextMut,
+    BeforeTransmitInterceptorContextRef, FinalizerInterceptorContextRef,
+};
+use aws_smithy_runtime_api::client::retries::classifiers::{RetryAction, RetryReason};
+use aws_smithy_runtime_api::client::retries::RequestAttempts;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_runtime_api::client::{
     interceptors::Intercept, orchestrator::Metadata, runtime_components::RuntimeComponentsBuilder,
     runtime_plugin::RuntimePlugin,
 };
-use aws_smithy_types::config_bag::{FrozenLayer, Layer, Storable, StoreReplace};
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer, Layer, Storable, StoreReplace};
+use std::sync::OnceLock;
+use std::time::Duration;
 use std::{borrow::Cow, sync::Arc, time::SystemTime};
 
+use crate::client::retries::classifiers::run_classifiers_on_ctx;
+
 /// Struct to hold metric data in the ConfigBag
 #[derive(Debug, Clone)]
 pub(crate) struct MeasurementsContainer {
     call_start: SystemTime,
     attempts: u32,
     attempt_start: SystemTime,
+    attempt_end: Option<SystemTime>,
+    serialization_start: Option<SystemTime>,
+    deserialization_start: Option<SystemTime>,
 }
 
 impl Storable for MeasurementsContainer {
@@ -32,15 +51,25 @@ impl Storable for MeasurementsContainer {
 pub(crate) struct OperationTelemetry {
     pub(crate) operation_duration: Arc<dyn Histogram>,
     pub(crate) attempt_duration: Arc<dyn Histogram>,
+    pub(crate) attempts: Arc<dyn MonotonicCounter>,
+    pub(crate) errors: Arc<dyn MonotonicCounter>,
+    pub(crate) retry_backoff_duration: Arc<dyn Histogram>,
+    pub(crate) serialization_duration: Arc<dyn Histogram>,
+    pub(crate) deserialization_duration: Arc<dyn Histogram>,
+    pub(crate) resolve_identity_duration: Arc<dyn Histogram>,
+    pub(crate) token_bucket_available_permits: Arc<dyn AsyncMeasure<Value = f64>>,
+    pub(crate) rate_limiter_fill_rate: Arc<dyn AsyncMeasure<Value = f64>>,
+    pub(crate) circuit_breaker_state: Arc<dyn AsyncMeasure<Value = f64>>,
+    pub(crate) tracer: Tracer,
 }
 
 impl OperationTelemetry {
     pub(crate) fn new(scope: &'static str) -> Result<Self, ObservabilityError> {
-        let meter = get_telemetry_provider()?
-            .meter_provider()
-            .get_meter(scope, None);
+        let telemetry_provider = get_telemetry_provider()?;
+        let meter = telemetry_provider.meter_provider().get_meter(scope, None);
 
         Ok(Self{
+            tracer: telemetry_provider.tracer_provider().get_tracer(scope, None),
             operation_duration: meter
                 .create_histogram("smithy.client.call.duration")
                 .set_units("s")
@@ -51,8 +80,107 @@ impl OperationTelemetry {
                 .set_units("s")
                 .set_description("The time it takes to connect to the service, send the request, and get back HTTP status code and headers (including time queued waiting to be sent)")
                 .build(),
+            attempts: meter
+                .create_monotonic_counter("smithy.client.call.attempts")
+                .set_units("{attempt}")
+                .set_description("The number of attempts for an individual operation")
+                .build(),
+            errors: meter
+                .create_monotonic_counter("smithy.client.call.errors")
+                .set_units("{error}")
+                .set_description("The number of errors for an operation, by the `error.type` of the error")
+                .build(),
+            retry_backoff_duration: meter
+                .create_histogram("smithy.client.call.retry_backoff_duration")
+                .set_units("s")
+                .set_description("The time spent waiting between attempts before retrying an operation")
+                .build(),
+            serialization_duration: meter
+                .create_histogram("smithy.client.call.serialization_duration")
+                .set_units("s")
+                .set_description("The time it takes to serialize a request message body")
+                .build(),
+            deserialization_duration: meter
+                .create_histogram("smithy.client.call.deserialization_duration")
+                .set_units("s")
+                .set_description("The time it takes to deserialize a response message body")
+                .build(),
+            resolve_identity_duration: meter
+                .create_histogram("smithy.client.call.auth.resolve_identity_duration")
+                .set_units("s")
+                .set_description("The time it takes to resolve an identity for signing a request, including the identity cache lookup")
+                .build(),
+            // These gauges are recorded by the retry strategy whenever it updates them, so their
+            // callbacks don't observe anything
+            token_bucket_available_permits: meter
+                .create_gauge("smithy.client.retries.token_bucket.available_permits", |_| {})
+                .set_units("{permit}")
+                .set_description("The retry quota remaining in the token bucket of the retry partition")
+                .build(),
+            rate_limiter_fill_rate: meter
+                .create_gauge("smithy.client.retries.rate_limiter.fill_rate", |_| {})
+                .set_units("{token}/s")
+                .set_description("The rate at which the adaptive retry rate limiter is refilled")
+                .build(),
+            circuit_breaker_state: meter
+                .create_gauge("smithy.client.circuit_breaker.state", |_| {})
+                .set_units("{state}")
+                .set_description("The state of a circuit of the circuit breaker: 0 when closed, 1 when open, and 2 when half-open")
+                .build(),
         })
     }
+
+    /// Records the time it took to resolve an identity for `scheme_id`
+    pub(crate) fn record_resolve_identity_duration(
+        &self,
+        scheme_id: &AuthSchemeId,
+        elapsed: Duration,
+        cfg: &ConfigBag,
+    ) {
+        let mut attributes = operation_attributes(cfg).unwrap_or_default();
+        attributes.set(
+            "auth.scheme_id",
+            AttributeValue::String(scheme_id.inner().to_string()),
+        );
+        self.resolve_identity_duration
+            .record(elapsed.as_secs_f64(), Some(&attributes), None);
+    }
+
+    /// Records the state of the retry quota, after the retry strategy has updated it
+    pub(crate) fn record_retry_quota(
+        &self,
+        token_bucket_available_permits: usize,
+        rate_limiter_fill_rate: Option<f64>,
+        cfg: &ConfigBag,
+    ) {
+        let attributes = operation_attributes(cfg);
+        self.token_bucket_available_permits.record(
+            token_bucket_available_permits as f64,
+            attributes.as_ref(),
+            None,
+        );
+        if let Some(fill_rate) = rate_limiter_fill_rate {
+            self.rate_limiter_fill_rate
+                .record(fill_rate, attributes.as_ref(), None);
+        }
+    }
+
+    /// Records the state of the circuit breaker's circuit for `partition`
+    pub(crate) fn record_circuit_breaker_state(
+        &self,
+        partition: &str,
+        state: f64,
+        cfg: &ConfigBag,
+    ) {
+        // The circuit is shared by operations, so it isn't attributed to the operation's method
+        let mut attributes = Attributes::new();
+        if let Some(md) = cfg.load::<Metadata>() {
+            attributes.set("rpc.service", AttributeValue::String(md.service().into()));
+        }
+        attributes.set("circuit", AttributeValue::String(partition.to_string()));
+        self.circuit_breaker_state
+            .record(state, Some(&attributes), None);
+    }
 }
 
 impl Storable for OperationTelemetry {
@@ -76,17 +204,7 @@ impl MetricsInterceptor {
         &self,
         cfg: &aws_smithy_types::config_bag::ConfigBag,
     ) -> Option<Attributes> {
-        let operation_metadata = cfg.load::<Metadata>();
-
-        if let Some(md) = operation_metadata {
-            let mut attributes = Attributes::new();
-            attributes.set("rpc.service", AttributeValue::String(md.service().into()));
-            attributes.set("rpc.method", AttributeValue::String(md.name().into()));
-
-            Some(attributes)
-        } else {
-            None
-        }
+        operation_attributes(cfg)
     }
 
     pub(crate) fn get_measurements_and_instruments<'a>(
@@ -103,6 +221,19 @@ impl MetricsInterceptor {
 
         (measurements, instruments)
     }
+
+    /// Records the time since `start` on `histogram`
+    fn record_elapsed(
+        &self,
+        histogram: &Arc<dyn Histogram>,
+        start: Option<SystemTime>,
+        cfg: &ConfigBag,
+    ) {
+        let elapsed = start.map(|start| self.time_source.now().duration_since(start));
+        if let (Some(Ok(elapsed)), Some(attrs)) = (elapsed, self.get_attrs_from_cfg(cfg)) {
+            histogram.record(elapsed.as_secs_f64(), Some(&attrs), None);
+        }
+    }
 }
 
 impl Intercept for MetricsInterceptor {
@@ -119,6 +250,9 @@ impl Intercept for MetricsInterceptor {
             call_start: self.time_source.now(),
             attempts: 0,
             attempt_start: SystemTime::UNIX_EPOCH,
+            attempt_end: None,
+            serialization_start: None,
+            deserialization_start: None,
         });
 
         Ok(())
@@ -153,20 +287,38 @@ impl Intercept for MetricsInterceptor {
         _runtime_components: &aws_smithy_runtime_api::client::runtime_components::RuntimeComponents,
         cfg: &mut aws_smithy_types::config_bag::ConfigBag,
     ) -> Result<(), aws_smithy_runtime_api::box_error::BoxError> {
+        let attributes = self.get_attrs_from_cfg(cfg);
         let measurements = cfg
             .get_mut::<MeasurementsContainer>()
             .expect("set in `read_before_execution`");
 
         measurements.attempts += 1;
         measurements.attempt_start = self.time_source.now();
+        let backoff_duration = measurements
+            .attempt_end
+            .map(|attempt_end| measurements.attempt_start.duration_since(attempt_end));
+
+        let instruments = cfg
+            .load::<OperationTelemetry>()
+            .expect("set in RuntimePlugin");
+        if let Some(attrs) = attributes {
+            instruments.attempts.add(1, Some(&attrs), None);
+            if let Some(Ok(elapsed)) = backoff_duration {
+                instruments.retry_backoff_duration.record(
+                    elapsed.as_secs_f64(),
+                    Some(&attrs),
+                    None,
+                );
+            }
+        }
 
         Ok(())
     }
 
     fn read_after_attempt(
         &self,
-        _context: &aws_smithy_runtime_api::client::interceptors::context::FinalizerInterceptorContextRef<'_>,
-        _runtime_components: &aws_smithy_runtime_api::client::runtime_components::RuntimeComponents,
+        context: &aws_smithy_runtime_api::client::interceptors::context::FinalizerInterceptorContextRef<'_>,
+        runtime_components: &aws_smithy_runtime_api::client::runtime_components::RuntimeComponents,
         cfg: &mut aws_smithy_types::config_bag::ConfigBag,
     ) -> Result<(), aws_smithy_runtime_api::box_error::BoxError> {
         let (measurements, instruments) = self.get_measurements_and_instruments(cfg);
@@ -175,23 +327,421 @@ impl Intercept for MetricsInterceptor {
         let attempt_duration = attempt_end.duration_since(measurements.attempt_start);
         let attributes = self.get_attrs_from_cfg(cfg);
 
-        if let (Ok(elapsed), Some(mut attrs)) = (attempt_duration, attributes) {
-            attrs.set("attempt", AttributeValue::I64(measurements.attempts.into()));
+        if let Some(mut attrs) = attributes {
+            if let Some(Err(_)) = context.output_or_error() {
+                let mut error_attrs = attrs.clone();
+                let error_type = match run_classifiers_on_ctx(
+                    runtime_components.retry_classifiers(),
+                    context.inner(),
+                ) {
+                    RetryAction::RetryIndicated(RetryReason::RetryableError { kind, .. }) => {
+                        format!("{kind:?}")
+                    }
+                    _ => "Unclassified".to_string(),
+                };
+                error_attrs.set("error.type", AttributeValue::String(error_type));
+                instruments.errors.add(1, Some(&error_attrs), None);
+            }
+
+            if let Ok(elapsed) = attempt_duration {
+                attrs.set("attempt", AttributeValue::I64(measurements.attempts.into()));
 
-            instruments
-                .attempt_duration
-                .record(elapsed.as_secs_f64(), Some(&attrs), None);
+                instruments
+                    .attempt_duration
+                    .record(elapsed.as_secs_f64(), Some(&attrs), None);
+            }
         }
+
+        cfg.get_mut::<MeasurementsContainer>()
+            .expect("set in `read_before_execution`")
+            .attempt_end = Some(attempt_end);
+        Ok(())
+    }
+
+    fn read_before_serialization(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        cfg.get_mut::<MeasurementsContainer>()
+            .expect("set in `read_before_execution`")
+            .serialization_start = Some(self.time_source.now());
+        Ok(())
+    }
+
+    fn read_after_serialization(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let (measurements, instruments) = self.get_measurements_and_instruments(cfg);
+        self.record_elapsed(
+            &instruments.serialization_duration,
+            measurements.serialization_start,
+            cfg,
+        );
+        Ok(())
+    }
+
+    fn read_before_deserialization(
+        &self,
+        _context: &BeforeDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        cfg.get_mut::<MeasurementsContainer>()
+            .expect("set in `read_before_execution`")
+            .deserialization_start = Some(self.time_source.now());
+        Ok(())
+    }
+
+    fn read_after_deserialization(
+        &self,
+        _context: &AfterDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let (measurements, instruments) = self.get_measurements_and_instruments(cfg);
+        self.record_elapsed(
+            &instruments.deserialization_duration,
+            measurements.deserialization_start,
+            cfg,
+        );
         Ok(())
     }
 }
 
-/// Runtime plugin that adds an interceptor for collecting metrics
+/// Returns the `rpc.service` and `rpc.method` attributes for the operation being invoked
+fn operation_attributes(cfg: &ConfigBag) -> Option<Attributes> {
+    let md = cfg.load::<Metadata>()?;
+    let mut attributes = Attributes::new();
+    attributes.set("rpc.service", AttributeValue::String(md.service().into()));
+    attributes.set("rpc.method", AttributeValue::String(md.name().into()));
+    Some(attributes)
+}
+
+/// Whether the trace context of an operation is propagated to the service in request headers
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TraceContextPropagation {
+    /// Trace context is not propagated
+    #[default]
+    Disabled,
+    /// The [W3C `traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header) header
+    /// is added to requests after they're signed
+    W3c,
+}
+
+impl Storable for TraceContextPropagation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// The open spans of the operation being invoked
+#[derive(Debug, Clone)]
+struct OperationSpans {
+    call_start: SystemTime,
+    call: Option<Arc<dyn Span>>,
+    attempt: Option<Arc<dyn Span>>,
+    /// The current serialization, signing, transmit, or deserialization span
+    phase: Option<Arc<dyn Span>>,
+}
+
+impl Storable for OperationSpans {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Ends `span`, with a status based on the outcome of the operation or attempt
+fn end_span(span: &dyn Span, context: &FinalizerInterceptorContextRef<'_>) {
+    match context.output_or_error() {
+        Some(Err(err)) => {
+            span.record_error(err);
+            span.set_status(SpanStatus::Error {
+                description: err.to_string().into(),
+            });
+        }
+        Some(Ok(_)) => span.set_status(SpanStatus::Ok),
+        None => {}
+    }
+    span.end();
+}
+
+#[derive(Debug)]
+pub(crate) struct TracingInterceptor {
+    time_source: SharedTimeSource,
+}
+
+impl TracingInterceptor {
+    pub(crate) fn new(time_source: SharedTimeSource) -> Self {
+        Self { time_source }
+    }
+
+    /// Returns the span for the whole call, starting it if necessary
+    ///
+    /// The call span can't be started in `read_before_execution` since the operation's metadata
+    /// isn't in the config bag yet when client interceptors run, so it's started by the first
+    /// hook that needs it, backdated to when execution began.
+    fn call_span(&self, cfg: &mut ConfigBag) -> Option<Arc<dyn Span>> {
+        let spans = cfg.load::<OperationSpans>()?;
+        if let Some(call) = &spans.call {
+            return Some(call.clone());
+        }
+        let call_start = spans.call_start;
+        let tracer = &cfg.load::<OperationTelemetry>()?.tracer;
+        let name = match cfg.load::<Metadata>() {
+            Some(md) => format!("{}.{}", md.service(), md.name()),
+            None => "call".to_string(),
+        };
+        let mut span = tracer
+            .create_span(name)
+            .set_kind(SpanKind::Client)
+            .set_start_time(call_start);
+        if let Some(attributes) = operation_attributes(cfg) {
+            span = span.set_attributes(attributes);
+        }
+        let span = span.start();
+        cfg.get_mut::<OperationSpans>()?.call = Some(span.clone());
+        Some(span)
+    }
+
+    /// Starts the span for a phase of the current attempt (or of the call, for serialization)
+    fn start_phase(&self, name: &'static str, cfg: &mut ConfigBag) {
+        let Some(call) = self.call_span(cfg) else {
+            return;
+        };
+        let Some(tracer) = cfg.load::<OperationTelemetry>().map(|t| t.tracer.clone()) else {
+            return;
+        };
+        let Some(spans) = cfg.get_mut::<OperationSpans>() else {
+            return;
+        };
+        let parent = spans.attempt.as_ref().unwrap_or(&call).span_context();
+        let span = tracer.create_span(name).set_parent(Some(parent)).start();
+        if let Some(previous) = spans.phase.replace(span) {
+            previous.end();
+        }
+    }
+
+    /// Ends the span for the current phase
+    fn end_phase(&self, cfg: &mut ConfigBag) -> Option<Arc<dyn Span>> {
+        let span = cfg.get_mut::<OperationSpans>()?.phase.take()?;
+        span.set_status(SpanStatus::Ok);
+        span.end();
+        Some(span)
+    }
+}
+
+impl Intercept for TracingInterceptor {
+    fn name(&self) -> &'static str {
+        "TracingInterceptor"
+    }
+
+    fn read_before_execution(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        cfg.interceptor_state().store_put(OperationSpans {
+            call_start: self.time_source.now(),
+            call: None,
+            attempt: None,
+            phase: None,
+        });
+        Ok(())
+    }
+
+    fn read_before_serialization(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.start_phase("serialization", cfg);
+        Ok(())
+    }
+
+    fn read_after_serialization(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.end_phase(cfg);
+        Ok(())
+    }
+
+    fn read_before_attempt(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let Some(call) = self.call_span(cfg) else {
+            return Ok(());
+        };
+        let Some(tracer) = cfg.load::<OperationTelemetry>().map(|t| t.tracer.clone()) else {
+            return Ok(());
+        };
+        let mut attributes = operation_attributes(cfg).unwrap_or_default();
+        if let Some(attempt) = cfg.load::<RequestAttempts>() {
+            attributes.set("attempt", AttributeValue::I64(attempt.attempts().into()));
+            if attempt.attempts() > 1 {
+                let mut event_attributes = Attributes::new();
+                event_attributes.set("attempt", AttributeValue::I64(attempt.attempts().into()));
+                call.add_event("retry".into(), Some(&event_attributes));
+            }
+        }
+        let span = tracer
+            .create_span("attempt")
+            .set_parent(Some(call.span_context()))
+            .set_attributes(attributes)
+            .start();
+        if let Some(spans) = cfg.get_mut::<OperationSpans>() {
+            spans.attempt = Some(span);
+        }
+        Ok(())
+    }
+
+    fn read_before_signing(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.start_phase("signing", cfg);
+        Ok(())
+    }
+
+    fn read_after_signing(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.end_phase(cfg);
+        Ok(())
+    }
+
+    // The header is added after signing so that it isn't signed. Proxies and tracing sidecars
+    // may rewrite it, which would otherwise invalidate the signature.
+    fn modify_before_transmit(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if cfg.load::<TraceContextPropagation>() != Some(&TraceContextPropagation::W3c) {
+            return Ok(());
+        }
+        let traceparent = cfg
+            .load::<OperationSpans>()
+            .and_then(|spans| spans.attempt.as_ref())
+            .and_then(|attempt| attempt.span_context().to_traceparent());
+        if let Some(traceparent) = traceparent {
+            context
+                .request_mut()
+                .headers_mut()
+                .insert("traceparent", traceparent);
+        }
+        Ok(())
+    }
+
+    fn read_before_transmit(
+        &self,
+        _context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.start_phase("transmit", cfg);
+        Ok(())
+    }
+
+    fn read_after_transmit(
+        &self,
+        context: &BeforeDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(span) = self.end_phase(cfg) {
+            span.set_attribute(
+                "http.response.status_code".into(),
+                AttributeValue::I64(context.response().status().as_u16().into()),
+            );
+        }
+        Ok(())
+    }
+
+    fn read_before_deserialization(
+        &self,
+        _context: &BeforeDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.start_phase("deserialization", cfg);
+        Ok(())
+    }
+
+    fn read_after_deserialization(
+        &self,
+        _context: &AfterDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        self.end_phase(cfg);
+        Ok(())
+    }
+
+    fn read_after_attempt(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(spans) = cfg.get_mut::<OperationSpans>() {
+            // a phase span is still open if that phase failed
+            for span in [spans.phase.take(), spans.attempt.take()]
+                .into_iter()
+                .flatten()
+            {
+                end_span(span.as_ref(), context);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_after_execution(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        // start the call span if nothing did, so that calls that fail early are traced
+        self.call_span(cfg);
+        if let Some(spans) = cfg.get_mut::<OperationSpans>() {
+            for span in [spans.phase.take(), spans.attempt.take(), spans.call.take()]
+                .into_iter()
+                .flatten()
+            {
+                end_span(span.as_ref(), context);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runtime plugin that adds interceptors for collecting metrics and traces
+///
+/// Spans are created with the [`Tracer`] of the global telemetry provider for the whole call,
+/// each attempt, and the serialization, signing, transmit, and deserialization phases.
 #[derive(Debug, Default)]
 pub struct MetricsRuntimePlugin {
     scope: &'static str,
     time_source: SharedTimeSource,
     metadata: Option<Metadata>,
+    trace_context_propagation: Option<TraceContextPropagation>,
+    /// Instruments are created once, since the gauges among them can't be unregistered
+    telemetry: OnceLock<OperationTelemetry>,
 }
 
 impl MetricsRuntimePlugin {
@@ -207,15 +757,21 @@ impl RuntimePlugin for MetricsRuntimePlugin {
         _current_components: &RuntimeComponentsBuilder,
     ) -> Cow<'_, RuntimeComponentsBuilder> {
         let interceptor = MetricsInterceptor::new(self.time_source.clone());
+        let components = RuntimeComponentsBuilder::new("Metrics")
+            .with_interceptor(TracingInterceptor::new(self.time_source.clone()));
         if let Ok(interceptor) = interceptor {
-            Cow::Owned(RuntimeComponentsBuilder::new("Metrics").with_interceptor(interceptor))
+            Cow::Owned(components.with_interceptor(interceptor))
         } else {
-            Cow::Owned(RuntimeComponentsBuilder::new("Metrics"))
+            Cow::Owned(components)
         }
     }
 
     fn config(&self) -> Option<FrozenLayer> {
-        let instruments = OperationTelemetry::new(self.scope);
+        let instruments = match self.telemetry.get() {
+            Some(instruments) => Ok(instruments.clone()),
+            None => OperationTelemetry::new(self.scope)
+                .map(|instruments| self.telemetry.get_or_init(|| instruments).clone()),
+        };
 
         if let Ok(instruments) = instruments {
             let mut cfg = Layer::new("Metrics");
@@ -224,6 +780,9 @@ impl RuntimePlugin for MetricsRuntimePlugin {
             if let Some(metadata) = &self.metadata {
                 cfg.store_put(metadata.clone());
             }
+            if let Some(trace_context_propagation) = self.trace_context_propagation {
+                cfg.store_put(trace_context_propagation);
+            }
 
             Some(cfg.freeze())
         } else {
@@ -238,6 +797,7 @@ pub struct MetricsRuntimePluginBuilder {
     scope: Option<&'static str>,
     time_source: Option<SharedTimeSource>,
     metadata: Option<Metadata>,
+    trace_context_propagation: Option<TraceContextPropagation>,
 }
 
 impl MetricsRuntimePluginBuilder {
@@ -262,6 +822,18 @@ impl MetricsRuntimePluginBuilder {
         self
     }
 
+    /// Set whether the trace context is propagated to the service in request headers.
+    ///
+    /// Defaults to [`TraceContextPropagation::Disabled`]. This can also be configured by storing
+    /// a [`TraceContextPropagation`] in the config bag.
+    pub fn with_trace_context_propagation(
+        mut self,
+        trace_context_propagation: TraceContextPropagation,
+    ) -> Self {
+        self.trace_context_propagation = Some(trace_context_propagation);
+        self
+    }
+
     /// Build a [MetricsRuntimePlugin]
     pub fn build(
         self,
@@ -271,6 +843,8 @@ impl MetricsRuntimePluginBuilder {
                 scope,
                 time_source: self.time_source.unwrap_or_default(),
                 metadata: self.metadata,
+                trace_context_propagation: self.trace_context_propagation,
+                telemetry: OnceLock::new(),
             })
         } else {
             Err("Scope is required for MetricsRuntimePlugin.".into())
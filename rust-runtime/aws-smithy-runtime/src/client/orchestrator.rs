@@ -3,8 +3,10 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::client::circuit_breaker;
 use crate::client::interceptors::Interceptors;
 use crate::client::orchestrator::http::{log_response_body, read_body};
+use crate::client::retries::hedging;
 use crate::client::timeout::{MaybeTimeout, MaybeTimeoutConfig, TimeoutKind};
 use crate::client::{
     http::body::minimum_throughput::MaybeUploadThroughputCheckFuture,
@@ -13,7 +15,7 @@ use crate::client::{
 use auth::{resolve_identity, sign_request};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_runtime_api::box_error::BoxError;
-use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnector, HttpConnectorSettings};
+use aws_smithy_runtime_api::client::http::{HttpClient, HttpConnectorSettings};
 use aws_smithy_runtime_api::client::interceptors::context::{
     Error, Input, InterceptorContext, Output, RewindResult,
 };
@@ -393,6 +395,9 @@ async fn try_attempt(
         return;
     }
 
+    // Only requests that are actually sent, unlike presigned ones, go through the circuit breaker
+    halt_on_err!([ctx] => circuit_breaker::admit(ctx, runtime_components, cfg).map_err(|err| OrchestratorError::circuit_open(err.into())));
+
     // The connection consumes the request but we need to keep a copy of it
     // within the interceptor context, so we clone it here.
     ctx.enter_transmit_phase();
@@ -411,10 +416,11 @@ async fn try_attempt(
             builder.build()
         };
         let connector = http_client.http_connector(&settings, runtime_components);
+        let connector_future = hedging::transmit(connector, request, runtime_components, cfg);
         let response_future = MaybeUploadThroughputCheckFuture::new(
             cfg,
             runtime_components,
-            connector.call(request),
+            connector_future,
         );
         response_future.await.map_err(OrchestratorError::connector)
     });
@@ -428,6 +434,14 @@ async fn try_attempt(
         read_before_deserialization(ctx, runtime_components, cfg);
     });
 
+    // Decompression is applied after the interceptors so that body wrappers they install,
+    // like checksum validation, observe the response body as it was sent over the wire.
+    #[cfg(feature = "response-decompression")]
+    crate::client::http::body::response_decompression::decompress_response_body(
+        ctx.response_mut().expect("set during transmit"),
+        cfg,
+    );
+
     ctx.enter_deserialization_phase();
     let output_or_error = async {
         let response = ctx.response_mut().expect("set during transmit");
@@ -486,13 +500,19 @@ async fn finally_op(
 #[cfg(all(test, any(feature = "test-util", feature = "legacy-test-util")))]
 mod tests {
     use crate::client::auth::no_auth::{NoAuthRuntimePlugin, NO_AUTH_SCHEME_ID};
+    use crate::client::circuit_breaker::{
+        CircuitBreaker, CircuitBreakerConfig, CircuitBreakerPartition, CircuitBreakerRuntimePlugin,
+        CircuitState,
+    };
     use crate::client::orchestrator::endpoints::StaticUriEndpointResolver;
     use crate::client::orchestrator::{invoke, invoke_with_stop_point, StopPoint};
+    use crate::client::retries::classifiers::HttpStatusCodeClassifier;
     use crate::client::retries::strategy::NeverRetryStrategy;
     use crate::client::test_util::{
         deserializer::CannedResponseDeserializer, serializer::CannedRequestSerializer,
     };
-    use aws_smithy_http_client::test_util::NeverClient;
+    use aws_smithy_async::test_util::ManualTimeSource;
+    use aws_smithy_http_client::test_util::{infallible_client_fn, NeverClient};
     use aws_smithy_runtime_api::box_error::BoxError;
     use aws_smithy_runtime_api::client::auth::static_resolver::StaticAuthSchemeOptionResolver;
     use aws_smithy_runtime_api::client::auth::{
@@ -517,7 +537,9 @@ mod tests {
     use aws_smithy_runtime_api::client::runtime_components::{
         RuntimeComponents, RuntimeComponentsBuilder,
     };
-    use aws_smithy_runtime_api::client::runtime_plugin::{RuntimePlugin, RuntimePlugins};
+    use aws_smithy_runtime_api::client::runtime_plugin::{
+        RuntimePlugin, RuntimePlugins, StaticRuntimePlugin,
+    };
     use aws_smithy_runtime_api::client::ser_de::{
         SharedRequestSerializer, SharedResponseDeserializer,
     };
@@ -528,7 +550,8 @@ mod tests {
     use http_02x::{Response, StatusCode};
     use std::borrow::Cow;
     use std::sync::atomic::{AtomicBool, Ordering};
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, UNIX_EPOCH};
     use tracing_test::traced_test;
 
     fn new_request_serializer() -> CannedRequestSerializer {
@@ -1258,6 +1281,81 @@ mod tests {
         assert!(context.response().is_none());
     }
 
+    /// Presigning stops before transmit, so it shouldn't use the circuit breaker's admissions
+    #[tokio::test]
+    async fn test_stop_points_dont_use_circuit_breaker_admissions() {
+        let time_source = ManualTimeSource::new(UNIX_EPOCH);
+        let circuit_breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::builder()
+                .partition(CircuitBreakerPartition::Endpoint)
+                .consecutive_failures(1)
+                .half_open_requests(1)
+                .open_duration(Duration::from_secs(10))
+                .build(),
+        );
+        let statuses = Arc::new(Mutex::new(vec![200, 503]));
+        let runtime_plugins = || {
+            let statuses = statuses.clone();
+            RuntimePlugins::new()
+                .with_operation_plugin(TestOperationRuntimePlugin::new())
+                .with_operation_plugin(NoAuthRuntimePlugin::new())
+                .with_operation_plugin(CircuitBreakerRuntimePlugin::new(circuit_breaker.clone()))
+                .with_operation_plugin(
+                    StaticRuntimePlugin::new().with_runtime_components(
+                        RuntimeComponentsBuilder::new("test")
+                            .with_time_source(Some(time_source.clone()))
+                            .with_retry_classifier(HttpStatusCodeClassifier::default())
+                            .with_http_client(Some(infallible_client_fn(move |_request| {
+                                let status = statuses.lock().unwrap().pop().expect("a status");
+                                http_1x::Response::builder()
+                                    .status(status)
+                                    .body(SdkBody::empty())
+                                    .unwrap()
+                            }))),
+                    ),
+                )
+        };
+        let partition = "localhost:8080";
+
+        invoke_with_stop_point(
+            "test",
+            "test",
+            Input::doesnt_matter(),
+            &runtime_plugins(),
+            StopPoint::None,
+        )
+        .await
+        .expect("the canned deserializer succeeds");
+        assert_eq!(CircuitState::Open, circuit_breaker.state(partition));
+
+        time_source.advance(Duration::from_secs(10));
+        for _ in 0..2 {
+            invoke_with_stop_point(
+                "test",
+                "test",
+                Input::doesnt_matter(),
+                &runtime_plugins(),
+                StopPoint::BeforeTransmit,
+            )
+            .await
+            .expect("presigning isn't affected by the circuit breaker");
+        }
+        assert_eq!(CircuitState::Open, circuit_breaker.state(partition));
+
+        // the probe is still available to a request that's sent
+        invoke_with_stop_point(
+            "test",
+            "test",
+            Input::doesnt_matter(),
+            &runtime_plugins(),
+            StopPoint::None,
+        )
+        .await
+        .expect("the probe is admitted");
+        assert_eq!(CircuitState::Closed, circuit_breaker.state(partition));
+        assert!(statuses.lock().unwrap().is_empty());
+    }
+
     /// The "finally" interceptors should run upon error when the StopPoint is set to BeforeTransmit
     #[tokio::test]
     async fn test_stop_points_error_handling() {
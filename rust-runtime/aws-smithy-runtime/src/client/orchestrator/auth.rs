@@ -5,6 +5,7 @@
 
 use crate::client::auth::no_auth::NO_AUTH_SCHEME_ID;
 use crate::client::identity::IdentityCache;
+use crate::client::metrics::OperationTelemetry;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::auth::{
     AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, AuthSchemeOption,
@@ -158,9 +159,21 @@ pub(super) async fn resolve_identity(
                         if let Some(properties) = auth_scheme_option.properties() {
                             cfg.push_shared_layer(properties);
                         }
+                        // Only timed when metrics are enabled
+                        let time_source = runtime_components
+                            .time_source()
+                            .filter(|_| cfg.load::<OperationTelemetry>().is_some());
+                        let resolve_start = time_source.as_ref().map(|ts| ts.now());
                         let identity = identity_cache
                             .resolve_cached_identity(identity_resolver, runtime_components, cfg)
                             .await?;
+                        if let (Some(telemetry), Some(time_source), Some(resolve_start)) =
+                            (cfg.load::<OperationTelemetry>(), time_source, resolve_start)
+                        {
+                            if let Ok(elapsed) = time_source.now().duration_since(resolve_start) {
+                                telemetry.record_resolve_identity_duration(scheme_id, elapsed, cfg);
+                            }
+                        }
                         trace!(identity = ?identity, "resolved identity");
                         // Extract the FrozenLayer placed in the Identity property bag by the From<Credentials> impl.
                         // This layer contains feature data for the user agent and potentially other metadata.
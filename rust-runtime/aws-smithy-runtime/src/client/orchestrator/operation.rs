@@ -4,11 +4,13 @@
  */
 
 use crate::client::auth::no_auth::{NoAuthScheme, NO_AUTH_SCHEME_ID};
+use crate::client::circuit_breaker::{CircuitBreaker, CircuitBreakerRuntimePlugin};
 use crate::client::defaults::{default_plugins, DefaultPluginParams};
 use crate::client::http::connection_poisoning::ConnectionPoisoningInterceptor;
 use crate::client::identity::no_auth::NoAuthIdentityResolver;
 use crate::client::identity::IdentityCache;
 use crate::client::orchestrator::endpoints::StaticUriEndpointResolver;
+use crate::client::retries::hedging::{HedgingConfig, IdempotentOperation};
 use crate::client::retries::strategy::{NeverRetryStrategy, StandardRetryStrategy};
 use aws_smithy_async::rt::sleep::AsyncSleep;
 use aws_smithy_async::time::TimeSource;
@@ -245,6 +247,24 @@ impl<I, O, E> OperationBuilder<I, O, E> {
         self
     }
 
+    /// Enables hedged requests with the given config. Only operations marked
+    /// [idempotent](Self::idempotent) are hedged.
+    pub fn hedging(mut self, hedging_config: HedgingConfig) -> Self {
+        self.config.store_put(hedging_config);
+        self
+    }
+
+    /// Enables the given circuit breaker for the operation.
+    pub fn circuit_breaker(self, circuit_breaker: CircuitBreaker) -> Self {
+        self.runtime_plugin(CircuitBreakerRuntimePlugin::new(circuit_breaker))
+    }
+
+    /// Marks the operation as idempotent, which allows its requests to be hedged.
+    pub fn idempotent(mut self) -> Self {
+        self.config.store_put(IdempotentOperation);
+        self
+    }
+
     /// Configures the timeout configuration for the builder.
     pub fn timeout_config(mut self, timeout_config: TimeoutConfig) -> Self {
         self.config.store_put(timeout_config);
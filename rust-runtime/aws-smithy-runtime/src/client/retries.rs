@@ -9,7 +9,10 @@ pub mod classifiers;
 /// Smithy retry strategies.
 pub mod strategy;
 
+pub mod hedging;
+
 mod client_rate_limiter;
+mod retry_budget;
 mod token_bucket;
 
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
@@ -18,6 +21,7 @@ use std::fmt;
 pub use client_rate_limiter::{
     ClientRateLimiter, ClientRateLimiterBuilder, ClientRateLimiterPartition,
 };
+pub use retry_budget::{RetryBudget, RetryBudgetBuilder};
 pub use token_bucket::{TokenBucket, TokenBucketBuilder};
 
 use std::borrow::Cow;
@@ -25,16 +29,16 @@ use std::borrow::Cow;
 /// Represents the retry partition, e.g. an endpoint, a region
 ///
 /// A retry partition created with [`RetryPartition::new`] uses built-in
-/// token bucket and rate limiter settings, with no option for customization.
-/// Default partitions with the same name share the same token bucket
-/// and client rate limiter.
+/// token bucket, rate limiter, and retry budget settings, with no option for customization.
+/// Default partitions with the same name share the same token bucket,
+/// client rate limiter, and retry budget.
 ///
 /// To customize these components, use a custom retry partition via [`RetryPartition::custom`].
-/// A custom partition owns its token bucket and rate limiter, which:
+/// A custom partition owns its token bucket, rate limiter, and retry budget, which:
 /// - Are independent from those in any default partition.
 /// - Are not shared with other custom partitions, even if they have the same name.
 ///
-/// To share a token bucket and rate limiter among custom partitions,
+/// To share these components among custom partitions,
 /// either clone the custom partition itself or clone these components
 /// beforehand and pass them to each custom partition.
 #[non_exhaustive]
@@ -50,6 +54,7 @@ pub(crate) enum RetryPartitionInner {
         name: Cow<'static, str>,
         token_bucket: TokenBucket,
         client_rate_limiter: ClientRateLimiter,
+        retry_budget: RetryBudget,
     },
 }
 
@@ -67,6 +72,7 @@ impl RetryPartition {
             name: name.into(),
             token_bucket: None,
             client_rate_limiter: None,
+            retry_budget: None,
         }
     }
 
@@ -128,6 +134,7 @@ pub struct RetryPartitionBuilder {
     name: Cow<'static, str>,
     token_bucket: Option<TokenBucket>,
     client_rate_limiter: Option<ClientRateLimiter>,
+    retry_budget: Option<RetryBudget>,
 }
 
 impl RetryPartitionBuilder {
@@ -143,6 +150,12 @@ impl RetryPartitionBuilder {
         self
     }
 
+    /// Sets the retry budget used by the [budget retry mode](aws_smithy_types::retry::RetryMode::Budget).
+    pub fn retry_budget(mut self, retry_budget: RetryBudget) -> Self {
+        self.retry_budget = Some(retry_budget);
+        self
+    }
+
     /// Builds the custom retry partition.
     pub fn build(self) -> RetryPartition {
         RetryPartition {
@@ -150,6 +163,7 @@ impl RetryPartitionBuilder {
                 name: self.name,
                 token_bucket: self.token_bucket.unwrap_or_default(),
                 client_rate_limiter: self.client_rate_limiter.unwrap_or_default(),
+                retry_budget: self.retry_budget.unwrap_or_default(),
             },
         }
     }
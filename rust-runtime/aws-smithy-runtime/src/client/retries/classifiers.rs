@@ -3,14 +3,18 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
 use aws_smithy_runtime_api::client::retries::classifiers::{
-    ClassifyRetry, RetryAction, RetryClassifierPriority, SharedRetryClassifier,
+    ClassifyRetry, RetryAction, RetryClassifierPriority, RetryReason, SharedRetryClassifier,
 };
-use aws_smithy_types::retry::ProvideErrorKind;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind};
+use aws_smithy_types::DateTime;
 use std::borrow::Cow;
 use std::error::Error as StdError;
 use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
 
 /// A retry classifier for checking if an error is modeled as retryable.
 #[derive(Debug, Default)]
@@ -176,9 +180,113 @@ impl ClassifyRetry for HttpStatusCodeClassifier {
     }
 }
 
+const RETRY_AFTER_STATUS_CODES: &[u16] = &[429, 503];
+
+/// A retry classifier that honors the standard HTTP
+/// [`Retry-After`](https://www.rfc-editor.org/rfc/rfc9110#field.retry-after) header of
+/// 429 (Too Many Requests) and 503 (Service Unavailable) responses.
+///
+/// The delay the header specifies, either in seconds or as an HTTP date, is added to the
+/// classification of the classifiers that ran before this one, keeping the kind of error they
+/// chose. Responses that no earlier classifier found retryable are classified as throttling
+/// errors (429) or transient errors (503). The retry strategy never waits longer than its max
+/// backoff, even if the server asks it to.
+///
+/// An HTTP date is compared to the `Date` header of the response, so that the delay isn't
+/// affected by clock skew, or to the current time if the response has no `Date` header.
+#[derive(Debug, Default)]
+pub struct RetryAfterClassifier {
+    time_source: SharedTimeSource,
+}
+
+impl RetryAfterClassifier {
+    /// Create a new `RetryAfterClassifier` that gets the current time from the system clock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `RetryAfterClassifier` that gets the current time from `time_source`.
+    pub fn with_time_source(time_source: impl TimeSource + 'static) -> Self {
+        Self {
+            time_source: SharedTimeSource::new(time_source),
+        }
+    }
+
+    /// Return the priority of this retry classifier.
+    ///
+    /// This classifier runs after the other built-in classifiers, so that the delay requested by
+    /// the server applies to errors they classify as retryable too.
+    pub fn priority() -> RetryClassifierPriority {
+        RetryClassifierPriority::run_after(RetryClassifierPriority::transient_error_classifier())
+    }
+}
+
+impl ClassifyRetry for RetryAfterClassifier {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> RetryAction {
+        self.classify_retry_with_previous(ctx, &RetryAction::NoActionIndicated)
+    }
+
+    fn classify_retry_with_previous(
+        &self,
+        ctx: &InterceptorContext,
+        previous: &RetryAction,
+    ) -> RetryAction {
+        if !ctx.is_failed() {
+            return RetryAction::NoActionIndicated;
+        }
+        let Some(response) = ctx.response() else {
+            return RetryAction::NoActionIndicated;
+        };
+        let status = response.status().as_u16();
+        if !RETRY_AFTER_STATUS_CODES.contains(&status) {
+            return RetryAction::NoActionIndicated;
+        }
+        let headers = response.headers();
+        let Some(delay) = headers.get("retry-after").and_then(|retry_after| {
+            parse_retry_after(retry_after, headers.get("date"), &self.time_source)
+        }) else {
+            return RetryAction::NoActionIndicated;
+        };
+        let kind = match previous {
+            RetryAction::RetryIndicated(RetryReason::RetryableError { kind, .. }) => *kind,
+            _ if status == 429 => ErrorKind::ThrottlingError,
+            _ => ErrorKind::TransientError,
+        };
+        RetryAction::retryable_error_with_explicit_delay(kind, delay)
+    }
+
+    fn name(&self) -> &'static str {
+        "Retry-After"
+    }
+
+    fn priority(&self) -> RetryClassifierPriority {
+        Self::priority()
+    }
+}
+
+/// Parses a `Retry-After` header value in delta-seconds or HTTP date form
+fn parse_retry_after(
+    retry_after: &str,
+    date: Option<&str>,
+    time_source: &SharedTimeSource,
+) -> Option<Duration> {
+    let retry_after = retry_after.trim();
+    if let Ok(seconds) = retry_after.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let retry_at =
+        SystemTime::try_from(DateTime::from_str(retry_after, Format::HttpDate).ok()?).ok()?;
+    let now = date
+        .and_then(|date| DateTime::from_str(date.trim(), Format::HttpDate).ok())
+        .and_then(|date| SystemTime::try_from(date).ok())
+        .unwrap_or_else(|| time_source.now());
+    // A date in the past means the request can be retried immediately
+    Some(retry_at.duration_since(now).unwrap_or_default())
+}
+
 /// Given an iterator of retry classifiers and an interceptor context, run retry classifiers on the
-/// context. Each classifier is passed the classification result from the previous classifier (the
-/// 'root' classifier is passed `None`.)
+/// context. Each classifier is passed the classification result from the previous classifiers (the
+/// 'root' classifier is passed `RetryAction::NoActionIndicated`.)
 pub fn run_classifiers_on_ctx(
     classifiers: impl Iterator<Item = SharedRetryClassifier>,
     ctx: &InterceptorContext,
@@ -187,7 +295,7 @@ pub fn run_classifiers_on_ctx(
     let mut result = RetryAction::NoActionIndicated;
 
     for classifier in classifiers {
-        let new_result = classifier.classify_retry(ctx);
+        let new_result = classifier.classify_retry_with_previous(ctx, &result);
 
         // If the result is `NoActionIndicated`, continue to the next classifier
         // without overriding any previously-set result.
@@ -216,14 +324,19 @@ pub fn run_classifiers_on_ctx(
 #[cfg(test)]
 mod test {
     use crate::client::retries::classifiers::{
-        HttpStatusCodeClassifier, ModeledAsRetryableClassifier,
+        run_classifiers_on_ctx, HttpStatusCodeClassifier, ModeledAsRetryableClassifier,
+        RetryAfterClassifier,
     };
+    use aws_smithy_async::time::StaticTimeSource;
     use aws_smithy_runtime_api::client::interceptors::context::{Error, Input, InterceptorContext};
     use aws_smithy_runtime_api::client::orchestrator::OrchestratorError;
-    use aws_smithy_runtime_api::client::retries::classifiers::{ClassifyRetry, RetryAction};
+    use aws_smithy_runtime_api::client::retries::classifiers::{
+        ClassifyRetry, RetryAction, SharedRetryClassifier,
+    };
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::retry::{ErrorKind, ProvideErrorKind};
     use std::fmt;
+    use std::time::Duration;
 
     use super::TransientErrorClassifier;
 
@@ -316,4 +429,101 @@ mod test {
         )));
         assert_eq!(policy.classify_retry(&ctx), RetryAction::transient_error(),);
     }
+
+    fn retry_after_ctx(status: u16, headers: &[(&str, &str)]) -> InterceptorContext {
+        let mut response = http_1x::Response::builder().status(status);
+        for (name, value) in headers {
+            response = response.header(*name, *value);
+        }
+        let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+        ctx.set_response(response.body(SdkBody::empty()).unwrap().try_into().unwrap());
+        ctx.set_output_or_error(Err(OrchestratorError::other("error")));
+        ctx
+    }
+
+    #[test]
+    fn classify_retry_after_delta_seconds() {
+        let policy = RetryAfterClassifier::new();
+        let ctx = retry_after_ctx(429, &[("retry-after", "5")]);
+        assert_eq!(
+            policy.classify_retry(&ctx),
+            RetryAction::retryable_error_with_explicit_delay(
+                ErrorKind::ThrottlingError,
+                Duration::from_secs(5)
+            )
+        );
+    }
+
+    #[test]
+    fn classify_retry_after_http_date() {
+        let policy = RetryAfterClassifier::new();
+        let ctx = retry_after_ctx(
+            503,
+            &[
+                ("retry-after", "Wed, 21 Oct 2015 07:28:30 GMT"),
+                ("date", "Wed, 21 Oct 2015 07:28:00 GMT"),
+            ],
+        );
+        assert_eq!(
+            policy.classify_retry(&ctx),
+            RetryAction::retryable_error_with_explicit_delay(
+                ErrorKind::TransientError,
+                Duration::from_secs(30)
+            )
+        );
+
+        // without a `Date` header, the delay is relative to the current time, and dates in the
+        // past allow an immediate retry
+        let policy = RetryAfterClassifier::with_time_source(StaticTimeSource::from_secs(
+            1445412540, // 07:29:00
+        ));
+        let ctx = retry_after_ctx(503, &[("retry-after", "Wed, 21 Oct 2015 07:28:30 GMT")]);
+        assert_eq!(
+            policy.classify_retry(&ctx),
+            RetryAction::retryable_error_with_explicit_delay(
+                ErrorKind::TransientError,
+                Duration::ZERO
+            )
+        );
+    }
+
+    #[test]
+    fn retry_after_is_ignored_unless_valid_on_429_or_503() {
+        let policy = RetryAfterClassifier::new();
+        for ctx in [
+            retry_after_ctx(500, &[("retry-after", "5")]),
+            retry_after_ctx(429, &[("retry-after", "soon")]),
+            retry_after_ctx(429, &[]),
+        ] {
+            assert_eq!(policy.classify_retry(&ctx), RetryAction::NoActionIndicated);
+        }
+    }
+
+    #[test]
+    fn retry_after_keeps_the_earlier_error_kind() {
+        let policy = RetryAfterClassifier::new();
+        let ctx = retry_after_ctx(429, &[("retry-after", "5")]);
+        assert_eq!(
+            policy.classify_retry_with_previous(&ctx, &RetryAction::server_error()),
+            RetryAction::retryable_error_with_explicit_delay(
+                ErrorKind::ServerError,
+                Duration::from_secs(5)
+            )
+        );
+
+        // a 503 is classified as transient by the status code classifier, which the explicit
+        // delay doesn't change
+        let ctx = retry_after_ctx(503, &[("retry-after", "5")]);
+        let classifiers = [
+            SharedRetryClassifier::new(HttpStatusCodeClassifier::default()),
+            SharedRetryClassifier::new(RetryAfterClassifier::new()),
+        ];
+        assert_eq!(
+            run_classifiers_on_ctx(classifiers.into_iter(), &ctx),
+            RetryAction::retryable_error_with_explicit_delay(
+                ErrorKind::TransientError,
+                Duration::from_secs(5)
+            )
+        );
+    }
 }
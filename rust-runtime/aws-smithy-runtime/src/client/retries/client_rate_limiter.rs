@@ -98,6 +98,11 @@ impl ClientRateLimiter {
         ClientRateLimiterBuilder::new()
     }
 
+    /// The rate at which the token bucket is refilled, in tokens per second
+    pub(crate) fn fill_rate(&self) -> f64 {
+        self.inner.lock().unwrap().fill_rate
+    }
+
     pub(crate) fn acquire_permission_to_send_a_request(
         &self,
         seconds_since_unix_epoch: f64,
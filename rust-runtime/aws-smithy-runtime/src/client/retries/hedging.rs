@@ -0,0 +1,378 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Hedged requests for idempotent operations.
+//!
+//! The retry strategy only sends another attempt after one fails, so a slow but otherwise
+//! healthy attempt isn't retried until it times out. With hedging enabled, the orchestrator
+//! sends a second copy of the request to an [idempotent](IdempotentOperation) operation when no
+//! response has arrived after a percentile of recently observed latencies, and takes whichever
+//! response succeeds first. The other request is canceled.
+//!
+//! Each hedge spends the retry cost from the retry [`TokenBucket`], which is only refilled by
+//! successful requests, so hedging backs off when a service is failing instead of amplifying the
+//! load on it.
+
+use crate::client::retries::TokenBucket;
+use aws_smithy_async::rt::sleep::{AsyncSleep, SharedAsyncSleep};
+use aws_smithy_async::time::SharedTimeSource;
+use aws_smithy_runtime_api::client::http::{
+    HttpConnector, HttpConnectorFuture, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+const DEFAULT_PERCENTILE: f64 = 95.0;
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+/// The number of most recent latencies the hedge delay is computed from
+const LATENCY_WINDOW: usize = 128;
+/// The number of latencies that must be observed before they're used for the hedge delay
+const MIN_LATENCY_SAMPLES: usize = 10;
+
+/// Marks an operation as safe to send more than once, which is required for it to be hedged.
+///
+/// This should be stored in the config bag of operations modeled with the `@idempotent` or
+/// `@readonly` traits.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IdempotentOperation;
+
+impl Storable for IdempotentOperation {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Configuration for hedging requests to idempotent operations.
+///
+/// The latencies observed by an operation are shared with every clone of its config, so a config
+/// should be set on a client rather than on each operation.
+#[derive(Clone, Debug)]
+pub struct HedgingConfig {
+    percentile: f64,
+    initial_delay: Duration,
+    latencies: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl Storable for HedgingConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self {
+            percentile: DEFAULT_PERCENTILE,
+            initial_delay: DEFAULT_INITIAL_DELAY,
+            latencies: Default::default(),
+        }
+    }
+}
+
+impl HedgingConfig {
+    /// Creates a builder for constructing a `HedgingConfig`.
+    pub fn builder() -> HedgingConfigBuilder {
+        HedgingConfigBuilder::default()
+    }
+
+    /// The percentile of recent response latencies after which a hedge is sent.
+    pub fn percentile(&self) -> f64 {
+        self.percentile
+    }
+
+    /// The delay before a hedge is sent until enough response latencies have been observed.
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Returns the delay after which a request is hedged
+    pub(crate) fn hedge_delay(&self) -> Duration {
+        let latencies = self.latencies.lock().unwrap();
+        if latencies.len() < MIN_LATENCY_SAMPLES {
+            return self.initial_delay;
+        }
+        let mut sorted = latencies.iter().copied().collect::<Vec<_>>();
+        sorted.sort();
+        // nearest-rank percentile
+        let rank = (self.percentile / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.clamp(1, sorted.len()) - 1]
+    }
+
+    /// Records the latency of a response
+    pub(crate) fn record_latency(&self, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap();
+        if latencies.len() == LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
+/// Builder for constructing a `HedgingConfig`.
+#[derive(Clone, Debug, Default)]
+pub struct HedgingConfigBuilder {
+    percentile: Option<f64>,
+    initial_delay: Option<Duration>,
+}
+
+impl HedgingConfigBuilder {
+    /// Creates a new `HedgingConfigBuilder` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the percentile, greater than 0 and at most 100, of recent response latencies after
+    /// which a hedge is sent. Defaults to 95.
+    ///
+    /// [`build`](Self::build) fails if the percentile is out of range.
+    pub fn percentile(mut self, percentile: f64) -> Self {
+        self.percentile = Some(percentile);
+        self
+    }
+
+    /// Sets the delay before a hedge is sent until enough response latencies have been
+    /// observed to compute the percentile. Defaults to one second.
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = Some(initial_delay);
+        self
+    }
+
+    /// Builds a `HedgingConfig`, otherwise returns a [`HedgingConfigBuilderError`] if the
+    /// percentile is out of range.
+    pub fn build(self) -> Result<HedgingConfig, HedgingConfigBuilderError> {
+        let percentile = self.percentile.unwrap_or(DEFAULT_PERCENTILE);
+        // Written so that NaN is rejected too
+        if !(percentile > 0.0 && percentile <= 100.0) {
+            return Err(ErrorKind::InvalidPercentile(percentile).into());
+        }
+        Ok(HedgingConfig {
+            percentile,
+            initial_delay: self.initial_delay.unwrap_or(DEFAULT_INITIAL_DELAY),
+            latencies: Default::default(),
+        })
+    }
+}
+
+#[derive(Debug)]
+enum ErrorKind {
+    InvalidPercentile(f64),
+}
+
+impl From<ErrorKind> for HedgingConfigBuilderError {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// The error type returned when failing to build a [`HedgingConfig`] from the builder
+#[derive(Debug)]
+pub struct HedgingConfigBuilderError {
+    kind: ErrorKind,
+}
+
+impl fmt::Display for HedgingConfigBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::InvalidPercentile(percentile) => write!(
+                f,
+                "percentile must be greater than 0 and at most 100, but was {percentile}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HedgingConfigBuilderError {}
+
+/// Sends the request with the connector, hedging it if hedging is configured and applies to it
+pub(crate) fn transmit(
+    connector: SharedHttpConnector,
+    request: HttpRequest,
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+) -> HttpConnectorFuture {
+    match Hedge::new(&request, runtime_components, cfg) {
+        Some(hedge) => HttpConnectorFuture::new(hedge.transmit(connector, request)),
+        None => connector.call(request),
+    }
+}
+
+struct Hedge {
+    config: HedgingConfig,
+    token_bucket: TokenBucket,
+    sleep_impl: SharedAsyncSleep,
+    time_source: SharedTimeSource,
+    request: HttpRequest,
+}
+
+impl Hedge {
+    fn new(
+        request: &HttpRequest,
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Option<Self> {
+        let config = cfg.load::<HedgingConfig>()?.clone();
+        if cfg.load::<IdempotentOperation>().is_none() {
+            debug!("not hedging the request because the operation isn't idempotent");
+            return None;
+        }
+        let Some(token_bucket) = cfg.load::<TokenBucket>().cloned() else {
+            debug!("not hedging the request because there's no retry token bucket to charge");
+            return None;
+        };
+        let (Some(sleep_impl), Some(time_source)) = (
+            runtime_components.sleep_impl(),
+            runtime_components.time_source(),
+        ) else {
+            debug!("not hedging the request because no sleep impl or time source was set");
+            return None;
+        };
+        let Some(request) = request.try_clone() else {
+            debug!("not hedging the request because its body can't be cloned");
+            return None;
+        };
+        Some(Self {
+            config,
+            token_bucket,
+            sleep_impl,
+            time_source,
+            request,
+        })
+    }
+
+    async fn transmit(
+        self,
+        connector: SharedHttpConnector,
+        request: HttpRequest,
+    ) -> Result<HttpResponse, ConnectorError> {
+        let Hedge {
+            config,
+            token_bucket,
+            sleep_impl,
+            time_source,
+            request: hedge_request,
+        } = self;
+        let record_latency = |sent_at: SystemTime, result: &Result<_, _>| {
+            if result.is_ok() {
+                if let Ok(latency) = time_source.now().duration_since(sent_at) {
+                    config.record_latency(latency);
+                }
+            }
+        };
+
+        let sent_at = time_source.now();
+        let mut primary = pin!(connector.call(request));
+        let delay = config.hedge_delay();
+        let mut sleep = pin!(sleep_impl.sleep(delay));
+        let early_result = poll_fn(|cx| match primary.as_mut().poll(cx) {
+            Poll::Ready(result) => Poll::Ready(Some(result)),
+            Poll::Pending => sleep.as_mut().poll(cx).map(|_| None),
+        })
+        .await;
+        if let Some(result) = early_result {
+            record_latency(sent_at, &result);
+            return result;
+        }
+
+        let Some(permit) = token_bucket.acquire_hedge() else {
+            debug!("not hedging the request after {delay:?} because not enough retry quota is available");
+            let result = primary.await;
+            record_latency(sent_at, &result);
+            return result;
+        };
+        // The quota is spent whether or not the hedge wins, and is refilled by successful requests
+        permit.forget();
+
+        debug!("no response after {delay:?}; hedging the request");
+        let hedged_at = time_source.now();
+        let mut hedge = pin!(connector.call(hedge_request));
+        let (mut primary_error, mut hedge_error) = (None, None);
+        // The first successful response wins, and the other request is canceled when dropped
+        let (result, sent_at) = poll_fn(|cx| {
+            if primary_error.is_none() {
+                match primary.as_mut().poll(cx) {
+                    Poll::Ready(Ok(response)) => return Poll::Ready((Ok(response), sent_at)),
+                    Poll::Ready(Err(err)) => primary_error = Some(err),
+                    Poll::Pending => {}
+                }
+            }
+            if hedge_error.is_none() {
+                match hedge.as_mut().poll(cx) {
+                    Poll::Ready(Ok(response)) => {
+                        debug!("the hedged request completed first");
+                        return Poll::Ready((Ok(response), hedged_at));
+                    }
+                    Poll::Ready(Err(err)) => hedge_error = Some(err),
+                    Poll::Pending => {}
+                }
+            }
+            match (primary_error.take(), hedge_error.is_some()) {
+                (Some(err), true) => Poll::Ready((Err(err), sent_at)),
+                (primary, _) => {
+                    primary_error = primary;
+                    Poll::Pending
+                }
+            }
+        })
+        .await;
+        record_latency(sent_at, &result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HedgingConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn hedge_delay_is_a_percentile_of_recent_latencies() {
+        let config = HedgingConfig::builder()
+            .percentile(90.0)
+            .initial_delay(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        for millis in 1..10 {
+            config.record_latency(Duration::from_millis(millis));
+        }
+        // not enough latencies have been observed yet
+        assert_eq!(Duration::from_millis(500), config.hedge_delay());
+
+        config.record_latency(Duration::from_millis(10));
+        assert_eq!(Duration::from_millis(9), config.hedge_delay());
+
+        // only the most recent latencies are used
+        for _ in 0..128 {
+            config.record_latency(Duration::from_millis(100));
+        }
+        assert_eq!(Duration::from_millis(100), config.hedge_delay());
+
+        // clones share their latencies
+        let clone = config.clone();
+        for _ in 0..128 {
+            clone.record_latency(Duration::from_millis(20));
+        }
+        assert_eq!(Duration::from_millis(20), config.hedge_delay());
+    }
+
+    #[test]
+    fn percentile_must_be_in_range() {
+        for percentile in [0.0, -1.0, 100.5, f64::NAN] {
+            let error = HedgingConfig::builder()
+                .percentile(percentile)
+                .build()
+                .unwrap_err();
+            assert!(error.to_string().contains("percentile"), "{error}");
+        }
+        let config = HedgingConfig::builder().percentile(100.0).build().unwrap();
+        assert_eq!(100.0, config.percentile());
+        assert_eq!(95.0, HedgingConfig::default().percentile());
+    }
+}
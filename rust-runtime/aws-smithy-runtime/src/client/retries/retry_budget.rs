@@ -0,0 +1,213 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A retry budget that limits retries to a ratio of initial requests, used by the
+//! [budget retry mode](aws_smithy_types::retry::RetryMode::Budget).
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_RATIO: f64 = 0.2;
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10);
+const DEFAULT_MIN_RETRIES: u64 = 10;
+/// The number of slots the window is divided into. Requests are counted per slot, so the window
+/// slides one slot at a time.
+const SLOTS: u32 = 10;
+
+/// Budget for retries, shared by every operation of a retry partition.
+///
+/// A retry is allowed while the number of retries sent over the sliding window is less than the
+/// [ratio](RetryBudgetBuilder::ratio) of initial requests sent over the same window, or the
+/// [minimum number of retries](RetryBudgetBuilder::min_retries) allowed regardless of the number
+/// of initial requests.
+#[derive(Clone, Debug)]
+pub struct RetryBudget {
+    ratio: f64,
+    window: Duration,
+    min_retries: u64,
+    slots: Arc<Mutex<VecDeque<Slot>>>,
+}
+
+/// The requests counted during one slot of the window
+#[derive(Debug)]
+struct Slot {
+    index: u128,
+    initial_requests: u64,
+    retries: u64,
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl RetryBudget {
+    /// Creates a builder for constructing a `RetryBudget`.
+    pub fn builder() -> RetryBudgetBuilder {
+        RetryBudgetBuilder::default()
+    }
+
+    /// The maximum ratio of retries to initial requests over the window.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// The duration of the sliding window requests are counted over.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// The number of retries allowed over the window regardless of the number of initial requests.
+    pub fn min_retries(&self) -> u64 {
+        self.min_retries
+    }
+
+    /// Counts an initial request sent at `now`
+    pub(crate) fn record_initial_request(&self, now: SystemTime) {
+        let mut slots = self.slots.lock().unwrap();
+        self.current_slot(&mut slots, now).initial_requests += 1;
+    }
+
+    /// Counts a retry sent at `now` and returns `true` if the budget allows it, or returns `false`
+    pub(crate) fn try_acquire_retry(&self, now: SystemTime) -> bool {
+        let mut slots = self.slots.lock().unwrap();
+        self.current_slot(&mut slots, now);
+        let (initial_requests, retries) = slots.iter().fold((0, 0), |(initial, retries), slot| {
+            (initial + slot.initial_requests, retries + slot.retries)
+        });
+        let allowed = (initial_requests as f64 * self.ratio) as u64;
+        if retries >= allowed.max(self.min_retries) {
+            return false;
+        }
+        slots.back_mut().expect("current slot exists").retries += 1;
+        true
+    }
+
+    /// Drops the slots that have left the window, and returns the slot for `now`
+    fn current_slot<'a>(&self, slots: &'a mut VecDeque<Slot>, now: SystemTime) -> &'a mut Slot {
+        let slot_length = (self.window / SLOTS).as_nanos().max(1);
+        let index = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos()
+            / slot_length;
+        while slots
+            .front()
+            .is_some_and(|slot| slot.index + u128::from(SLOTS) <= index)
+        {
+            slots.pop_front();
+        }
+        if slots.back().is_none_or(|slot| slot.index < index) {
+            slots.push_back(Slot {
+                index,
+                initial_requests: 0,
+                retries: 0,
+            });
+        }
+        slots.back_mut().expect("just pushed")
+    }
+}
+
+/// Builder for constructing a `RetryBudget`.
+#[derive(Clone, Debug, Default)]
+pub struct RetryBudgetBuilder {
+    ratio: Option<f64>,
+    window: Option<Duration>,
+    min_retries: Option<u64>,
+}
+
+impl RetryBudgetBuilder {
+    /// Creates a new `RetryBudgetBuilder` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum ratio of retries to initial requests over the window. Defaults to 0.2,
+    /// which allows one retry for every five initial requests.
+    ///
+    /// # Panics
+    /// If `ratio` is negative.
+    pub fn ratio(mut self, ratio: f64) -> Self {
+        assert!(ratio >= 0.0, "ratio must not be negative");
+        self.ratio = Some(ratio);
+        self
+    }
+
+    /// Sets the duration of the sliding window requests are counted over. Defaults to ten
+    /// seconds.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Sets the number of retries allowed over the window regardless of the number of initial
+    /// requests, so that clients sending few requests can still retry. Defaults to 10.
+    pub fn min_retries(mut self, min_retries: u64) -> Self {
+        self.min_retries = Some(min_retries);
+        self
+    }
+
+    /// Builds a `RetryBudget`.
+    pub fn build(self) -> RetryBudget {
+        RetryBudget {
+            ratio: self.ratio.unwrap_or(DEFAULT_RATIO),
+            window: self.window.unwrap_or(DEFAULT_WINDOW),
+            min_retries: self.min_retries.unwrap_or(DEFAULT_MIN_RETRIES),
+            slots: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetryBudget;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn retries_are_limited_to_a_ratio_of_initial_requests() {
+        let budget = RetryBudget::builder()
+            .ratio(0.5)
+            .min_retries(1)
+            .window(Duration::from_secs(10))
+            .build();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+
+        // the minimum is allowed without any initial requests
+        assert!(budget.try_acquire_retry(now));
+        assert!(!budget.try_acquire_retry(now));
+
+        for _ in 0..6 {
+            budget.record_initial_request(now);
+        }
+        assert!(budget.try_acquire_retry(now));
+        assert!(budget.try_acquire_retry(now));
+        assert!(!budget.try_acquire_retry(now));
+    }
+
+    #[test]
+    fn requests_leave_the_window() {
+        let budget = RetryBudget::builder()
+            .ratio(1.0)
+            .min_retries(0)
+            .window(Duration::from_secs(10))
+            .build();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        budget.record_initial_request(start);
+        budget.record_initial_request(start + Duration::from_secs(5));
+        assert!(budget.try_acquire_retry(start + Duration::from_secs(5)));
+
+        // the first initial request has left the window, but the retry hasn't
+        let later = start + Duration::from_secs(10);
+        assert!(!budget.try_acquire_retry(later));
+
+        // everything has left the window
+        let much_later = start + Duration::from_secs(15);
+        assert!(!budget.try_acquire_retry(much_later));
+        budget.record_initial_request(much_later);
+        assert!(budget.try_acquire_retry(much_later));
+    }
+}
@@ -20,22 +20,30 @@ use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
 use aws_smithy_types::config_bag::{ConfigBag, Layer, Storable, StoreReplace};
 use aws_smithy_types::retry::{ErrorKind, RetryConfig, RetryMode};
 
+use crate::client::metrics::OperationTelemetry;
 use crate::client::retries::classifiers::run_classifiers_on_ctx;
 use crate::client::retries::client_rate_limiter::{ClientRateLimiter, RequestReason};
 use crate::client::retries::strategy::standard::ReleaseResult::{
     APermitWasReleased, NoPermitWasReleased,
 };
 use crate::client::retries::token_bucket::TokenBucket;
-use crate::client::retries::{ClientRateLimiterPartition, RetryPartition, RetryPartitionInner};
+use crate::client::retries::{
+    ClientRateLimiterPartition, RetryBudget, RetryPartition, RetryPartitionInner,
+};
 use crate::static_partition_map::StaticPartitionMap;
 
 static CLIENT_RATE_LIMITER: StaticPartitionMap<ClientRateLimiterPartition, ClientRateLimiter> =
     StaticPartitionMap::new();
 
+static RETRY_BUDGET: StaticPartitionMap<RetryPartition, RetryBudget> = StaticPartitionMap::new();
+
 /// Used by token bucket interceptor to ensure a TokenBucket always exists in config bag
 static TOKEN_BUCKET: StaticPartitionMap<RetryPartition, TokenBucket> = StaticPartitionMap::new();
 
 /// Retry strategy with exponential backoff, max attempts, and a token bucket.
+///
+/// With the [budget retry mode](RetryMode::Budget), retries are limited by the [`RetryBudget`] of
+/// the retry partition instead of the token bucket.
 #[derive(Debug, Default)]
 pub struct StandardRetryStrategy {
     retry_permit: Mutex<Option<OwnedSemaphorePermit>>,
@@ -104,6 +112,29 @@ impl StandardRetryStrategy {
         None
     }
 
+    /// Returns the [`RetryBudget`] and the current time if the budget retry mode is configured.
+    fn retry_budget(
+        runtime_components: &RuntimeComponents,
+        cfg: &ConfigBag,
+    ) -> Option<(RetryBudget, SystemTime)> {
+        let retry_config = cfg.load::<RetryConfig>().expect("retry config is required");
+        if retry_config.mode() != RetryMode::Budget {
+            return None;
+        }
+        let Some(time_source) = runtime_components.time_source() else {
+            debug!("no time source configured, so retries aren't limited by a retry budget");
+            return None;
+        };
+        let retry_partition = cfg.load::<RetryPartition>().expect("set in default config");
+        let retry_budget = match &retry_partition.inner {
+            RetryPartitionInner::Default(_) => {
+                RETRY_BUDGET.get_or_init_default(retry_partition.clone())
+            }
+            RetryPartitionInner::Custom { retry_budget, .. } => retry_budget.clone(),
+        };
+        Some((retry_budget, time_source.now()))
+    }
+
     fn calculate_backoff(
         &self,
         runtime_components: &RuntimeComponents,
@@ -182,6 +213,9 @@ impl RetryStrategy for StandardRetryStrategy {
         } else {
             debug!("no client rate limiter configured, so no token is required for the initial request.");
         }
+        if let Some((retry_budget, now)) = Self::retry_budget(runtime_components, cfg) {
+            retry_budget.record_initial_request(now);
+        }
 
         Ok(ShouldAttempt::Yes)
     }
@@ -220,6 +254,7 @@ impl RetryStrategy for StandardRetryStrategy {
                 token_bucket.regenerate_a_token();
             }
         }
+        record_retry_quota(runtime_components, cfg, token_bucket);
         // end bookkeeping
 
         let request_attempts = cfg
@@ -248,12 +283,22 @@ impl RetryStrategy for StandardRetryStrategy {
 
         //  acquire permit for retry
         let error_kind = error_kind.expect("result was classified retryable");
-        match token_bucket.acquire(&error_kind) {
-            Some(permit) => self.set_retry_permit(permit),
-            None => {
-                debug!("attempt #{request_attempts} failed with {error_kind:?}; However, not enough retry quota is available for another attempt so no retry will be attempted.");
+        if let Some((retry_budget, now)) = Self::retry_budget(runtime_components, cfg) {
+            if !retry_budget.try_acquire_retry(now) {
+                debug!("attempt #{request_attempts} failed with {error_kind:?}; However, the retry budget is exhausted so no retry will be attempted.");
                 return Ok(ShouldAttempt::No);
             }
+        } else {
+            match token_bucket.acquire(&error_kind) {
+                Some(permit) => {
+                    self.set_retry_permit(permit);
+                    record_retry_quota(runtime_components, cfg, token_bucket);
+                }
+                None => {
+                    debug!("attempt #{request_attempts} failed with {error_kind:?}; However, not enough retry quota is available for another attempt so no retry will be attempted.");
+                    return Ok(ShouldAttempt::No);
+                }
+            }
         }
 
         // calculate delay until next attempt
@@ -291,6 +336,23 @@ fn update_rate_limiter_if_exists(
     }
 }
 
+/// Records the retry quota available to the operation, if metrics are enabled
+fn record_retry_quota(
+    runtime_components: &RuntimeComponents,
+    cfg: &ConfigBag,
+    token_bucket: &TokenBucket,
+) {
+    if let Some(telemetry) = cfg.load::<OperationTelemetry>() {
+        let rate_limiter =
+            StandardRetryStrategy::adaptive_retry_rate_limiter(runtime_components, cfg);
+        telemetry.record_retry_quota(
+            token_bucket.available_permits(),
+            rate_limiter.map(|crl| crl.fill_rate()),
+            cfg,
+        );
+    }
+}
+
 fn check_rate_limiter_for_delay(
     runtime_components: &RuntimeComponents,
     cfg: &ConfigBag,
@@ -433,7 +495,7 @@ mod tests {
     use aws_smithy_types::retry::{ErrorKind, RetryConfig};
 
     use super::{calculate_exponential_backoff, StandardRetryStrategy};
-    use crate::client::retries::{ClientRateLimiter, RetryPartition, TokenBucket};
+    use crate::client::retries::{ClientRateLimiter, RetryBudget, RetryPartition, TokenBucket};
 
     #[test]
     fn no_retry_necessary_for_ok_result() {
@@ -819,6 +881,42 @@ mod tests {
         assert_eq!(token_bucket.available_permits(), 0);
     }
 
+    #[cfg(any(feature = "test-util", feature = "legacy-test-util"))]
+    #[test]
+    fn budget_mode_limits_retries_by_the_retry_budget() {
+        let (mut cfg, rc, ctx) = setup_test(
+            vec![RetryAction::server_error()],
+            RetryConfig::budget()
+                .with_use_static_exponential_base(true)
+                .with_max_attempts(5),
+        );
+        let rc = RuntimeComponentsBuilder::from_runtime_components(rc, "test")
+            .with_time_source(Some(SystemTimeSource::new()))
+            .build()
+            .unwrap();
+        let strategy = StandardRetryStrategy::new();
+        cfg.interceptor_state().store_put(TokenBucket::new(5));
+        let token_bucket = cfg.load::<TokenBucket>().unwrap().clone();
+        cfg.interceptor_state().store_put(
+            RetryPartition::custom("budget")
+                .retry_budget(RetryBudget::builder().ratio(0.5).min_retries(0).build())
+                .build(),
+        );
+
+        for _ in 0..2 {
+            strategy.should_attempt_initial_request(&rc, &cfg).unwrap();
+        }
+        cfg.interceptor_state().store_put(RequestAttempts::new(1));
+        let should_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(should_retry.expect_delay(), Duration::from_secs(1));
+        // the token bucket isn't used
+        assert_eq!(token_bucket.available_permits(), 5);
+
+        cfg.interceptor_state().store_put(RequestAttempts::new(2));
+        let no_retry = strategy.should_attempt_retry(&ctx, &rc, &cfg).unwrap();
+        assert_eq!(no_retry, ShouldAttempt::No);
+    }
+
     #[cfg(any(feature = "test-util", feature = "legacy-test-util"))]
     #[test]
     fn quota_replenishes_on_success() {
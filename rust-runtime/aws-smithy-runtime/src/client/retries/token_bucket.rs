@@ -76,6 +76,14 @@ impl TokenBucket {
             .ok()
     }
 
+    /// Acquires the quota for a hedged request, which costs as much as a retry
+    pub(crate) fn acquire_hedge(&self) -> Option<OwnedSemaphorePermit> {
+        self.semaphore
+            .clone()
+            .try_acquire_many_owned(self.retry_cost)
+            .ok()
+    }
+
     pub(crate) fn regenerate_a_token(&self) {
         if self.semaphore.available_permits() < self.max_permits {
             trace!("adding {PERMIT_REGENERATION_AMOUNT} back into the bucket");
@@ -83,7 +91,6 @@ impl TokenBucket {
         }
     }
 
-    #[cfg(all(test, any(feature = "test-util", feature = "legacy-test-util")))]
     pub(crate) fn available_permits(&self) -> usize {
         self.semaphore.available_permits()
     }
@@ -4,6 +4,7 @@
  */
 
 use crate::client::waiters::backoff::{Backoff, RandomImpl};
+use aws_smithy_async::future::pagination_stream::fn_stream::FnStream;
 use aws_smithy_async::{
     rt::sleep::{AsyncSleep, SharedAsyncSleep},
     time::SharedTimeSource,
@@ -12,10 +13,18 @@ use aws_smithy_runtime_api::client::waiters::FinalPoll;
 use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkError};
 use aws_smithy_runtime_api::client::{
     result::CreateUnhandledError,
-    waiters::error::{ExceededMaxWait, FailureState, OperationFailed, WaiterError},
+    waiters::error::{Canceled, ExceededMaxWait, FailureState, OperationFailed, WaiterError},
 };
-use std::future::Future;
-use std::time::Duration;
+use aws_smithy_types::date_time::{DateTime, Format};
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Notify;
 
 mod backoff;
 
@@ -41,16 +50,252 @@ pub enum AcceptorState {
     Retry,
 }
 
+/// The result of a single poll made by a waiter, along with the acceptor state it matched.
+#[derive(Debug)]
+pub struct WaiterPoll<O, E> {
+    acceptor_state: AcceptorState,
+    result: Result<O, SdkError<E, HttpResponse>>,
+    checkpoint: WaiterCheckpoint,
+}
+
+impl<O, E> WaiterPoll<O, E> {
+    /// Returns the acceptor state the result of this poll matched.
+    pub fn acceptor_state(&self) -> AcceptorState {
+        self.acceptor_state
+    }
+
+    /// Returns the result of the polling operation.
+    pub fn result(&self) -> &Result<O, SdkError<E, HttpResponse>> {
+        &self.result
+    }
+
+    /// Grants ownership of the result of the polling operation.
+    pub fn into_result(self) -> Result<O, SdkError<E, HttpResponse>> {
+        self.result
+    }
+
+    /// Returns a checkpoint that waiting can be [resumed](WaiterOrchestratorBuilder::resume_from)
+    /// from after this poll.
+    pub fn checkpoint(&self) -> WaiterCheckpoint {
+        self.checkpoint
+    }
+}
+
+/// Progress of a waiter that can be saved and used to resume waiting later, for example after a
+/// process restart.
+///
+/// A resumed waiter counts its elapsed time from when the original waiter started, so the max
+/// wait time isn't reset. The checkpoint converts to and from a string with [`Display`](fmt::Display)
+/// and [`FromStr`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct WaiterCheckpoint {
+    started_at: SystemTime,
+    poll_count: u32,
+}
+
+impl WaiterCheckpoint {
+    /// Creates a checkpoint for a waiter that started at `started_at` and has made `poll_count` polls.
+    pub fn new(started_at: SystemTime, poll_count: u32) -> Self {
+        Self {
+            started_at,
+            poll_count,
+        }
+    }
+
+    /// Returns the time the waiter started.
+    pub fn started_at(&self) -> SystemTime {
+        self.started_at
+    }
+
+    /// Returns the number of polls the waiter has made.
+    pub fn poll_count(&self) -> u32 {
+        self.poll_count
+    }
+}
+
+impl fmt::Display for WaiterCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let started_at = DateTime::from(self.started_at)
+            .fmt(Format::EpochSeconds)
+            .map_err(|_| fmt::Error)?;
+        write!(f, "{started_at}:{}", self.poll_count)
+    }
+}
+
+impl FromStr for WaiterCheckpoint {
+    type Err = InvalidWaiterCheckpoint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidWaiterCheckpoint(s.into());
+        let (started_at, poll_count) = s.split_once(':').ok_or_else(invalid)?;
+        let started_at = DateTime::from_str(started_at, Format::EpochSeconds)
+            .ok()
+            .and_then(|started_at| SystemTime::try_from(started_at).ok())
+            .ok_or_else(invalid)?;
+        let poll_count = poll_count.parse().map_err(|_| invalid())?;
+        Ok(Self::new(started_at, poll_count))
+    }
+}
+
+/// Error returned when a string can't be parsed into a [`WaiterCheckpoint`].
+#[derive(Debug)]
+pub struct InvalidWaiterCheckpoint(String);
+
+impl fmt::Display for InvalidWaiterCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid waiter checkpoint: `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidWaiterCheckpoint {}
+
+/// Token used to cancel a waiter.
+///
+/// Clones of the token share their state, so one clone can be given to the waiter and another
+/// used to cancel it. A canceled waiter stops polling and fails with [`WaiterError::Canceled`].
+#[derive(Clone, Debug, Default)]
+pub struct WaiterCancellationToken {
+    inner: Arc<CancellationInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationInner {
+    canceled: AtomicBool,
+    notify: Notify,
+}
+
+impl WaiterCancellationToken {
+    /// Creates a new token that hasn't been canceled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the waiters using this token.
+    pub fn cancel(&self) {
+        self.inner.canceled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Returns true if [`cancel`](Self::cancel) was called.
+    pub fn is_canceled(&self) -> bool {
+        self.inner.canceled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves when the token is canceled
+    async fn canceled(&self) {
+        // The future is notified by `notify_waiters` as soon as it's created
+        let notified = self.inner.notify.notified();
+        if self.is_canceled() {
+            return;
+        }
+        notified.await
+    }
+}
+
+/// Hook called with each poll made by a waiter.
+///
+/// This is implemented for closures taking a [`WaiterPoll`], and for `()` to not hook anything.
+pub trait OnPoll<O, E>: Send + Sync {
+    /// Called with a poll made by the waiter.
+    fn on_poll(&self, poll: &WaiterPoll<O, E>);
+}
+
+impl<O, E> OnPoll<O, E> for () {
+    fn on_poll(&self, _poll: &WaiterPoll<O, E>) {}
+}
+
+impl<O, E, F> OnPoll<O, E> for F
+where
+    F: Fn(&WaiterPoll<O, E>) + Send + Sync,
+{
+    fn on_poll(&self, poll: &WaiterPoll<O, E>) {
+        self(poll)
+    }
+}
+
+/// Boxed [`OnPoll`] hook, as set by [`WaiterOptions::on_poll`].
+pub type BoxedOnPoll<O, E> = Box<dyn Fn(&WaiterPoll<O, E>) + Send + Sync>;
+
+/// Settings for a waiter beyond how long it waits.
+///
+/// Generated waiters take these in their `wait_with` and `wait_stream` methods.
+pub struct WaiterOptions<O, E> {
+    cancellation_token: Option<WaiterCancellationToken>,
+    checkpoint: Option<WaiterCheckpoint>,
+    on_poll: Option<BoxedOnPoll<O, E>>,
+}
+
+impl<O, E> Default for WaiterOptions<O, E> {
+    fn default() -> Self {
+        Self {
+            cancellation_token: None,
+            checkpoint: None,
+            on_poll: None,
+        }
+    }
+}
+
+impl<O, E> fmt::Debug for WaiterOptions<O, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaiterOptions")
+            .field("cancellation_token", &self.cancellation_token)
+            .field("checkpoint", &self.checkpoint)
+            .field("on_poll", &self.on_poll.as_ref().map(|_| "** hook **"))
+            .finish()
+    }
+}
+
+impl<O, E> WaiterOptions<O, E> {
+    /// Creates options that don't change how the waiter behaves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a token that cancels the waiter when it's canceled.
+    pub fn cancellation_token(mut self, cancellation_token: WaiterCancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Resume waiting from a checkpoint saved from a poll of an earlier waiter.
+    ///
+    /// See [`WaiterOrchestratorBuilder::resume_from`].
+    pub fn resume_from(mut self, checkpoint: WaiterCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Set a hook that's called with each poll the waiter makes, for example to report progress.
+    pub fn on_poll(mut self, on_poll: impl Fn(&WaiterPoll<O, E>) + Send + Sync + 'static) -> Self {
+        self.on_poll = Some(Box::new(on_poll));
+        self
+    }
+}
+
+/// What the waiter does on its next poll
+#[derive(Debug)]
+enum NextPoll {
+    Now,
+    After(Duration),
+    ExceededMaxWait(ExceededMaxWait),
+}
+
 /// Orchestrates waiting via polling with jittered exponential backoff.
 ///
 /// This is meant to be used internally by the generated code to provide
 /// waiter functionality.
-pub struct WaiterOrchestrator<AcceptorFn, OperationFn> {
+pub struct WaiterOrchestrator<AcceptorFn, OperationFn, OnPollFn = ()> {
     backoff: Backoff,
     time_source: SharedTimeSource,
     sleep_impl: SharedAsyncSleep,
     acceptor_fn: AcceptorFn,
     operation_fn: OperationFn,
+    on_poll: OnPollFn,
+    cancellation_token: Option<WaiterCancellationToken>,
+    started_at: Option<SystemTime>,
+    poll_count: u32,
+    done_retrying: bool,
+    next_poll: Option<NextPoll>,
 }
 
 impl WaiterOrchestrator<(), ()> {
@@ -60,113 +305,198 @@ impl WaiterOrchestrator<(), ()> {
     }
 }
 
-impl<AcceptorFn, OperationFn> WaiterOrchestrator<AcceptorFn, OperationFn> {
-    fn new(
-        backoff: Backoff,
-        time_source: SharedTimeSource,
-        sleep_impl: SharedAsyncSleep,
-        acceptor_fn: AcceptorFn,
-        operation_fn: OperationFn,
-    ) -> Self {
-        WaiterOrchestrator {
-            backoff,
-            time_source,
-            sleep_impl,
-            acceptor_fn,
-            operation_fn,
-        }
-    }
-}
-
-impl<AcceptorFn, OperationFn, O, E, Fut> WaiterOrchestrator<AcceptorFn, OperationFn>
+impl<AcceptorFn, OperationFn, OnPollFn, O, E, Fut>
+    WaiterOrchestrator<AcceptorFn, OperationFn, OnPollFn>
 where
     AcceptorFn: Fn(Result<&O, &E>) -> AcceptorState,
     OperationFn: Fn() -> Fut,
+    OnPollFn: OnPoll<O, E>,
     Fut: Future<Output = Result<O, SdkError<E, HttpResponse>>>,
     E: CreateUnhandledError + std::error::Error + Send + Sync + 'static,
 {
     /// Orchestrates waiting via polling with jittered exponential backoff.
     pub async fn orchestrate(
-        self,
+        mut self,
     ) -> Result<FinalPoll<O, SdkError<E, HttpResponse>>, WaiterError<O, E>> {
-        let start_time = self.time_source.now();
-        let mut attempt = 0;
-        let mut done_retrying = false;
         loop {
-            tracing::debug!("executing waiter poll attempt #{}", attempt + 1);
-            let result = (self.operation_fn)().await;
-            let error = result.is_err();
-
-            // "acceptable result" in this context means "an acceptor's matcher can match this result type"
-            let acceptable_result = result.as_ref().map_err(|err| err.as_service_error());
-            let acceptor_state = match acceptable_result {
-                Ok(output) => (self.acceptor_fn)(Ok(output)),
-                Err(Some(err)) => (self.acceptor_fn)(Err(err)),
-                _ => {
-                    // If we got an unmatchable failure (basically anything unmodeled), then just immediately exit
-                    return Err(WaiterError::OperationFailed(OperationFailed::new(
-                        result.err().expect("can only be an err in this branch"),
-                    )));
-                }
-            };
-
-            tracing::debug!("waiter acceptor state: {acceptor_state:?}");
-            match acceptor_state {
-                AcceptorState::Success => return Ok(FinalPoll::new(result)),
+            let poll = self
+                .next_poll()
+                .await
+                .expect("waiting only ends after a success or failure state, or an error")?;
+            match poll.acceptor_state {
+                AcceptorState::Success => return Ok(FinalPoll::new(poll.result)),
                 AcceptorState::Failure => {
                     return Err(WaiterError::FailureState(FailureState::new(
-                        FinalPoll::new(result.map_err(|err| err.into_service_error())),
+                        FinalPoll::new(poll.result.map_err(|err| err.into_service_error())),
                     )))
                 }
-                // This occurs when there was a modeled error response, but none of the acceptors matched it
-                AcceptorState::NoAcceptorsMatched if error => {
-                    return Err(WaiterError::OperationFailed(OperationFailed::new(
-                        result.err().expect("checked above"),
-                    )))
+                AcceptorState::Retry | AcceptorState::NoAcceptorsMatched => {}
+            }
+        }
+    }
+
+    /// Makes the next poll, returning `None` once waiting has ended
+    async fn next_poll(&mut self) -> Option<Result<WaiterPoll<O, E>, WaiterError<O, E>>> {
+        let started_at = *self
+            .started_at
+            .get_or_insert_with(|| self.time_source.now());
+        match self.next_poll.take()? {
+            NextPoll::Now => {}
+            NextPoll::After(delay) => {
+                let sleep = self.sleep_impl.sleep(delay);
+                if unless_canceled(self.cancellation_token.as_ref(), sleep)
+                    .await
+                    .is_none()
+                {
+                    return Some(Err(self.canceled(started_at)));
                 }
-                AcceptorState::Retry | AcceptorState::NoAcceptorsMatched => {
-                    attempt += 1;
-
-                    let now = self.time_source.now();
-                    let elapsed = now.duration_since(start_time).unwrap_or_default();
-                    if !done_retrying && elapsed <= self.backoff.max_wait() {
-                        let delay = self.backoff.delay(attempt, elapsed);
-
-                        // The backoff function returns a zero delay when it is min_delay time away
-                        // from max_time. If we didn't detect this and stop polling, then we could
-                        // slam the server at the very end of the wait period for servers that are
-                        // really fast (for example, a few milliseconds total round-trip latency).
-                        if delay.is_zero() {
-                            tracing::debug!(
-                                "delay calculated for attempt #{attempt}; elapsed ({elapsed:?}); waiter is close to max time; will immediately poll one last time"
-                            );
-                            done_retrying = true;
-                        } else {
-                            tracing::debug!(
-                                "delay calculated for attempt #{attempt}; elapsed ({elapsed:?}); waiter will poll again in {delay:?}"
-                            );
-                            self.sleep_impl.sleep(delay).await;
-                        }
+            }
+            NextPoll::ExceededMaxWait(context) => {
+                return Some(Err(WaiterError::ExceededMaxWait(context)))
+            }
+        }
+
+        tracing::debug!("executing waiter poll attempt #{}", self.poll_count + 1);
+        let operation = (self.operation_fn)();
+        let Some(result) = unless_canceled(self.cancellation_token.as_ref(), operation).await
+        else {
+            return Some(Err(self.canceled(started_at)));
+        };
+        self.poll_count += 1;
+        let error = result.is_err();
+
+        // "acceptable result" in this context means "an acceptor's matcher can match this result type"
+        let acceptable_result = result.as_ref().map_err(|err| err.as_service_error());
+        let acceptor_state = match acceptable_result {
+            Ok(output) => (self.acceptor_fn)(Ok(output)),
+            Err(Some(err)) => (self.acceptor_fn)(Err(err)),
+            _ => {
+                // If we got an unmatchable failure (basically anything unmodeled), then just immediately exit
+                return Some(Err(WaiterError::OperationFailed(OperationFailed::new(
+                    result.err().expect("can only be an err in this branch"),
+                ))));
+            }
+        };
+
+        tracing::debug!("waiter acceptor state: {acceptor_state:?}");
+        match acceptor_state {
+            // This occurs when there was a modeled error response, but none of the acceptors matched it
+            AcceptorState::NoAcceptorsMatched if error => {
+                return Some(Err(WaiterError::OperationFailed(OperationFailed::new(
+                    result.err().expect("checked above"),
+                ))))
+            }
+            AcceptorState::Retry | AcceptorState::NoAcceptorsMatched => {
+                let attempt = self.poll_count;
+                let now = self.time_source.now();
+                let elapsed = now.duration_since(started_at).unwrap_or_default();
+                if !self.done_retrying && elapsed <= self.backoff.max_wait() {
+                    let delay = self.backoff.delay(attempt, elapsed);
+
+                    // The backoff function returns a zero delay when it is min_delay time away
+                    // from max_time. If we didn't detect this and stop polling, then we could
+                    // slam the server at the very end of the wait period for servers that are
+                    // really fast (for example, a few milliseconds total round-trip latency).
+                    if delay.is_zero() {
+                        tracing::debug!(
+                            "delay calculated for attempt #{attempt}; elapsed ({elapsed:?}); waiter is close to max time; will immediately poll one last time"
+                        );
+                        self.done_retrying = true;
+                        self.next_poll = Some(NextPoll::Now);
                     } else {
                         tracing::debug!(
-                            "waiter exceeded max wait time of {:?}",
-                            self.backoff.max_wait()
+                            "delay calculated for attempt #{attempt}; elapsed ({elapsed:?}); waiter will poll again in {delay:?}"
                         );
-                        return Err(WaiterError::ExceededMaxWait(ExceededMaxWait::new(
-                            self.backoff.max_wait(),
-                            elapsed,
-                            attempt,
-                        )));
+                        self.next_poll = Some(NextPoll::After(delay));
                     }
+                } else {
+                    tracing::debug!(
+                        "waiter exceeded max wait time of {:?}",
+                        self.backoff.max_wait()
+                    );
+                    self.next_poll = Some(NextPoll::ExceededMaxWait(ExceededMaxWait::new(
+                        self.backoff.max_wait(),
+                        elapsed,
+                        attempt,
+                    )));
                 }
             }
+            AcceptorState::Success | AcceptorState::Failure => {}
         }
+
+        let poll = WaiterPoll {
+            acceptor_state,
+            result,
+            checkpoint: WaiterCheckpoint::new(started_at, self.poll_count),
+        };
+        self.on_poll.on_poll(&poll);
+        Some(Ok(poll))
+    }
+
+    fn canceled(&mut self, started_at: SystemTime) -> WaiterError<O, E> {
+        tracing::debug!("waiter was canceled");
+        self.next_poll = None;
+        let elapsed = self
+            .time_source
+            .now()
+            .duration_since(started_at)
+            .unwrap_or_default();
+        WaiterError::Canceled(Canceled::new(elapsed, self.poll_count))
+    }
+}
+
+impl<AcceptorFn, OperationFn, OnPollFn, O, E, Fut>
+    WaiterOrchestrator<AcceptorFn, OperationFn, OnPollFn>
+where
+    AcceptorFn: Fn(Result<&O, &E>) -> AcceptorState + Send + 'static,
+    OperationFn: Fn() -> Fut + Send + 'static,
+    OnPollFn: OnPoll<O, E> + 'static,
+    Fut: Future<Output = Result<O, SdkError<E, HttpResponse>>> + Send,
+    O: Send + 'static,
+    E: CreateUnhandledError + std::error::Error + Send + Sync + 'static,
+{
+    /// Polls with jittered exponential backoff, yielding each poll as it's made.
+    ///
+    /// The stream ends after the poll that matched a success or failure state. A waiter
+    /// that ends without matching either of those, because the max wait time was exceeded,
+    /// the waiter was canceled, or the operation failed with an unmodeled error, yields a
+    /// [`WaiterError`] as its last item. Unlike [`orchestrate`](Self::orchestrate), a failure
+    /// state is yielded as a poll rather than an error.
+    pub fn orchestrate_stream(mut self) -> FnStream<Result<WaiterPoll<O, E>, WaiterError<O, E>>> {
+        FnStream::new(|tx| {
+            Box::pin(async move {
+                while let Some(poll) = self.next_poll().await {
+                    if tx.send(poll).await.is_err() {
+                        return;
+                    }
+                }
+            })
+        })
     }
 }
 
+/// Runs the future to completion, or returns `None` if the waiter is canceled first
+async fn unless_canceled<F: Future>(
+    token: Option<&WaiterCancellationToken>,
+    future: F,
+) -> Option<F::Output> {
+    let Some(token) = token else {
+        return Some(future.await);
+    };
+    let mut future = pin!(future);
+    let mut canceled = pin!(token.canceled());
+    poll_fn(|cx| {
+        if canceled.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        future.as_mut().poll(cx).map(Some)
+    })
+    .await
+}
+
 /// Builder for [`WaiterOrchestrator`].
 #[derive(Default)]
-pub struct WaiterOrchestratorBuilder<AcceptorFn = (), OperationFn = ()> {
+pub struct WaiterOrchestratorBuilder<AcceptorFn = (), OperationFn = (), OnPollFn = ()> {
     min_delay: Option<Duration>,
     max_delay: Option<Duration>,
     max_wait: Option<Duration>,
@@ -175,9 +505,14 @@ pub struct WaiterOrchestratorBuilder<AcceptorFn = (), OperationFn = ()> {
     random_fn: RandomImpl,
     acceptor_fn: Option<AcceptorFn>,
     operation_fn: Option<OperationFn>,
+    on_poll: OnPollFn,
+    cancellation_token: Option<WaiterCancellationToken>,
+    checkpoint: Option<WaiterCheckpoint>,
 }
 
-impl<AcceptorFn, OperationFn> WaiterOrchestratorBuilder<AcceptorFn, OperationFn> {
+impl<AcceptorFn, OperationFn, OnPollFn>
+    WaiterOrchestratorBuilder<AcceptorFn, OperationFn, OnPollFn>
+{
     /// Set the minimum delay time for the waiter.
     pub fn min_delay(mut self, min_delay: Duration) -> Self {
         self.min_delay = Some(min_delay);
@@ -214,29 +549,52 @@ impl<AcceptorFn, OperationFn> WaiterOrchestratorBuilder<AcceptorFn, OperationFn>
         self
     }
 
+    /// Set a token that cancels the waiter when it's canceled.
+    pub fn cancellation_token(mut self, cancellation_token: WaiterCancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Resume waiting from a checkpoint saved from a poll of an earlier waiter.
+    ///
+    /// The elapsed time is counted from when the earlier waiter started, and the backoff
+    /// continues from the number of polls it made.
+    pub fn resume_from(mut self, checkpoint: WaiterCheckpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
     /// Build a waiter orchestrator.
-    pub fn build(self) -> WaiterOrchestrator<AcceptorFn, OperationFn> {
-        WaiterOrchestrator::new(
-            Backoff::new(
+    pub fn build(self) -> WaiterOrchestrator<AcceptorFn, OperationFn, OnPollFn> {
+        WaiterOrchestrator {
+            backoff: Backoff::new(
                 self.min_delay.expect("min delay is required"),
                 self.max_delay.expect("max delay is required"),
                 self.max_wait.expect("max wait is required"),
                 self.random_fn,
             ),
-            self.time_source.expect("time source required"),
-            self.sleep_impl.expect("sleep impl required"),
-            self.acceptor_fn.expect("acceptor fn required"),
-            self.operation_fn.expect("operation fn required"),
-        )
+            time_source: self.time_source.expect("time source required"),
+            sleep_impl: self.sleep_impl.expect("sleep impl required"),
+            acceptor_fn: self.acceptor_fn.expect("acceptor fn required"),
+            operation_fn: self.operation_fn.expect("operation fn required"),
+            on_poll: self.on_poll,
+            cancellation_token: self.cancellation_token,
+            started_at: self.checkpoint.map(|checkpoint| checkpoint.started_at),
+            poll_count: self
+                .checkpoint
+                .map_or(0, |checkpoint| checkpoint.poll_count),
+            done_retrying: false,
+            next_poll: Some(NextPoll::Now),
+        }
     }
 }
 
-impl<OperationFn> WaiterOrchestratorBuilder<(), OperationFn> {
+impl<OperationFn, OnPollFn> WaiterOrchestratorBuilder<(), OperationFn, OnPollFn> {
     /// Set the acceptor function for the waiter.
     pub fn acceptor<AcceptorFn>(
         self,
         acceptor: AcceptorFn,
-    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn> {
+    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn, OnPollFn> {
         WaiterOrchestratorBuilder {
             min_delay: self.min_delay,
             max_delay: self.max_delay,
@@ -246,16 +604,19 @@ impl<OperationFn> WaiterOrchestratorBuilder<(), OperationFn> {
             random_fn: self.random_fn,
             acceptor_fn: Some(acceptor),
             operation_fn: self.operation_fn,
+            on_poll: self.on_poll,
+            cancellation_token: self.cancellation_token,
+            checkpoint: self.checkpoint,
         }
     }
 }
 
-impl<AcceptorFn> WaiterOrchestratorBuilder<AcceptorFn, ()> {
+impl<AcceptorFn, OnPollFn> WaiterOrchestratorBuilder<AcceptorFn, (), OnPollFn> {
     /// Set the operation function for the waiter.
     pub fn operation<OperationFn>(
         self,
         operation: OperationFn,
-    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn> {
+    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn, OnPollFn> {
         WaiterOrchestratorBuilder {
             min_delay: self.min_delay,
             max_delay: self.max_delay,
@@ -265,6 +626,55 @@ impl<AcceptorFn> WaiterOrchestratorBuilder<AcceptorFn, ()> {
             random_fn: self.random_fn,
             acceptor_fn: self.acceptor_fn,
             operation_fn: Some(operation),
+            on_poll: self.on_poll,
+            cancellation_token: self.cancellation_token,
+            checkpoint: self.checkpoint,
+        }
+    }
+}
+
+impl<AcceptorFn, OperationFn> WaiterOrchestratorBuilder<AcceptorFn, OperationFn, ()> {
+    /// Set a hook that's called with each poll the waiter makes, for example to report progress.
+    pub fn on_poll<OnPollFn>(
+        self,
+        on_poll: OnPollFn,
+    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn, OnPollFn> {
+        WaiterOrchestratorBuilder {
+            min_delay: self.min_delay,
+            max_delay: self.max_delay,
+            max_wait: self.max_wait,
+            time_source: self.time_source,
+            sleep_impl: self.sleep_impl,
+            random_fn: self.random_fn,
+            acceptor_fn: self.acceptor_fn,
+            operation_fn: self.operation_fn,
+            on_poll,
+            cancellation_token: self.cancellation_token,
+            checkpoint: self.checkpoint,
+        }
+    }
+
+    /// Apply the given options, replacing any cancellation token or checkpoint already set.
+    pub fn options<O, E>(
+        self,
+        options: WaiterOptions<O, E>,
+    ) -> WaiterOrchestratorBuilder<AcceptorFn, OperationFn, BoxedOnPoll<O, E>>
+    where
+        O: 'static,
+        E: 'static,
+    {
+        WaiterOrchestratorBuilder {
+            min_delay: self.min_delay,
+            max_delay: self.max_delay,
+            max_wait: self.max_wait,
+            time_source: self.time_source,
+            sleep_impl: self.sleep_impl,
+            random_fn: self.random_fn,
+            acceptor_fn: self.acceptor_fn,
+            operation_fn: self.operation_fn,
+            on_poll: options.on_poll.unwrap_or_else(|| Box::new(|_| {})),
+            cancellation_token: options.cancellation_token.or(self.cancellation_token),
+            checkpoint: options.checkpoint.or(self.checkpoint),
         }
     }
 }
@@ -526,4 +936,186 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().as_result().is_err());
     }
+
+    #[tokio::test]
+    async fn stream_yields_each_poll() {
+        let _logs = show_test_logs();
+        let (time_source, sleep_impl) = tick_advance_time_and_sleep();
+
+        let attempt = Arc::new(AtomicUsize::new(1));
+        let polled_states = Arc::new(Mutex::new(Vec::new()));
+        let orchestrator = test_orchestrator(sleep_impl, time_source.clone())
+            .acceptor(|result: Result<&usize, &TestError>| match result {
+                Ok(3) => AcceptorState::Success,
+                _ => AcceptorState::Retry,
+            })
+            .operation(move || {
+                let attempt = attempt.fetch_add(1, Ordering::SeqCst);
+                async move { Result::<_, SdkError<TestError, HttpResponse>>::Ok(attempt) }
+            })
+            .on_poll({
+                let polled_states = polled_states.clone();
+                move |poll: &WaiterPoll<usize, TestError>| {
+                    polled_states.lock().unwrap().push(poll.acceptor_state())
+                }
+            })
+            .build();
+
+        let task = tokio::spawn(orchestrator.orchestrate_stream().collect::<Vec<_>>());
+        tokio::task::yield_now().await;
+        time_source.tick(Duration::from_secs(500)).await;
+        let polls = task.await.unwrap();
+
+        let polls = polls
+            .into_iter()
+            .map(|poll| {
+                let poll = poll.expect("no errors");
+                (
+                    poll.acceptor_state(),
+                    poll.checkpoint().poll_count(),
+                    *poll.result().as_ref().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (AcceptorState::Retry, 1, 1),
+                (AcceptorState::Retry, 2, 2),
+                (AcceptorState::Success, 3, 3),
+            ],
+            polls
+        );
+        assert_eq!(
+            vec![
+                AcceptorState::Retry,
+                AcceptorState::Retry,
+                AcceptorState::Success
+            ],
+            *polled_states.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_ends_with_exceeded_max_wait() {
+        let _logs = show_test_logs();
+        let (time_source, sleep_impl) = tick_advance_time_and_sleep();
+        let orchestrator = test_orchestrator(sleep_impl, time_source.clone())
+            .acceptor(|_result: Result<&usize, &TestError>| AcceptorState::Retry)
+            .operation(|| async { Result::<_, SdkError<TestError, HttpResponse>>::Ok(1) })
+            .build();
+
+        let task = tokio::spawn(orchestrator.orchestrate_stream().collect::<Vec<_>>());
+        tokio::task::yield_now().await;
+        time_source.tick(Duration::from_secs(500)).await;
+        let mut polls = task.await.unwrap();
+
+        match polls.pop() {
+            Some(Err(WaiterError::ExceededMaxWait(context))) => {
+                assert_eq!(12, context.poll_count())
+            }
+            result => panic!("expected ExceededMaxWait, got {result:?}"),
+        }
+        assert_eq!(12, polls.len());
+        assert!(polls.iter().all(|poll| poll.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn cancel_while_waiting() {
+        let _logs = show_test_logs();
+        let (time_source, sleep_impl) = tick_advance_time_and_sleep();
+        let token = WaiterCancellationToken::new();
+        let orchestrator = test_orchestrator(sleep_impl, time_source)
+            .acceptor(|_result: Result<&usize, &TestError>| AcceptorState::Retry)
+            .operation(|| async { Result::<_, SdkError<TestError, HttpResponse>>::Ok(1) })
+            .cancellation_token(token.clone())
+            .build();
+
+        let task = tokio::spawn(orchestrator.orchestrate());
+        tokio::task::yield_now().await;
+        token.cancel();
+        match task.await.unwrap() {
+            Err(WaiterError::Canceled(context)) => {
+                assert_eq!(1, context.poll_count());
+                assert_eq!(Duration::ZERO, context.elapsed());
+            }
+            result => panic!("expected Canceled, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn resume_from_checkpoint_keeps_max_wait_accounting() {
+        let _logs = show_test_logs();
+        let (time_source, sleep_impl) = tick_advance_time_and_sleep();
+        time_source.tick(Duration::from_secs(1000)).await;
+        let checkpoint: WaiterCheckpoint =
+            WaiterCheckpoint::new(time_source.now() - Duration::from_secs(301), 11)
+                .to_string()
+                .parse()
+                .unwrap();
+
+        let orchestrator = test_orchestrator(sleep_impl, time_source)
+            .acceptor(|_result: Result<&usize, &TestError>| AcceptorState::Retry)
+            .operation(|| async { Result::<_, SdkError<TestError, HttpResponse>>::Ok(1) })
+            .resume_from(checkpoint)
+            .build();
+
+        match orchestrator.orchestrate().await {
+            Err(WaiterError::ExceededMaxWait(context)) => {
+                assert_eq!(301, context.elapsed().as_secs());
+                assert_eq!(12, context.poll_count());
+            }
+            result => panic!("expected ExceededMaxWait, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn options_set_the_hook_token_and_checkpoint() {
+        let _logs = show_test_logs();
+        let (time_source, sleep_impl) = tick_advance_time_and_sleep();
+        time_source.tick(Duration::from_secs(1000)).await;
+        let checkpoint = WaiterCheckpoint::new(time_source.now() - Duration::from_secs(10), 4);
+        let token = WaiterCancellationToken::new();
+        let poll_counts = Arc::new(Mutex::new(Vec::new()));
+        let options = WaiterOptions::new()
+            .resume_from(checkpoint)
+            .cancellation_token(token.clone())
+            .on_poll({
+                let poll_counts = poll_counts.clone();
+                move |poll: &WaiterPoll<usize, TestError>| {
+                    poll_counts
+                        .lock()
+                        .unwrap()
+                        .push(poll.checkpoint().poll_count())
+                }
+            });
+        let orchestrator = test_orchestrator(sleep_impl, time_source)
+            .acceptor(|_result: Result<&usize, &TestError>| AcceptorState::Retry)
+            .operation(|| async { Result::<_, SdkError<TestError, HttpResponse>>::Ok(1) })
+            .options(options)
+            .build();
+
+        let task = tokio::spawn(orchestrator.orchestrate());
+        tokio::task::yield_now().await;
+        token.cancel();
+        match task.await.unwrap() {
+            Err(WaiterError::Canceled(context)) => {
+                assert_eq!(5, context.poll_count());
+                assert_eq!(10, context.elapsed().as_secs());
+            }
+            result => panic!("expected Canceled, got {result:?}"),
+        }
+        assert_eq!(vec![5], *poll_counts.lock().unwrap());
+    }
+
+    #[test]
+    fn checkpoint_string_round_trip() {
+        let checkpoint = WaiterCheckpoint::new(
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_500),
+            3,
+        );
+        assert_eq!("1700000000.5:3", checkpoint.to_string());
+        assert_eq!(checkpoint, "1700000000.5:3".parse().unwrap());
+        assert!("1700000000.5".parse::<WaiterCheckpoint>().is_err());
+        assert!("soon:3".parse::<WaiterCheckpoint>().is_err());
+    }
 }
@@ -0,0 +1,157 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(feature = "client", feature = "test-util"))]
+
+use aws_smithy_async::rt::sleep::TokioSleep;
+use aws_smithy_async::test_util::ManualTimeSource;
+use aws_smithy_runtime::client::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerPartition, CircuitState,
+};
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::client::retries::classifiers::HttpStatusCodeClassifier;
+use aws_smithy_runtime_api::client::http::{
+    http_client_fn, HttpConnector, HttpConnectorFuture, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse, OrchestratorError};
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout::TimeoutConfig;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Connector that responds with each status in turn, and with 200 once they run out
+#[derive(Clone, Debug, Default)]
+struct TestConnector {
+    statuses: Arc<Mutex<Vec<u16>>>,
+    calls: Arc<AtomicUsize>,
+}
+
+impl TestConnector {
+    fn new(statuses: impl IntoIterator<Item = u16>) -> Self {
+        let mut statuses = statuses.into_iter().collect::<Vec<_>>();
+        statuses.reverse();
+        Self {
+            statuses: Arc::new(Mutex::new(statuses)),
+            ..Default::default()
+        }
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+impl HttpConnector for TestConnector {
+    fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let status = self.statuses.lock().unwrap().pop().unwrap_or(200);
+        let response = http_1x::Response::builder()
+            .status(status)
+            .body(SdkBody::empty())
+            .unwrap();
+        HttpConnectorFuture::ready(Ok(HttpResponse::try_from(response).unwrap()))
+    }
+}
+
+fn operation(
+    connector: &TestConnector,
+    endpoint_url: &str,
+    circuit_breaker: &CircuitBreaker,
+    time_source: &ManualTimeSource,
+) -> Operation<(), u16, Infallible> {
+    let connector = connector.clone();
+    Operation::builder()
+        .service_name("test-service")
+        .operation_name("TestOperation")
+        .http_client(http_client_fn(move |_, _| {
+            SharedHttpConnector::new(connector.clone())
+        }))
+        .endpoint_url(endpoint_url)
+        .no_auth()
+        .standard_retry(&RetryConfig::disabled())
+        .retry_classifier(HttpStatusCodeClassifier::default())
+        .timeout_config(TimeoutConfig::disabled())
+        .sleep_impl(TokioSleep::new())
+        .time_source(time_source.clone())
+        .circuit_breaker(circuit_breaker.clone())
+        .serializer(|_input: ()| Ok(HttpRequest::new(SdkBody::empty())))
+        .deserializer::<_, Infallible>(|response| match response.status().as_u16() {
+            200 => Ok(200),
+            status => Err(OrchestratorError::other(format!("status {status}"))),
+        })
+        .build()
+}
+
+#[tokio::test]
+async fn open_circuit_fails_fast_until_a_probe_succeeds() {
+    let time_source = ManualTimeSource::new(UNIX_EPOCH);
+    let circuit_breaker = CircuitBreaker::new(
+        CircuitBreakerConfig::builder()
+            .consecutive_failures(3)
+            .open_duration(Duration::from_secs(10))
+            .build(),
+    );
+    // client errors don't count as failures
+    let connector = TestConnector::new([500, 400, 503, 500, 500]);
+    let operation = operation(
+        &connector,
+        "http://localhost:1234",
+        &circuit_breaker,
+        &time_source,
+    );
+
+    for _ in 0..5 {
+        operation.invoke(()).await.expect_err("failure");
+    }
+    assert_eq!(5, connector.calls());
+    assert_eq!(CircuitState::Open, circuit_breaker.state("test-service"));
+
+    let err = operation.invoke(()).await.expect_err("circuit is open");
+    assert!(matches!(err, SdkError::CircuitOpen(_)), "{err:?}");
+    assert_eq!(5, connector.calls(), "the request shouldn't be sent");
+
+    time_source.advance(Duration::from_secs(10));
+    assert_eq!(200, operation.invoke(()).await.expect("probe succeeds"));
+    assert_eq!(CircuitState::Closed, circuit_breaker.state("test-service"));
+    assert_eq!(200, operation.invoke(()).await.expect("success"));
+    assert_eq!(7, connector.calls());
+}
+
+#[tokio::test]
+async fn circuits_can_be_partitioned_by_endpoint() {
+    let time_source = ManualTimeSource::new(UNIX_EPOCH);
+    let circuit_breaker = CircuitBreaker::new(
+        CircuitBreakerConfig::builder()
+            .partition(CircuitBreakerPartition::Endpoint)
+            .consecutive_failures(1)
+            .build(),
+    );
+    let failing_connector = TestConnector::new([500]);
+    let failing = operation(
+        &failing_connector,
+        "http://failing:1234",
+        &circuit_breaker,
+        &time_source,
+    );
+    let healthy_connector = TestConnector::default();
+    let healthy = operation(
+        &healthy_connector,
+        "http://healthy:1234",
+        &circuit_breaker,
+        &time_source,
+    );
+
+    failing.invoke(()).await.expect_err("failure");
+    let err = failing.invoke(()).await.expect_err("circuit is open");
+    assert!(matches!(err, SdkError::CircuitOpen(_)), "{err:?}");
+    assert_eq!(CircuitState::Open, circuit_breaker.state("failing:1234"));
+
+    assert_eq!(200, healthy.invoke(()).await.expect("success"));
+    assert_eq!(CircuitState::Closed, circuit_breaker.state("healthy:1234"));
+}
@@ -0,0 +1,283 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(feature = "client", feature = "test-util"))]
+
+use aws_smithy_async::rt::sleep::{SharedAsyncSleep, TokioSleep};
+use aws_smithy_http_client::test_util::{ReplayEvent, StaticReplayClient};
+use aws_smithy_observability::instruments::{
+    AsyncInstrumentBuilder, AsyncMeasure, Histogram, InstrumentBuilder, MonotonicCounter,
+    ProvideInstrument, UpDownCounter,
+};
+use aws_smithy_observability::meter::{Meter, ProvideMeter};
+use aws_smithy_observability::{global, AttributeValue, Attributes, Context, TelemetryProvider};
+use aws_smithy_runtime::client::metrics::MetricsRuntimePlugin;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::client::retries::classifiers::HttpStatusCodeClassifier;
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, OrchestratorError};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout::TimeoutConfig;
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type Measurements = Arc<Mutex<Vec<(String, f64, Option<Attributes>)>>>;
+
+/// Instrument that records every measurement made with it
+#[derive(Debug)]
+struct Recorder {
+    name: String,
+    measurements: Measurements,
+}
+
+impl Recorder {
+    fn record(&self, value: f64, attributes: Option<&Attributes>) {
+        self.measurements
+            .lock()
+            .unwrap()
+            .push((self.name.clone(), value, attributes.cloned()));
+    }
+}
+
+impl Histogram for Recorder {
+    fn record(&self, value: f64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        Recorder::record(self, value, attributes)
+    }
+}
+
+impl MonotonicCounter for Recorder {
+    fn add(&self, value: u64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.record(value as f64, attributes)
+    }
+}
+
+impl UpDownCounter for Recorder {
+    fn add(&self, value: i64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.record(value as f64, attributes)
+    }
+}
+
+impl AsyncMeasure for Recorder {
+    type Value = f64;
+
+    fn record(&self, value: f64, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        Recorder::record(self, value, attributes)
+    }
+
+    fn stop(&self) {}
+}
+
+#[derive(Debug, Default, Clone)]
+struct RecordingMeter {
+    measurements: Measurements,
+}
+
+impl RecordingMeter {
+    fn recorder(&self, name: &str) -> Arc<Recorder> {
+        Arc::new(Recorder {
+            name: name.to_string(),
+            measurements: self.measurements.clone(),
+        })
+    }
+
+    fn values(&self, name: &str) -> Vec<f64> {
+        self.measurements
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(n, _, _)| n == name)
+            .map(|(_, value, _)| *value)
+            .collect()
+    }
+
+    fn attribute(&self, name: &str, key: &str) -> Vec<AttributeValue> {
+        self.measurements
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(n, _, _)| n == name)
+            .filter_map(|(_, _, attrs)| attrs.as_ref()?.get(key).cloned())
+            .collect()
+    }
+}
+
+impl ProvideMeter for RecordingMeter {
+    fn get_meter(&self, _scope: &'static str, _attributes: Option<&Attributes>) -> Meter {
+        Meter::new(Arc::new(self.clone()))
+    }
+}
+
+impl ProvideInstrument for RecordingMeter {
+    fn create_gauge(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = f64>>, f64>,
+    ) -> Arc<dyn AsyncMeasure<Value = f64>> {
+        self.recorder(builder.get_name())
+    }
+
+    fn create_up_down_counter(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn UpDownCounter>>,
+    ) -> Arc<dyn UpDownCounter> {
+        self.recorder(builder.get_name())
+    }
+
+    fn create_async_up_down_counter(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = i64>>, i64>,
+    ) -> Arc<dyn AsyncMeasure<Value = i64>> {
+        Arc::new(IntRecorder(self.recorder(builder.get_name()), PhantomData))
+    }
+
+    fn create_monotonic_counter(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn MonotonicCounter>>,
+    ) -> Arc<dyn MonotonicCounter> {
+        self.recorder(builder.get_name())
+    }
+
+    fn create_async_monotonic_counter(
+        &self,
+        builder: AsyncInstrumentBuilder<'_, Arc<dyn AsyncMeasure<Value = u64>>, u64>,
+    ) -> Arc<dyn AsyncMeasure<Value = u64>> {
+        Arc::new(IntRecorder(self.recorder(builder.get_name()), PhantomData))
+    }
+
+    fn create_histogram(
+        &self,
+        builder: InstrumentBuilder<'_, Arc<dyn Histogram>>,
+    ) -> Arc<dyn Histogram> {
+        self.recorder(builder.get_name())
+    }
+}
+
+/// Records integer measurements of type `T`
+#[derive(Debug)]
+struct IntRecorder<T>(Arc<Recorder>, PhantomData<T>);
+
+impl<T: Into<i128> + Debug + Send + Sync> AsyncMeasure for IntRecorder<T> {
+    type Value = T;
+
+    fn record(&self, value: T, attributes: Option<&Attributes>, _context: Option<&dyn Context>) {
+        self.0.record(value.into() as f64, attributes)
+    }
+
+    fn stop(&self) {}
+}
+
+fn replay_event(status: u16) -> ReplayEvent {
+    ReplayEvent::new(
+        http_1x::Request::builder()
+            .uri("http://localhost:1234/")
+            .body(SdkBody::empty())
+            .unwrap(),
+        http_1x::Response::builder()
+            .status(status)
+            .body(SdkBody::empty())
+            .unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn retried_operation_metrics() {
+    let meter = RecordingMeter::default();
+    global::set_telemetry_provider(
+        TelemetryProvider::builder()
+            .meter_provider(Arc::new(meter.clone()))
+            .build(),
+    )
+    .unwrap();
+
+    let http_client = StaticReplayClient::new(vec![replay_event(503), replay_event(200)]);
+    let operation = Operation::builder()
+        .service_name("test-service")
+        .operation_name("TestOperation")
+        .http_client(http_client.clone())
+        .endpoint_url("http://localhost:1234")
+        .no_auth()
+        .standard_retry(
+            &RetryConfig::standard()
+                .with_max_attempts(2)
+                .with_initial_backoff(Duration::from_millis(1)),
+        )
+        .retry_classifier(HttpStatusCodeClassifier::default())
+        .timeout_config(TimeoutConfig::disabled())
+        .sleep_impl(SharedAsyncSleep::new(TokioSleep::new()))
+        .runtime_plugin(
+            MetricsRuntimePlugin::builder()
+                .with_scope("test")
+                .build()
+                .unwrap(),
+        )
+        .serializer(|_input: ()| Ok(HttpRequest::new(SdkBody::empty())))
+        .deserializer::<_, Infallible>(|response| {
+            if response.status().is_success() {
+                Ok(())
+            } else {
+                Err(OrchestratorError::connector(ConnectorError::io(
+                    "service unavailable".into(),
+                )))
+            }
+        })
+        .build();
+
+    operation.invoke(()).await.expect("success");
+    http_client.assert_requests_match(&[]);
+
+    assert_eq!(1, meter.values("smithy.client.call.duration").len());
+    assert_eq!(2, meter.values("smithy.client.call.attempt.duration").len());
+    assert_eq!(vec![1.0, 1.0], meter.values("smithy.client.call.attempts"));
+    assert_eq!(vec![1.0], meter.values("smithy.client.call.errors"));
+    assert_eq!(
+        vec![AttributeValue::String("TransientError".into())],
+        meter.attribute("smithy.client.call.errors", "error.type")
+    );
+    assert_eq!(
+        1,
+        meter
+            .values("smithy.client.call.retry_backoff_duration")
+            .len()
+    );
+    assert_eq!(
+        1,
+        meter
+            .values("smithy.client.call.serialization_duration")
+            .len()
+    );
+    assert_eq!(
+        2,
+        meter
+            .values("smithy.client.call.deserialization_duration")
+            .len()
+    );
+    assert_eq!(
+        vec![AttributeValue::String("noAuth".into()); 2],
+        meter.attribute(
+            "smithy.client.call.auth.resolve_identity_duration",
+            "auth.scheme_id"
+        )
+    );
+    assert_eq!(
+        vec![AttributeValue::String("TestOperation".into()); 2],
+        meter.attribute(
+            "smithy.client.call.auth.resolve_identity_duration",
+            "rpc.method"
+        )
+    );
+
+    // the retry of a transient error costs 10 permits, which are returned when it succeeds
+    assert_eq!(
+        vec![500.0, 490.0, 500.0],
+        meter.values("smithy.client.retries.token_bucket.available_permits")
+    );
+    // the rate limiter is only used in adaptive retry mode
+    assert!(meter
+        .values("smithy.client.retries.rate_limiter.fill_rate")
+        .is_empty());
+}
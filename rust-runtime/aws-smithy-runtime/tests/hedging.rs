@@ -0,0 +1,159 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(feature = "client", feature = "test-util"))]
+
+use aws_smithy_async::rt::sleep::TokioSleep;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime::client::retries::hedging::HedgingConfig;
+use aws_smithy_runtime::client::retries::{RetryPartition, TokenBucket};
+use aws_smithy_runtime_api::client::http::{
+    http_client_fn, HttpConnector, HttpConnectorFuture, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::runtime_plugin::StaticRuntimePlugin;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::Layer;
+use aws_smithy_types::retry::RetryConfig;
+use aws_smithy_types::timeout::TimeoutConfig;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const SLOW_RESPONSE: Duration = Duration::from_secs(60);
+
+/// Connector that responds with the number of each call, after a delay for the slow calls
+#[derive(Clone, Debug, Default)]
+struct TestConnector {
+    slow_calls: Vec<usize>,
+    calls: Arc<AtomicUsize>,
+    canceled: Arc<AtomicBool>,
+}
+
+/// Flags a call as canceled if it's dropped before completing
+struct CancelGuard(Option<Arc<AtomicBool>>);
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        if let Some(canceled) = self.0.take() {
+            canceled.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+impl HttpConnector for TestConnector {
+    fn call(&self, _request: HttpRequest) -> HttpConnectorFuture {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let slow = self.slow_calls.contains(&call);
+        let mut guard = CancelGuard(Some(self.canceled.clone()));
+        HttpConnectorFuture::new(async move {
+            if slow {
+                tokio::time::sleep(SLOW_RESPONSE).await;
+            }
+            guard.0 = None;
+            let response = http_1x::Response::builder()
+                .header("x-call", call.to_string())
+                .body(SdkBody::empty())
+                .unwrap();
+            Ok(HttpResponse::try_from(response).unwrap())
+        })
+    }
+}
+
+fn operation(
+    connector: &TestConnector,
+    token_bucket: TokenBucket,
+    idempotent: bool,
+) -> Operation<(), usize, Infallible> {
+    let connector = connector.clone();
+    let mut retry_partition = Layer::new("hedging");
+    retry_partition.store_put(
+        RetryPartition::custom("hedging")
+            .token_bucket(token_bucket)
+            .build(),
+    );
+    let mut builder = Operation::builder()
+        .service_name("test-service")
+        .operation_name("TestOperation")
+        .http_client(http_client_fn(move |_, _| {
+            SharedHttpConnector::new(connector.clone())
+        }))
+        .endpoint_url("http://localhost:1234")
+        .no_auth()
+        .standard_retry(&RetryConfig::standard())
+        .timeout_config(TimeoutConfig::disabled())
+        .sleep_impl(TokioSleep::new())
+        .hedging(
+            HedgingConfig::builder()
+                .initial_delay(Duration::from_millis(100))
+                .build()
+                .unwrap(),
+        )
+        .runtime_plugin(StaticRuntimePlugin::new().with_config(retry_partition.freeze()))
+        .serializer(|_input: ()| Ok(HttpRequest::new(SdkBody::from("request"))))
+        .deserializer::<_, Infallible>(|response| {
+            Ok(response
+                .headers()
+                .get("x-call")
+                .unwrap()
+                .parse::<usize>()
+                .unwrap())
+        });
+    if idempotent {
+        builder = builder.idempotent();
+    }
+    builder.build()
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_requests_are_hedged_while_quota_is_available() {
+    let connector = TestConnector {
+        slow_calls: vec![1, 3],
+        ..Default::default()
+    };
+    // enough quota for a single hedge
+    let operation = operation(&connector, TokenBucket::new(5), true);
+
+    let start = Instant::now();
+    let output = operation.invoke(()).await.expect("success");
+    assert_eq!(2, output, "the hedged request should win");
+    assert_eq!(Duration::from_millis(100), start.elapsed());
+    assert!(
+        connector.canceled.load(Ordering::SeqCst),
+        "the slow request should be canceled"
+    );
+
+    let start = Instant::now();
+    let output = operation.invoke(()).await.expect("success");
+    assert_eq!(3, output, "the request shouldn't be hedged without quota");
+    assert_eq!(SLOW_RESPONSE, start.elapsed());
+    assert_eq!(3, connector.calls.load(Ordering::SeqCst));
+}
+
+#[tokio::test(start_paused = true)]
+async fn fast_requests_are_not_hedged() {
+    let connector = TestConnector::default();
+    let operation = operation(&connector, TokenBucket::default(), true);
+
+    assert_eq!(1, operation.invoke(()).await.expect("success"));
+    assert_eq!(1, connector.calls.load(Ordering::SeqCst));
+    assert!(!connector.canceled.load(Ordering::SeqCst));
+}
+
+#[tokio::test(start_paused = true)]
+async fn non_idempotent_operations_are_not_hedged() {
+    let connector = TestConnector {
+        slow_calls: vec![1],
+        ..Default::default()
+    };
+    let operation = operation(&connector, TokenBucket::default(), false);
+
+    let start = Instant::now();
+    assert_eq!(1, operation.invoke(()).await.expect("success"));
+    assert_eq!(SLOW_RESPONSE, start.elapsed());
+    assert_eq!(1, connector.calls.load(Ordering::SeqCst));
+}
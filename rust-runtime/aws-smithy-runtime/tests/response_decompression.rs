@@ -0,0 +1,123 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(
+    feature = "client",
+    feature = "test-util",
+    feature = "response-decompression"
+))]
+
+use aws_smithy_runtime::client::http::body::response_decompression::{
+    ResponseDecompressionConfig, ResponseDecompressionRuntimePlugin,
+};
+use aws_smithy_runtime::client::http::test_util::infallible_client_fn;
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::BeforeDeserializationInterceptorContextMut;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::timeout::TimeoutConfig;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+// "hello world" compressed with gzip
+const GZIP_HELLO_WORLD: &[u8] = &[
+    31, 139, 8, 0, 0, 0, 0, 0, 0, 255, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0, 133,
+    17, 74, 13, 11, 0, 0, 0,
+];
+
+/// Records the response body as interceptors see it, like checksum validation would.
+#[derive(Debug, Clone, Default)]
+struct RecordBodyInterceptor {
+    body: Arc<Mutex<Option<Vec<u8>>>>,
+}
+
+impl Intercept for RecordBodyInterceptor {
+    fn name(&self) -> &'static str {
+        "RecordBodyInterceptor"
+    }
+
+    fn modify_before_deserialization(
+        &self,
+        context: &mut BeforeDeserializationInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let body = context.response().body().bytes().expect("in-memory body");
+        *self.body.lock().unwrap() = Some(body.to_vec());
+        Ok(())
+    }
+}
+
+fn operation(
+    config: ResponseDecompressionConfig,
+    interceptor: RecordBodyInterceptor,
+) -> Operation<(), String, Infallible> {
+    let http_client = infallible_client_fn(|req| {
+        assert_eq!(
+            Some("gzip, zstd, br"),
+            req.headers()
+                .get("accept-encoding")
+                .and_then(|v| v.to_str().ok())
+        );
+        http_02x::Response::builder()
+            .status(200)
+            .header("content-encoding", "gzip")
+            .header("content-length", GZIP_HELLO_WORLD.len())
+            .body(SdkBody::from(GZIP_HELLO_WORLD))
+            .unwrap()
+    });
+    Operation::builder()
+        .service_name("test")
+        .operation_name("test")
+        .http_client(http_client)
+        .endpoint_url("http://localhost:1234/doesntmatter")
+        .no_auth()
+        .no_retry()
+        .timeout_config(TimeoutConfig::disabled())
+        .runtime_plugin(ResponseDecompressionRuntimePlugin::new(config))
+        .interceptor(interceptor)
+        .serializer(|_body: ()| Ok(HttpRequest::new(SdkBody::empty())))
+        .deserializer::<_, Infallible>(|resp| {
+            assert!(resp.headers().get("content-encoding").is_none());
+            let body = resp.body().bytes().expect("body was read");
+            Ok(String::from_utf8(body.to_vec()).unwrap())
+        })
+        .build()
+}
+
+#[tokio::test]
+async fn response_is_decompressed_after_interceptors_see_it() {
+    let interceptor = RecordBodyInterceptor::default();
+    let output = operation(ResponseDecompressionConfig::new(), interceptor.clone())
+        .invoke(())
+        .await
+        .expect("success");
+
+    assert_eq!("hello world", output);
+    assert_eq!(
+        Some(GZIP_HELLO_WORLD),
+        interceptor.body.lock().unwrap().as_deref()
+    );
+}
+
+#[tokio::test]
+async fn max_decompressed_size_fails_the_request() {
+    let config = ResponseDecompressionConfig::new().with_max_decompressed_size_bytes(5);
+    let err = operation(config, RecordBodyInterceptor::default())
+        .invoke(())
+        .await
+        .expect_err("response is too large");
+
+    let message = format!("{}", DisplayErrorContext(&err));
+    assert!(
+        message.contains("exceeded the maximum allowed size of 5 bytes"),
+        "unexpected error: {message}"
+    );
+}
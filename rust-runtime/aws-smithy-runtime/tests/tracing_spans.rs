@@ -0,0 +1,307 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+#![cfg(all(feature = "client", feature = "test-util"))]
+
+use aws_smithy_observability::tracer::{
+    ProvideSpan, ProvideTracer, Span, SpanBuilder, SpanContext, SpanKind, SpanStatus, Tracer,
+};
+use aws_smithy_observability::{global, AttributeValue, Attributes, TelemetryProvider};
+use aws_smithy_runtime::client::http::test_util::infallible_client_fn;
+use aws_smithy_runtime::client::metrics::{MetricsRuntimePlugin, TraceContextPropagation};
+use aws_smithy_runtime::client::orchestrator::operation::Operation;
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::interceptors::context::BeforeTransmitInterceptorContextRef;
+use aws_smithy_runtime_api::client::interceptors::Intercept;
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, OrchestratorError};
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::timeout::TimeoutConfig;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug, Default)]
+struct RecordedSpan {
+    name: String,
+    kind: Option<SpanKind>,
+    context: Option<SpanContext>,
+    parent: Option<SpanContext>,
+    attributes: HashMap<String, AttributeValue>,
+    status: Option<SpanStatus>,
+    errors: Vec<String>,
+    events: Vec<String>,
+    ended: bool,
+}
+
+#[derive(Debug)]
+struct TestSpan(Arc<Mutex<RecordedSpan>>);
+
+impl Span for TestSpan {
+    fn set_attribute(&self, key: Cow<'static, str>, value: AttributeValue) {
+        self.0.lock().unwrap().attributes.insert(key.into(), value);
+    }
+
+    fn set_status(&self, status: SpanStatus) {
+        self.0.lock().unwrap().status = Some(status);
+    }
+
+    fn record_error(&self, error: &(dyn Error + 'static)) {
+        self.0.lock().unwrap().errors.push(error.to_string());
+    }
+
+    fn add_event(&self, name: Cow<'static, str>, _attributes: Option<&Attributes>) {
+        self.0.lock().unwrap().events.push(name.into());
+    }
+
+    fn span_context(&self) -> SpanContext {
+        self.0.lock().unwrap().context.unwrap()
+    }
+
+    fn end(&self) {
+        let mut span = self.0.lock().unwrap();
+        assert!(!span.ended, "span `{}` ended twice", span.name);
+        span.ended = true;
+    }
+}
+
+/// Tracer that records every span it starts
+#[derive(Debug, Default, Clone)]
+struct RecordingTracer {
+    spans: Arc<Mutex<Vec<Arc<Mutex<RecordedSpan>>>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl RecordingTracer {
+    /// Returns a snapshot of the spans in the trace of the call to `rpc_method`
+    fn spans(&self, rpc_method: &str) -> Vec<RecordedSpan> {
+        let spans = self
+            .spans
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|span| span.lock().unwrap().clone())
+            .collect::<Vec<_>>();
+        let trace_id = get(&spans, &format!("test-service.{rpc_method}"))
+            .context
+            .unwrap()
+            .trace_id();
+        spans
+            .into_iter()
+            .filter(|span| span.context.unwrap().trace_id() == trace_id)
+            .collect()
+    }
+}
+
+impl ProvideTracer for RecordingTracer {
+    fn get_tracer(&self, _scope: &'static str, _attributes: Option<&Attributes>) -> Tracer {
+        Tracer::new(Arc::new(self.clone()))
+    }
+}
+
+impl ProvideSpan for RecordingTracer {
+    fn start_span(&self, builder: SpanBuilder<'_>) -> Arc<dyn Span> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let trace_id = match builder.get_parent() {
+            Some(parent) => parent.trace_id(),
+            None => u128::from(id).to_be_bytes(),
+        };
+        let span = Arc::new(Mutex::new(RecordedSpan {
+            name: builder.get_name().to_string(),
+            kind: Some(builder.get_kind()),
+            context: Some(SpanContext::new(trace_id, id.to_be_bytes(), true)),
+            parent: builder.get_parent().copied(),
+            attributes: builder
+                .get_attributes()
+                .map(|attrs| attrs.attributes().clone())
+                .unwrap_or_default(),
+            ..Default::default()
+        }));
+        self.spans.lock().unwrap().push(span.clone());
+        Arc::new(TestSpan(span))
+    }
+}
+
+#[derive(Debug)]
+struct TestError;
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "test error")
+    }
+}
+
+impl Error for TestError {}
+
+/// Fails the operation if the `traceparent` header was present when the request was signed
+#[derive(Debug)]
+struct TraceparentIsNotSigned;
+
+impl Intercept for TraceparentIsNotSigned {
+    fn name(&self) -> &'static str {
+        "TraceparentIsNotSigned"
+    }
+
+    fn read_after_signing(
+        &self,
+        context: &BeforeTransmitInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        match context.request().headers().get("traceparent") {
+            Some(_) => Err("the traceparent header must be added after signing".into()),
+            None => Ok(()),
+        }
+    }
+}
+
+fn operation(
+    operation_name: &'static str,
+    status: u16,
+    traceparent: Arc<Mutex<Option<String>>>,
+) -> Operation<(), (), TestError> {
+    let http_client = infallible_client_fn(move |req| {
+        *traceparent.lock().unwrap() = req
+            .headers()
+            .get("traceparent")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        http_02x::Response::builder()
+            .status(status)
+            .body(SdkBody::empty())
+            .unwrap()
+    });
+    Operation::builder()
+        .service_name("test-service")
+        .operation_name(operation_name)
+        .http_client(http_client)
+        .endpoint_url("http://localhost:1234/doesntmatter")
+        .no_auth()
+        .no_retry()
+        .timeout_config(TimeoutConfig::disabled())
+        .runtime_plugin(
+            MetricsRuntimePlugin::builder()
+                .with_scope("test")
+                .with_trace_context_propagation(TraceContextPropagation::W3c)
+                .build()
+                .unwrap(),
+        )
+        .interceptor(TraceparentIsNotSigned)
+        .serializer(|_body: ()| Ok(HttpRequest::new(SdkBody::empty())))
+        .deserializer::<_, TestError>(|resp| {
+            if resp.status().is_success() {
+                Ok(())
+            } else {
+                Err(OrchestratorError::operation(TestError))
+            }
+        })
+        .build()
+}
+
+fn get<'a>(spans: &'a [RecordedSpan], name: &str) -> &'a RecordedSpan {
+    spans
+        .iter()
+        .find(|span| span.name == name)
+        .unwrap_or_else(|| panic!("no `{name}` span"))
+}
+
+#[tokio::test]
+async fn operation_spans() {
+    let tracer = RecordingTracer::default();
+    global::set_telemetry_provider(
+        TelemetryProvider::builder()
+            .tracer_provider(Arc::new(tracer.clone()))
+            .build(),
+    )
+    .unwrap();
+
+    // successful call
+    let traceparent = Arc::new(Mutex::new(None));
+    operation("Succeed", 200, traceparent.clone())
+        .invoke(())
+        .await
+        .expect("success");
+
+    let spans = tracer.spans("Succeed");
+    let names = spans
+        .iter()
+        .map(|span| span.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "test-service.Succeed",
+            "serialization",
+            "attempt",
+            "signing",
+            "transmit",
+            "deserialization"
+        ],
+        names
+    );
+    for span in &spans {
+        assert!(span.ended, "`{}` wasn't ended", span.name);
+        assert_eq!(Some(SpanStatus::Ok), span.status, "`{}`", span.name);
+    }
+
+    let call = get(&spans, "test-service.Succeed");
+    assert_eq!(Some(SpanKind::Client), call.kind);
+    assert_eq!(None, call.parent);
+    assert_eq!(
+        Some(&AttributeValue::String("test-service".into())),
+        call.attributes.get("rpc.service")
+    );
+    assert_eq!(
+        Some(&AttributeValue::String("Succeed".into())),
+        call.attributes.get("rpc.method")
+    );
+    // no retry events were recorded for a single attempt
+    assert!(call.events.is_empty());
+
+    let attempt = get(&spans, "attempt");
+    assert_eq!(call.context, attempt.parent);
+    assert_eq!(
+        Some(&AttributeValue::I64(1)),
+        attempt.attributes.get("attempt")
+    );
+    assert_eq!(
+        attempt.context.unwrap().to_traceparent(),
+        *traceparent.lock().unwrap()
+    );
+
+    assert_eq!(call.context, get(&spans, "serialization").parent);
+    for phase in ["signing", "transmit", "deserialization"] {
+        assert_eq!(attempt.context, get(&spans, phase).parent);
+    }
+    assert_eq!(
+        Some(&AttributeValue::I64(200)),
+        get(&spans, "transmit")
+            .attributes
+            .get("http.response.status_code")
+    );
+
+    // failed call
+    operation("Fail", 500, Arc::new(Mutex::new(None)))
+        .invoke(())
+        .await
+        .expect_err("failure");
+
+    let spans = tracer.spans("Fail");
+    for span in &spans {
+        assert!(span.ended, "`{}` wasn't ended", span.name);
+    }
+    for name in ["test-service.Fail", "attempt"] {
+        let span = get(&spans, name);
+        assert!(
+            matches!(span.status, Some(SpanStatus::Error { .. })),
+            "`{name}` should have failed"
+        );
+        assert_eq!(1, span.errors.len());
+    }
+    assert_eq!(Some(SpanStatus::Ok), get(&spans, "deserialization").status);
+}
@@ -103,6 +103,12 @@ pub enum RetryMode {
     /// automatic client-side throttling. Because this mode is experimental, it might change
     /// behavior in the future.
     Adaptive,
+
+    /// A retry mode that includes the functionality of standard mode, but limits retries with a
+    /// retry budget instead of a retry quota: the number of retries sent over a sliding window
+    /// can't exceed a ratio of the number of initial requests. This protects services from retry
+    /// storms while they're degraded, without penalizing a client that only sees a few errors.
+    Budget,
 }
 
 impl FromStr for RetryMode {
@@ -116,6 +122,8 @@ impl FromStr for RetryMode {
             Ok(RetryMode::Standard)
         } else if string.eq_ignore_ascii_case("adaptive") {
             Ok(RetryMode::Adaptive)
+        } else if string.eq_ignore_ascii_case("budget") {
+            Ok(RetryMode::Budget)
         } else {
             Err(RetryModeParseError::new(string))
         }
@@ -341,6 +349,11 @@ impl RetryConfig {
         }
     }
 
+    /// Creates a default `RetryConfig` with `RetryMode::Budget` and max attempts of three.
+    pub fn budget() -> Self {
+        Self::standard().with_retry_mode(RetryMode::Budget)
+    }
+
     /// Creates a `RetryConfig` that has retries disabled.
     pub fn disabled() -> Self {
         Self::standard().with_max_attempts(1)
@@ -499,6 +512,15 @@ mod tests {
         // );
     }
 
+    #[test]
+    fn retry_mode_from_str_parses_budget() {
+        assert_eq!(RetryMode::from_str("budget").ok(), Some(RetryMode::Budget));
+        assert_eq!(
+            RetryMode::from_str(" BUDGET ").ok(),
+            Some(RetryMode::Budget)
+        );
+    }
+
     #[test]
     fn retry_mode_from_str_ignores_whitespace_before_and_after() {
         assert_eq!(
@@ -3,9 +3,9 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use aws_smithy_compression::body::compress::CompressedBody;
-use aws_smithy_compression::http::http_body_0_4_x::CompressRequest;
+use aws_smithy_compression::body::compress::{CompressedBody, StreamingCompressedBody};
 use aws_smithy_compression::{CompressionAlgorithm, CompressionOptions};
+use aws_smithy_runtime::client::http::body::request_compression::CompressedStreamingBody;
 use aws_smithy_runtime::client::sdk_feature::SmithySdkFeature;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::interceptors::context::{
@@ -29,10 +29,14 @@ pub(crate) struct RequestCompressionRuntimePlugin {
 }
 
 impl RequestCompressionRuntimePlugin {
-    pub(crate) fn new() -> Self {
+    /// Create a new `RequestCompressionRuntimePlugin` for an operation that supports the given
+    /// `encodings`, listed in order of preference as they appear in the `@requestCompression` trait.
+    pub(crate) fn new(encodings: &[&str]) -> Self {
         Self {
             runtime_components: RuntimeComponentsBuilder::new("RequestCompressionRuntimePlugin")
-                .with_interceptor(SharedInterceptor::new(RequestCompressionInterceptor::new())),
+                .with_interceptor(SharedInterceptor::new(RequestCompressionInterceptor::new(
+                    encodings,
+                ))),
         }
     }
 }
@@ -58,17 +62,28 @@ impl Storable for RequestCompressionInterceptorState {
 /// Interceptor for Smithy [`@requestCompression`][spec].
 ///
 /// [spec]: https://smithy.io/2.0/spec/behavior-traits.html#requestcompression-trait
-pub(crate) struct RequestCompressionInterceptor {}
+pub(crate) struct RequestCompressionInterceptor {
+    compression_algorithm: Option<CompressionAlgorithm>,
+}
 
 impl fmt::Debug for RequestCompressionInterceptor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("RequestCompressionInterceptor").finish()
+        f.debug_struct("RequestCompressionInterceptor")
+            .field("compression_algorithm", &self.compression_algorithm)
+            .finish()
     }
 }
 
 impl RequestCompressionInterceptor {
-    pub(crate) fn new() -> Self {
-        Self {}
+    /// Create a new interceptor that compresses requests with the first of the given `encodings`
+    /// supported by this client. Unsupported encodings are skipped.
+    pub(crate) fn new(encodings: &[&str]) -> Self {
+        let compression_algorithm = encodings
+            .iter()
+            .find_map(|encoding| encoding.parse::<CompressionAlgorithm>().ok());
+        Self {
+            compression_algorithm,
+        }
     }
 }
 
@@ -123,40 +138,61 @@ impl Intercept for RequestCompressionInterceptor {
             return Ok(());
         }
 
+        // Don't wrap a body if none of the operation's encodings are supported.
+        let Some(compression_algorithm) = self.compression_algorithm else {
+            tracing::trace!("no supported compression algorithm; request will not be compressed");
+            return Ok(());
+        };
+
         // Don't wrap a body if it's below the minimum size
         //
         // Because compressing small amounts of data can actually increase its size,
         // we check to see if the data is big enough to make compression worthwhile.
-        let size_hint = http_body::Body::size_hint(request.body()).exact();
-        if let Some(known_size) = size_hint {
-            if known_size < options.min_compression_size_bytes() as u64 {
+        // For unsized streaming bodies, the upper bound of the size hint is used if known.
+        let size_hint = http_body::Body::size_hint(request.body());
+        if let Some(max_size) = size_hint.upper() {
+            if max_size < options.min_compression_size_bytes() as u64 {
                 tracing::trace!(
                     min_compression_size_bytes = options.min_compression_size_bytes(),
-                    known_size,
+                    max_size,
                     "request body is below minimum size and will not be compressed"
                 );
                 return Ok(());
             }
+        }
+        if size_hint.exact().is_some() {
             tracing::trace!("compressing sized request body...");
         } else {
             tracing::trace!("compressing unsized request body...");
         }
 
-        wrap_request_body_in_compressed_body(
-            request,
-            CompressionAlgorithm::Gzip.into_impl_http_body_0_4_x(&options),
-        )?;
-        cfg.interceptor_state()
-            .store_append::<SmithySdkFeature>(SmithySdkFeature::GzipRequestCompression);
+        let is_streaming = request.body().is_streaming();
+        wrap_request_body_in_compressed_body(request, compression_algorithm, &options)?;
+        if is_streaming {
+            cfg.interceptor_state().store_put(CompressedStreamingBody);
+        }
+        if compression_algorithm == CompressionAlgorithm::Gzip {
+            cfg.interceptor_state()
+                .store_append::<SmithySdkFeature>(SmithySdkFeature::GzipRequestCompression);
+        }
 
         Ok(())
     }
 }
 
+/// Wrap the request body so that it's compressed with the given algorithm.
+///
+/// In-memory bodies are compressed immediately. Streaming bodies are compressed frame by frame as
+/// they're sent, as a single compressed stream, which means their compressed length is unknown
+/// and the `Content-Length` header must be removed. Because this happens in
+/// `modify_before_retry_loop`, any checksum calculated in `modify_before_signing` covers the
+/// compressed payload, and signing comes last.
 fn wrap_request_body_in_compressed_body(
     request: &mut HttpRequest,
-    request_compress_impl: Box<dyn CompressRequest>,
+    compression_algorithm: CompressionAlgorithm,
+    options: &CompressionOptions,
 ) -> Result<(), BuildError> {
+    let request_compress_impl = compression_algorithm.into_impl_http_body_0_4_x(options);
     request.headers_mut().append(
         request_compress_impl.header_name(),
         request_compress_impl.header_value(),
@@ -166,12 +202,13 @@ fn wrap_request_body_in_compressed_body(
 
         if body.is_streaming() {
             request.headers_mut().remove(http::header::CONTENT_LENGTH);
+            let options = options.clone();
             body.map(move |body| {
-                let body = CompressedBody::new(body, request_compress_impl.clone());
-                SdkBody::from_body_0_4(body)
+                let compressor = compression_algorithm.into_streaming_impl(&options);
+                SdkBody::from_body_0_4(StreamingCompressedBody::new(body, compressor))
             })
         } else {
-            let body = CompressedBody::new(body, request_compress_impl.clone());
+            let body = CompressedBody::new(body, request_compress_impl);
             let body = body.into_compressed_sdk_body().map_err(BuildError::other)?;
 
             let content_length = body.content_length().expect("this payload is in-memory");
@@ -267,7 +304,8 @@ mod tests {
 
         wrap_request_body_in_compressed_body(
             &mut request,
-            compression_algorithm.into_impl_http_body_0_4_x(&compression_options),
+            compression_algorithm,
+            &compression_options,
         )
         .unwrap();
 
@@ -307,7 +345,7 @@ mod tests {
         let mut context = context();
         let ctx = Into::into(&context);
 
-        let sut = RequestCompressionInterceptor::new();
+        let sut = RequestCompressionInterceptor::new(&["gzip"]);
         sut.read_before_execution(&ctx, &mut cfg).unwrap();
 
         let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
@@ -320,4 +358,176 @@ mod tests {
             cfg.load::<SmithySdkFeature>().next().unwrap()
         );
     }
+
+    // These tests need the `zstd` and `brotli` features of `aws-smithy-compression`, and
+    // dependencies that generated clients don't have.
+    #[cfg(feature = "gated-tests")]
+    mod gated {
+        use super::{context, UNCOMPRESSED_INPUT};
+        use crate::client_request_compression::{
+            wrap_request_body_in_compressed_body, RequestCompressionInterceptor,
+            RequestMinCompressionSizeBytes,
+        };
+        use aws_smithy_compression::{CompressionAlgorithm, CompressionOptions};
+        use aws_smithy_runtime::client::http::body::request_compression::CompressedStreamingBody;
+        use aws_smithy_runtime::client::sdk_feature::SmithySdkFeature;
+        use aws_smithy_runtime_api::client::interceptors::context::{Input, InterceptorContext};
+        use aws_smithy_runtime_api::client::interceptors::Intercept;
+        use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+        use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+        use aws_smithy_types::body::SdkBody;
+        use aws_smithy_types::config_bag::{ConfigBag, Layer};
+        use http_body::Body;
+
+        #[test]
+        fn test_first_supported_encoding_is_selected() {
+            let sut = RequestCompressionInterceptor::new(&["unknown", "zstd", "gzip"]);
+            assert_eq!(Some(CompressionAlgorithm::Zstd), sut.compression_algorithm);
+
+            let sut = RequestCompressionInterceptor::new(&["br", "gzip"]);
+            assert_eq!(
+                Some(CompressionAlgorithm::Brotli),
+                sut.compression_algorithm
+            );
+
+            let sut = RequestCompressionInterceptor::new(&["unknown"]);
+            assert_eq!(None, sut.compression_algorithm);
+        }
+
+        #[tokio::test]
+        async fn test_zstd_compressed_body_is_decodable() {
+            let mut cfg = ConfigBag::base();
+            let mut layer = Layer::new("test");
+            layer.store_put(RequestMinCompressionSizeBytes::from(0));
+            cfg.push_layer(layer);
+            let mut context = context();
+            let ctx = Into::into(&context);
+
+            let sut = RequestCompressionInterceptor::new(&["zstd"]);
+            sut.read_before_execution(&ctx, &mut cfg).unwrap();
+
+            let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+            let mut ctx = Into::into(&mut context);
+            sut.modify_before_retry_loop(&mut ctx, &rc, &mut cfg)
+                .unwrap();
+
+            let request = context.request().unwrap();
+            assert_eq!(Some("zstd"), request.headers().get("content-encoding"));
+            let body = request.body().bytes().expect("body is in-memory");
+            assert_eq!(
+                UNCOMPRESSED_INPUT,
+                zstd::decode_all(body).unwrap().as_slice()
+            );
+            // Only gzip compression is tracked as an SDK feature
+            assert!(cfg.load::<SmithySdkFeature>().next().is_none());
+            // In-memory bodies keep a known length
+            assert!(cfg.load::<CompressedStreamingBody>().is_none());
+        }
+
+        #[tokio::test]
+        async fn test_streaming_body_is_compressed_as_a_single_stream() {
+            let input = UNCOMPRESSED_INPUT.repeat(1000);
+            let streaming_input = input.clone();
+            let mut request: HttpRequest = http::Request::builder()
+                .header(http::header::CONTENT_LENGTH, input.len())
+                .body(SdkBody::retryable(move || {
+                    SdkBody::from_body_0_4(http_body::Full::new(bytes::Bytes::from(
+                        streaming_input.clone(),
+                    )))
+                }))
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert!(request.body().is_streaming());
+
+            let compression_options = CompressionOptions::default()
+                .with_min_compression_size_bytes(0)
+                .unwrap();
+            wrap_request_body_in_compressed_body(
+                &mut request,
+                CompressionAlgorithm::Zstd,
+                &compression_options,
+            )
+            .unwrap();
+
+            // The compressed length isn't known until the whole stream is compressed
+            assert!(request.headers().get("content-length").is_none());
+            assert_eq!(None, request.body().size_hint().exact());
+
+            // Each attempt should compress the stream from scratch
+            for _ in 0..2 {
+                let mut body = request.body().try_clone().expect("body is retryable");
+                let mut body_data = Vec::new();
+                while let Some(data) = body.data().await {
+                    body_data.extend_from_slice(&data.unwrap())
+                }
+                assert_eq!(input, zstd::decode_all(body_data.as_slice()).unwrap());
+            }
+        }
+
+        #[tokio::test]
+        async fn test_streaming_body_below_minimum_size_is_not_compressed() {
+            let mut cfg = ConfigBag::base();
+            let mut context = InterceptorContext::new(Input::doesnt_matter());
+            context.enter_serialization_phase();
+            context.set_request(
+                http::Request::builder()
+                    .body(SdkBody::from_body_0_4(http_body::Full::new(
+                        bytes::Bytes::from_static(UNCOMPRESSED_INPUT),
+                    )))
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            );
+            let _ = context.take_input();
+            context.enter_before_transmit_phase();
+            let ctx = Into::into(&context);
+
+            let sut = RequestCompressionInterceptor::new(&["gzip"]);
+            sut.read_before_execution(&ctx, &mut cfg).unwrap();
+
+            let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+            let mut ctx = Into::into(&mut context);
+            sut.modify_before_retry_loop(&mut ctx, &rc, &mut cfg)
+                .unwrap();
+
+            let request = context.request().unwrap();
+            assert!(request.headers().get("content-encoding").is_none());
+            assert!(cfg.load::<CompressedStreamingBody>().is_none());
+        }
+
+        #[tokio::test]
+        async fn test_compressed_streaming_body_is_marked() {
+            let mut cfg = ConfigBag::base();
+            let mut layer = Layer::new("test");
+            layer.store_put(RequestMinCompressionSizeBytes::from(0));
+            cfg.push_layer(layer);
+            let mut context = InterceptorContext::new(Input::doesnt_matter());
+            context.enter_serialization_phase();
+            context.set_request(
+                http::Request::builder()
+                    .body(SdkBody::from_body_0_4(http_body::Full::new(
+                        bytes::Bytes::from_static(UNCOMPRESSED_INPUT),
+                    )))
+                    .unwrap()
+                    .try_into()
+                    .unwrap(),
+            );
+            let _ = context.take_input();
+            context.enter_before_transmit_phase();
+            let ctx = Into::into(&context);
+
+            let sut = RequestCompressionInterceptor::new(&["gzip"]);
+            sut.read_before_execution(&ctx, &mut cfg).unwrap();
+
+            let rc = RuntimeComponentsBuilder::for_tests().build().unwrap();
+            let mut ctx = Into::into(&mut context);
+            sut.modify_before_retry_loop(&mut ctx, &rc, &mut cfg)
+                .unwrap();
+
+            let request = context.request().unwrap();
+            assert_eq!(Some("gzip"), request.headers().get("content-encoding"));
+            assert!(cfg.load::<CompressedStreamingBody>().is_some());
+        }
+    }
 }
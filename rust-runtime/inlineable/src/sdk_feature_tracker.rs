@@ -204,10 +204,11 @@ pub(crate) mod retry_mode {
             cfg: &mut ConfigBag,
         ) -> Result<(), BoxError> {
             cfg.load::<RetryConfig>()
-                .map(|retry_config| match retry_config.mode() {
-                    RetryMode::Standard => SmithySdkFeature::RetryModeStandard,
-                    RetryMode::Adaptive => SmithySdkFeature::RetryModeAdaptive,
-                    _ => unreachable!("retry mode must be standard or adaptive"),
+                .and_then(|retry_config| match retry_config.mode() {
+                    RetryMode::Standard => Some(SmithySdkFeature::RetryModeStandard),
+                    RetryMode::Adaptive => Some(SmithySdkFeature::RetryModeAdaptive),
+                    // There's no feature ID for retry budgets
+                    _ => None,
                 })
                 .map(|feature| {
                     cfg.interceptor_state()